@@ -1,8 +1,10 @@
 use std::fmt::{self, Display, Formatter};
 use std::path::PathBuf;
 
+use clap::builder::ValueParser;
 use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
 use semver::Version;
+use typst::layout::PageRanges;
 
 /// The character typically used to separate path components
 /// in environment variables.
@@ -44,6 +46,9 @@ pub enum Command {
     /// Lists all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 
+    /// Runs a language server for editors on standard input and output
+    Lsp(LspCommand),
+
     /// Self update the Typst CLI
     #[cfg_attr(not(feature = "self-update"), doc = " (disabled)")]
     Update(UpdateCommand),
@@ -56,13 +61,18 @@ pub struct CompileCommand {
     #[clap(flatten)]
     pub common: SharedArgs,
 
-    /// Path to output file (PDF, PNG, or SVG)
+    /// Path to output file (PDF, PNG, SVG, HTML, EPUB, MD, or TXT)
     pub output: Option<PathBuf>,
 
     /// The format of the output file, inferred from the extension by default
     #[arg(long = "format", short = 'f')]
     pub format: Option<OutputFormat>,
 
+    /// Which pages to export, e.g. `1-3,7,9-`. When unspecified, all pages
+    /// are exported. Only applies to PDF, PNG, and SVG output
+    #[arg(long = "pages", value_name = "PAGES", value_parser = parse_page_ranges)]
+    pub pages: Option<PageRanges>,
+
     /// Opens the output file using the default viewer after compilation
     #[arg(long = "open")]
     pub open: Option<Option<String>>,
@@ -71,6 +81,55 @@ pub struct CompileCommand {
     #[arg(long = "ppi", default_value_t = 144.0)]
     pub ppi: f32,
 
+    /// Leaves out the invisible layer of selectable text in SVG export
+    #[arg(long = "no-svg-text-layer")]
+    pub no_svg_text_layer: bool,
+
+    /// A PNG or JPEG image to use as the cover for EPUB export
+    #[arg(long = "epub-cover", value_name = "IMAGE")]
+    pub epub_cover: Option<PathBuf>,
+
+    /// The document's creation date formatted as a UNIX timestamp, used
+    /// instead of the current time to make the output reproducible
+    #[arg(
+        long = "creation-timestamp",
+        env = "SOURCE_DATE_EPOCH",
+        value_name = "UNIX_TIMESTAMP"
+    )]
+    pub creation_timestamp: Option<i64>,
+
+    /// The PDF standard the output should conform to
+    #[arg(long = "pdf-standard", default_value_t = PdfStandard::V17)]
+    pub pdf_standard: PdfStandard,
+
+    /// Encrypts the PDF with the given password, which is then required to
+    /// open it
+    #[arg(long = "pdf-password", env = "TYPST_PDF_PASSWORD", value_name = "PASSWORD")]
+    pub pdf_password: Option<String>,
+
+    /// Encrypts the PDF with the given password, which grants full access
+    /// regardless of `--pdf-deny`
+    #[arg(
+        long = "pdf-owner-password",
+        env = "TYPST_PDF_OWNER_PASSWORD",
+        value_name = "PASSWORD"
+    )]
+    pub pdf_owner_password: Option<String>,
+
+    /// Actions that an encrypted PDF does not permit without its owner
+    /// password
+    #[arg(long = "pdf-deny", value_name = "ACTIONS", value_delimiter = ',')]
+    pub pdf_deny: Vec<PdfAction>,
+
+    /// The cipher to encrypt the PDF with
+    #[arg(long = "pdf-cipher", default_value_t = PdfCipher::Aes256)]
+    pub pdf_cipher: PdfCipher,
+
+    /// The number of threads to use for parallel work like exporting
+    /// images, defaults to the number of CPUs
+    #[arg(long = "jobs", short = 'j', value_name = "COUNT")]
+    pub jobs: Option<usize>,
+
     /// Produces a flamegraph of the compilation process
     #[arg(long = "flamegraph", value_name = "OUTPUT_SVG")]
     pub flamegraph: Option<Option<PathBuf>>,
@@ -94,6 +153,10 @@ pub struct QueryCommand {
     #[clap(long = "one", default_value = "false")]
     pub one: bool,
 
+    /// Wraps each result with the number of the page it is on
+    #[clap(long = "pages", default_value = "false")]
+    pub pages: bool,
+
     /// The format to serialize in
     #[clap(long = "format", default_value = "json")]
     pub format: SerializationFormat,
@@ -109,7 +172,8 @@ pub enum SerializationFormat {
 /// Common arguments of compile, watch, and query.
 #[derive(Debug, Clone, Args)]
 pub struct SharedArgs {
-    /// Path to input Typst file
+    /// Path to input Typst file, or to a project directory or its
+    /// `typst.toml` manifest
     pub input: PathBuf,
 
     /// Configures the project root (for absolute paths)
@@ -125,6 +189,15 @@ pub struct SharedArgs {
     )]
     pub font_paths: Vec<PathBuf>,
 
+    /// Add a string key-value pair visible through `sys.inputs`
+    #[clap(
+        long = "input",
+        value_name = "key=value",
+        action = ArgAction::Append,
+        value_parser = ValueParser::new(parse_input_pair),
+    )]
+    pub inputs: Vec<(String, String)>,
+
     /// The format to emit diagnostics in
     #[clap(
         long,
@@ -134,6 +207,27 @@ pub struct SharedArgs {
     pub diagnostic_format: DiagnosticFormat,
 }
 
+/// Parses key/value pairs split by the first equal sign.
+///
+/// This function will return an error if the argument contains no equals sign
+/// or if the key (before the equals sign) is empty.
+fn parse_input_pair(raw: &str) -> Result<(String, String), String> {
+    let (key, val) = raw
+        .split_once('=')
+        .ok_or("input must be a key and a value separated by an equal sign")?;
+    let key = key.trim().to_owned();
+    if key.is_empty() {
+        return Err("the key was missing or empty".to_owned());
+    }
+    let val = val.trim().to_owned();
+    Ok((key, val))
+}
+
+/// Parses a comma-separated list of page ranges.
+fn parse_page_ranges(raw: &str) -> Result<PageRanges, String> {
+    raw.parse::<PageRanges>().map_err(|err| err.to_string())
+}
+
 /// Lists all discovered fonts in system and custom font paths
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
@@ -151,11 +245,55 @@ pub struct FontsCommand {
     pub variants: bool,
 }
 
+/// Runs a language server for editors on standard input and output
+#[derive(Debug, Clone, Parser)]
+pub struct LspCommand {
+    /// Configures the project root (for absolute paths), defaults to the
+    /// editor's workspace
+    #[clap(long = "root", env = "TYPST_ROOT", value_name = "DIR")]
+    pub root: Option<PathBuf>,
+
+    /// The main file of the project, relative to the root, which is compiled
+    /// whenever one of its files changes; defaults to the first document that
+    /// is opened
+    #[clap(long = "main", value_name = "FILE")]
+    pub main: Option<PathBuf>,
+
+    /// Adds additional directories to search for fonts
+    #[clap(
+        long = "font-path",
+        env = "TYPST_FONT_PATHS",
+        value_name = "DIR",
+        value_delimiter = ENV_PATH_SEP,
+    )]
+    pub font_paths: Vec<PathBuf>,
+
+    /// Add a string key-value pair visible through `sys.inputs`
+    #[clap(
+        long = "input",
+        value_name = "key=value",
+        action = ArgAction::Append,
+        value_parser = ValueParser::new(parse_input_pair),
+    )]
+    pub inputs: Vec<(String, String)>,
+
+    /// The format that the documents are exported to, visible through
+    /// `sys.target`
+    #[clap(
+        long = "target",
+        default_value_t = OutputFormat::Pdf,
+        value_parser = clap::value_parser!(OutputFormat)
+    )]
+    pub target: OutputFormat,
+}
+
 /// Which format to use for diagnostics.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, ValueEnum)]
 pub enum DiagnosticFormat {
     Human,
     Short,
+    /// One JSON object per diagnostic and line, for tools and editors.
+    Json,
 }
 
 impl Display for DiagnosticFormat {
@@ -189,6 +327,14 @@ pub enum OutputFormat {
     Pdf,
     Png,
     Svg,
+    /// Experimental. Maps the document's structure to HTML, without pagination.
+    Html,
+    /// Experimental. Packages the HTML export into an EPUB 3 e-book.
+    Epub,
+    /// Experimental. Flattens the document's structure into CommonMark.
+    Md,
+    /// Experimental. Flattens the document into plain text.
+    Txt,
 }
 
 impl Display for OutputFormat {
@@ -199,3 +345,62 @@ impl Display for OutputFormat {
             .fmt(f)
     }
 }
+
+/// An action that an encrypted PDF can forbid.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PdfAction {
+    /// Printing the document.
+    Print,
+    /// Modifying the document.
+    Modify,
+    /// Copying text and graphics out of the document.
+    Copy,
+    /// Adding annotations and filling in forms.
+    Annotate,
+}
+
+/// A cipher that a PDF can be encrypted with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PdfCipher {
+    /// AES with a 128-bit key, for readers of PDF 1.6 and later.
+    #[value(name = "aes-128")]
+    Aes128,
+    /// AES with a 256-bit key, for readers of PDF 2.0.
+    #[value(name = "aes-256")]
+    Aes256,
+}
+
+impl Display for PdfCipher {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
+
+/// A PDF standard that Typst can enforce conformance with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum PdfStandard {
+    /// PDF 1.7.
+    #[value(name = "1.7")]
+    V17,
+    /// PDF/A-2b.
+    #[value(name = "a-2b")]
+    A2b,
+    /// PDF/A-3b.
+    #[value(name = "a-3b")]
+    A3b,
+    /// PDF/UA-1.
+    #[value(name = "ua-1")]
+    Ua1,
+}
+
+impl Display for PdfStandard {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.to_possible_value()
+            .expect("no values are skipped")
+            .get_name()
+            .fmt(f)
+    }
+}
@@ -1,20 +1,31 @@
 use std::fs;
+use std::io::BufWriter;
 use std::path::{Path, PathBuf};
 
 use chrono::{Datelike, Timelike};
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 use termcolor::{ColorChoice, StandardStream};
-use typst::diag::{bail, At, Severity, SourceDiagnostic, StrResult};
-use typst::eval::Tracer;
-use typst::foundations::Datetime;
+use typst::diag::{
+    bail, At, Severity, SourceDiagnostic, SourceResult, StrResult, Tracepoint,
+};
+use typst::eval::{Dependency, Tracer};
+use typst::foundations::sys::Target;
+use typst::foundations::{Content, Datetime, StyleChain};
 use typst::model::Document;
 use typst::syntax::{FileId, Source, Span};
 use typst::visualize::Color;
 use typst::{World, WorldExt};
-
-use crate::args::{CompileCommand, DiagnosticFormat, OutputFormat};
+use typst_html::EpubOptions;
+use typst_pdf::{PdfEncryption, PdfOptions, PdfPermissions};
+use typst_svg::SvgOptions;
+
+use crate::args::{
+    CompileCommand, DiagnosticFormat, OutputFormat, PdfAction, PdfCipher, PdfStandard,
+};
+use crate::project::apply_project;
 use crate::watch::Status;
 use crate::world::SystemWorld;
 use crate::{color_stream, set_failed};
@@ -31,11 +42,20 @@ impl CompileCommand {
                     OutputFormat::Pdf => "pdf",
                     OutputFormat::Png => "png",
                     OutputFormat::Svg => "svg",
+                    OutputFormat::Html => "html",
+                    OutputFormat::Epub => "epub",
+                    OutputFormat::Md => "md",
+                    OutputFormat::Txt => "txt",
                 },
             )
         })
     }
 
+    /// The export target visible through `sys.target`.
+    pub fn target(&self) -> Target {
+        export_target(self.output_format().unwrap_or(OutputFormat::Pdf))
+    }
+
     /// The format to use for generated output, either specified by the user or inferred from the extension.
     ///
     /// Will return `Err` if the format was not specified and could not be inferred.
@@ -47,6 +67,10 @@ impl CompileCommand {
                 Some(ext) if ext.eq_ignore_ascii_case("pdf") => OutputFormat::Pdf,
                 Some(ext) if ext.eq_ignore_ascii_case("png") => OutputFormat::Png,
                 Some(ext) if ext.eq_ignore_ascii_case("svg") => OutputFormat::Svg,
+                Some(ext) if ext.eq_ignore_ascii_case("html") => OutputFormat::Html,
+                Some(ext) if ext.eq_ignore_ascii_case("epub") => OutputFormat::Epub,
+                Some(ext) if ext.eq_ignore_ascii_case("md") => OutputFormat::Md,
+                Some(ext) if ext.eq_ignore_ascii_case("txt") => OutputFormat::Txt,
                 _ => bail!("could not infer output format for path {}.\nconsider providing the format manually with `--format/-f`", output.display()),
             }
         } else {
@@ -55,9 +79,23 @@ impl CompileCommand {
     }
 }
 
+/// The export target of an output format, visible through `sys.target`.
+pub fn export_target(format: OutputFormat) -> Target {
+    match format {
+        OutputFormat::Pdf => Target::Pdf,
+        OutputFormat::Png => Target::Png,
+        OutputFormat::Svg => Target::Svg,
+        OutputFormat::Html => Target::Html,
+        OutputFormat::Epub => Target::Epub,
+        OutputFormat::Md => Target::Md,
+        OutputFormat::Txt => Target::Txt,
+    }
+}
+
 /// Execute a compilation command.
 pub fn compile(mut command: CompileCommand) -> StrResult<()> {
-    let mut world = SystemWorld::new(&command.common)?;
+    apply_project(&mut command.common)?;
+    let mut world = SystemWorld::new(&command.common, command.target())?;
     compile_once(&mut world, &mut command, false)?;
     Ok(())
 }
@@ -80,6 +118,7 @@ pub fn compile_once(
 
     // Check if main file can be read and opened.
     if let Err(errors) = world.source(world.main()).at(Span::detached()) {
+        world.set_dependencies(&[Dependency::File(world.main())]);
         set_failed();
         tracing::info!("Failed to open and decode main file");
 
@@ -93,14 +132,40 @@ pub fn compile_once(
         return Ok(());
     }
 
+    // The structural exporters additionally need the realized content.
+    let structural = matches!(
+        command.output_format()?,
+        OutputFormat::Html | OutputFormat::Epub | OutputFormat::Md | OutputFormat::Txt
+    );
+
     let mut tracer = Tracer::new();
-    let result = typst::compile(world, &mut tracer);
+    let result = typst::compile(world, &mut tracer).and_then(|document| {
+        let content = structural
+            .then(|| typst::structure(&*world, &mut tracer, &document))
+            .transpose()?;
+        Ok((document, content))
+    });
+    world.set_dependencies(tracer.dependencies());
+    tracing::info!("Layout took {} passes", tracer.passes());
+    let stats = typst::memory::shaping_stats();
+    tracing::info!(
+        "Shaping cache hit rate is {:.1}% ({} hits, {} runs cached)",
+        100.0 * stats.hit_rate(),
+        stats.hits,
+        stats.entries,
+    );
+    for (c, family) in &tracer.coverage().fallbacks {
+        tracing::info!("Character {c:?} (U+{:04X}) fell back to {family}", *c as u32);
+    }
     let warnings = tracer.warnings();
 
+    // Export the PDF / PNG.
+    let result = result.and_then(|(document, content)| {
+        export(world, &document, content.as_ref(), command, watching)
+    });
+
     match result {
-        // Export the PDF / PNG.
-        Ok(document) => {
-            export(world, &document, command, watching)?;
+        Ok(()) => {
             let duration = start.elapsed();
 
             tracing::info!("Compilation succeeded in {duration:?}");
@@ -143,20 +208,43 @@ pub fn compile_once(
 }
 
 /// Export into the target format.
+///
+/// The `content` is the realized content of the main file, which is only
+/// available for the structural formats.
 fn export(
     world: &mut SystemWorld,
     document: &Document,
+    content: Option<&Content>,
     command: &CompileCommand,
     watching: bool,
-) -> StrResult<()> {
-    match command.output_format()? {
+) -> SourceResult<()> {
+    let structure = || {
+        content
+            .ok_or("document structure is not available")
+            .at(Span::detached())
+    };
+    match command.output_format().at(Span::detached())? {
         OutputFormat::Png => {
             export_image(world, document, command, watching, ImageExportFormat::Png)
+                .at(Span::detached())
         }
         OutputFormat::Svg => {
             export_image(world, document, command, watching, ImageExportFormat::Svg)
+                .at(Span::detached())
         }
         OutputFormat::Pdf => export_pdf(document, command, world),
+        OutputFormat::Html => {
+            export_html(structure()?, command, world).at(Span::detached())
+        }
+        OutputFormat::Epub => {
+            export_epub(document, structure()?, command, world).at(Span::detached())
+        }
+        OutputFormat::Md => {
+            export_text(structure()?, command, world, typst_html::markdown)
+                .at(Span::detached())
+        }
+        OutputFormat::Txt => export_text(structure()?, command, world, typst_html::text)
+            .at(Span::detached()),
     }
 }
 
@@ -165,25 +253,174 @@ fn export_pdf(
     document: &Document,
     command: &CompileCommand,
     world: &SystemWorld,
-) -> StrResult<()> {
+) -> SourceResult<()> {
+    selected_pages(document, command).at(Span::detached())?;
     let ident = world.input().to_string_lossy();
-    let buffer = typst_pdf::pdf(document, Some(&ident), now());
+    let standard = match command.pdf_standard {
+        PdfStandard::V17 => typst_pdf::PdfStandard::V17,
+        PdfStandard::A2b => typst_pdf::PdfStandard::A2b,
+        PdfStandard::A3b => typst_pdf::PdfStandard::A3b,
+        PdfStandard::Ua1 => typst_pdf::PdfStandard::Ua1,
+    };
+    let timestamp = creation_timestamp(command).at(Span::detached())?;
+    let options = PdfOptions {
+        ident: Some(&ident),
+        timestamp,
+        standard,
+        page_ranges: command.pages.clone(),
+        encryption: pdf_encryption(command).at(Span::detached())?,
+        reproducible_encryption: false,
+    };
+
+    // Write the pages to the file as they are produced, so that they don't
+    // have to be kept in memory all at once.
     let output = command.output();
-    fs::write(output, buffer)
-        .map_err(|err| eco_format!("failed to write PDF file ({err})"))?;
+    let file = fs::File::create(&output)
+        .map_err(|err| eco_format!("failed to create PDF file ({err})"))
+        .at(Span::detached())?;
+    let result = typst_pdf::pdf_stream(document, &options, &mut BufWriter::new(file));
+    if result.is_err() {
+        // Don't leave a truncated file behind.
+        fs::remove_file(&output).ok();
+    }
+    result
+}
+
+/// Determine the indices of the pages that are selected for export.
+///
+/// Fails if `--pages` doesn't select any page of the document.
+fn selected_pages(
+    document: &Document,
+    command: &CompileCommand,
+) -> StrResult<Vec<usize>> {
+    let Some(ranges) = &command.pages else {
+        return Ok((0..document.pages.len()).collect());
+    };
+
+    let selected: Vec<_> = (0..document.pages.len())
+        .filter(|&i| ranges.includes_page_index(i))
+        .collect();
+    if selected.is_empty() {
+        bail!(
+            "`--pages` does not select any of the document's {} pages",
+            document.pages.len()
+        );
+    }
+
+    Ok(selected)
+}
+
+/// Determine how to encrypt the PDF, if at all.
+fn pdf_encryption(command: &CompileCommand) -> StrResult<Option<PdfEncryption>> {
+    if command.pdf_password.is_none() && command.pdf_owner_password.is_none() {
+        if !command.pdf_deny.is_empty() {
+            bail!("`--pdf-deny` requires `--pdf-password` or `--pdf-owner-password`");
+        }
+        return Ok(None);
+    }
+
+    if matches!(command.pdf_standard, PdfStandard::A2b | PdfStandard::A3b) {
+        bail!("PDF/A does not allow encryption");
+    }
+
+    let allow = |action| !command.pdf_deny.contains(&action);
+    Ok(Some(PdfEncryption {
+        user_password: command.pdf_password.clone().unwrap_or_default(),
+        owner_password: command.pdf_owner_password.clone().unwrap_or_default(),
+        permissions: PdfPermissions {
+            print: allow(PdfAction::Print),
+            modify: allow(PdfAction::Modify),
+            copy: allow(PdfAction::Copy),
+            annotate: allow(PdfAction::Annotate),
+        },
+        cipher: match command.pdf_cipher {
+            PdfCipher::Aes128 => typst_pdf::PdfCipher::Aes128,
+            PdfCipher::Aes256 => typst_pdf::PdfCipher::Aes256,
+        },
+    }))
+}
+
+/// Export to HTML.
+fn export_html(
+    content: &Content,
+    command: &CompileCommand,
+    world: &SystemWorld,
+) -> StrResult<()> {
+    let styles = StyleChain::new(&world.library().styles);
+    let html = typst_html::html(content, styles);
+    fs::write(command.output(), html)
+        .map_err(|err| eco_format!("failed to write HTML file ({err})"))?;
+    Ok(())
+}
+
+/// Export to EPUB.
+fn export_epub(
+    document: &Document,
+    content: &Content,
+    command: &CompileCommand,
+    world: &SystemWorld,
+) -> StrResult<()> {
+    let styles = StyleChain::new(&world.library().styles);
+    let cover = command
+        .epub_cover
+        .as_ref()
+        .map(|path| {
+            fs::read(path).map_err(|err| {
+                eco_format!("failed to read cover image {} ({err})", path.display())
+            })
+        })
+        .transpose()?;
+
+    let ident = world.input().to_string_lossy();
+    let options = EpubOptions {
+        ident: Some(&ident),
+        timestamp: creation_timestamp(command)?,
+        cover: cover.as_deref(),
+    };
+
+    let buffer = typst_html::epub(document, content, styles, &options)?;
+    fs::write(command.output(), buffer)
+        .map_err(|err| eco_format!("failed to write EPUB file ({err})"))?;
     Ok(())
 }
 
-/// Get the current date and time in UTC.
-fn now() -> Option<Datetime> {
-    let now = chrono::Local::now().naive_utc();
+/// Export to Markdown or plain text.
+fn export_text(
+    content: &Content,
+    command: &CompileCommand,
+    world: &SystemWorld,
+    export: fn(&Content, StyleChain) -> String,
+) -> StrResult<()> {
+    let styles = StyleChain::new(&world.library().styles);
+    let text = export(content, styles);
+    fs::write(command.output(), text)
+        .map_err(|err| eco_format!("failed to write text file ({err})"))?;
+    Ok(())
+}
+
+/// Determine the creation date of the document in UTC.
+///
+/// This is the timestamp given through `--creation-timestamp` or the
+/// `SOURCE_DATE_EPOCH` environment variable if any, and the current time
+/// otherwise.
+fn creation_timestamp(command: &CompileCommand) -> StrResult<Option<Datetime>> {
+    let time = match command.creation_timestamp {
+        Some(secs) => chrono::NaiveDateTime::from_timestamp_opt(secs, 0)
+            .ok_or("creation timestamp is out of range")?,
+        None => chrono::Local::now().naive_utc(),
+    };
+    Ok(convert_datetime(time))
+}
+
+/// Convert a chrono datetime into a Typst datetime.
+fn convert_datetime(time: chrono::NaiveDateTime) -> Option<Datetime> {
     Datetime::from_ymd_hms(
-        now.year(),
-        now.month().try_into().ok()?,
-        now.day().try_into().ok()?,
-        now.hour().try_into().ok()?,
-        now.minute().try_into().ok()?,
-        now.second().try_into().ok()?,
+        time.year(),
+        time.month().try_into().ok()?,
+        time.day().try_into().ok()?,
+        time.hour().try_into().ok()?,
+        time.minute().try_into().ok()?,
+        time.second().try_into().ok()?,
     )
 }
 
@@ -205,7 +442,8 @@ fn export_image(
     let output = command.output();
     let string = output.to_str().unwrap_or_default();
     let numbered = string.contains("{n}");
-    if !numbered && document.pages.len() > 1 {
+    let selected = selected_pages(document, command)?;
+    if !numbered && selected.len() > 1 {
         bail!("cannot export multiple images without `{{n}}` in output path");
     }
 
@@ -213,41 +451,44 @@ fn export_image(
     // first page should be numbered "001" if there are between 100 and
     // 999 pages.
     let width = 1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize;
-    let mut storage;
 
+    // Determine the pages that need to be exported. If we are not watching,
+    // don't use the cache. If the frame is in the cache, skip it. If the
+    // file does not exist, always create it.
     let cache = world.export_cache();
-    for (i, frame) in document.pages.iter().enumerate() {
-        let path = if numbered {
-            storage = string.replace("{n}", &format!("{:0width$}", i + 1));
-            Path::new(&storage)
-        } else {
-            output.as_path()
-        };
-
-        // If we are not watching, don't use the cache.
-        // If the frame is in the cache, skip it.
-        // If the file does not exist, always create it.
-        if watching && cache.is_cached(i, frame) && path.exists() {
-            continue;
+    let pages: Vec<_> = document
+        .pages
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| selected.binary_search(&i).is_ok())
+        .map(|(i, frame)| {
+            let path = if numbered {
+                PathBuf::from(string.replace("{n}", &format!("{:0width$}", i + 1)))
+            } else {
+                output.clone()
+            };
+            (i, frame, path)
+        })
+        .filter(|(i, frame, path)| {
+            !(watching && cache.is_cached(*i, frame) && path.exists())
+        })
+        .collect();
+
+    // The pages are independent, so we can render them in parallel.
+    pages.into_par_iter().try_for_each(|(_, frame, path)| match fmt {
+        ImageExportFormat::Png => {
+            let pixmap = typst_render::render(frame, command.ppi / 72.0, Color::WHITE);
+            pixmap
+                .save_png(path)
+                .map_err(|err| eco_format!("failed to write PNG file ({err})"))
         }
-
-        match fmt {
-            ImageExportFormat::Png => {
-                let pixmap =
-                    typst_render::render(frame, command.ppi / 72.0, Color::WHITE);
-                pixmap
-                    .save_png(path)
-                    .map_err(|err| eco_format!("failed to write PNG file ({err})"))?;
-            }
-            ImageExportFormat::Svg => {
-                let svg = typst_svg::svg(frame);
-                fs::write(path, svg.as_bytes())
-                    .map_err(|err| eco_format!("failed to write SVG file ({err})"))?;
-            }
+        ImageExportFormat::Svg => {
+            let options = SvgOptions { text_layer: !command.no_svg_text_layer };
+            let svg = typst_svg::svg(frame, &options);
+            fs::write(path, svg.as_bytes())
+                .map_err(|err| eco_format!("failed to write SVG file ({err})"))
         }
-    }
-
-    Ok(())
+    })
 }
 
 /// Opens the given file using:
@@ -273,6 +514,10 @@ pub fn print_diagnostics(
     let mut w = match diagnostic_format {
         DiagnosticFormat::Human => color_stream(),
         DiagnosticFormat::Short => StandardStream::stderr(ColorChoice::Never),
+        DiagnosticFormat::Json => {
+            print_json_diagnostics(world, warnings.iter().chain(errors));
+            return Ok(());
+        }
     };
 
     let mut config = term::Config { tab_width: 2, ..Default::default() };
@@ -286,20 +531,38 @@ pub fn print_diagnostics(
             Severity::Warning => Diagnostic::warning(),
         }
         .with_message(diagnostic.message.clone())
+        .with_code(diagnostic.code.unwrap_or_default())
         .with_notes(
             diagnostic
                 .hints
                 .iter()
                 .map(|e| (eco_format!("hint: {e}")).into())
+                .chain(diagnostic.suggestions.iter().map(|suggestion| {
+                    eco_format!(
+                        "help: {} (replace with `{}`)",
+                        suggestion.message,
+                        suggestion.replacement
+                    )
+                    .into()
+                }))
                 .collect(),
         )
-        .with_labels(label(world, diagnostic.span).into_iter().collect());
+        .with_labels(
+            label(world, diagnostic.span)
+                .into_iter()
+                .chain(diagnostic.labels.iter().filter_map(|spanned| {
+                    let label = secondary_label(world, spanned.span)?;
+                    Some(label.with_message(spanned.v.clone()))
+                }))
+                .collect(),
+        );
 
         term::emit(&mut w, &config, world, &diag)?;
 
         // Stacktrace-like helper diagnostics.
         for point in &diagnostic.trace {
-            let message = point.v.to_string();
+            let (tracepoint, count) = &point.v;
+            let message = trace_message(tracepoint, *count);
             let help = Diagnostic::help()
                 .with_message(message)
                 .with_labels(label(world, point.span).into_iter().collect());
@@ -311,11 +574,104 @@ pub fn print_diagnostics(
     Ok(())
 }
 
+/// The message for a tracepoint that occurred `count` times in a row.
+pub fn trace_message(point: &Tracepoint, count: usize) -> EcoString {
+    if count > 1 {
+        eco_format!("{point} (repeated {count} times)")
+    } else {
+        eco_format!("{point}")
+    }
+}
+
 /// Create a label for a span.
 fn label(world: &SystemWorld, span: Span) -> Option<Label<FileId>> {
     Some(Label::primary(span.id()?, world.range(span)?))
 }
 
+/// Create a secondary label for a span.
+fn secondary_label(world: &SystemWorld, span: Span) -> Option<Label<FileId>> {
+    Some(Label::secondary(span.id()?, world.range(span)?))
+}
+
+/// Print diagnostics as JSON, one object per line.
+fn print_json_diagnostics<'a>(
+    world: &SystemWorld,
+    diagnostics: impl Iterator<Item = &'a SourceDiagnostic>,
+) {
+    for diagnostic in diagnostics {
+        let severity = match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        let labels: Vec<_> = diagnostic
+            .labels
+            .iter()
+            .map(|label| {
+                serde_json::json!({
+                    "message": label.v,
+                    "span": json_span(world, label.span),
+                })
+            })
+            .collect();
+
+        let suggestions: Vec<_> = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                serde_json::json!({
+                    "message": suggestion.message,
+                    "replacement": suggestion.replacement,
+                    "span": json_span(world, suggestion.span),
+                })
+            })
+            .collect();
+
+        let trace: Vec<_> = diagnostic
+            .trace
+            .iter()
+            .map(|point| {
+                let (tracepoint, count) = &point.v;
+                serde_json::json!({
+                    "message": trace_message(tracepoint, *count),
+                    "span": json_span(world, point.span),
+                })
+            })
+            .collect();
+
+        let json = serde_json::json!({
+            "severity": severity,
+            "code": diagnostic.code,
+            "message": diagnostic.message,
+            "span": json_span(world, diagnostic.span),
+            "hints": diagnostic.hints,
+            "labels": labels,
+            "suggestions": suggestions,
+            "trace": trace,
+        });
+
+        eprintln!("{json}");
+    }
+}
+
+/// Resolve a span into a JSON object with its file, byte range, and
+/// zero-based line and column.
+fn json_span(world: &SystemWorld, span: Span) -> serde_json::Value {
+    let Some((id, range)) = span.id().zip(world.range(span)) else {
+        return serde_json::Value::Null;
+    };
+
+    let source = world.lookup(id);
+    let name = codespan_reporting::files::Files::name(world, id).unwrap_or_default();
+    serde_json::json!({
+        "file": name,
+        "start": range.start,
+        "end": range.end,
+        "line": source.byte_to_line(range.start),
+        "column": source.byte_to_column(range.start),
+    })
+}
+
 impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
     type FileId = FileId;
     type Name = String;
@@ -379,3 +735,28 @@ impl<'a> codespan_reporting::files::Files<'a> for SystemWorld {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use typst::layout::{Frame, Size};
+
+    use super::*;
+
+    #[test]
+    fn test_selected_pages() {
+        let pages = vec![Frame::soft(Size::zero()); 3];
+        let document = Document { pages, ..Document::default() };
+        let select = |pages: &str| {
+            let args = ["compile", "main.typ", "--pages", pages];
+            selected_pages(&document, &CompileCommand::try_parse_from(args).unwrap())
+        };
+
+        assert_eq!(select("2-").unwrap(), [1, 2]);
+        assert_eq!(select("1,3").unwrap(), [0, 2]);
+        assert_eq!(
+            select("4-").unwrap_err(),
+            "`--pages` does not select any of the document's 3 pages"
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use std::cell::OnceCell;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use fontdb::{Database, Source};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use typst::diag::StrResult;
 use typst::text::{Font, FontBook, FontInfo, FontVariant};
 
@@ -46,6 +47,12 @@ pub struct FontSlot {
 }
 
 impl FontSlot {
+    /// The path at which the font can be found on the system. Empty for
+    /// embedded fonts.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
     /// Get the font for this slot.
     pub fn get(&self) -> Option<Font> {
         self.font
@@ -75,7 +82,19 @@ impl FontSearcher {
         // System fonts have second priority.
         db.load_system_fonts();
 
-        for face in db.faces() {
+        // Reading the metadata of a font requires going through its whole
+        // character map, which takes a while for large CJK fonts. We thus do
+        // it in parallel, but keep the order of the faces for priority.
+        let faces: Vec<_> = db.faces().collect();
+        let infos: Vec<_> = faces
+            .par_iter()
+            .map(|face| {
+                db.with_face_data(face.id, FontInfo::new)
+                    .expect("database must contain this font")
+            })
+            .collect();
+
+        for (face, info) in faces.into_iter().zip(infos) {
             let path = match &face.source {
                 Source::File(path) | Source::SharedFile(path, _) => path,
                 // We never add binary sources to the database, so there
@@ -83,10 +102,6 @@ impl FontSearcher {
                 Source::Binary(_) => continue,
             };
 
-            let info = db
-                .with_face_data(face.id, FontInfo::new)
-                .expect("database must contain this font");
-
             if let Some(info) = info {
                 self.book.push(info);
                 self.fonts.push(FontSlot {
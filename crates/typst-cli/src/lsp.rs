@@ -0,0 +1,1015 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use ecow::eco_format;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument,
+    DidOpenTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, DocumentSymbolRequest, GotoDefinition, HoverRequest, RegisterCapability,
+    Rename, Request as _, SemanticTokensFullRequest,
+};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams,
+    CompletionResponse, CompletionTextEdit, CompletionTriggerKind, Diagnostic,
+    DiagnosticRelatedInformation, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidChangeWatchedFilesParams, DidChangeWatchedFilesRegistrationOptions,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DocumentSymbol,
+    DocumentSymbolParams, DocumentSymbolResponse, FileChangeType, FileSystemWatcher,
+    GlobPattern, GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, InitializeParams, InsertTextFormat, Location,
+    MarkupContent, MarkupKind, NumberOrString, OneOf, Position, PublishDiagnosticsParams,
+    Registration, RegistrationParams, RenameParams, SemanticToken, SemanticTokenType,
+    SemanticTokens, SemanticTokensFullOptions, SemanticTokensLegend,
+    SemanticTokensOptions, SemanticTokensParams, SemanticTokensResult,
+    SemanticTokensServerCapabilities, ServerCapabilities, SymbolKind,
+    TextDocumentPositionParams, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, Url, WorkspaceEdit,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use typst::diag::{Severity, SourceDiagnostic, StrResult};
+use typst::eval::Tracer;
+use typst::model::Document;
+use typst::syntax::{is_newline, FileId, Source, Span, Tag};
+use typst::World;
+use typst_ide::{CompletionKind, Tooltip};
+
+use crate::args::LspCommand;
+use crate::compile::{export_target, trace_message};
+use crate::world::SystemWorld;
+
+/// Execute the language server on standard input and output.
+pub fn lsp(command: &LspCommand) -> StrResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = serde_json::to_value(capabilities()).unwrap();
+    let params = connection
+        .initialize(capabilities)
+        .map_err(|err| eco_format!("failed to initialize language server ({err})"))?;
+    let params: InitializeParams = serde_json::from_value(params)
+        .map_err(|err| eco_format!("invalid initialization parameters ({err})"))?;
+
+    let root = command
+        .root
+        .clone()
+        .or_else(|| workspace_root(&params))
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let target = export_target(command.target);
+    let world =
+        SystemWorld::with_root(&root, &command.font_paths, &command.inputs, target)?;
+    let main = command
+        .main
+        .as_ref()
+        .and_then(|path| world.file_id(&world.root().join(path)));
+
+    let mut server = Server::new(connection, world, main);
+    server.watch_files(&params)?;
+    server.run()?;
+    drop(server);
+
+    io_threads
+        .join()
+        .map_err(|err| eco_format!("failed to shut down language server ({err})"))
+}
+
+/// What the language server can do.
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(
+            TextDocumentSyncKind::FULL,
+        )),
+        definition_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        rename_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(
+                ["#", ".", "(", ",", ":", "\"", "/", "@", "<"]
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+        semantic_tokens_provider: Some(
+            SemanticTokensServerCapabilities::SemanticTokensOptions(
+                SemanticTokensOptions {
+                    legend: SemanticTokensLegend {
+                        token_types: token_legend(),
+                        token_modifiers: vec![],
+                    },
+                    full: Some(SemanticTokensFullOptions::Bool(true)),
+                    ..Default::default()
+                },
+            ),
+        ),
+        ..Default::default()
+    }
+}
+
+/// The semantic token types of the highlighting tags.
+///
+/// The legend lists the distinct types in the order they first appear in this
+/// table. Tags that share a type share its index.
+const TOKEN_TYPES: &[(Tag, &str)] = &[
+    (Tag::Comment, "comment"),
+    (Tag::Punctuation, "punctuation"),
+    (Tag::Escape, "escape"),
+    (Tag::Strong, "strong"),
+    (Tag::Emph, "emph"),
+    (Tag::Link, "link"),
+    (Tag::Raw, "raw"),
+    (Tag::Label, "label"),
+    (Tag::Ref, "ref"),
+    (Tag::Heading, "heading"),
+    (Tag::ListMarker, "marker"),
+    (Tag::ListTerm, "term"),
+    (Tag::MathDelimiter, "delim"),
+    (Tag::MathOperator, "operator"),
+    (Tag::Keyword, "keyword"),
+    (Tag::Operator, "operator"),
+    (Tag::Number, "number"),
+    (Tag::String, "string"),
+    (Tag::Function, "function"),
+    (Tag::Interpolated, "variable"),
+    (Tag::Error, "error"),
+];
+
+/// The distinct token type names in the order of the legend.
+fn token_names() -> Vec<&'static str> {
+    let mut names = vec![];
+    for &(_, name) in TOKEN_TYPES {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The semantic token types in the order of the legend.
+fn token_legend() -> Vec<SemanticTokenType> {
+    token_names().into_iter().map(SemanticTokenType::new).collect()
+}
+
+/// The index of a highlighting tag's token type in the legend.
+fn token_index(tag: Tag) -> Option<u32> {
+    let &(_, name) = TOKEN_TYPES.iter().find(|&&(t, _)| t == tag)?;
+    token_names().iter().position(|&n| n == name).map(|i| i as u32)
+}
+
+/// The first workspace folder of the editor.
+#[allow(deprecated)]
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    params
+        .workspace_folders
+        .iter()
+        .flatten()
+        .map(|folder| &folder.uri)
+        .chain(params.root_uri.as_ref())
+        .find_map(|uri| uri.to_file_path().ok())
+}
+
+/// The state of the language server.
+struct Server {
+    /// The connection to the editor.
+    connection: Connection,
+    /// The world with the editor's unsaved documents as overlays.
+    world: SystemWorld,
+    /// The entrypoint of the project, which is compiled whenever any of its
+    /// documents changes.
+    main: Option<FileId>,
+    /// The document from the last successful compilation, which enhances
+    /// tooltips and completions.
+    document: Option<Document>,
+    /// The files for which diagnostics were published last time.
+    published: HashSet<Url>,
+}
+
+impl Server {
+    /// Create a server for the given project.
+    ///
+    /// Without an explicit entrypoint, the first document that the editor
+    /// opens becomes the entrypoint.
+    fn new(connection: Connection, world: SystemWorld, main: Option<FileId>) -> Self {
+        Self {
+            connection,
+            world,
+            main,
+            document: None,
+            published: HashSet::new(),
+        }
+    }
+
+    /// Handle messages until the editor shuts the server down.
+    fn run(&mut self) -> StrResult<()> {
+        let receiver = self.connection.receiver.clone();
+        for message in receiver {
+            match message {
+                Message::Request(request) => {
+                    if self
+                        .connection
+                        .handle_shutdown(&request)
+                        .map_err(|err| eco_format!("failed to shut down ({err})"))?
+                    {
+                        return Ok(());
+                    }
+
+                    let response = self.request(request);
+                    self.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => self.notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Ask the editor to report when files in the workspace are created or
+    /// removed, if it supports that.
+    fn watch_files(&self, params: &InitializeParams) -> StrResult<()> {
+        let supported = params
+            .capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+            .and_then(|capability| capability.dynamic_registration)
+            .unwrap_or(false);
+        if !supported {
+            return Ok(());
+        }
+
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String("**/*".into()),
+                kind: None,
+            }],
+        };
+
+        let params = RegistrationParams {
+            registrations: vec![Registration {
+                id: "watch-files".into(),
+                method: DidChangeWatchedFiles::METHOD.into(),
+                register_options: serde_json::to_value(options).ok(),
+            }],
+        };
+
+        let request = Request::new(
+            "watch-files".to_string().into(),
+            RegisterCapability::METHOD.into(),
+            params,
+        );
+        self.send(Message::Request(request))
+    }
+
+    /// Send a message to the editor.
+    fn send(&self, message: Message) -> StrResult<()> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|_| "language client disconnected".into())
+    }
+
+    /// Answer a request.
+    fn request(&mut self, request: Request) -> Response {
+        let Request { id, method, params } = request;
+        let result = match method.as_str() {
+            GotoDefinition::METHOD => {
+                self.call::<GotoDefinition>(params, Self::definition)
+            }
+            HoverRequest::METHOD => self.call::<HoverRequest>(params, Self::hover),
+            DocumentSymbolRequest::METHOD => {
+                self.call::<DocumentSymbolRequest>(params, Self::symbols)
+            }
+            Rename::METHOD => self.call::<Rename>(params, Self::rename),
+            Completion::METHOD => self.call::<Completion>(params, Self::complete),
+            SemanticTokensFullRequest::METHOD => {
+                self.call::<SemanticTokensFullRequest>(params, Self::tokens)
+            }
+            _ => {
+                let message = format!("unsupported request: {method}");
+                return Response::new_err(id, ErrorCode::MethodNotFound as i32, message);
+            }
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(message) => {
+                Response::new_err(id, ErrorCode::InvalidParams as i32, message)
+            }
+        }
+    }
+
+    /// Parse the parameters of a request and answer it.
+    fn call<R>(
+        &mut self,
+        params: serde_json::Value,
+        f: fn(&mut Self, R::Params) -> R::Result,
+    ) -> Result<serde_json::Value, String>
+    where
+        R: lsp_types::request::Request,
+        R::Params: DeserializeOwned,
+        R::Result: Serialize,
+    {
+        let params = serde_json::from_value(params).map_err(|err| err.to_string())?;
+        serde_json::to_value(f(self, params)).map_err(|err| err.to_string())
+    }
+
+    /// Handle a notification.
+    fn notification(&mut self, notification: Notification) -> StrResult<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = parse(notification.params)?;
+                let document = params.text_document;
+                self.change(&document.uri, document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = parse(notification.params)?;
+                // We only support full synchronization, so the last change
+                // contains the whole text.
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.change(&params.text_document.uri, change.text),
+                    None => Ok(()),
+                }
+            }
+            DidChangeWatchedFiles::METHOD => {
+                let params: DidChangeWatchedFilesParams = parse(notification.params)?;
+                // Path completions list the files in the workspace.
+                if params
+                    .changes
+                    .iter()
+                    .any(|change| change.typ != FileChangeType::CHANGED)
+                {
+                    self.world.invalidate_files();
+                }
+                Ok(())
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = parse(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Some(id) = self.file_id(&uri) {
+                    self.world.remove_overlay(id);
+                }
+                self.published.remove(&uri);
+                self.publish(uri, vec![])
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Update a document and check the project again.
+    fn change(&mut self, uri: &Url, text: String) -> StrResult<()> {
+        let Some(id) = self.file_id(uri) else { return Ok(()) };
+        self.world.set_overlay(id, text);
+        let main = *self.main.get_or_insert(id);
+        self.check(main)
+    }
+
+    /// Compile the entrypoint and publish the diagnostics for each of the
+    /// files they occur in.
+    fn check(&mut self, main: FileId) -> StrResult<()> {
+        self.world.reset();
+        self.world.set_main(main);
+
+        let mut tracer = Tracer::new();
+        let result = typst::compile(&self.world, &mut tracer);
+        typst::memory::evict(10);
+
+        let mut diagnostics: HashMap<Url, Vec<Diagnostic>> = HashMap::new();
+        if let Some(uri) = self.uri(main) {
+            diagnostics.insert(uri, vec![]);
+        }
+
+        let errors = match result {
+            Ok(document) => {
+                self.document = Some(document);
+                Default::default()
+            }
+            Err(errors) => errors,
+        };
+
+        for diagnostic in errors.iter().chain(&tracer.warnings()) {
+            if let Some((uri, diagnostic)) = self.diagnostic(diagnostic) {
+                diagnostics.entry(uri).or_default().push(diagnostic);
+            }
+        }
+
+        // Clear the diagnostics of files that don't have any anymore.
+        let stale: Vec<_> = self
+            .published
+            .iter()
+            .filter(|uri| !diagnostics.contains_key(uri))
+            .cloned()
+            .collect();
+        for uri in stale {
+            self.published.remove(&uri);
+            self.publish(uri, vec![])?;
+        }
+
+        for (uri, list) in diagnostics {
+            if !list.is_empty() {
+                self.published.insert(uri.clone());
+            }
+            self.publish(uri, list)?;
+        }
+
+        Ok(())
+    }
+
+    /// Publish the diagnostics for a file.
+    fn publish(&self, uri: Url, diagnostics: Vec<Diagnostic>) -> StrResult<()> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        let notification = Notification::new(PublishDiagnostics::METHOD.into(), params);
+        self.send(Message::Notification(notification))
+    }
+
+    /// Convert a diagnostic into the editor's format.
+    fn diagnostic(&self, diagnostic: &SourceDiagnostic) -> Option<(Url, Diagnostic)> {
+        let (uri, range) = self.location(diagnostic.span)?;
+
+        let mut message = diagnostic.message.to_string();
+        for hint in &diagnostic.hints {
+            message.push_str("\nhint: ");
+            message.push_str(hint);
+        }
+
+        let related = diagnostic
+            .labels
+            .iter()
+            .map(|label| (label.span, label.v.clone()))
+            .chain(
+                diagnostic
+                    .trace
+                    .iter()
+                    .map(|point| (point.span, trace_message(&point.v.0, point.v.1))),
+            )
+            .filter_map(|(span, message)| {
+                let (uri, range) = self.location(span)?;
+                Some(DiagnosticRelatedInformation {
+                    location: Location::new(uri, range),
+                    message: message.into(),
+                })
+            })
+            .collect();
+
+        let severity = match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        };
+
+        Some((
+            uri,
+            Diagnostic {
+                range,
+                severity: Some(severity),
+                code: diagnostic.code.map(|code| NumberOrString::String(code.into())),
+                source: Some("typst".into()),
+                message,
+                related_information: Some(related),
+                ..Default::default()
+            },
+        ))
+    }
+
+    /// Jump to the definition of the identifier under the cursor.
+    fn definition(
+        &mut self,
+        params: GotoDefinitionParams,
+    ) -> Option<GotoDefinitionResponse> {
+        let (source, cursor) = self.cursor(&params.text_document_position_params)?;
+        let definition = typst_ide::definition(&self.world, &source, cursor)?;
+        let (uri, range) = self.location(definition.span)?;
+        Some(GotoDefinitionResponse::Scalar(Location::new(uri, range)))
+    }
+
+    /// Describe the item under the cursor.
+    fn hover(&mut self, params: HoverParams) -> Option<Hover> {
+        let (source, cursor) = self.cursor(&params.text_document_position_params)?;
+        let tooltip =
+            typst_ide::tooltip(&self.world, self.document.as_ref(), &source, cursor)?;
+        let value = match tooltip {
+            Tooltip::Text(text) => text.into(),
+            Tooltip::Code(code) => format!("```typst\n{code}\n```"),
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    /// List the headings and bindings of a document.
+    fn symbols(
+        &mut self,
+        params: DocumentSymbolParams,
+    ) -> Option<DocumentSymbolResponse> {
+        let source = self.source(&params.text_document.uri)?;
+        let symbols = typst_ide::document_symbols(&source);
+        Some(DocumentSymbolResponse::Nested(
+            symbols
+                .into_iter()
+                .map(|symbol| document_symbol(&source, symbol))
+                .collect(),
+        ))
+    }
+
+    /// Rename the binding under the cursor and all its references.
+    fn rename(&mut self, params: RenameParams) -> Option<WorkspaceEdit> {
+        if !typst::syntax::is_ident(&params.new_name) {
+            return None;
+        }
+
+        let (source, cursor) = self.cursor(&params.text_document_position)?;
+        let ranges = typst_ide::references(&source, cursor);
+        if ranges.is_empty() {
+            return None;
+        }
+
+        let edits = ranges
+            .into_iter()
+            .map(|range| {
+                TextEdit::new(lsp_range(&source, range), params.new_name.clone())
+            })
+            .collect();
+
+        let uri = params.text_document_position.text_document.uri;
+        Some(WorkspaceEdit::new(HashMap::from([(uri, edits)])))
+    }
+
+    /// Complete the input at the cursor.
+    fn complete(&mut self, params: CompletionParams) -> Option<CompletionResponse> {
+        let (source, cursor) = self.cursor(&params.text_document_position)?;
+        let explicit = params.context.map_or(true, |context| {
+            context.trigger_kind == CompletionTriggerKind::INVOKED
+        });
+
+        let (from, completions) = typst_ide::autocomplete(
+            &self.world,
+            self.document.as_ref(),
+            &source,
+            cursor,
+            explicit,
+        )?;
+
+        let range = lsp_range(&source, from..cursor);
+        let items = completions
+            .into_iter()
+            .map(|completion| {
+                let apply = completion.apply.as_ref().unwrap_or(&completion.label);
+                let edit = TextEdit::new(range, snippet(apply));
+                CompletionItem {
+                    kind: Some(completion_kind(&completion.kind)),
+                    detail: completion.detail.map(Into::into),
+                    insert_text_format: Some(InsertTextFormat::SNIPPET),
+                    text_edit: Some(CompletionTextEdit::Edit(edit)),
+                    label: completion.label.into(),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Some(CompletionResponse::Array(items))
+    }
+
+    /// Classify the syntax of a document for highlighting.
+    fn tokens(&mut self, params: SemanticTokensParams) -> Option<SemanticTokensResult> {
+        let source = self.source(&params.text_document.uri)?;
+        let mut data = vec![];
+        let mut prev = Position::new(0, 0);
+        for (span, tag) in typst_ide::highlight(source.root()) {
+            let Some(range) = source.range(span) else { continue };
+            let Some(token_type) = token_index(tag) else { continue };
+
+            // Tokens may not span multiple lines, so multi-line tokens are split
+            // into one piece per line, without the line breaks.
+            let mut start = range.start;
+            while start < range.end {
+                let Some(line) = source.byte_to_line(start) else { break };
+                let line_end = source.line_to_range(line).map_or(range.end, |r| r.end);
+                let end = range.end.min(line_end);
+                let from = position(&source, start);
+                let piece = source.text()[start..end].trim_end_matches(is_newline);
+                let length = piece.encode_utf16().count() as u32;
+
+                if length > 0 {
+                    data.push(SemanticToken {
+                        delta_line: from.line - prev.line,
+                        delta_start: if from.line == prev.line {
+                            from.character - prev.character
+                        } else {
+                            from.character
+                        },
+                        length,
+                        token_type,
+                        token_modifiers_bitset: 0,
+                    });
+                    prev = from;
+                }
+
+                start = end;
+            }
+        }
+
+        Some(SemanticTokensResult::Tokens(SemanticTokens { result_id: None, data }))
+    }
+
+    /// The source and cursor offset for a position in a document.
+    fn cursor(&self, position: &TextDocumentPositionParams) -> Option<(Source, usize)> {
+        let source = self.source(&position.text_document.uri)?;
+        let cursor = offset(&source, position.position)?;
+        Some((source, cursor))
+    }
+
+    /// The source file of a document.
+    fn source(&self, uri: &Url) -> Option<Source> {
+        self.world.source(self.file_id(uri)?).ok()
+    }
+
+    /// The file id of a document.
+    fn file_id(&self, uri: &Url) -> Option<FileId> {
+        self.world.file_id(&uri.to_file_path().ok()?)
+    }
+
+    /// The document for a file id.
+    fn uri(&self, id: FileId) -> Option<Url> {
+        Url::from_file_path(self.world.path(id).ok()?).ok()
+    }
+
+    /// The document and range of a span.
+    fn location(&self, span: Span) -> Option<(Url, lsp_types::Range)> {
+        let id = span.id()?;
+        let source = self.world.source(id).ok()?;
+        let range = source.range(span)?;
+        Some((self.uri(id)?, lsp_range(&source, range)))
+    }
+}
+
+/// Parse the parameters of a notification.
+fn parse<T: DeserializeOwned>(params: serde_json::Value) -> StrResult<T> {
+    serde_json::from_value(params)
+        .map_err(|err| eco_format!("invalid notification ({err})"))
+}
+
+/// Convert a symbol into the editor's format.
+fn document_symbol(source: &Source, symbol: typst_ide::Symbol) -> DocumentSymbol {
+    let kind = match symbol.kind {
+        typst_ide::SymbolKind::Heading => SymbolKind::NAMESPACE,
+        typst_ide::SymbolKind::Function => SymbolKind::FUNCTION,
+        typst_ide::SymbolKind::Variable => SymbolKind::VARIABLE,
+    };
+
+    let children = symbol
+        .children
+        .into_iter()
+        .map(|child| document_symbol(source, child))
+        .collect();
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: symbol.name.into(),
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range: lsp_range(source, symbol.range),
+        selection_range: lsp_range(source, symbol.selection),
+        children: Some(children),
+    }
+}
+
+/// The editor's kind for a completion.
+fn completion_kind(kind: &CompletionKind) -> CompletionItemKind {
+    match kind {
+        CompletionKind::Syntax => CompletionItemKind::SNIPPET,
+        CompletionKind::Func => CompletionItemKind::FUNCTION,
+        CompletionKind::Type => CompletionItemKind::CLASS,
+        CompletionKind::Param => CompletionItemKind::VARIABLE,
+        CompletionKind::Constant => CompletionItemKind::CONSTANT,
+        CompletionKind::Symbol(_) => CompletionItemKind::TEXT,
+    }
+}
+
+/// Convert Typst's snippet syntax (`${name}` and `${}`) into numbered
+/// placeholders.
+///
+/// Everything else is escaped, so that a `$` in math or a closing brace in
+/// code is inserted literally.
+fn snippet(apply: &str) -> String {
+    let mut out = String::new();
+    let mut rest = apply;
+    let mut count = 0;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}').map(|end| start + end) else { break };
+        count += 1;
+        escape_snippet(&mut out, &rest[..start]);
+        match &rest[start + 2..end] {
+            "" => out.push_str(&format!("${count}")),
+            name => {
+                out.push_str(&format!("${{{count}:"));
+                escape_snippet(&mut out, name);
+                out.push('}');
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    escape_snippet(&mut out, rest);
+    out
+}
+
+/// Escape the characters that have a meaning in the editor's snippet syntax.
+fn escape_snippet(out: &mut String, text: &str) {
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Convert a byte range into the editor's format, which counts UTF-16 code
+/// units.
+fn lsp_range(source: &Source, range: Range<usize>) -> lsp_types::Range {
+    lsp_types::Range::new(position(source, range.start), position(source, range.end))
+}
+
+/// Convert a byte offset into the editor's format.
+fn position(source: &Source, offset: usize) -> Position {
+    let line = source.byte_to_line(offset).unwrap_or(0);
+    let start = source.line_to_byte(line).unwrap_or(0);
+    let utf16 = |byte| source.byte_to_utf16(byte).unwrap_or(0);
+    let column = utf16(offset).saturating_sub(utf16(start));
+    Position::new(line as u32, column as u32)
+}
+
+/// Convert a position from the editor's format into a byte offset.
+fn offset(source: &Source, position: Position) -> Option<usize> {
+    let start = source.line_to_byte(position.line as usize)?;
+    let utf16 = source.byte_to_utf16(start)? + position.character as usize;
+    source.utf16_to_byte(utf16.min(source.len_utf16()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use lsp_types::{
+        FileEvent, TextDocumentContentChangeEvent, TextDocumentIdentifier,
+        TextDocumentItem, VersionedTextDocumentIdentifier,
+    };
+    use typst::foundations::sys::Target;
+
+    use super::*;
+
+    /// A server for a fresh project directory and the editor's end of its
+    /// connection.
+    struct Editor {
+        server: Server,
+        client: Connection,
+        root: PathBuf,
+    }
+
+    impl Editor {
+        fn new(name: &str) -> Self {
+            Self::with_inputs(name, &[], Target::default())
+        }
+
+        fn with_inputs(name: &str, inputs: &[(String, String)], target: Target) -> Self {
+            let root = std::env::temp_dir()
+                .join(format!("typst-lsp-{name}-{}", std::process::id()));
+            fs::create_dir_all(&root).unwrap();
+            let root = root.canonicalize().unwrap();
+            let world = SystemWorld::with_root(&root, &[], inputs, target).unwrap();
+            let (connection, client) = Connection::memory();
+            Self {
+                server: Server::new(connection, world, None),
+                client,
+                root,
+            }
+        }
+
+        fn uri(&self, path: &str) -> Url {
+            Url::from_file_path(self.root.join(path)).unwrap()
+        }
+
+        fn notify<N: lsp_types::notification::Notification>(
+            &mut self,
+            params: N::Params,
+        ) {
+            let notification = Notification::new(N::METHOD.into(), params);
+            self.server.notification(notification).unwrap();
+        }
+
+        fn open(&mut self, path: &str, text: &str) {
+            let item =
+                TextDocumentItem::new(self.uri(path), "typst".into(), 0, text.into());
+            self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+                text_document: item,
+            });
+        }
+
+        fn edit(&mut self, path: &str, text: &str) {
+            self.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+                text_document: VersionedTextDocumentIdentifier::new(self.uri(path), 1),
+                content_changes: vec![TextDocumentContentChangeEvent {
+                    range: None,
+                    range_length: None,
+                    text: text.into(),
+                }],
+            });
+        }
+
+        /// The diagnostics that were published since the last call, by file.
+        fn published(&self) -> HashMap<Url, Vec<Diagnostic>> {
+            let mut published = HashMap::new();
+            for message in self.client.receiver.try_iter() {
+                let Message::Notification(notification) = message else { continue };
+                assert_eq!(notification.method, PublishDiagnostics::METHOD);
+                let params: PublishDiagnosticsParams =
+                    serde_json::from_value(notification.params).unwrap();
+                published.insert(params.uri, params.diagnostics);
+            }
+            published
+        }
+    }
+
+    impl Drop for Editor {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.root).ok();
+        }
+    }
+
+    #[test]
+    fn test_lsp_did_open_publishes_diagnostics() {
+        let mut editor = Editor::new("open");
+        editor.open("main.typ", "#let x = 1\n#y");
+
+        let published = editor.published();
+        let diagnostics = &published[&editor.uri("main.typ")];
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "unknown variable: y");
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 1));
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("unknown-variable".into()))
+        );
+    }
+
+    #[test]
+    fn test_lsp_did_change_checks_entrypoint() {
+        let mut editor = Editor::new("change");
+        fs::write(editor.root.join("chapter.typ"), "= Chapter").unwrap();
+        editor.open("main.typ", "#include \"chapter.typ\"");
+        assert!(editor.published()[&editor.uri("main.typ")].is_empty());
+
+        // Errors in an included file are reported for that file.
+        editor.open("chapter.typ", "= Chapter\n#missing");
+        let published = editor.published();
+        assert!(published[&editor.uri("main.typ")].is_empty());
+        assert_eq!(published[&editor.uri("chapter.typ")].len(), 1);
+        assert_eq!(editor.server.main, editor.server.file_id(&editor.uri("main.typ")));
+
+        // And cleared once they are fixed.
+        editor.edit("chapter.typ", "= Chapter\nFixed.");
+        assert!(editor.published()[&editor.uri("chapter.typ")].is_empty());
+    }
+
+    #[test]
+    fn test_lsp_sys_inputs() {
+        let inputs = [("edition".to_string(), "teacher".to_string())];
+        let mut editor = Editor::with_inputs("inputs", &inputs, Target::default());
+        editor.open("main.typ", "#assert.eq(sys.inputs, (edition: \"teacher\"))");
+        assert!(editor.published()[&editor.uri("main.typ")].is_empty());
+    }
+
+    #[test]
+    fn test_lsp_sys_target() {
+        let mut editor = Editor::with_inputs("target", &[], Target::Html);
+        editor.open("main.typ", "#assert.eq(sys.target, \"html\")");
+        assert!(editor.published()[&editor.uri("main.typ")].is_empty());
+    }
+
+    #[test]
+    fn test_lsp_completion() {
+        let mut editor = Editor::new("complete");
+        editor.open("main.typ", "#alig");
+        editor.published();
+
+        let params = CompletionParams {
+            text_document_position: TextDocumentPositionParams::new(
+                TextDocumentIdentifier::new(editor.uri("main.typ")),
+                Position::new(0, 5),
+            ),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        };
+
+        let Some(CompletionResponse::Array(items)) = editor.server.complete(params)
+        else {
+            panic!("expected completions");
+        };
+
+        let align = items.iter().find(|item| item.label == "align").unwrap();
+        assert_eq!(align.kind, Some(CompletionItemKind::FUNCTION));
+        let Some(CompletionTextEdit::Edit(edit)) = &align.text_edit else {
+            panic!("expected a text edit");
+        };
+        assert_eq!(
+            edit.range,
+            lsp_types::Range::new(Position::new(0, 1), Position::new(0, 5))
+        );
+        assert_eq!(edit.new_text, "align($1)");
+    }
+
+    #[test]
+    fn test_lsp_watched_files_invalidate_listing() {
+        let mut editor = Editor::new("watch");
+        fs::write(editor.root.join("main.typ"), "").unwrap();
+        let listed = |editor: &Editor| editor.server.world.files().len();
+        assert_eq!(listed(&editor), 1);
+
+        // The listing is cached until the editor reports new files.
+        fs::write(editor.root.join("image.svg"), "").unwrap();
+        assert_eq!(listed(&editor), 1);
+
+        editor.notify::<DidChangeWatchedFiles>(DidChangeWatchedFilesParams {
+            changes: vec![FileEvent::new(
+                editor.uri("image.svg"),
+                FileChangeType::CREATED,
+            )],
+        });
+        assert_eq!(listed(&editor), 2);
+    }
+
+    #[test]
+    fn test_lsp_semantic_tokens() {
+        // Every tag has exactly one token type.
+        for &tag in Tag::LIST {
+            assert_eq!(TOKEN_TYPES.iter().filter(|&&(t, _)| t == tag).count(), 1);
+        }
+
+        // Tags with the same type share its index.
+        let legend = token_legend();
+        assert_eq!(legend.iter().filter(|ty| ty.as_str() == "operator").count(), 1);
+        assert_eq!(token_index(Tag::MathOperator), token_index(Tag::Operator));
+
+        let mut editor = Editor::new("tokens");
+        editor.open("main.typ", "#let x = 1");
+        let params = SemanticTokensParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier::new(editor.uri("main.typ")),
+        };
+
+        let Some(SemanticTokensResult::Tokens(tokens)) = editor.server.tokens(params)
+        else {
+            panic!("expected semantic tokens");
+        };
+
+        let types: Vec<_> = tokens
+            .data
+            .iter()
+            .map(|token| legend[token.token_type as usize].as_str())
+            .collect();
+        assert_eq!(types[..2], ["keyword", "keyword"]);
+        assert!(types.contains(&"number"));
+    }
+
+    #[test]
+    fn test_lsp_semantic_tokens_multi_line() {
+        let mut editor = Editor::new("tokens-lines");
+        editor.open("main.typ", "/* ab\r\ncd */");
+        let params = SemanticTokensParams {
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            text_document: TextDocumentIdentifier::new(editor.uri("main.typ")),
+        };
+
+        let Some(SemanticTokensResult::Tokens(tokens)) = editor.server.tokens(params)
+        else {
+            panic!("expected semantic tokens");
+        };
+
+        // The comment is split per line and the line break is not counted.
+        let pieces: Vec<_> = tokens
+            .data
+            .iter()
+            .map(|token| (token.delta_line, token.delta_start, token.length))
+            .collect();
+        assert_eq!(pieces, [(0, 0, 5), (1, 0, 5)]);
+    }
+
+    #[test]
+    fn test_lsp_snippet_escaping() {
+        assert_eq!(snippet("${}"), "$1");
+        assert_eq!(snippet("box(${body})"), "box(${1:body})");
+        assert_eq!(snippet("$${}$"), "\\$$1\\$");
+        assert_eq!(snippet("{${}} \\"), "{$1\\} \\\\");
+    }
+}
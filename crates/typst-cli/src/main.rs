@@ -2,7 +2,9 @@ mod args;
 mod compile;
 mod download;
 mod fonts;
+mod lsp;
 mod package;
+mod project;
 mod query;
 mod tracing;
 #[cfg(feature = "self-update")]
@@ -16,8 +18,10 @@ use std::process::ExitCode;
 
 use clap::Parser;
 use codespan_reporting::term::{self, termcolor};
+use ecow::eco_format;
 use once_cell::sync::Lazy;
 use termcolor::{ColorChoice, WriteColor};
+use typst::diag::StrResult;
 
 use crate::args::{CliArguments, Command};
 
@@ -39,13 +43,14 @@ fn main() -> ExitCode {
         }
     };
 
-    let res = match &ARGS.command {
+    let res = setup_threads().and_then(|()| match &ARGS.command {
         Command::Compile(command) => crate::compile::compile(command.clone()),
         Command::Watch(command) => crate::watch::watch(command.clone()),
         Command::Query(command) => crate::query::query(command),
         Command::Fonts(command) => crate::fonts::fonts(command),
+        Command::Lsp(command) => crate::lsp::lsp(command),
         Command::Update(command) => crate::update::update(command),
-    };
+    });
 
     if let Err(msg) = res {
         set_failed();
@@ -55,6 +60,20 @@ fn main() -> ExitCode {
     EXIT.with(|cell| cell.get())
 }
 
+/// Set up the global thread pool with the number of threads requested
+/// through `--jobs`, if any.
+fn setup_threads() -> StrResult<()> {
+    if let Command::Compile(command) | Command::Watch(command) = &ARGS.command {
+        if let Some(jobs) = command.jobs {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build_global()
+                .map_err(|err| eco_format!("failed to set up {jobs} threads ({err})"))?;
+        }
+    }
+    Ok(())
+}
+
 /// Ensure a failure exit code.
 fn set_failed() {
     EXIT.with(|cell| cell.set(ExitCode::FAILURE));
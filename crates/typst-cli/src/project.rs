@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ecow::eco_format;
+use serde::Deserialize;
+use typst::diag::{bail, StrResult};
+use typst::syntax::PackageSpec;
+
+use crate::args::SharedArgs;
+use crate::package::prepare_package;
+
+/// The file name of project manifests.
+const MANIFEST: &str = "typst.toml";
+
+/// A project manifest, which configures how the files of a project are
+/// compiled.
+///
+/// ```toml
+/// [project]
+/// entrypoint = "main.typ"
+/// font-paths = ["fonts"]
+/// dependencies = ["@preview/example:0.1.0"]
+///
+/// [project.inputs]
+/// edition = "zh"
+/// ```
+///
+/// Packages share the file name of their manifest with projects. Without a
+/// `project` key, a package's manifest is read as a project that consists of
+/// the package's entrypoint.
+#[derive(Debug, Clone, Deserialize)]
+struct ProjectManifest {
+    /// Details about the project.
+    project: Option<ProjectInfo>,
+    /// Details about the package, if the manifest is a package's manifest.
+    package: Option<PackageInfo>,
+}
+
+/// The relevant part of the `package` key in a package's manifest.
+#[derive(Debug, Clone, Deserialize)]
+struct PackageInfo {
+    /// The path of the package's main file, relative to the manifest.
+    entrypoint: PathBuf,
+}
+
+/// The `project` key in the manifest.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct ProjectInfo {
+    /// The path of the main file, relative to the manifest.
+    entrypoint: PathBuf,
+    /// Additional directories to search for fonts, relative to the manifest.
+    #[serde(default)]
+    font_paths: Vec<PathBuf>,
+    /// Packages that the project imports, like `@preview/example:0.1.0`.
+    #[serde(default)]
+    dependencies: Vec<String>,
+    /// String values that are visible through `sys.inputs`.
+    #[serde(default)]
+    inputs: BTreeMap<String, String>,
+}
+
+/// Apply the project manifest to the arguments if the input is a project
+/// directory or manifest instead of a Typst file.
+///
+/// The input becomes the manifest's entrypoint and the manifest's directory
+/// becomes the project root, unless `--root` is given. Font paths and inputs
+/// from the command line are combined with those from the manifest, with the
+/// command line taking precedence. Declared dependencies are downloaded
+/// upfront, so that later compilations don't need network access.
+pub fn apply_project(args: &mut SharedArgs) -> StrResult<()> {
+    let path = if args.input.is_dir() {
+        args.input.join(MANIFEST)
+    } else if args.input.file_name() == Some(MANIFEST.as_ref()) {
+        args.input.clone()
+    } else {
+        return Ok(());
+    };
+
+    let string = fs::read_to_string(&path).map_err(|err| {
+        eco_format!("failed to read project manifest at {} ({err})", path.display())
+    })?;
+
+    let info = parse_manifest(&string)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    args.input = dir.join(&info.entrypoint);
+    args.root.get_or_insert_with(|| dir.to_path_buf());
+    args.font_paths
+        .extend(info.font_paths.iter().map(|path| dir.join(path)));
+
+    // Later inputs replace earlier ones with the same key.
+    let inputs = std::mem::take(&mut args.inputs);
+    args.inputs = info.inputs.into_iter().chain(inputs).collect();
+
+    for dependency in &info.dependencies {
+        let spec: PackageSpec = dependency.parse().map_err(|err| {
+            eco_format!("project manifest has invalid dependency `{dependency}`: {err}")
+        })?;
+        prepare_package(&spec).map_err(|err| eco_format!("{err}"))?;
+    }
+
+    Ok(())
+}
+
+/// Parse a project manifest, or the manifest of a package as a project.
+fn parse_manifest(string: &str) -> StrResult<ProjectInfo> {
+    let manifest: ProjectManifest = toml::from_str(string)
+        .map_err(|err| eco_format!("project manifest is malformed: {}", err.message()))?;
+
+    match manifest {
+        ProjectManifest { project: Some(project), .. } => Ok(project),
+        ProjectManifest { package: Some(package), .. } => Ok(ProjectInfo {
+            entrypoint: package.entrypoint,
+            ..ProjectInfo::default()
+        }),
+        _ => bail!("project manifest is missing the `project` key"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::args::CompileCommand;
+
+    /// Parse the arguments of a compile command.
+    fn parse_args(args: &[&str]) -> SharedArgs {
+        let args = ["compile"].iter().chain(args);
+        CompileCommand::try_parse_from(args).unwrap().common
+    }
+
+    #[test]
+    fn test_apply_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = "[project]\n\
+             entrypoint = \"src/main.typ\"\n\
+             font-paths = [\"fonts\"]\n\
+             [project.inputs]\n\
+             edition = \"zh\"\n\
+             draft = \"true\"\n";
+        fs::write(dir.path().join(MANIFEST), manifest).unwrap();
+
+        let input = dir.path().to_str().unwrap();
+        let mut args =
+            parse_args(&[input, "--font-path", "extra", "--input", "edition=en"]);
+        apply_project(&mut args).unwrap();
+        assert_eq!(args.input, dir.path().join("src/main.typ"));
+        assert_eq!(args.root.as_deref(), Some(dir.path()));
+        assert_eq!(args.font_paths, [PathBuf::from("extra"), dir.path().join("fonts")]);
+
+        // The command line takes precedence over the manifest.
+        let inputs: BTreeMap<_, _> = args.inputs.into_iter().collect();
+        assert_eq!(inputs["edition"], "en");
+        assert_eq!(inputs["draft"], "true");
+
+        // Typst files are compiled as they are.
+        let mut args = parse_args(&["main.typ"]);
+        apply_project(&mut args).unwrap();
+        assert_eq!(args.input, PathBuf::from("main.typ"));
+        assert_eq!(args.root, None);
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let package = "[package]\n\
+             name = \"example\"\n\
+             version = \"0.1.0\"\n\
+             entrypoint = \"lib.typ\"\n";
+        let info = parse_manifest(package).unwrap();
+        assert_eq!(info.entrypoint, PathBuf::from("lib.typ"));
+        assert!(info.dependencies.is_empty());
+
+        assert_eq!(
+            parse_manifest("[tool]\n").unwrap_err(),
+            "project manifest is missing the `project` key"
+        );
+        assert!(parse_manifest("[project]\nfont-paths = []\n")
+            .unwrap_err()
+            .starts_with("project manifest is malformed"));
+    }
+}
@@ -1,21 +1,24 @@
 use comemo::Track;
-use ecow::{eco_format, EcoString};
+use ecow::eco_format;
 use serde::Serialize;
 use typst::diag::{bail, StrResult};
-use typst::eval::{eval_string, EvalMode, Tracer};
-use typst::foundations::{Content, IntoValue, LocatableSelector, Scope};
-use typst::model::Document;
-use typst::syntax::Span;
+use typst::eval::{eval_selector, Tracer};
+use typst::foundations::sys::Target;
+use typst::foundations::{dict, IntoValue, NativeElement, Selector};
+use typst::model::{Document, DocumentElem, QueryMatch};
 use typst::World;
 
 use crate::args::{QueryCommand, SerializationFormat};
 use crate::compile::print_diagnostics;
+use crate::project::apply_project;
 use crate::set_failed;
 use crate::world::SystemWorld;
 
 /// Execute a query command.
 pub fn query(command: &QueryCommand) -> StrResult<()> {
-    let mut world = SystemWorld::new(&command.common)?;
+    let mut common = command.common.clone();
+    apply_project(&mut common)?;
+    let mut world = SystemWorld::new(&common, Target::default())?;
     tracing::info!("Starting querying");
 
     // Reset everything and ensure that the main file is present.
@@ -29,8 +32,13 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     match result {
         // Retrieve and print query results.
         Ok(document) => {
-            let data = retrieve(&world, command, &document)?;
-            let serialized = format(data, command)?;
+            let selector =
+                eval_selector((&world as &dyn World).track(), &command.selector)?;
+            let serialized = if is_document(&selector) {
+                format_metadata(&document, command)?
+            } else {
+                format(document.query(&selector), command)?
+            };
             println!("{serialized}");
             print_diagnostics(&world, &[], &warnings, command.common.diagnostic_format)
                 .map_err(|err| eco_format!("failed to print diagnostics ({err})"))?;
@@ -52,48 +60,29 @@ pub fn query(command: &QueryCommand) -> StrResult<()> {
     Ok(())
 }
 
-/// Retrieve the matches for the selector.
-fn retrieve(
-    world: &dyn World,
-    command: &QueryCommand,
-    document: &Document,
-) -> StrResult<Vec<Content>> {
-    let selector = eval_string(
-        world.track(),
-        &command.selector,
-        Span::detached(),
-        EvalMode::Code,
-        Scope::default(),
-    )
-    .map_err(|errors| {
-        let mut message = EcoString::from("failed to evaluate selector");
-        for (i, error) in errors.into_iter().enumerate() {
-            message.push_str(if i == 0 { ": " } else { ", " });
-            message.push_str(&error.message);
-        }
-        message
-    })?
-    .cast::<LocatableSelector>()?;
-
-    Ok(document
-        .introspector
-        .query(&selector.0)
-        .into_iter()
-        .map(|x| x.into_inner())
-        .collect::<Vec<_>>())
+/// Whether the selector matches the document element itself.
+fn is_document(selector: &Selector) -> bool {
+    matches!(selector, Selector::Elem(elem, None) if *elem == DocumentElem::elem())
 }
 
 /// Format the query result in the output format.
-fn format(elements: Vec<Content>, command: &QueryCommand) -> StrResult<String> {
-    if command.one && elements.len() != 1 {
-        bail!("expected exactly one element, found {}", elements.len());
+fn format(matches: Vec<QueryMatch>, command: &QueryCommand) -> StrResult<String> {
+    if command.one && matches.len() != 1 {
+        bail!("expected exactly one element, found {}", matches.len());
     }
 
-    let mapped: Vec<_> = elements
+    let mapped: Vec<_> = matches
         .into_iter()
-        .filter_map(|c| match &command.field {
-            Some(field) => c.get_by_name(field),
-            _ => Some(c.into_value()),
+        .filter_map(|m| {
+            let value = match &command.field {
+                Some(field) => m.element.get_by_name(field)?,
+                _ => m.element.into_value(),
+            };
+            Some(if command.pages {
+                dict! { "value" => value, "page" => m.page }.into_value()
+            } else {
+                value
+            })
         })
         .collect();
 
@@ -107,6 +96,29 @@ fn format(elements: Vec<Content>, command: &QueryCommand) -> StrResult<String> {
     }
 }
 
+/// Format the document's metadata in the output format.
+///
+/// The document element is not part of the introspected elements, so the
+/// `document` selector is answered with the metadata of the whole document
+/// instead. It is treated like a single match on the first page.
+fn format_metadata(document: &Document, command: &QueryCommand) -> StrResult<String> {
+    let metadata = document.metadata();
+    let mut value = match &command.field {
+        Some(field) => metadata.get(field)?.clone(),
+        None => metadata.into_value(),
+    };
+
+    if command.pages {
+        value = dict! { "value" => value, "page" => 1 }.into_value();
+    }
+
+    if command.one {
+        serialize(&value, command.format)
+    } else {
+        serialize(&[value], command.format)
+    }
+}
+
 /// Serialize data to the output format.
 fn serialize(data: &impl Serialize, format: SerializationFormat) -> StrResult<String> {
     match format {
@@ -118,3 +130,48 @@ fn serialize(data: &impl Serialize, format: SerializationFormat) -> StrResult<St
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+    use typst::foundations::{Label, Smart, Value};
+
+    use super::*;
+
+    fn command(args: &[&str]) -> QueryCommand {
+        QueryCommand::try_parse_from(["query", "main.typ"].iter().chain(args)).unwrap()
+    }
+
+    fn metadata(args: &[&str]) -> serde_json::Value {
+        let document = Document {
+            title: Some("Notes".into()),
+            author: vec!["Ada".into()],
+            date: Smart::Custom(None),
+            ..Document::default()
+        };
+        let json = format_metadata(&document, &command(args)).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn test_query_document_selector() {
+        assert!(is_document(&DocumentElem::elem().select()));
+        let fields = std::iter::once((0, Value::None)).collect();
+        assert!(!is_document(&DocumentElem::elem().where_(fields)));
+        assert!(!is_document(&Selector::Label(Label::new("document"))));
+    }
+
+    #[test]
+    fn test_query_document_metadata() {
+        let value = metadata(&["document", "--one"]);
+        assert_eq!(value["title"], "Notes");
+        assert_eq!(value["author"], serde_json::json!(["Ada"]));
+        assert_eq!(value["date"], serde_json::Value::Null);
+
+        let value = metadata(&["document", "--field", "title"]);
+        assert_eq!(value, serde_json::json!(["Notes"]));
+
+        let value = metadata(&["document", "--field", "title", "--pages"]);
+        assert_eq!(value, serde_json::json!([{ "value": "Notes", "page": 1 }]));
+    }
+}
@@ -29,6 +29,7 @@ pub fn setup_tracing(args: &CliArguments) -> io::Result<Option<impl Drop>> {
     // Short circuit if we don't need to initialize flamegraph or debugging.
     if flamegraph.is_none() && args.verbosity == 0 {
         tracing_subscriber::fmt()
+            .with_writer(io::stderr)
             .without_time()
             .with_max_level(level_filter(args))
             .init();
@@ -37,7 +38,10 @@ pub fn setup_tracing(args: &CliArguments) -> io::Result<Option<impl Drop>> {
     }
 
     // Build the FMT layer printing to the console.
-    let fmt_layer = fmt::Layer::default().without_time().with_filter(level_filter(args));
+    let fmt_layer = fmt::Layer::default()
+        .with_writer(io::stderr)
+        .without_time()
+        .with_filter(level_filter(args));
 
     // Error layer for building backtraces
     let error_layer = ErrorLayer::default();
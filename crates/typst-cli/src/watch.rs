@@ -12,12 +12,15 @@ use typst::diag::StrResult;
 use crate::args::CompileCommand;
 use crate::color_stream;
 use crate::compile::compile_once;
+use crate::project::apply_project;
 use crate::world::SystemWorld;
 
 /// Execute a watching compilation command.
 pub fn watch(mut command: CompileCommand) -> StrResult<()> {
+    apply_project(&mut command.common)?;
+
     // Create the world that serves sources, files, and fonts.
-    let mut world = SystemWorld::new(&command.common)?;
+    let mut world = SystemWorld::new(&command.common, command.target())?;
 
     // Perform initial compilation.
     compile_once(&mut world, &mut command, true)?;
@@ -59,6 +62,17 @@ pub fn watch(mut command: CompileCommand) -> StrResult<()> {
                 watcher.unwatch(path).ok();
             }
 
+            // The listing of the project's files is outdated once files
+            // are created, removed, or renamed.
+            if matches!(
+                event.kind,
+                notify::EventKind::Create(_)
+                    | notify::EventKind::Remove(_)
+                    | notify::EventKind::Modify(notify::event::ModifyKind::Name(_))
+            ) {
+                world.invalidate_files();
+            }
+
             recompile |= is_event_relevant(&event, &output);
         }
 
@@ -68,7 +82,7 @@ pub fn watch(mut command: CompileCommand) -> StrResult<()> {
 
             // Recompile.
             compile_once(&mut world, &mut command, true)?;
-            comemo::evict(10);
+            typst::memory::evict(10);
 
             // Adjust the file watching.
             watch_dependencies(&mut world, &mut watcher, &mut watched)?;
@@ -7,7 +7,9 @@ use chrono::{DateTime, Datelike, Local};
 use comemo::Prehashed;
 use ecow::eco_format;
 use typst::diag::{FileError, FileResult, StrResult};
-use typst::foundations::{Bytes, Datetime};
+use typst::eval::Dependency;
+use typst::foundations::sys::Target;
+use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
 use typst::layout::Frame;
 use typst::syntax::{FileId, Source, VirtualPath};
 use typst::text::{Font, FontBook};
@@ -36,6 +38,13 @@ pub struct SystemWorld {
     fonts: Vec<FontSlot>,
     /// Maps file ids to source files and buffers.
     slots: RefCell<HashMap<FileId, FileSlot>>,
+    /// The files and fonts that the last compilation depended on.
+    dependencies: Vec<Dependency>,
+    /// Sources whose text is provided by an editor instead of the file
+    /// system, used by the language server.
+    overlays: HashMap<FileId, Source>,
+    /// The files in the project root, which are listed on first use.
+    files: OnceCell<Vec<FileId>>,
     /// The current datetime if requested. This is stored here to ensure it is
     /// always the same within one compilation. Reset between compilations.
     now: OnceCell<DateTime<Local>>,
@@ -46,10 +55,7 @@ pub struct SystemWorld {
 
 impl SystemWorld {
     /// Create a new system world.
-    pub fn new(command: &SharedArgs) -> StrResult<Self> {
-        let mut searcher = FontSearcher::new();
-        searcher.search(&command.font_paths);
-
+    pub fn new(command: &SharedArgs, target: Target) -> StrResult<Self> {
         // Resolve the system-global input path.
         let input = command.input.canonicalize().map_err(|_| {
             eco_format!("input file not found (searched at {})", command.input.display())
@@ -71,18 +77,58 @@ impl SystemWorld {
         let main_path = VirtualPath::within_root(&input, &root)
             .ok_or("input file must be contained in project root")?;
 
-        Ok(Self {
+        let main = FileId::new(None, main_path);
+        let inputs = inputs(&command.inputs);
+        Ok(Self::build(input, root, main, &command.font_paths, inputs, target))
+    }
+
+    /// Create a world for the language server, whose main file changes with
+    /// the document that is being checked.
+    pub fn with_root(
+        root: &Path,
+        font_paths: &[PathBuf],
+        inputs: &[(String, String)],
+        target: Target,
+    ) -> StrResult<Self> {
+        let root = root.canonicalize().map_err(|_| {
+            eco_format!("root directory not found (searched at {})", root.display())
+        })?;
+
+        let main = FileId::new(None, VirtualPath::new("main.typ"));
+        let input = root.join("main.typ");
+        let inputs = self::inputs(inputs);
+        Ok(Self::build(input, root, main, font_paths, inputs, target))
+    }
+
+    /// Create a world from resolved paths.
+    fn build(
+        input: PathBuf,
+        root: PathBuf,
+        main: FileId,
+        font_paths: &[PathBuf],
+        inputs: Dict,
+        target: Target,
+    ) -> Self {
+        let mut searcher = FontSearcher::new();
+        searcher.search(font_paths);
+
+        Self {
             workdir: std::env::current_dir().ok(),
             input,
             root,
-            main: FileId::new(None, main_path),
-            library: Prehashed::new(Library::build()),
+            main,
+            library: Prehashed::new(
+                Library::builder().with_inputs(inputs).with_target(target).build(),
+            ),
             book: Prehashed::new(searcher.book),
             fonts: searcher.fonts,
             slots: RefCell::default(),
+            dependencies: vec![],
+            overlays: HashMap::new(),
+            files: OnceCell::new(),
             now: OnceCell::new(),
             export_cache: ExportCache::new(),
-        })
+        }
     }
 
     /// The id of the main source file.
@@ -90,23 +136,69 @@ impl SystemWorld {
         self.main
     }
 
+    /// Change the main source file.
+    pub fn set_main(&mut self, id: FileId) {
+        self.main = id;
+    }
+
     /// The root relative to which absolute paths are resolved.
     pub fn root(&self) -> &Path {
         &self.root
     }
 
+    /// The id of a file on the system, if it lies within the root.
+    pub fn file_id(&self, path: &Path) -> Option<FileId> {
+        VirtualPath::within_root(path, &self.root).map(|vpath| FileId::new(None, vpath))
+    }
+
+    /// The path of a file on the system.
+    pub fn path(&self, id: FileId) -> FileResult<PathBuf> {
+        self.slot(id)?.system_path(&self.root)
+    }
+
+    /// Use the given text for a source file instead of reading it from the
+    /// file system.
+    pub fn set_overlay(&mut self, id: FileId, text: String) {
+        match self.overlays.get_mut(&id) {
+            Some(source) => {
+                source.replace(&text);
+            }
+            None => {
+                self.overlays.insert(id, Source::new(id, text));
+            }
+        }
+    }
+
+    /// Read a source file from the file system again.
+    pub fn remove_overlay(&mut self, id: FileId) {
+        self.overlays.remove(&id);
+    }
+
+    /// Forget the listing of the project's files, after files were created,
+    /// removed, or renamed.
+    pub fn invalidate_files(&mut self) {
+        self.files.take();
+    }
+
     /// The current working directory.
     pub fn workdir(&self) -> &Path {
         self.workdir.as_deref().unwrap_or(Path::new("."))
     }
 
-    /// Return all paths the last compilation depended on.
-    pub fn dependencies(&mut self) -> impl Iterator<Item = PathBuf> + '_ {
-        self.slots
-            .get_mut()
-            .values()
-            .filter(|slot| slot.accessed())
-            .filter_map(|slot| slot.system_path(&self.root).ok())
+    /// Remember what the last compilation depended on, as reported by its
+    /// tracer.
+    pub fn set_dependencies(&mut self, dependencies: &[Dependency]) {
+        self.dependencies = dependencies.to_vec();
+    }
+
+    /// Return all paths the last compilation depended on, including fonts.
+    pub fn dependencies(&self) -> impl Iterator<Item = PathBuf> + '_ {
+        self.dependencies.iter().filter_map(|&dependency| match dependency {
+            Dependency::File(id) => self.path(id).ok(),
+            Dependency::Font(index) => {
+                self.fonts.get(index).map(|slot| slot.path().to_path_buf())
+            }
+        })
     }
 
     /// Reset the compilation state in preparation of a new compilation.
@@ -148,10 +240,16 @@ impl World for SystemWorld {
     }
 
     fn source(&self, id: FileId) -> FileResult<Source> {
+        if let Some(source) = self.overlays.get(&id) {
+            return Ok(source.clone());
+        }
         self.slot(id)?.source(&self.root)
     }
 
     fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(source) = self.overlays.get(&id) {
+            return Ok(Bytes::from(source.text().as_bytes()));
+        }
         self.slot(id)?.file(&self.root)
     }
 
@@ -159,6 +257,16 @@ impl World for SystemWorld {
         self.fonts[index].get()
     }
 
+    fn files(&self) -> Vec<FileId> {
+        self.files
+            .get_or_init(|| {
+                let mut files = vec![];
+                collect_files(&self.root, &self.root, &mut files);
+                files
+            })
+            .clone()
+    }
+
     fn today(&self, offset: Option<i64>) -> Option<Datetime> {
         let now = self.now.get_or_init(chrono::Local::now);
 
@@ -203,11 +311,6 @@ impl FileSlot {
         Self { id, file: SlotCell::new(), source: SlotCell::new() }
     }
 
-    /// Whether the file was accessed in the ongoing compilation.
-    fn accessed(&self) -> bool {
-        self.source.accessed() || self.file.accessed()
-    }
-
     /// Marks the file as not yet accessed in preparation of the next
     /// compilation.
     fn reset(&self) {
@@ -274,11 +377,6 @@ impl<T: Clone> SlotCell<T> {
         }
     }
 
-    /// Whether the cell was accessed in the ongoing compilation.
-    fn accessed(&self) -> bool {
-        self.accessed.get()
-    }
-
     /// Marks the cell as not yet accessed in preparation of the next
     /// compilation.
     fn reset(&self) {
@@ -351,6 +449,36 @@ impl ExportCache {
     }
 }
 
+/// Turn key-value pairs from the command line into the `sys.inputs`
+/// dictionary.
+fn inputs(pairs: &[(String, String)]) -> Dict {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.as_str().into(), value.as_str().into_value()))
+        .collect()
+}
+
+/// Collect the files in a directory recursively, skipping hidden ones.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<FileId>) {
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() => collect_files(root, &path, files),
+            Ok(kind) if kind.is_file() => {
+                if let Some(vpath) = VirtualPath::within_root(&path, root) {
+                    files.push(FileId::new(None, vpath));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
 /// Read a file.
 fn read(path: &Path) -> FileResult<Vec<u8>> {
     let f = |e| FileError::from_io(e, path);
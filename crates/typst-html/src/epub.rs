@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{Cursor, Write};
+
+use ecow::{eco_format, EcoString};
+use typst::diag::StrResult;
+use typst::foundations::{Content, Datetime, Smart, StyleChain};
+use typst::layout::{Frame, FrameItem};
+use typst::model::Document;
+use typst::text::Lang;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::escape;
+
+/// Settings for EPUB export.
+#[derive(Debug, Default, Clone)]
+pub struct EpubOptions<'a> {
+    /// A string that uniquely and stably identifies the book. Its hash is used
+    /// as the book's identifier. If this is `None`, a hash of the book's
+    /// content is used instead.
+    pub ident: Option<&'a str>,
+    /// The modification date of the book as a UTC datetime. It will only be
+    /// used if `set document(date: ..)` is `auto`.
+    pub timestamp: Option<Datetime>,
+    /// The raw bytes of a PNG or JPEG cover image.
+    pub cover: Option<&'a [u8]>,
+}
+
+/// Export a document into an EPUB 3 package.
+///
+/// The `document` provides the metadata and the main language, while the
+/// `content` and `styles` are exported through the HTML exporter. The book is
+/// split into one chapter per top-level heading. The fonts of the document
+/// are embedded, subset to the characters that it shows.
+#[tracing::instrument(skip_all)]
+pub fn epub(
+    document: &Document,
+    content: &Content,
+    styles: StyleChain,
+    options: &EpubOptions,
+) -> StrResult<Vec<u8>> {
+    let (body, _, images) = crate::body(content, styles, true);
+    let lang = main_lang(&document.pages);
+    let title = document.title.clone().unwrap_or_else(|| "Untitled".into());
+    let mut chapters = split_chapters(&body, &title);
+    link_chapters(&mut chapters);
+
+    let ident = match options.ident {
+        Some(ident) => typst::util::hash128(ident),
+        None => typst::util::hash128(&body),
+    };
+
+    let cover = match options.cover {
+        Some(data) => Some((cover_format(data)?, data)),
+        None => None,
+    };
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut write = |path: &str, data: &[u8], options: FileOptions| -> StrResult<()> {
+        zip.start_file(path, options)
+            .and_then(|_| zip.write_all(data).map_err(Into::into))
+            .map_err(|err| eco_format!("failed to write EPUB entry {path} ({err})"))
+    };
+
+    // The mimetype must be the first entry and must not be compressed.
+    write("mimetype", b"application/epub+zip", stored)?;
+    write("META-INF/container.xml", CONTAINER.as_bytes(), deflated)?;
+
+    let modified = match document.date {
+        Smart::Custom(date) => date,
+        Smart::Auto => options.timestamp,
+    };
+
+    let mut package = Package {
+        ident: eco_format!("urn:typst:{ident:032x}"),
+        title: &title,
+        authors: &document.author,
+        keywords: &document.keywords,
+        lang,
+        modified,
+        items: vec![],
+        spine: vec![],
+    };
+
+    if let Some((format, data)) = cover {
+        let path = eco_format!("cover.{}", format.extension());
+        write(&format!("EPUB/{path}"), data, stored)?;
+        package.items.push(Item {
+            id: "cover".into(),
+            href: path,
+            media_type: format.media_type(),
+            properties: Some("cover-image"),
+        });
+    }
+
+    // The fonts are declared in a stylesheet that all pages link to.
+    let fonts = crate::font::embed_fonts(&document.pages);
+    let mut paths = vec![];
+    for (i, font) in fonts.iter().enumerate() {
+        let path = eco_format!("fonts/font-{}.{}", i + 1, font.extension());
+        write(&format!("EPUB/{path}"), &font.data, deflated)?;
+        package.items.push(Item {
+            id: eco_format!("font-{}", i + 1),
+            href: path.clone(),
+            media_type: font.media_type(),
+            properties: None,
+        });
+        paths.push(path);
+    }
+
+    let stylesheet = !fonts.is_empty();
+    if stylesheet {
+        let css = crate::font::stylesheet(&fonts, &paths);
+        write("EPUB/style.css", css.as_bytes(), deflated)?;
+        package.items.push(Item {
+            id: "style".into(),
+            href: "style.css".into(),
+            media_type: "text/css",
+            properties: None,
+        });
+    }
+
+    for (i, image) in images.iter().enumerate() {
+        write(&format!("EPUB/{}", image.src), &image.data, stored)?;
+        package.items.push(Item {
+            id: eco_format!("image-{}", i + 1),
+            href: image.src.clone(),
+            media_type: image.media_type,
+            properties: None,
+        });
+    }
+
+    package.items.push(Item {
+        id: "nav".into(),
+        href: "nav.xhtml".into(),
+        media_type: "application/xhtml+xml",
+        properties: Some("nav"),
+    });
+    let nav = nav(&chapters, &title, lang, stylesheet);
+    write("EPUB/nav.xhtml", nav.as_bytes(), deflated)?;
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        let id = eco_format!("chapter-{}", i + 1);
+        let href = eco_format!("{id}.xhtml");
+        let page = xhtml(&chapter.title, lang, stylesheet, &chapter.body);
+        write(&format!("EPUB/{href}"), page.as_bytes(), deflated)?;
+        package.items.push(Item {
+            id: id.clone(),
+            href,
+            media_type: "application/xhtml+xml",
+            properties: None,
+        });
+        package.spine.push(id);
+    }
+
+    write("EPUB/package.opf", package.write().as_bytes(), deflated)?;
+
+    let cursor = zip
+        .finish()
+        .map_err(|err| eco_format!("failed to finish EPUB file ({err})"))?;
+    Ok(cursor.into_inner())
+}
+
+/// Points the reading system to the package document.
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+<rootfiles>
+<rootfile full-path="EPUB/package.opf" media-type="application/oebps-package+xml"/>
+</rootfiles>
+</container>
+"#;
+
+/// A chapter of the book.
+struct Chapter {
+    /// The escaped plain-text title of the chapter.
+    title: EcoString,
+    /// The chapter's HTML.
+    body: String,
+}
+
+/// Split the body into chapters at its top-level headings.
+///
+/// Content before the first heading becomes its own chapter, titled with the
+/// book's title.
+fn split_chapters(body: &str, title: &str) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = vec![];
+    for line in body.lines() {
+        if line.starts_with("<h1 ") || chapters.is_empty() {
+            let title = if line.starts_with("<h1 ") {
+                strip_tags(line)
+            } else {
+                escape(title).into()
+            };
+            chapters.push(Chapter { title, body: String::new() });
+        }
+
+        let chapter = chapters.last_mut().unwrap();
+        chapter.body.push_str(line);
+        chapter.body.push('\n');
+    }
+
+    if chapters.is_empty() {
+        chapters.push(Chapter { title: escape(title).into(), body: String::new() });
+    }
+
+    chapters
+}
+
+/// Point links to anchors in other chapters to the chapters' files.
+///
+/// The HTML exporter links to labelled content through fragments like
+/// `#intro`. In the book, each chapter is a file of its own, so these become
+/// `chapter-2.xhtml#intro`. Fragments without a matching anchor are kept.
+fn link_chapters(chapters: &mut [Chapter]) {
+    let mut anchors = HashMap::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        for id in attributes(&chapter.body, " id=\"") {
+            anchors.entry(id.to_string()).or_insert(i + 1);
+        }
+    }
+
+    for chapter in chapters {
+        let mut body = String::with_capacity(chapter.body.len());
+        let mut rest = chapter.body.as_str();
+        while let Some(start) = rest.find(" href=\"#") {
+            let (before, after) = rest.split_at(start + " href=\"".len());
+            body.push_str(before);
+            let fragment = &after[1..after.find('"').unwrap_or(after.len())];
+            if let Some(n) = anchors.get(fragment) {
+                write!(body, "chapter-{n}.xhtml").unwrap();
+            }
+            rest = after;
+        }
+        body.push_str(rest);
+        chapter.body = body;
+    }
+}
+
+/// Find the values of all attributes that start with the given prefix, e.g.
+/// ` id="`.
+fn attributes<'a>(html: &'a str, prefix: &'a str) -> impl Iterator<Item = &'a str> {
+    html.match_indices(prefix).filter_map(move |(i, _)| {
+        let value = &html[i + prefix.len()..];
+        value.find('"').map(|end| &value[..end])
+    })
+}
+
+/// Remove all tags from a line of HTML, retaining only its (still escaped)
+/// text.
+fn strip_tags(html: &str) -> EcoString {
+    let mut text = EcoString::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text
+}
+
+/// Write an XHTML content document, optionally linking to the stylesheet.
+fn xhtml(title: &str, lang: Lang, stylesheet: bool, body: &str) -> String {
+    let lang = lang.as_str();
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n");
+    writeln!(
+        out,
+        "<html xmlns=\"http://www.w3.org/1999/xhtml\" \
+         xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"{lang}\" xml:lang=\"{lang}\">"
+    )
+    .unwrap();
+    writeln!(out, "<head>\n<meta charset=\"utf-8\"/>\n<title>{title}</title>").unwrap();
+    if stylesheet {
+        out.push_str("<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n");
+    }
+    out.push_str("</head>\n");
+    out.push_str("<body>\n");
+    out.push_str(body);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Write the navigation document, which lists all chapters.
+fn nav(chapters: &[Chapter], title: &str, lang: Lang, stylesheet: bool) -> String {
+    let mut body = String::new();
+    body.push_str("<nav epub:type=\"toc\" id=\"toc\">\n");
+    writeln!(body, "<h1>{}</h1>\n<ol>", escape(title)).unwrap();
+    for (i, chapter) in chapters.iter().enumerate() {
+        writeln!(
+            body,
+            "<li><a href=\"chapter-{}.xhtml\">{}</a></li>",
+            i + 1,
+            chapter.title
+        )
+        .unwrap();
+    }
+    body.push_str("</ol>\n</nav>\n");
+    xhtml(&escape(title), lang, stylesheet, &body)
+}
+
+/// The package document's data.
+struct Package<'a> {
+    ident: EcoString,
+    title: &'a str,
+    authors: &'a [EcoString],
+    keywords: &'a [EcoString],
+    lang: Lang,
+    modified: Option<Datetime>,
+    items: Vec<Item>,
+    spine: Vec<EcoString>,
+}
+
+/// An item in the package's manifest.
+struct Item {
+    id: EcoString,
+    href: EcoString,
+    media_type: &'static str,
+    properties: Option<&'static str>,
+}
+
+impl Package<'_> {
+    /// Write the package document.
+    fn write(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        writeln!(
+            out,
+            "<package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" \
+             unique-identifier=\"uid\" xml:lang=\"{}\">",
+            self.lang.as_str()
+        )
+        .unwrap();
+
+        out.push_str("<metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n");
+        writeln!(out, "<dc:identifier id=\"uid\">{}</dc:identifier>", self.ident)
+            .unwrap();
+        writeln!(out, "<dc:title>{}</dc:title>", escape(self.title)).unwrap();
+        writeln!(out, "<dc:language>{}</dc:language>", self.lang.as_str()).unwrap();
+        for author in self.authors {
+            writeln!(out, "<dc:creator>{}</dc:creator>", escape(author)).unwrap();
+        }
+        for keyword in self.keywords {
+            writeln!(out, "<dc:subject>{}</dc:subject>", escape(keyword)).unwrap();
+        }
+        if let Some(date) = self.modified.and_then(|date| format_date(date, false)) {
+            writeln!(out, "<dc:date>{date}</dc:date>").unwrap();
+        }
+
+        // The modification date is mandatory in EPUB 3.
+        let modified = self
+            .modified
+            .and_then(|date| format_date(date, true))
+            .unwrap_or_else(|| "1970-01-01T00:00:00Z".into());
+        writeln!(out, "<meta property=\"dcterms:modified\">{modified}</meta>").unwrap();
+        if self.items.iter().any(|item| item.id == "cover") {
+            out.push_str("<meta name=\"cover\" content=\"cover\"/>\n");
+        }
+        out.push_str("</metadata>\n");
+
+        out.push_str("<manifest>\n");
+        for item in &self.items {
+            write!(
+                out,
+                "<item id=\"{}\" href=\"{}\" media-type=\"{}\"",
+                item.id, item.href, item.media_type
+            )
+            .unwrap();
+            if let Some(properties) = item.properties {
+                write!(out, " properties=\"{properties}\"").unwrap();
+            }
+            out.push_str("/>\n");
+        }
+        out.push_str("</manifest>\n");
+
+        out.push_str("<spine>\n");
+        for id in &self.spine {
+            writeln!(out, "<itemref idref=\"{id}\"/>").unwrap();
+        }
+        out.push_str("</spine>\n</package>\n");
+        out
+    }
+}
+
+/// Format a datetime as a W3C date, optionally including the time.
+fn format_date(date: Datetime, time: bool) -> Option<EcoString> {
+    let year = date.year().filter(|&year| year >= 0)?;
+    let month = date.month().unwrap_or(1);
+    let day = date.day().unwrap_or(1);
+    if !time {
+        return Some(eco_format!("{year:04}-{month:02}-{day:02}"));
+    }
+
+    let hour = date.hour().unwrap_or(0);
+    let minute = date.minute().unwrap_or(0);
+    let second = date.second().unwrap_or(0);
+    Some(eco_format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}Z"))
+}
+
+/// The format of a cover image.
+#[derive(Debug, Copy, Clone)]
+enum CoverFormat {
+    Png,
+    Jpg,
+}
+
+impl CoverFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpg => "jpg",
+        }
+    }
+
+    fn media_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpg => "image/jpeg",
+        }
+    }
+}
+
+/// Detect the format of a cover image from its magic bytes.
+fn cover_format(data: &[u8]) -> StrResult<CoverFormat> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Ok(CoverFormat::Png)
+    } else if data.starts_with(&[0xff, 0xd8, 0xff]) {
+        Ok(CoverFormat::Jpg)
+    } else {
+        Err("cover image must be a PNG or JPEG file".into())
+    }
+}
+
+/// Determine the main language of the document by counting the glyphs of each
+/// language, like the PDF exporter does.
+fn main_lang(frames: &[Frame]) -> Lang {
+    fn count(frame: &Frame, languages: &mut HashMap<Lang, usize>) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => count(&group.frame, languages),
+                FrameItem::Text(text) => {
+                    *languages.entry(text.lang).or_insert(0) += text.glyphs.len();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut languages = HashMap::new();
+    for frame in frames {
+        count(frame, &mut languages);
+    }
+
+    languages
+        .into_iter()
+        .max_by_key(|&(lang, count)| (count, lang))
+        .map_or(Lang::ENGLISH, |(lang, _)| lang)
+}
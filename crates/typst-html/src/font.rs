@@ -0,0 +1,286 @@
+//! Embedding of the document's fonts into EPUB packages.
+//!
+//! Each used font face is subset to the glyphs of the characters it shows.
+//! Unlike for PDF, reading systems shape the text anew, so the subset needs a
+//! character map and the tables that browsers require. Layout tables like
+//! `GSUB` and `GPOS` are dropped, as they refer to glyphs that might not be
+//! part of the subset.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use ecow::{eco_format, EcoString};
+use ttf_parser::{RawFace, Tag};
+use typst::layout::{Frame, FrameItem};
+use typst::text::{Font, FontStyle};
+
+/// The tables that are kept in addition to the generated `cmap`. They are
+/// taken from the subset if it has them and from the original font otherwise.
+const TABLES: [&[u8; 4]; 17] = [
+    b"CFF ", b"CFF2", b"OS/2", b"cvt ", b"fpgm", b"gasp", b"glyf", b"head", b"hhea",
+    b"hmtx", b"loca", b"maxp", b"name", b"post", b"prep", b"vhea", b"vmtx",
+];
+
+/// A font face of the document, subset to the characters it shows.
+pub(crate) struct EmbeddedFont {
+    /// The face's family name.
+    pub family: EcoString,
+    /// The face's weight, from 100 to 900.
+    pub weight: u16,
+    /// The face's style as a CSS keyword.
+    pub style: &'static str,
+    /// Whether the face has CFF outlines.
+    pub cff: bool,
+    /// The OpenType font file.
+    pub data: Vec<u8>,
+}
+
+impl EmbeddedFont {
+    /// The file extension of the font.
+    pub fn extension(&self) -> &'static str {
+        if self.cff {
+            "otf"
+        } else {
+            "ttf"
+        }
+    }
+
+    /// The media type of the font.
+    pub fn media_type(&self) -> &'static str {
+        if self.cff {
+            "font/otf"
+        } else {
+            "font/ttf"
+        }
+    }
+}
+
+/// Subset the fonts used in the frames, ordered from the most to the least
+/// used one.
+pub(crate) fn embed_fonts(frames: &[Frame]) -> Vec<EmbeddedFont> {
+    let mut used = Used::default();
+    for frame in frames {
+        used.collect(frame);
+    }
+
+    used.fonts.sort_by_key(|(_, count, _)| std::cmp::Reverse(*count));
+    used.fonts
+        .iter()
+        .filter_map(|(font, _, chars)| subset(font, chars))
+        .collect()
+}
+
+/// Write a stylesheet that declares the embedded fonts and sets the text in
+/// them. The `paths` are the fonts' paths relative to the stylesheet.
+pub(crate) fn stylesheet(fonts: &[EmbeddedFont], paths: &[EcoString]) -> String {
+    let mut out = String::new();
+    let mut families: Vec<&str> = vec![];
+    for (font, path) in fonts.iter().zip(paths) {
+        writeln!(
+            out,
+            "@font-face {{\n  font-family: \"{}\";\n  font-weight: {};\n  \
+             font-style: {};\n  src: url(\"{path}\");\n}}",
+            css_string(&font.family),
+            font.weight,
+            font.style,
+        )
+        .unwrap();
+        if !families.contains(&font.family.as_str()) {
+            families.push(&font.family);
+        }
+    }
+
+    let families: Vec<_> = families
+        .iter()
+        .map(|family| eco_format!("\"{}\"", css_string(family)))
+        .collect();
+    writeln!(out, "body {{\n  font-family: {};\n}}", families.join(", ")).unwrap();
+    out
+}
+
+/// The characters shown in each font, in the order the fonts first appear.
+#[derive(Default)]
+struct Used {
+    fonts: Vec<(Font, usize, BTreeSet<char>)>,
+    indices: HashMap<Font, usize>,
+}
+
+impl Used {
+    /// Collect the characters shown in a frame.
+    fn collect(&mut self, frame: &Frame) {
+        for (_, item) in frame.items() {
+            match item {
+                FrameItem::Group(group) => self.collect(&group.frame),
+                FrameItem::Text(text) => {
+                    let index =
+                        *self.indices.entry(text.font.clone()).or_insert_with(|| {
+                            self.fonts.push((text.font.clone(), 0, BTreeSet::new()));
+                            self.fonts.len() - 1
+                        });
+                    let (_, count, chars) = &mut self.fonts[index];
+                    *count += text.glyphs.len();
+                    chars.extend(text.text.chars().filter(|c| !c.is_control()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Subset a font to the glyphs of the given characters.
+fn subset(font: &Font, chars: &BTreeSet<char>) -> Option<EmbeddedFont> {
+    let ttf = font.ttf();
+    let mapping: Vec<(u32, u16)> = chars
+        .iter()
+        .filter_map(|&c| Some((c as u32, ttf.glyph_index(c)?.0)))
+        .collect();
+    if mapping.is_empty() {
+        return None;
+    }
+
+    let mut glyphs: Vec<u16> = mapping.iter().map(|&(_, glyph)| glyph).collect();
+    glyphs.push(0);
+    glyphs.sort_unstable();
+    glyphs.dedup();
+
+    // The subset keeps the glyph IDs, so the tables of the original font
+    // still fit it.
+    let data = font.data();
+    let original = RawFace::parse(data, font.index()).ok()?;
+    let subsetted =
+        subsetter::subset(data, font.index(), subsetter::Profile::pdf(&glyphs)).ok();
+    let subset = subsetted.as_deref().and_then(|data| RawFace::parse(data, 0).ok());
+
+    let mut tables = vec![(*b"cmap", cmap(&mapping))];
+    for &tag in TABLES {
+        let table = Tag::from_bytes(tag);
+        let Some(data) = subset
+            .and_then(|face| face.table(table))
+            .or_else(|| original.table(table))
+        else {
+            continue;
+        };
+        tables.push((*tag, data.to_vec()));
+    }
+
+    let cff = tables.iter().any(|(tag, _)| tag.starts_with(b"CFF"));
+    let info = font.info();
+    Some(EmbeddedFont {
+        family: info.family.as_str().into(),
+        weight: info.variant.weight.to_number(),
+        style: match info.variant.style {
+            FontStyle::Normal => "normal",
+            FontStyle::Italic => "italic",
+            FontStyle::Oblique => "oblique",
+        },
+        cff,
+        data: assemble(tables, cff),
+    })
+}
+
+/// Write a character map with a single format 12 subtable for the given
+/// pairs of code points and glyph IDs, which must be sorted by code point.
+fn cmap(mapping: &[(u32, u16)]) -> Vec<u8> {
+    // Consecutive code points with consecutive glyphs form a group.
+    let mut groups: Vec<(u32, u32, u32)> = vec![];
+    for &(c, glyph) in mapping {
+        let glyph = u32::from(glyph);
+        match groups.last_mut() {
+            Some((start, end, first))
+                if c == *end + 1 && glyph == *first + c - *start =>
+            {
+                *end = c;
+            }
+            _ => groups.push((c, c, glyph)),
+        }
+    }
+
+    // The Unicode and the Windows encoding records share the subtable, which
+    // starts right after them.
+    let mut out = vec![];
+    out.extend(0u16.to_be_bytes());
+    out.extend(2u16.to_be_bytes());
+    for (platform, encoding) in [(0u16, 4u16), (3, 10)] {
+        out.extend(platform.to_be_bytes());
+        out.extend(encoding.to_be_bytes());
+        out.extend(20u32.to_be_bytes());
+    }
+
+    out.extend(12u16.to_be_bytes());
+    out.extend(0u16.to_be_bytes());
+    out.extend((16 + 12 * groups.len() as u32).to_be_bytes());
+    out.extend(0u32.to_be_bytes());
+    out.extend((groups.len() as u32).to_be_bytes());
+    for (start, end, glyph) in groups {
+        out.extend(start.to_be_bytes());
+        out.extend(end.to_be_bytes());
+        out.extend(glyph.to_be_bytes());
+    }
+    out
+}
+
+/// Assemble an OpenType font file from its tables.
+fn assemble(mut tables: Vec<([u8; 4], Vec<u8>)>, cff: bool) -> Vec<u8> {
+    tables.sort_by_key(|&(tag, _)| tag);
+
+    // The checksum adjustment of the `head` table is computed over the whole
+    // file, with the adjustment itself set to zero.
+    for (tag, data) in &mut tables {
+        if tag == b"head" && data.len() >= 12 {
+            data[8..12].fill(0);
+        }
+    }
+
+    let count = tables.len() as u16;
+    let selector = 15 - count.leading_zeros() as u16;
+    let range = 16 << selector;
+    let mut out = vec![];
+    out.extend(if cff { *b"OTTO" } else { [0, 1, 0, 0] });
+    for value in [count, range, selector, 16 * count - range] {
+        out.extend(value.to_be_bytes());
+    }
+
+    let mut head = None;
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        if tag == b"head" && data.len() >= 12 {
+            head = Some(offset);
+        }
+        out.extend(tag);
+        out.extend(checksum(data).to_be_bytes());
+        out.extend((offset as u32).to_be_bytes());
+        out.extend((data.len() as u32).to_be_bytes());
+        offset += padded(data.len());
+    }
+
+    for (_, data) in &tables {
+        out.extend(data);
+        out.resize(padded(out.len()), 0);
+    }
+
+    if let Some(head) = head {
+        let adjustment = 0xB1B0AFBAu32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+
+    out
+}
+
+/// The checksum of a table or a whole font file.
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Round a length up to a multiple of four, as tables are aligned to that.
+fn padded(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// Escape text for a quoted CSS string.
+fn css_string(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
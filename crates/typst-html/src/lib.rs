@@ -0,0 +1,353 @@
+//! Exporting into HTML documents.
+//!
+//! This exporter is experimental. Instead of working on the layouted frames
+//! like the other exporters, it works on the realized content and maps its
+//! structure (headings, paragraphs, lists, tables, raw text, and links) to
+//! semantic HTML. Precise pagination and positioning are lost in the process.
+//!
+//! Every element is emitted with a `typst-{name}` class, e.g. `typst-heading`,
+//! so that it can be styled through CSS.
+//!
+//! Building on the HTML export, the [`epub`] function packages a document into
+//! an EPUB 3 e-book. The [`markdown`] and [`text`] functions share the HTML
+//! exporter's view of the content's structure, but flatten it into CommonMark
+//! or plain text, e.g. for search indexing or for diffable review artifacts.
+
+mod epub;
+mod font;
+mod markdown;
+#[cfg(test)]
+mod tests;
+mod tree;
+
+pub use self::epub::{epub, EpubOptions};
+pub use self::markdown::{markdown, text};
+
+use std::ffi::OsStr;
+use std::fmt::Write;
+use std::path::Path;
+
+use ecow::{eco_format, EcoString};
+use typst::foundations::{Bytes, Content, Label, Smart, StyleChain};
+use typst::loading::Readable;
+use typst::model::{Destination, LinkTarget};
+use typst::text::TextElem;
+use typst::visualize::{ImageElem, ImageFormat, RasterFormat, VectorFormat};
+
+use crate::tree::{Block, Inline, ListKind, Metadata};
+
+/// Export content into an HTML document.
+///
+/// The `content` is typically the realized content of the main file, as
+/// returned by [`typst::structure`], and `styles` the default styles of the
+/// library.
+#[tracing::instrument(skip_all)]
+pub fn html(content: &Content, styles: StyleChain) -> String {
+    let (body, meta, _) = body(content, styles, false);
+    let lang = TextElem::lang_in(styles);
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n");
+    writeln!(out, "<html lang=\"{}\">", lang.as_str()).unwrap();
+    out.push_str("<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(title) = meta.title {
+        writeln!(out, "<title>{}</title>", escape(&title)).unwrap();
+    }
+    if !meta.author.is_empty() {
+        let joined = meta.author.join(", ");
+        writeln!(out, "<meta name=\"author\" content=\"{}\">", escape(&joined)).unwrap();
+    }
+    if !meta.keywords.is_empty() {
+        let joined = meta.keywords.join(", ");
+        writeln!(out, "<meta name=\"keywords\" content=\"{}\">", escape(&joined))
+            .unwrap();
+    }
+    out.push_str("</head>\n<body>\n");
+    out.push_str(&body);
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Write the body of an HTML document and return it alongside the document's
+/// metadata and the images it references.
+///
+/// If `package` is true, images are referenced through paths in an `images`
+/// directory, into which they can then be packaged. Otherwise, they are
+/// referenced through the paths given in the source.
+fn body(
+    content: &Content,
+    styles: StyleChain,
+    package: bool,
+) -> (String, Metadata, Vec<ImageFile>) {
+    let (flow, meta) = tree::build(content, styles);
+    let mut writer = HtmlWriter { package, images: vec![] };
+    let body = writer.blocks(&flow);
+    (body, meta, writer.images)
+}
+
+/// An image that is referenced by exported HTML.
+#[derive(Debug, Clone)]
+struct ImageFile {
+    /// The path through which the image is referenced.
+    src: EcoString,
+    /// The image's raw data.
+    data: Bytes,
+    /// The image's media type.
+    media_type: &'static str,
+}
+
+/// Writes the HTML for the structure of a piece of content.
+struct HtmlWriter {
+    /// Whether images are referenced for packaging, see [`body`].
+    package: bool,
+    /// The images referenced so far.
+    images: Vec<ImageFile>,
+}
+
+impl HtmlWriter {
+    /// Write blocks.
+    fn blocks(&mut self, flow: &[Block]) -> String {
+        let mut out = String::new();
+        for block in flow {
+            self.block(&mut out, block);
+        }
+        out
+    }
+
+    /// Write blocks, without wrapping them in a paragraph if they consist of
+    /// nothing but inline content.
+    fn inner(&mut self, flow: &[Block]) -> String {
+        match flow {
+            [] => String::new(),
+            [Block::Par(par)] => self.par(par),
+            _ => self.blocks(flow),
+        }
+    }
+
+    /// Write the inline content of a paragraph.
+    fn par(&mut self, par: &[Inline]) -> String {
+        let mut out = String::new();
+        for inline in par {
+            out.push_str(&self.inline(inline));
+        }
+        out.trim().into()
+    }
+
+    /// Write a block.
+    fn block(&mut self, out: &mut String, block: &Block) {
+        match block {
+            Block::Par(par) => {
+                let par = self.par(par);
+                if !par.is_empty() {
+                    writeln!(out, "<p class=\"typst-par\">{par}</p>").unwrap();
+                }
+            }
+            Block::Heading { label, level, body } => {
+                let level = (*level).min(6);
+                let id = id(*label);
+                let body = self.inner(body);
+                writeln!(out, "<h{level}{id} class=\"typst-heading\">{body}</h{level}>")
+                    .unwrap();
+            }
+            Block::List { kind, start, items } => {
+                let (tag, class) = match kind {
+                    ListKind::Bullet => ("ul", "typst-list"),
+                    ListKind::Numbered => ("ol", "typst-enum"),
+                    ListKind::Terms => ("dl", "typst-terms"),
+                };
+                write!(out, "<{tag} class=\"{class}\"").unwrap();
+                if *kind == ListKind::Numbered && *start != 1 {
+                    write!(out, " start=\"{start}\"").unwrap();
+                }
+                out.push_str(">\n");
+                for item in items {
+                    let body = self.inner(&item.body);
+                    match &item.term {
+                        Some(term) => {
+                            let term = self.inner(term);
+                            writeln!(out, "<dt>{term}</dt>\n<dd>{body}</dd>").unwrap();
+                        }
+                        None => writeln!(out, "<li>{body}</li>").unwrap(),
+                    }
+                }
+                writeln!(out, "</{tag}>").unwrap();
+            }
+            Block::Table { label, columns, cells } => {
+                writeln!(out, "<table{} class=\"typst-table\">", id(*label)).unwrap();
+                for row in cells.chunks(*columns) {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        let cell = self.inner(cell);
+                        write!(out, "<td>{cell}</td>").unwrap();
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+            Block::Raw { label, lang, text } => {
+                write!(out, "<pre{} class=\"typst-raw\"><code", id(*label)).unwrap();
+                if let Some(lang) = lang {
+                    write!(out, " data-lang=\"{}\"", escape(lang)).unwrap();
+                }
+                writeln!(out, ">{}</code></pre>", escape(text)).unwrap();
+            }
+            Block::Equation { label, text } => {
+                let id = id(*label);
+                writeln!(out, "<div{id} class=\"typst-equation\">{}</div>", escape(text))
+                    .unwrap();
+            }
+            Block::Figure { label, body, caption } => {
+                let body = self.blocks(body);
+                writeln!(out, "<figure{} class=\"typst-figure\">", id(*label)).unwrap();
+                out.push_str(&body);
+                if let Some(caption) = caption {
+                    let caption = self.inner(caption);
+                    writeln!(out, "<figcaption>{caption}</figcaption>").unwrap();
+                }
+                out.push_str("</figure>\n");
+            }
+            Block::Quote { label, body } => {
+                let body = self.blocks(body);
+                writeln!(out, "<blockquote{} class=\"typst-quote\">", id(*label))
+                    .unwrap();
+                out.push_str(&body);
+                out.push_str("</blockquote>\n");
+            }
+            Block::Div { label, name, body } => {
+                let body = self.blocks(body);
+                writeln!(out, "<div{} class=\"typst-{name}\">", id(*label)).unwrap();
+                out.push_str(&body);
+                out.push_str("</div>\n");
+            }
+        }
+    }
+
+    /// Write inline content.
+    fn inline(&mut self, inline: &Inline) -> String {
+        match inline {
+            Inline::Space => " ".into(),
+            Inline::Text(text) | Inline::Plain(text) => escape(text),
+            Inline::Linebreak => "<br/>".into(),
+            Inline::Quote { double } => if *double { "\"" } else { "'" }.into(),
+            Inline::Strong(body) => format!("<strong>{}</strong>", self.inner(body)),
+            Inline::Emph(body) => format!("<em>{}</em>", self.inner(body)),
+            Inline::Raw(text) => {
+                format!("<code class=\"typst-raw\">{}</code>", escape(text))
+            }
+            Inline::Link { dest, body } => {
+                let body = self.inner(body);
+                match dest {
+                    LinkTarget::Dest(Destination::Url(url)) => format!(
+                        "<a class=\"typst-link\" href=\"{}\">{body}</a>",
+                        escape(url)
+                    ),
+                    LinkTarget::Label(label) => format!(
+                        "<a class=\"typst-link\" href=\"#{}\">{body}</a>",
+                        escape(label.as_str())
+                    ),
+                    LinkTarget::Dest(_) => format!("<a class=\"typst-link\">{body}</a>"),
+                }
+            }
+            Inline::Image { elem, alt, kind } => {
+                let src = escape(&self.image(elem, *kind));
+                let mut img = format!("<img class=\"typst-image\" src=\"{src}\"");
+                if let Some(alt) = alt {
+                    write!(img, " alt=\"{}\"", escape(alt)).unwrap();
+                }
+                img.push_str("/>");
+                img
+            }
+            Inline::Span { name, body } => {
+                // Elements we don't know about are transparent, but keep a CSS
+                // hook.
+                format!("<span class=\"typst-{name}\">{}</span>", self.inner(body))
+            }
+            Inline::Anchor(label, inline) => {
+                format!("<span{}>{}</span>", id(Some(*label)), self.inline(inline))
+            }
+        }
+    }
+
+    /// Determine the path through which an image is referenced, remembering
+    /// it for packaging if necessary.
+    fn image(
+        &mut self,
+        image: &ImageElem,
+        kind: Option<(&'static str, &'static str)>,
+    ) -> EcoString {
+        if !self.package {
+            return image.path().clone();
+        }
+
+        let data: Bytes = image.data().clone().into();
+        if let Some(file) = self.images.iter().find(|file| file.data == data) {
+            return file.src.clone();
+        }
+
+        let Some((extension, media_type)) = kind else {
+            return image.path().clone();
+        };
+
+        let src = eco_format!("images/image-{}.{extension}", self.images.len() + 1);
+        self.images.push(ImageFile { src: src.clone(), data, media_type });
+        src
+    }
+}
+
+/// The attribute through which labelled content can be linked to, if any.
+fn id(label: Option<Label>) -> String {
+    match label {
+        Some(label) => format!(" id=\"{}\"", escape(label.as_str())),
+        None => String::new(),
+    }
+}
+
+/// Determine the file extension and media type of an image.
+fn image_type(
+    image: &ImageElem,
+    styles: StyleChain,
+) -> Option<(&'static str, &'static str)> {
+    let format = match image.format(styles) {
+        Smart::Custom(format) => format,
+        Smart::Auto => {
+            let ext = Path::new(image.path().as_str())
+                .extension()
+                .and_then(OsStr::to_str)
+                .unwrap_or_default()
+                .to_lowercase();
+            match ext.as_str() {
+                "png" => RasterFormat::Png.into(),
+                "jpg" | "jpeg" => RasterFormat::Jpg.into(),
+                "gif" => RasterFormat::Gif.into(),
+                "svg" | "svgz" => VectorFormat::Svg.into(),
+                _ => match image.data() {
+                    Readable::Str(_) => VectorFormat::Svg.into(),
+                    Readable::Bytes(bytes) => RasterFormat::detect(bytes)?.into(),
+                },
+            }
+        }
+    };
+
+    Some(match format {
+        ImageFormat::Raster(RasterFormat::Png) => ("png", "image/png"),
+        ImageFormat::Raster(RasterFormat::Jpg) => ("jpg", "image/jpeg"),
+        ImageFormat::Raster(RasterFormat::Gif) => ("gif", "image/gif"),
+        ImageFormat::Vector(VectorFormat::Svg) => ("svg", "image/svg+xml"),
+    })
+}
+
+/// Escape text for use in HTML text and attribute values.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,278 @@
+use std::fmt::Write;
+
+use typst::foundations::{Content, StyleChain};
+use typst::model::{Destination, LinkTarget};
+
+use crate::tree::{self, Block, Inline, Item, ListKind};
+
+/// Export content into a CommonMark document.
+///
+/// Headings, paragraphs, lists, raw text, links, and images are preserved.
+/// Tables are written in the widespread pipe syntax. Everything else is
+/// flattened to its text.
+#[tracing::instrument(skip_all)]
+pub fn markdown(content: &Content, styles: StyleChain) -> String {
+    write(content, styles, Flavor::Markdown)
+}
+
+/// Export content into plain text.
+///
+/// Paragraphs are separated by blank lines. Headings and list items keep their
+/// own lines, the latter prefixed with their marker, but all other markup is
+/// dropped.
+#[tracing::instrument(skip_all)]
+pub fn text(content: &Content, styles: StyleChain) -> String {
+    write(content, styles, Flavor::Plain)
+}
+
+/// Write content in the given flavor.
+fn write(content: &Content, styles: StyleChain, flavor: Flavor) -> String {
+    let (flow, _) = tree::build(content, styles);
+    let mut out = TextWriter { flavor }.blocks(&flow);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// The output format of a [`TextWriter`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Flavor {
+    Plain,
+    Markdown,
+}
+
+/// Writes the text for the structure of a piece of content.
+struct TextWriter {
+    flavor: Flavor,
+}
+
+impl TextWriter {
+    fn markdown(&self) -> bool {
+        self.flavor == Flavor::Markdown
+    }
+
+    /// Write blocks, separated by blank lines.
+    fn blocks(&self, flow: &[Block]) -> String {
+        let blocks: Vec<_> = flow
+            .iter()
+            .map(|block| self.block(block))
+            .filter(|block| !block.is_empty())
+            .collect();
+        blocks.join("\n\n")
+    }
+
+    /// Write a block.
+    fn block(&self, block: &Block) -> String {
+        match block {
+            Block::Par(par) => {
+                let par: String = par.iter().map(|inline| self.inline(inline)).collect();
+                par.trim().into()
+            }
+            Block::Heading { level, body, .. } => {
+                let body = self.blocks(body).replace('\n', " ");
+                if self.markdown() {
+                    format!("{} {body}", "#".repeat((*level).min(6)))
+                } else {
+                    body
+                }
+            }
+            Block::List { kind, start, items } => self.list(*kind, *start, items),
+            Block::Table { columns, cells, .. } => self.table(*columns, cells),
+            Block::Raw { lang, text, .. } => {
+                let text = text.trim_end();
+                if self.markdown() {
+                    let fence = fence(text, '`', 3);
+                    let lang = lang.clone().unwrap_or_default();
+                    format!("{fence}{lang}\n{text}\n{fence}")
+                } else {
+                    text.into()
+                }
+            }
+            Block::Equation { text, .. } => text.trim().into(),
+            Block::Figure { body, caption, .. } => {
+                let mut inner = self.blocks(body);
+                if let Some(caption) = caption {
+                    inner.push_str("\n\n");
+                    inner.push_str(&self.blocks(caption));
+                }
+                inner.trim().into()
+            }
+            Block::Quote { body, .. } => {
+                let body = self.blocks(body);
+                if !self.markdown() {
+                    return body;
+                }
+                let lines: Vec<_> =
+                    body.lines()
+                        .map(|line| {
+                            if line.is_empty() {
+                                ">".into()
+                            } else {
+                                format!("> {line}")
+                            }
+                        })
+                        .collect();
+                lines.join("\n")
+            }
+            Block::Div { body, .. } => self.blocks(body),
+        }
+    }
+
+    /// Write a list, with one item per line.
+    fn list(&self, kind: ListKind, start: usize, items: &[Item]) -> String {
+        let mut out = String::new();
+        for (i, item) in items.iter().enumerate() {
+            let marker = match kind {
+                ListKind::Numbered => format!("{}. ", start + i),
+                ListKind::Bullet | ListKind::Terms => "- ".into(),
+            };
+
+            let body = self.blocks(&item.body);
+            let text = match &item.term {
+                Some(term) if self.markdown() => {
+                    format!("**{}**: {body}", self.blocks(term))
+                }
+                Some(term) => format!("{}: {body}", self.blocks(term)),
+                None => body,
+            };
+
+            // Indent continuation lines so that they belong to the item.
+            let indent = " ".repeat(marker.chars().count());
+            out.push_str(&marker);
+            for (i, line) in text.trim().lines().enumerate() {
+                if i > 0 {
+                    out.push('\n');
+                    if !line.is_empty() {
+                        out.push_str(&indent);
+                    }
+                }
+                out.push_str(line);
+            }
+            out.push('\n');
+        }
+        out.trim_end().into()
+    }
+
+    /// Write a table, splitting its cells into rows.
+    fn table(&self, columns: usize, cells: &[tree::Flow]) -> String {
+        let separator = if self.markdown() { " | " } else { "\t" };
+        let mut out = String::new();
+        for (i, row) in cells.chunks(columns).enumerate() {
+            let cells: Vec<_> = row
+                .iter()
+                .map(|cell| {
+                    let cell = self.blocks(cell).replace('\n', " ");
+                    if self.markdown() {
+                        cell.replace('|', "\\|")
+                    } else {
+                        cell
+                    }
+                })
+                .collect();
+
+            if self.markdown() {
+                writeln!(out, "| {} |", cells.join(separator)).unwrap();
+                if i == 0 {
+                    writeln!(out, "|{}", " --- |".repeat(columns)).unwrap();
+                }
+            } else {
+                writeln!(out, "{}", cells.join(separator)).unwrap();
+            }
+        }
+        out.trim_end().into()
+    }
+
+    /// Write inline content.
+    fn inline(&self, inline: &Inline) -> String {
+        let markdown = self.markdown();
+        match inline {
+            Inline::Space => " ".into(),
+            Inline::Text(text) | Inline::Plain(text) => {
+                if markdown {
+                    escape(text)
+                } else {
+                    text.to_string()
+                }
+            }
+            Inline::Linebreak => if markdown { "\\\n" } else { "\n" }.to_string(),
+            Inline::Quote { double } => if *double { "\"" } else { "'" }.to_string(),
+            Inline::Strong(body) => {
+                let body = self.blocks(body);
+                if markdown {
+                    format!("**{body}**")
+                } else {
+                    body
+                }
+            }
+            Inline::Emph(body) => {
+                let body = self.blocks(body);
+                if markdown {
+                    format!("*{body}*")
+                } else {
+                    body
+                }
+            }
+            Inline::Raw(text) => {
+                if markdown {
+                    let fence = fence(text, '`', 1);
+                    let padded = text.starts_with('`') || text.ends_with('`');
+                    let pad = if padded { " " } else { "" };
+                    format!("{fence}{pad}{text}{pad}{fence}")
+                } else {
+                    text.to_string()
+                }
+            }
+            Inline::Link { dest, body } => {
+                let body = self.blocks(body);
+                match dest {
+                    LinkTarget::Dest(Destination::Url(url)) if markdown => {
+                        format!("[{body}](<{url}>)")
+                    }
+                    LinkTarget::Label(label) if markdown => {
+                        format!("[{body}](#{})", label.as_str())
+                    }
+                    _ => body,
+                }
+            }
+            Inline::Image { elem, alt, .. } => {
+                let alt = alt.clone().unwrap_or_default();
+                if markdown {
+                    format!("![{}](<{}>)", escape(&alt), elem.path())
+                } else {
+                    alt.into()
+                }
+            }
+            Inline::Span { body, .. } => self.blocks(body),
+            Inline::Anchor(_, inline) => self.inline(inline),
+        }
+    }
+}
+
+/// Create a fence for raw text that is longer than any run of the fence
+/// character within the text.
+fn fence(text: &str, c: char, min: usize) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for d in text.chars() {
+        if d == c {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    c.to_string().repeat(min.max(longest + 1))
+}
+
+/// Escape characters that have a meaning in CommonMark.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,211 @@
+use std::io::{Cursor, Read};
+
+use typst::eval::Tracer;
+use typst::foundations::{Content, StyleChain};
+use typst::model::Document;
+use typst::World;
+use typst_tests::TestWorld;
+use zip::ZipArchive;
+
+use crate::{epub, html, markdown, text, EpubOptions};
+
+/// Compile a world's main file and realize its structure.
+fn realize(world: &TestWorld) -> (Document, Content) {
+    let mut tracer = Tracer::new();
+    let document = typst::compile(world, &mut tracer).expect("document should compile");
+    let content =
+        typst::structure(world, &mut tracer, &document).expect("document should realize");
+    (document, content)
+}
+
+/// Compile markup and export it with one of the structural exporters.
+pub fn export(text: &str, f: fn(&Content, StyleChain) -> String) -> String {
+    let world = TestWorld::new(text);
+    let (_, content) = realize(&world);
+    f(&content, StyleChain::new(&world.library().styles))
+}
+
+/// Compile markup, export it into an EPUB file, and read the file back.
+fn book(text: &str) -> ZipArchive<Cursor<Vec<u8>>> {
+    let world = TestWorld::new(text);
+    let (document, content) = realize(&world);
+    let styles = StyleChain::new(&world.library().styles);
+    let buffer = epub(&document, &content, styles, &EpubOptions::default())
+        .expect("document should export");
+    ZipArchive::new(Cursor::new(buffer)).unwrap()
+}
+
+/// Read an entry of an EPUB file.
+fn entry(book: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+    let mut text = String::new();
+    book.by_name(name).unwrap().read_to_string(&mut text).unwrap();
+    text
+}
+
+/// Export markup into HTML and return the contents of the body.
+fn body(text: &str) -> String {
+    let html = export(text, html);
+    let start = html.find("<body>\n").unwrap() + "<body>\n".len();
+    let end = html.find("</body>").unwrap();
+    html[start..end].into()
+}
+
+#[test]
+fn test_html_headings() {
+    assert_eq!(
+        body("= Intro\nSome *bold* text."),
+        "<h1 class=\"typst-heading\">Intro</h1>\n\
+         <p class=\"typst-par\">Some <strong>bold</strong> text.</p>\n",
+    );
+}
+
+#[test]
+fn test_html_show_rules() {
+    assert_eq!(
+        body("#show heading: it => [Chapter: #it.body]\n= Intro"),
+        "<p class=\"typst-par\">Chapter: Intro</p>\n",
+    );
+}
+
+#[test]
+fn test_html_lists() {
+    assert_eq!(
+        body("- a\n- b"),
+        "<ul class=\"typst-list\">\n<li>a</li>\n<li>b</li>\n</ul>\n",
+    );
+    assert_eq!(
+        body("+ a\n+ b"),
+        "<ol class=\"typst-enum\">\n<li>a</li>\n<li>b</li>\n</ol>\n",
+    );
+}
+
+#[test]
+fn test_html_links() {
+    assert_eq!(
+        body("#link(\"https://typst.app\")[Typst]"),
+        "<p class=\"typst-par\">\
+         <a class=\"typst-link\" href=\"https://typst.app\">Typst</a></p>\n",
+    );
+}
+
+#[test]
+fn test_html_tables() {
+    assert_eq!(
+        body("#table(columns: 2, [a], [b], [c], [d])"),
+        "<table class=\"typst-table\">\n\
+         <tr><td>a</td><td>b</td></tr>\n\
+         <tr><td>c</td><td>d</td></tr>\n\
+         </table>\n",
+    );
+}
+
+#[test]
+fn test_html_math_fallback() {
+    assert_eq!(body("$ x $"), "<div class=\"typst-equation\">x</div>\n");
+    assert_eq!(
+        body("Let $x$ be."),
+        "<p class=\"typst-par\">Let <span class=\"typst-equation\">x</span> be.</p>\n",
+    );
+}
+
+#[test]
+fn test_html_labels() {
+    assert_eq!(
+        body("= Intro <intro>\nSee #link(<intro>)[there]."),
+        "<h1 id=\"intro\" class=\"typst-heading\">Intro</h1>\n\
+         <p class=\"typst-par\">See <a class=\"typst-link\" href=\"#intro\">there</a>.</p>\n",
+    );
+}
+
+#[test]
+fn test_epub_links_between_chapters() {
+    let mut book = book("= One\nOn to #link(<two>)[two].\n= Two <two>\nBack.");
+    let first = entry(&mut book, "EPUB/chapter-1.xhtml");
+    assert!(first.contains("href=\"chapter-2.xhtml#two\""));
+    let second = entry(&mut book, "EPUB/chapter-2.xhtml");
+    assert!(second.contains("<h1 id=\"two\" class=\"typst-heading\">Two</h1>"));
+}
+
+#[test]
+fn test_epub_packages_images() {
+    let svg = r#"<svg xmlns='http://www.w3.org/2000/svg' width='10' height='10'/>"#;
+    let mut book = book(&format!("#image.decode(\"{svg}\")"));
+    let chapter = entry(&mut book, "EPUB/chapter-1.xhtml");
+    assert!(chapter.contains("src=\"images/image-1.svg\""));
+    assert_eq!(entry(&mut book, "EPUB/images/image-1.svg"), svg);
+    let package = entry(&mut book, "EPUB/package.opf");
+    assert!(package.contains(
+        "<item id=\"image-1\" href=\"images/image-1.svg\" media-type=\"image/svg+xml\"/>"
+    ));
+}
+
+#[test]
+fn test_epub_embeds_subset_fonts() {
+    let mut book = book("= Intro\nSome text.");
+    let package = entry(&mut book, "EPUB/package.opf");
+    assert!(package.contains(
+        "<item id=\"font-1\" href=\"fonts/font-1.ttf\" media-type=\"font/ttf\"/>"
+    ));
+    assert!(package
+        .contains("<item id=\"style\" href=\"style.css\" media-type=\"text/css\"/>"));
+
+    let css = entry(&mut book, "EPUB/style.css");
+    assert!(css.contains("font-family: \"Linux Libertine\";"));
+    assert!(css.contains("src: url(\"fonts/font-1.ttf\");"));
+    let chapter = entry(&mut book, "EPUB/chapter-1.xhtml");
+    assert!(chapter
+        .contains("<link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>"));
+
+    // The subset maps the shown characters, but no others.
+    let mut data = vec![];
+    book.by_name("EPUB/fonts/font-1.ttf")
+        .unwrap()
+        .read_to_end(&mut data)
+        .unwrap();
+    let face = ttf_parser::Face::parse(&data, 0).unwrap();
+    assert!(face.glyph_index('S').is_some());
+    assert!(face.glyph_index('x').is_some());
+    assert!(face.glyph_index('Q').is_none());
+}
+
+#[test]
+fn test_markdown_blocks() {
+    assert_eq!(
+        export("= Intro\nSome *bold* and _emph_ text.", markdown),
+        "# Intro\n\nSome **bold** and *emph* text.\n",
+    );
+    assert_eq!(
+        export("```rust\nfn main() {}\n```", markdown),
+        "```rust\nfn main() {}\n```\n",
+    );
+}
+
+#[test]
+fn test_markdown_lists() {
+    assert_eq!(
+        export("- a\n- b\n\n+ one\n+ two", markdown),
+        "- a\n- b\n\n1. one\n2. two\n",
+    );
+}
+
+#[test]
+fn test_markdown_inline() {
+    assert_eq!(
+        export("#link(\"https://typst.app\")[Typst] and `code`", markdown),
+        "[Typst](<https://typst.app>) and `code`\n",
+    );
+    assert_eq!(export("a\\*b", markdown), "a\\*b\n");
+}
+
+#[test]
+fn test_markdown_tables() {
+    assert_eq!(
+        export("#table(columns: 2, [a], [b], [c], [d])", markdown),
+        "| a | b |\n| --- | --- |\n| c | d |\n",
+    );
+}
+
+#[test]
+fn test_plain_text() {
+    assert_eq!(export("= Intro\n- a\n- *b*", text), "Intro\n\n- a\n- b\n");
+}
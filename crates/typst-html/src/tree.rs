@@ -0,0 +1,347 @@
+use ecow::EcoString;
+use typst::foundations::{Content, Label, StyleChain, Value};
+use typst::layout::{AlignElem, BlockElem, ColumnsElem, PadElem, PageElem};
+use typst::math::EquationElem;
+use typst::model::{
+    DocumentElem, EmphElem, EnumElem, EnumItem, FigureElem, HeadingElem, LinkElem,
+    LinkTarget, ListElem, ListItem, ParbreakElem, QuoteElem, StrongElem, TableElem,
+    TermItem, TermsElem,
+};
+use typst::text::{LinebreakElem, RawElem, SmartQuoteElem, SpaceElem, TextElem};
+use typst::visualize::ImageElem;
+
+use crate::image_type;
+
+/// Build the structure of a piece of content, which all structural exporters
+/// then write in their own format.
+///
+/// Returns the blocks alongside the document metadata.
+pub(crate) fn build(content: &Content, styles: StyleChain) -> (Flow, Metadata) {
+    let mut builder = Builder::default();
+    builder.node(content, styles);
+    let meta = std::mem::take(&mut builder.meta);
+    (builder.finish(), meta)
+}
+
+/// A sequence of blocks.
+pub(crate) type Flow = Vec<Block>;
+
+/// Document metadata picked up from `document` set rules.
+#[derive(Debug, Default)]
+pub(crate) struct Metadata {
+    /// The document title, if any was set.
+    pub title: Option<EcoString>,
+    /// The document's authors.
+    pub author: Vec<EcoString>,
+    /// The document's keywords.
+    pub keywords: Vec<EcoString>,
+}
+
+/// Block-level content.
+#[derive(Debug)]
+pub(crate) enum Block {
+    /// A paragraph.
+    Par(Vec<Inline>),
+    /// A heading with its level.
+    Heading { label: Option<Label>, level: usize, body: Flow },
+    /// A list with the number of its first item, which is only relevant for
+    /// numbered lists.
+    List { kind: ListKind, start: usize, items: Vec<Item> },
+    /// A table with its number of columns and its cells in row-major order.
+    Table { label: Option<Label>, columns: usize, cells: Vec<Flow> },
+    /// A raw block with its language.
+    Raw { label: Option<Label>, lang: Option<EcoString>, text: EcoString },
+    /// A block-level equation, flattened to its text.
+    Equation { label: Option<Label>, text: EcoString },
+    /// A figure with its caption.
+    Figure { label: Option<Label>, body: Flow, caption: Option<Flow> },
+    /// A block quote.
+    Quote { label: Option<Label>, body: Flow },
+    /// A container with the name of its element, e.g. `block` or `align`.
+    Div { label: Option<Label>, name: &'static str, body: Flow },
+}
+
+/// The kind of a list.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum ListKind {
+    Bullet,
+    Numbered,
+    Terms,
+}
+
+/// An item of a list.
+#[derive(Debug)]
+pub(crate) struct Item {
+    /// The term that is described, for items of a term list.
+    pub term: Option<Flow>,
+    /// The body of a bullet or numbered item or the description of a term.
+    pub body: Flow,
+}
+
+/// Inline content.
+#[derive(Debug)]
+pub(crate) enum Inline {
+    /// A space between words.
+    Space,
+    /// Text.
+    Text(EcoString),
+    /// A line break.
+    Linebreak,
+    /// A quote, which is either double or single.
+    Quote { double: bool },
+    /// Strongly emphasized content.
+    Strong(Flow),
+    /// Emphasized content.
+    Emph(Flow),
+    /// Inline raw text.
+    Raw(EcoString),
+    /// A link.
+    Link { dest: LinkTarget, body: Flow },
+    /// An image with its description and, if it could be determined, its file
+    /// extension and media type.
+    Image {
+        elem: ImageElem,
+        alt: Option<EcoString>,
+        kind: Option<(&'static str, &'static str)>,
+    },
+    /// An element that is not known to the exporters, but has a body.
+    Span { name: &'static str, body: Flow },
+    /// Anything else, flattened to its text.
+    Plain(EcoString),
+    /// Labelled inline content.
+    Anchor(Label, Box<Inline>),
+}
+
+impl Inline {
+    /// Whether this inline content is a space.
+    fn is_space(&self) -> bool {
+        matches!(self, Self::Space)
+    }
+}
+
+/// Builds the structure of a piece of content.
+#[derive(Default)]
+struct Builder {
+    /// The finished blocks.
+    blocks: Flow,
+    /// The inline content of the current paragraph.
+    par: Vec<Inline>,
+    /// The list that is currently being built from consecutive items.
+    list: Option<(ListKind, usize, Vec<Item>)>,
+    /// The document metadata found so far.
+    meta: Metadata,
+}
+
+impl Builder {
+    /// Finish building and return the blocks.
+    fn finish(mut self) -> Flow {
+        self.flush();
+        self.blocks
+    }
+
+    /// Finish the pending paragraph and close the pending list.
+    fn flush(&mut self) {
+        self.close_list();
+        let par = std::mem::take(&mut self.par);
+        if !par.iter().all(Inline::is_space) {
+            self.blocks.push(Block::Par(par));
+        }
+    }
+
+    /// Close the list that is being built from consecutive items.
+    fn close_list(&mut self) {
+        if let Some((kind, start, items)) = self.list.take() {
+            self.blocks.push(Block::List { kind, start, items });
+        }
+    }
+
+    /// Add an item to the list that is being built, opening a new list if
+    /// the kind of list changes.
+    fn push_item(&mut self, kind: ListKind, start: usize, item: Item) {
+        if !matches!(&self.list, Some((open, ..)) if *open == kind) {
+            self.flush();
+            self.list = Some((kind, start, vec![]));
+        }
+        self.list.as_mut().unwrap().2.push(item);
+    }
+
+    /// Build any piece of content.
+    fn node(&mut self, content: &Content, styles: StyleChain) {
+        if let Some(children) = content.to_sequence() {
+            for child in children {
+                self.node(child, styles);
+            }
+        } else if let Some((inner, local)) = content.to_styled() {
+            let styles = styles.chain(local);
+            if self.meta.title.is_none() {
+                self.meta.title =
+                    DocumentElem::title_in(styles).map(|title| title.plain_text());
+            }
+            if self.meta.author.is_empty() {
+                self.meta.author = DocumentElem::author_in(styles).0;
+            }
+            if self.meta.keywords.is_empty() {
+                self.meta.keywords = DocumentElem::keywords_in(styles).0;
+            }
+            self.node(inner, styles);
+        } else if content.is::<SpaceElem>() {
+            if self.list.is_none() {
+                self.par.push(Inline::Space);
+            }
+        } else if content.is::<ParbreakElem>() {
+            if self.list.is_none() {
+                self.flush();
+            }
+        } else if let Some(item) = content.to::<ListItem>() {
+            let item = Item { term: None, body: flow(item.body(), styles) };
+            self.push_item(ListKind::Bullet, 1, item);
+        } else if let Some(item) = content.to::<EnumItem>() {
+            let start = item.number(styles).unwrap_or(1);
+            let item = Item { term: None, body: flow(item.body(), styles) };
+            self.push_item(ListKind::Numbered, start, item);
+        } else if let Some(item) = content.to::<TermItem>() {
+            self.push_item(ListKind::Terms, 1, term(item, styles));
+        } else if let Some(block) = block(content, styles) {
+            self.flush();
+            self.blocks.push(block);
+        } else if content.is::<PageElem>() {
+            // Pagination is not preserved, but the body of a page is.
+            self.flush();
+            if let Some(Value::Content(body)) = content.get_by_name("body") {
+                self.node(&body, styles);
+                self.flush();
+            }
+        } else {
+            self.close_list();
+            self.par.push(inline(content, styles));
+        }
+    }
+}
+
+/// Try to build block-level content.
+fn block(content: &Content, styles: StyleChain) -> Option<Block> {
+    let label = content.label();
+    Some(if let Some(heading) = content.to::<HeadingElem>() {
+        let level = heading.level(styles).get();
+        Block::Heading { label, level, body: flow(heading.body(), styles) }
+    } else if let Some(list) = content.to::<ListElem>() {
+        let items = list
+            .children()
+            .iter()
+            .map(|item| Item { term: None, body: flow(item.body(), styles) })
+            .collect();
+        Block::List { kind: ListKind::Bullet, start: 1, items }
+    } else if let Some(list) = content.to::<EnumElem>() {
+        let items = list
+            .children()
+            .iter()
+            .map(|item| Item { term: None, body: flow(item.body(), styles) })
+            .collect();
+        Block::List {
+            kind: ListKind::Numbered,
+            start: list.start(styles),
+            items,
+        }
+    } else if let Some(list) = content.to::<TermsElem>() {
+        let items = list.children().iter().map(|item| term(item, styles)).collect();
+        Block::List { kind: ListKind::Terms, start: 1, items }
+    } else if let Some(table) = content.to::<TableElem>() {
+        let columns = table.columns(styles).0.len().max(1);
+        let cells = table.children().iter().map(|cell| flow(cell, styles)).collect();
+        Block::Table { label, columns, cells }
+    } else if let Some(raw) = content.to::<RawElem>() {
+        if !raw.block(styles) {
+            return None;
+        }
+        let lang = raw.lang(styles).clone();
+        Block::Raw { label, lang, text: raw.text().clone() }
+    } else if let Some(equation) = content.to::<EquationElem>() {
+        if !equation.block(styles) {
+            return None;
+        }
+        Block::Equation { label, text: equation.body().plain_text() }
+    } else if let Some(figure) = content.to::<FigureElem>() {
+        let body = flow(&figure.described_body(styles), styles);
+        let caption = figure.caption(styles).map(|caption| flow(caption.body(), styles));
+        Block::Figure { label, body, caption }
+    } else if content.is::<QuoteElem>() && is_block_quote(content) {
+        Block::Quote { label, body: body(content, styles) }
+    } else if content.is::<BlockElem>()
+        || content.is::<AlignElem>()
+        || content.is::<PadElem>()
+        || content.is::<ColumnsElem>()
+    {
+        let name = content.func().name();
+        Block::Div { label, name, body: body(content, styles) }
+    } else {
+        return None;
+    })
+}
+
+/// Build content in a nested builder.
+fn flow(content: &Content, styles: StyleChain) -> Flow {
+    let mut builder = Builder::default();
+    builder.node(content, styles);
+    builder.finish()
+}
+
+/// Build the body of an element, if it has one.
+fn body(content: &Content, styles: StyleChain) -> Flow {
+    match content.get_by_name("body") {
+        Some(Value::Content(body)) => flow(&body, styles),
+        _ => vec![],
+    }
+}
+
+/// Build an item of a term list.
+fn term(item: &TermItem, styles: StyleChain) -> Item {
+    Item {
+        term: Some(flow(item.term(), styles)),
+        body: flow(item.description(), styles),
+    }
+}
+
+/// Build inline content.
+fn inline(content: &Content, styles: StyleChain) -> Inline {
+    let inline = if let Some(text) = content.to::<TextElem>() {
+        Inline::Text(text.text().clone())
+    } else if content.is::<LinebreakElem>() {
+        Inline::Linebreak
+    } else if let Some(quote) = content.to::<SmartQuoteElem>() {
+        Inline::Quote { double: quote.double(styles) }
+    } else if let Some(strong) = content.to::<StrongElem>() {
+        Inline::Strong(flow(strong.body(), styles))
+    } else if let Some(emph) = content.to::<EmphElem>() {
+        Inline::Emph(flow(emph.body(), styles))
+    } else if let Some(raw) = content.to::<RawElem>() {
+        Inline::Raw(raw.text().clone())
+    } else if let Some(link) = content.to::<LinkElem>() {
+        Inline::Link {
+            dest: link.dest().clone(),
+            body: flow(link.body(), styles),
+        }
+    } else if let Some(image) = content.to::<ImageElem>() {
+        Inline::Image {
+            elem: image.clone(),
+            alt: image.alt(styles),
+            kind: image_type(image, styles),
+        }
+    } else if let Some(Value::Content(body)) = content.get_by_name("body") {
+        Inline::Span {
+            name: content.func().name(),
+            body: flow(&body, styles),
+        }
+    } else {
+        Inline::Plain(content.plain_text())
+    };
+
+    match content.label() {
+        Some(label) => Inline::Anchor(label, Box::new(inline)),
+        None => inline,
+    }
+}
+
+/// Whether a quote element should be displayed as a block.
+fn is_block_quote(content: &Content) -> bool {
+    matches!(content.get_by_name("block"), Some(Value::Bool(true)))
+}
@@ -1,5 +1,6 @@
 use std::cmp::Reverse;
 use std::collections::{BTreeSet, HashSet};
+use std::path::Path;
 
 use ecow::{eco_format, EcoString};
 use if_chain::if_chain;
@@ -44,6 +45,7 @@ pub fn autocomplete(
         || complete_field_accesses(&mut ctx)
         || complete_open_labels(&mut ctx)
         || complete_imports(&mut ctx)
+        || complete_paths(&mut ctx)
         || complete_rules(&mut ctx)
         || complete_params(&mut ctx)
         || complete_markup(&mut ctx)
@@ -502,6 +504,55 @@ fn complete_imports(ctx: &mut CompletionContext) -> bool {
     false
 }
 
+/// Complete file paths in strings.
+fn complete_paths(ctx: &mut CompletionContext) -> bool {
+    // In the path of an import or include: "#import "|",
+    // or of a function that loads a file: "#image("|".
+    if_chain! {
+        if let Some(ast::Expr::Str(str)) = ctx.leaf.cast();
+        if !str.get().starts_with('@');
+        if let Some(extensions) = path_extensions(&ctx.leaf);
+        then {
+            ctx.from = ctx.leaf.offset();
+            ctx.path_completions(extensions);
+            return true;
+        }
+    }
+
+    false
+}
+
+/// The file extensions that make sense for a string if it is a path. An empty
+/// list permits all files.
+fn path_extensions(leaf: &LinkedNode) -> Option<&'static [&'static str]> {
+    let parent = leaf.parent()?;
+    if matches!(parent.kind(), SyntaxKind::ModuleImport | SyntaxKind::ModuleInclude) {
+        return Some(&["typ"]);
+    }
+
+    // The path is always the first positional argument.
+    if parent.kind() != SyntaxKind::Args
+        || leaf.prev_sibling_kind() != Some(SyntaxKind::LeftParen)
+    {
+        return None;
+    }
+
+    let call = parent.parent()?.get().cast::<ast::FuncCall>()?;
+    let ast::Expr::Ident(callee) = call.callee() else { return None };
+    Some(match callee.as_str() {
+        "image" => &["png", "jpg", "jpeg", "gif", "svg"],
+        "bibliography" => &["bib", "yml", "yaml"],
+        "csv" => &["csv"],
+        "json" => &["json"],
+        "toml" => &["toml"],
+        "yaml" => &["yaml", "yml"],
+        "xml" => &["xml"],
+        "cbor" => &["cbor"],
+        "read" => &[],
+        _ => return None,
+    })
+}
+
 /// Add completions for all exports of a module.
 fn import_item_completions<'a>(
     ctx: &mut CompletionContext<'a>,
@@ -1074,6 +1125,35 @@ impl<'a> CompletionContext<'a> {
         }
     }
 
+    /// Add completions for the paths of project files with one of the given
+    /// extensions, relative to the current file.
+    fn path_completions(&mut self, extensions: &[&str]) {
+        let Some(current) = self.leaf.span().id() else { return };
+        let dir = current.vpath().as_rooted_path().parent().unwrap_or(Path::new("/"));
+        let mut paths: Vec<_> = self
+            .world
+            .files()
+            .into_iter()
+            .filter(|&id| id != current && id.package() == current.package())
+            .map(|id| id.vpath().as_rooted_path())
+            .filter(|path| {
+                extensions.is_empty()
+                    || path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| {
+                        extensions.iter().any(|e| ext.eq_ignore_ascii_case(e))
+                    })
+            })
+            .map(|path| {
+                let path = path.strip_prefix(dir).unwrap_or(path);
+                path.to_string_lossy().replace('\\', "/")
+            })
+            .collect();
+
+        paths.sort();
+        for path in paths {
+            self.value_completion(None, &Value::Str(path.as_str().into()), false, None);
+        }
+    }
+
     /// Add completions for raw block tags.
     fn raw_completions(&mut self) {
         for (name, mut tags) in RawElem::languages() {
@@ -0,0 +1,224 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+use typst::syntax::{ast, LinkedNode, Source, Span, SyntaxKind};
+use typst::World;
+
+/// The definition of an identifier.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Definition {
+    /// The name of the defined item.
+    pub name: EcoString,
+    /// The span of the identifier that binds the item.
+    pub span: Span,
+}
+
+/// Find the definition of the identifier under the cursor.
+///
+/// Bindings from `let`, closure parameters, `for` loops, and imports are
+/// resolved syntactically. Items that are imported from another file are
+/// followed into that file. Items from the standard library don't have a
+/// definition.
+pub fn definition(
+    world: &dyn World,
+    source: &Source,
+    cursor: usize,
+) -> Option<Definition> {
+    let leaf = LinkedNode::new(source.root()).leaf_at(cursor)?;
+    let binding = find_binding(&leaf)?;
+    Some(follow_import(world, &binding).unwrap_or_else(|| Definition {
+        name: binding.text().clone(),
+        span: binding.span(),
+    }))
+}
+
+/// Find all references to the binding under the cursor within its file,
+/// including the binding itself.
+pub fn references(source: &Source, cursor: usize) -> Vec<Range<usize>> {
+    let root = LinkedNode::new(source.root());
+    let Some(binding) = root.leaf_at(cursor).and_then(|leaf| find_binding(&leaf)) else {
+        return vec![];
+    };
+
+    let mut ranges = vec![];
+    collect_references(&root, &binding, &mut ranges);
+    ranges
+}
+
+/// Collect the ranges of all identifiers that resolve to the `binding`.
+fn collect_references(
+    node: &LinkedNode,
+    binding: &LinkedNode,
+    ranges: &mut Vec<Range<usize>>,
+) {
+    if is_ident(node)
+        && node.text() == binding.text()
+        && find_binding(node).is_some_and(|found| found.span() == binding.span())
+    {
+        ranges.push(node.range());
+    }
+
+    for child in node.children() {
+        collect_references(&child, binding, ranges);
+    }
+}
+
+/// Find the identifier that binds the given identifier by walking backwards
+/// through the scopes that are visible from it.
+///
+/// Returns the identifier itself if it is a binding.
+fn find_binding<'a>(leaf: &LinkedNode<'a>) -> Option<LinkedNode<'a>> {
+    if !is_ident(leaf) || !is_reference(leaf) {
+        return None;
+    }
+
+    let name = leaf.text();
+    let mut ancestor = leaf.clone();
+    loop {
+        let mut sibling = Some(ancestor.clone());
+        while let Some(node) = sibling {
+            if let Some(found) = bound_by_sibling(&node, name, leaf) {
+                return Some(found);
+            }
+            sibling = node.prev_sibling();
+        }
+
+        let parent = ancestor.parent()?.clone();
+        if let Some(found) = bound_by_parent(&parent, &ancestor, name) {
+            return Some(found);
+        }
+
+        ancestor = parent;
+    }
+}
+
+/// Find a binding of `name` in a `let` binding or an import that precedes
+/// the identifier (or contains it).
+fn bound_by_sibling<'a>(
+    node: &LinkedNode<'a>,
+    name: &str,
+    leaf: &LinkedNode,
+) -> Option<LinkedNode<'a>> {
+    let idents = if let Some(binding) = node.get().cast::<ast::LetBinding>() {
+        let inside = node.range().contains(&leaf.offset());
+        match binding.kind() {
+            // A normal binding isn't visible in its own initializer.
+            ast::LetBindingKind::Normal(pattern) if inside => pattern
+                .idents()
+                .into_iter()
+                .filter(|ident| ident.span() == leaf.span())
+                .collect(),
+            kind => kind.idents(),
+        }
+    } else if let Some(import) = node.get().cast::<ast::ModuleImport>() {
+        let mut idents: Vec<_> = match import.imports() {
+            Some(ast::Imports::Items(items)) => {
+                items.iter().map(ast::ImportItem::bound_name).collect()
+            }
+            _ => vec![],
+        };
+        idents.extend(import.new_name());
+        idents
+    } else {
+        return None;
+    };
+
+    let ident = idents.into_iter().rev().find(|ident| ident.as_str() == name)?;
+    node.find(ident.span())
+}
+
+/// Find a binding of `name` that a parent introduces for its `child`, like
+/// the parameters of a closure for its body.
+fn bound_by_parent<'a>(
+    parent: &LinkedNode<'a>,
+    child: &LinkedNode,
+    name: &str,
+) -> Option<LinkedNode<'a>> {
+    let idents = if let Some(v) = parent.get().cast::<ast::ForLoop>() {
+        if child.prev_sibling_kind() == Some(SyntaxKind::In) {
+            return None;
+        }
+        v.pattern().idents()
+    } else if let Some(v) = parent.get().cast::<ast::Closure>() {
+        // The parameters aren't visible from the closure's name.
+        if v.name().is_some_and(|name| name.span() == child.span()) {
+            return None;
+        }
+        v.params()
+            .children()
+            .flat_map(|param| match param {
+                ast::Param::Pos(pattern) => pattern.idents(),
+                ast::Param::Named(named) => vec![named.name()],
+                ast::Param::Sink(spread) => spread.name().into_iter().collect(),
+            })
+            .collect()
+    } else {
+        return None;
+    };
+
+    let ident = idents.into_iter().rev().find(|ident| ident.as_str() == name)?;
+    parent.find(ident.span())
+}
+
+/// Follow an imported item into the file that defines it.
+fn follow_import(world: &dyn World, binding: &LinkedNode) -> Option<Definition> {
+    let mut ancestor = binding.parent()?;
+    let original = match ancestor.get().cast::<ast::RenamedImportItem>() {
+        Some(item) => {
+            ancestor = ancestor.parent()?;
+            item.original_name()
+        }
+        None => binding.get().cast::<ast::Ident>()?,
+    };
+
+    let import = ancestor.parent()?.get().cast::<ast::ModuleImport>()?;
+    let ast::Expr::Str(path) = import.source() else { return None };
+    if path.get().starts_with('@') {
+        return None;
+    }
+
+    let id = binding.span().id()?.join(&path.get());
+    let source = world.source(id).ok()?;
+    let name = original.as_str();
+    let span = source.root().children().rev().find_map(|child| {
+        let idents = if let Some(v) = child.cast::<ast::LetBinding>() {
+            v.kind().idents()
+        } else if let Some(v) = child.cast::<ast::ModuleImport>() {
+            match v.imports() {
+                Some(ast::Imports::Items(items)) => {
+                    items.iter().map(ast::ImportItem::bound_name).collect()
+                }
+                _ => vec![],
+            }
+        } else {
+            return None;
+        };
+        idents
+            .into_iter()
+            .find(|ident| ident.as_str() == name)
+            .map(|ident| ident.span())
+    })?;
+
+    Some(Definition { name: name.into(), span })
+}
+
+/// Whether the node is an identifier.
+fn is_ident(node: &LinkedNode) -> bool {
+    matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+}
+
+/// Whether an identifier refers to a binding, as opposed to naming a field, a
+/// named argument, or an item in another module.
+fn is_reference(leaf: &LinkedNode) -> bool {
+    let first = leaf.prev_sibling().is_none();
+    match leaf.parent_kind() {
+        Some(SyntaxKind::FieldAccess) => first,
+        Some(SyntaxKind::Named) => {
+            !first
+                || leaf.parent().and_then(LinkedNode::parent_kind)
+                    == Some(SyntaxKind::Params)
+        }
+        Some(SyntaxKind::RenamedImportItem) => !first,
+        _ => true,
+    }
+}
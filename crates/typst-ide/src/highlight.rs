@@ -0,0 +1,27 @@
+use typst::syntax::{LinkedNode, Span, SyntaxNode, Tag};
+
+/// Classify the leaves of a syntax tree for syntax highlighting.
+///
+/// Each leaf gets the tag of its innermost highlighted node, so that the
+/// result is in source order and free of overlaps, as editors expect for
+/// semantic tokens. Leaves without a tag are left out.
+pub fn highlight(root: &SyntaxNode) -> Vec<(Span, Tag)> {
+    let mut tags = vec![];
+    highlight_impl(&LinkedNode::new(root), None, &mut tags);
+    tags
+}
+
+/// Classify the leaves below a node with the tag of the closest highlighted
+/// ancestor as a fallback.
+fn highlight_impl(node: &LinkedNode, outer: Option<Tag>, tags: &mut Vec<(Span, Tag)>) {
+    let tag = typst::syntax::highlight(node).or(outer);
+    if node.get().children().len() > 0 {
+        for child in node.children() {
+            highlight_impl(&child, tag, tags);
+        }
+    } else if let Some(tag) = tag {
+        if !node.text().is_empty() {
+            tags.push((node.span(), tag));
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use std::num::NonZeroUsize;
-
 use ecow::EcoString;
 use typst::introspection::Meta;
 use typst::layout::{Frame, FrameItem, Point, Position, Size};
@@ -98,7 +96,7 @@ pub fn jump_from_click(
                 }
             }
 
-            FrameItem::Image(_, size, span) if is_in_rect(pos, *size, click) => {
+            FrameItem::Image(_, size, _, span) if is_in_rect(pos, *size, click) => {
                 return Jump::from_span(world, *span);
             }
 
@@ -120,40 +118,7 @@ pub fn jump_from_cursor(
         return None;
     }
 
-    let span = node.span();
-    for (i, frame) in document.pages.iter().enumerate() {
-        if let Some(pos) = find_in_frame(frame, span) {
-            return Some(Position {
-                page: NonZeroUsize::new(i + 1).unwrap(),
-                point: pos,
-            });
-        }
-    }
-
-    None
-}
-
-/// Find the position of a span in a frame.
-fn find_in_frame(frame: &Frame, span: Span) -> Option<Point> {
-    for (mut pos, item) in frame.items() {
-        if let FrameItem::Group(group) = item {
-            // TODO: Handle transformation.
-            if let Some(point) = find_in_frame(&group.frame, span) {
-                return Some(point + pos);
-            }
-        }
-
-        if let FrameItem::Text(text) = item {
-            for glyph in &text.glyphs {
-                if glyph.span.0 == span {
-                    return Some(pos);
-                }
-                pos.x += glyph.x_advance.at(text.size);
-            }
-        }
-    }
-
-    None
+    document.position_of(node.span()).into_iter().next()
 }
 
 /// Whether a rectangle with the given size at the given position contains the
@@ -2,12 +2,18 @@
 
 mod analyze;
 mod complete;
+mod definition;
+mod highlight;
 mod jump;
+mod symbols;
 mod tooltip;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::{autocomplete, Completion, CompletionKind};
+pub use self::definition::{definition, references, Definition};
+pub use self::highlight::highlight;
 pub use self::jump::{jump_from_click, jump_from_cursor, Jump};
+pub use self::symbols::{document_symbols, Symbol, SymbolKind};
 pub use self::tooltip::{tooltip, Tooltip};
 
 use std::fmt::Write;
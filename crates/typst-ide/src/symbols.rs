@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+use typst::syntax::{ast, LinkedNode, Source};
+
+/// A symbol in the outline of a source file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    /// The symbol's name, i.e. the text of a heading or a bound identifier.
+    pub name: EcoString,
+    /// What kind of symbol this is.
+    pub kind: SymbolKind,
+    /// The range of the whole syntax that defines the symbol.
+    pub range: Range<usize>,
+    /// The range of just the name.
+    pub selection: Range<usize>,
+    /// Symbols that belong to this one, e.g. subheadings.
+    pub children: Vec<Symbol>,
+}
+
+/// A kind of [`Symbol`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SymbolKind {
+    /// A heading.
+    Heading,
+    /// A binding of a function.
+    Function,
+    /// A binding of any other value.
+    Variable,
+}
+
+/// Find the headings and top-level bindings of a source file.
+///
+/// Headings are nested by their level. Bindings are placed into the heading
+/// they appear under.
+pub fn document_symbols(source: &Source) -> Vec<Symbol> {
+    let mut symbols = vec![];
+    let mut stack: Vec<(usize, Symbol)> = vec![];
+
+    for node in LinkedNode::new(source.root()).children() {
+        if let Some(heading) = node.get().cast::<ast::Heading>() {
+            let level = heading.level().get();
+            while stack.last().is_some_and(|&(open, _)| open >= level) {
+                let (_, symbol) = stack.pop().unwrap();
+                attach(&mut stack, &mut symbols, symbol);
+            }
+
+            let body = heading.body().to_untyped();
+            let selection = node.find(body.span()).map_or(node.range(), |n| n.range());
+            stack.push((
+                level,
+                Symbol {
+                    name: body.clone().into_text().trim().into(),
+                    kind: SymbolKind::Heading,
+                    range: node.range(),
+                    selection,
+                    children: vec![],
+                },
+            ));
+        } else if let Some(binding) = node.get().cast::<ast::LetBinding>() {
+            let kind = match binding.init() {
+                Some(ast::Expr::Closure(_)) => SymbolKind::Function,
+                _ => SymbolKind::Variable,
+            };
+
+            for ident in binding.kind().idents() {
+                let Some(name) = node.find(ident.span()) else { continue };
+                let symbol = Symbol {
+                    name: ident.get().clone(),
+                    kind,
+                    range: node.range(),
+                    selection: name.range(),
+                    children: vec![],
+                };
+                attach(&mut stack, &mut symbols, symbol);
+            }
+        }
+    }
+
+    while let Some((_, symbol)) = stack.pop() {
+        attach(&mut stack, &mut symbols, symbol);
+    }
+
+    symbols
+}
+
+/// Add a symbol to the innermost open heading or the top level.
+fn attach(stack: &mut [(usize, Symbol)], symbols: &mut Vec<Symbol>, symbol: Symbol) {
+    match stack.last_mut() {
+        Some((_, parent)) => parent.children.push(symbol),
+        None => symbols.push(symbol),
+    }
+}
@@ -3,6 +3,7 @@ use pdf_writer::types::DeviceNSubtype;
 use pdf_writer::{writers, Chunk, Dict, Filter, Name, Ref};
 use typst::visualize::{Color, ColorSpace, Paint};
 
+use crate::crypt::Crypt;
 use crate::deflate;
 use crate::page::{PageContext, Transforms};
 
@@ -26,7 +27,7 @@ const HSL_S: Name<'static> = Name(b"S");
 const HSL_L: Name<'static> = Name(b"L");
 
 // The ICC profiles.
-static SRGB_ICC_DEFLATED: Lazy<Vec<u8>> =
+pub static SRGB_ICC_DEFLATED: Lazy<Vec<u8>> =
     Lazy::new(|| deflate(include_bytes!("icc/sRGB-v4.icc")));
 static GRAY_ICC_DEFLATED: Lazy<Vec<u8>> =
     Lazy::new(|| deflate(include_bytes!("icc/sGrey-v4.icc")));
@@ -166,11 +167,11 @@ impl ColorSpaces {
 
     /// Write the necessary color spaces functions and ICC profiles to the
     /// PDF file.
-    pub fn write_functions(&self, chunk: &mut Chunk) {
+    pub fn write_functions(&self, chunk: &mut Chunk, crypt: &Crypt) {
         // Write the Oklab function & color space.
         if let Some(oklab) = self.oklab {
             chunk
-                .post_script_function(oklab, &OKLAB_DEFLATED)
+                .post_script_function(oklab, &crypt.data(oklab, &OKLAB_DEFLATED))
                 .domain([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -179,7 +180,7 @@ impl ColorSpaces {
         // Write the HSV function & color space.
         if let Some(hsv) = self.hsv {
             chunk
-                .post_script_function(hsv, &HSV_DEFLATED)
+                .post_script_function(hsv, &crypt.data(hsv, &HSV_DEFLATED))
                 .domain([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -188,7 +189,7 @@ impl ColorSpaces {
         // Write the HSL function & color space.
         if let Some(hsl) = self.hsl {
             chunk
-                .post_script_function(hsl, &HSL_DEFLATED)
+                .post_script_function(hsl, &crypt.data(hsl, &HSL_DEFLATED))
                 .domain([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -197,7 +198,7 @@ impl ColorSpaces {
         // Write the sRGB color space.
         if let Some(srgb) = self.srgb {
             chunk
-                .icc_profile(srgb, &SRGB_ICC_DEFLATED)
+                .icc_profile(srgb, &crypt.data(srgb, &SRGB_ICC_DEFLATED))
                 .n(3)
                 .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -206,7 +207,7 @@ impl ColorSpaces {
         // Write the gray color space.
         if let Some(gray) = self.d65_gray {
             chunk
-                .icc_profile(gray, &GRAY_ICC_DEFLATED)
+                .icc_profile(gray, &crypt.data(gray, &GRAY_ICC_DEFLATED))
                 .n(1)
                 .range([0.0, 1.0])
                 .filter(Filter::FlateDecode);
@@ -0,0 +1,1177 @@
+//! Encryption with the PDF standard security handler.
+//!
+//! Documents are encrypted with AES, either with a 128-bit key (revision 4,
+//! AESV2) or with a 256-bit key (revision 6, AESV3). The strings and streams
+//! of each object are encrypted as the object is written. Only the trailer's
+//! reference to the encryption dictionary is added to the finished file, as
+//! `pdf-writer` can't write it.
+
+use std::borrow::Cow;
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use aes::{Aes128, Aes256};
+use pdf_writer::{Chunk, Dict, Finish, Name, Null, Obj, Ref, Str, TextStr};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+
+/// Settings for encrypting an exported PDF.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct PdfEncryption {
+    /// The password needed to open the document. If it is empty, anyone can
+    /// open the document, but the permissions still apply.
+    pub user_password: String,
+    /// The password that grants full access to the document regardless of
+    /// the permissions. If it is empty, the user password is used instead.
+    pub owner_password: String,
+    /// What is allowed without the owner password.
+    pub permissions: PdfPermissions,
+    /// How the document is encrypted.
+    pub cipher: PdfCipher,
+}
+
+/// What a user may do with an encrypted PDF without its owner password.
+///
+/// PDF readers are expected to honor these flags, but the format cannot
+/// enforce them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct PdfPermissions {
+    /// Whether the document may be printed.
+    pub print: bool,
+    /// Whether the document may be modified.
+    pub modify: bool,
+    /// Whether text and graphics may be copied out of the document.
+    pub copy: bool,
+    /// Whether annotations may be added and forms filled in.
+    pub annotate: bool,
+}
+
+impl PdfPermissions {
+    /// The value of the encryption dictionary's `P` entry.
+    fn bits(self) -> i32 {
+        // Bits 7, 8, and 13 to 32 (one-based) must be set. Extracting text
+        // for accessibility (bit 10) is always allowed.
+        let mut bits: u32 = 0xFFFF_F0C0 | 1 << 9;
+        if self.print {
+            bits |= 1 << 2 | 1 << 11;
+        }
+        if self.modify {
+            bits |= 1 << 3 | 1 << 10;
+        }
+        if self.copy {
+            bits |= 1 << 4;
+        }
+        if self.annotate {
+            bits |= 1 << 5 | 1 << 8;
+        }
+        bits as i32
+    }
+}
+
+impl Default for PdfPermissions {
+    fn default() -> Self {
+        Self {
+            print: true,
+            modify: true,
+            copy: true,
+            annotate: true,
+        }
+    }
+}
+
+/// The cipher an encrypted PDF is encrypted with.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfCipher {
+    /// AES with a 128-bit key, which readers for PDF 1.6 and later support.
+    Aes128,
+    /// AES with a 256-bit key, which readers for PDF 2.0 support.
+    #[default]
+    Aes256,
+}
+
+/// Encrypts the strings and streams of objects while they are written.
+///
+/// Without encryption, all data is written as is.
+#[derive(Default)]
+pub(crate) struct Crypt(Option<Handler>);
+
+impl Crypt {
+    /// Prepare the encryption of a file with the given first part of its
+    /// identifier.
+    ///
+    /// If `reproducible` is set, the values that should be random are derived
+    /// from the identifier and the passwords instead.
+    pub fn new(encryption: &PdfEncryption, id: &[u8], reproducible: bool) -> Self {
+        Self(Some(Handler::new(encryption, id, reproducible)))
+    }
+
+    /// Whether the document is encrypted.
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Encrypt a string or the data of a stream that belongs to the object
+    /// with the given ID.
+    pub fn data<'a>(&self, id: Ref, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match &self.0 {
+            Some(handler) => Cow::Owned(handler.encrypt(id, data)),
+            None => Cow::Borrowed(data),
+        }
+    }
+
+    /// Write a text string into a dictionary that belongs to the object with
+    /// the given ID.
+    pub fn text(&self, dict: &mut Dict, id: Ref, key: Name, text: &str) {
+        match &self.0 {
+            Some(handler) => {
+                dict.pair(key, Str(&handler.encrypt(id, &encode_text(text))));
+            }
+            None => {
+                dict.pair(key, TextStr(text));
+            }
+        }
+    }
+
+    /// Write the encryption dictionary with the given ID, if the document is
+    /// encrypted.
+    pub fn write(&self, chunk: &mut Chunk, id: Ref) {
+        let Some(handler) = &self.0 else { return };
+        let (version, revision, method) = match handler.cipher {
+            PdfCipher::Aes128 => (4, 4, Name(b"AESV2")),
+            PdfCipher::Aes256 => (5, 6, Name(b"AESV3")),
+        };
+
+        let mut dict = chunk.indirect(id).dict();
+        dict.pair(Name(b"Filter"), Name(b"Standard"));
+        dict.pair(Name(b"V"), version);
+        dict.pair(Name(b"R"), revision);
+        dict.pair(Name(b"Length"), 8 * handler.key.len() as i32);
+
+        // All strings and streams use the same crypt filter.
+        let mut filters = dict.insert(Name(b"CF")).dict();
+        let mut filter = filters.insert(Name(b"StdCF")).dict();
+        filter.pair(Name(b"Type"), Name(b"CryptFilter"));
+        filter.pair(Name(b"CFM"), method);
+        filter.pair(Name(b"AuthEvent"), Name(b"DocOpen"));
+        filter.pair(Name(b"Length"), handler.key.len() as i32);
+        filter.finish();
+        filters.finish();
+        dict.pair(Name(b"StmF"), Name(b"StdCF"));
+        dict.pair(Name(b"StrF"), Name(b"StdCF"));
+
+        dict.pair(Name(b"O"), Str(&handler.o));
+        dict.pair(Name(b"U"), Str(&handler.u));
+        if handler.cipher == PdfCipher::Aes256 {
+            dict.pair(Name(b"OE"), Str(&handler.oe));
+            dict.pair(Name(b"UE"), Str(&handler.ue));
+            dict.pair(Name(b"Perms"), Str(&handler.perms));
+        }
+        dict.pair(Name(b"P"), handler.p);
+        dict.pair(Name(b"EncryptMetadata"), true);
+        dict.finish();
+    }
+
+    /// Write the objects of a chunk into another chunk, renumbering them with
+    /// `mapping` like [`Chunk::renumber_into`] does.
+    ///
+    /// If the document is encrypted, the objects are parsed and written anew
+    /// to encrypt their strings and streams. Returns `None` if the chunk
+    /// can't be parsed, which only happens if it wasn't written by
+    /// `pdf-writer`.
+    pub fn renumber_into(
+        &self,
+        chunk: &Chunk,
+        target: &mut Chunk,
+        mut mapping: impl FnMut(Ref) -> Ref,
+    ) -> Option<()> {
+        let Some(handler) = &self.0 else {
+            chunk.renumber_into(target, mapping);
+            return Some(());
+        };
+
+        for object in parse_chunk(chunk.as_bytes())? {
+            let id = mapping(Ref::new(object.id));
+            match object.stream {
+                Some(data) => {
+                    let Value::Dict(pairs) = &object.value else { return None };
+                    let data = handler.encrypt(id, data);
+                    let mut stream = target.stream(id, &data);
+                    for (key, value) in pairs {
+                        if key.as_slice() != b"Length" {
+                            let obj = stream.insert(Name(key.as_slice()));
+                            write_value(obj, value, handler, id, &mut mapping);
+                        }
+                    }
+                }
+                None => {
+                    let obj = target.indirect(id);
+                    write_value(obj, &object.value, handler, id, &mut mapping);
+                }
+            }
+        }
+
+        Some(())
+    }
+}
+
+/// Refer to the encryption dictionary with the given ID from the trailer of
+/// a finished file whose cross-reference table starts at `xref`.
+///
+/// The entry is inserted at the end of the trailer dictionary, the last
+/// dictionary of the file. Returns `None` if there is no such dictionary.
+pub(crate) fn refer(mut pdf: Vec<u8>, xref: usize, id: Ref) -> Option<Vec<u8>> {
+    let end = xref + pdf.get(xref..)?.windows(2).rposition(|w| w == b">>")?;
+    let entry = format!("  /Encrypt {} 0 R\n", id.get());
+    pdf.splice(end..end, entry.bytes());
+    Some(pdf)
+}
+
+/// The keys and entries of the standard security handler.
+struct Handler {
+    /// Which cipher to encrypt with.
+    cipher: PdfCipher,
+    /// The file encryption key.
+    key: Vec<u8>,
+    /// The owner password entry.
+    o: Vec<u8>,
+    /// The user password entry.
+    u: Vec<u8>,
+    /// The file encryption key, encrypted with the owner password. Only
+    /// used with AES-256.
+    oe: Vec<u8>,
+    /// The file encryption key, encrypted with the user password. Only used
+    /// with AES-256.
+    ue: Vec<u8>,
+    /// The encrypted permission flags. Only used with AES-256.
+    perms: Vec<u8>,
+    /// The permission flags.
+    p: i32,
+    /// Whether the initialization vectors are derived instead of random.
+    reproducible: bool,
+}
+
+impl Handler {
+    /// Compute the keys and entries for the given settings and file ID.
+    fn new(encryption: &PdfEncryption, id: &[u8], reproducible: bool) -> Self {
+        match encryption.cipher {
+            PdfCipher::Aes128 => Self::aes128(encryption, id, reproducible),
+            PdfCipher::Aes256 => Self::aes256(encryption, id, reproducible),
+        }
+    }
+
+    /// Compute the keys and entries of revision 4.
+    fn aes128(encryption: &PdfEncryption, id: &[u8], reproducible: bool) -> Self {
+        let user = pad(&encryption.user_password);
+        let owner = if encryption.owner_password.is_empty() {
+            user
+        } else {
+            pad(&encryption.owner_password)
+        };
+        let p = encryption.permissions.bits();
+
+        // Algorithm 3: The owner password entry.
+        let mut hash = md5::compute(owner).0;
+        for _ in 0..50 {
+            hash = md5::compute(hash).0;
+        }
+        let mut o = user;
+        rc4_rounds(&hash, &mut o);
+
+        // Algorithm 2: The file encryption key.
+        let mut context = md5::Context::new();
+        context.consume(user);
+        context.consume(o);
+        context.consume(p.to_le_bytes());
+        context.consume(id);
+        let mut key = context.compute().0;
+        for _ in 0..50 {
+            key = md5::compute(key).0;
+        }
+
+        // Algorithm 5: The user password entry.
+        let mut context = md5::Context::new();
+        context.consume(PADDING);
+        context.consume(id);
+        let mut hash = context.compute().0;
+        rc4_rounds(&key, &mut hash);
+        let mut u = [0; 32];
+        u[..16].copy_from_slice(&hash);
+
+        Self {
+            cipher: PdfCipher::Aes128,
+            key: key.to_vec(),
+            o: o.to_vec(),
+            u: u.to_vec(),
+            oe: vec![],
+            ue: vec![],
+            perms: vec![],
+            p,
+            reproducible,
+        }
+    }
+
+    /// Compute the keys and entries of revision 6.
+    ///
+    /// The file encryption key and the salts are random. Only if the output
+    /// must be reproducible, they are derived from the file ID and the hashed
+    /// passwords. Anyone who knows the file ID and guesses a password can
+    /// then predict them.
+    fn aes256(encryption: &PdfEncryption, id: &[u8], reproducible: bool) -> Self {
+        let user = truncate(&encryption.user_password);
+        let owner = if encryption.owner_password.is_empty() {
+            user
+        } else {
+            truncate(&encryption.owner_password)
+        };
+        let p = encryption.permissions.bits();
+
+        let seed = reproducible.then(|| {
+            let digest = Sha256::digest(id);
+            Sha256::new()
+                .chain_update(id)
+                .chain_update(hash(user, &digest[..8], &[]))
+                .chain_update(hash(owner, &digest[..8], &[]))
+                .finalize()
+        });
+        let derive = |label: &[u8]| -> [u8; 32] {
+            match &seed {
+                Some(seed) => Sha256::new()
+                    .chain_update(seed.as_slice())
+                    .chain_update(label)
+                    .finalize()
+                    .into(),
+                None => random(),
+            }
+        };
+
+        let key = derive(b"key").to_vec();
+        let salts = derive(b"salts");
+        let (user_validation, user_key) = (&salts[..8], &salts[8..16]);
+        let (owner_validation, owner_key) = (&salts[16..24], &salts[24..]);
+
+        // Algorithm 8: The user password entries.
+        let mut u = hash(user, user_validation, &[]).to_vec();
+        u.extend_from_slice(user_validation);
+        u.extend_from_slice(user_key);
+        let mut ue = key.clone();
+        cbc(&hash(user, user_key, &[]), &[0; 16], &mut ue);
+
+        // Algorithm 9: The owner password entries.
+        let mut o = hash(owner, owner_validation, &u).to_vec();
+        o.extend_from_slice(owner_validation);
+        o.extend_from_slice(owner_key);
+        let mut oe = key.clone();
+        cbc(&hash(owner, owner_key, &u), &[0; 16], &mut oe);
+
+        // Algorithm 10: The encrypted permissions. The metadata is encrypted
+        // as well, which the `T` indicates.
+        let mut perms = [0; 16];
+        perms[..4].copy_from_slice(&p.to_le_bytes());
+        perms[4..8].fill(0xFF);
+        perms[8..12].copy_from_slice(b"Tadb");
+        perms[12..].copy_from_slice(&derive(b"perms")[..4]);
+        cbc(&key, &[0; 16], &mut perms);
+
+        Self {
+            cipher: PdfCipher::Aes256,
+            key,
+            o,
+            u,
+            oe,
+            ue,
+            perms: perms.to_vec(),
+            p,
+            reproducible,
+        }
+    }
+
+    /// The key for the strings and streams of the object with the given ID.
+    fn object_key(&self, id: Ref) -> Cow<'_, [u8]> {
+        match self.cipher {
+            // Algorithm 1: Revision 4 derives a key for each object.
+            PdfCipher::Aes128 => {
+                let mut context = md5::Context::new();
+                context.consume(&self.key);
+                context.consume(&id.get().to_le_bytes()[..3]);
+                context.consume([0u8, 0]);
+                context.consume(b"sAlT");
+                Cow::Owned(context.compute().0.to_vec())
+            }
+            PdfCipher::Aes256 => Cow::Borrowed(&self.key),
+        }
+    }
+
+    /// Encrypt a string or the data of a stream that belongs to the object
+    /// with the given ID.
+    ///
+    /// The result starts with the initialization vector. It is random, unless
+    /// the output must be reproducible. Then, it is derived from the key, the
+    /// object and the data.
+    fn encrypt(&self, id: Ref, data: &[u8]) -> Vec<u8> {
+        let key = self.object_key(id);
+        let iv: [u8; 32] = if self.reproducible {
+            Sha256::new()
+                .chain_update(&*key)
+                .chain_update(id.get().to_le_bytes())
+                .chain_update(data)
+                .finalize()
+                .into()
+        } else {
+            random()
+        };
+
+        // The data is padded to a multiple of the block size as defined in
+        // RFC 8018, section 6.1.1.
+        let padding = 16 - data.len() % 16;
+        let mut out = Vec::with_capacity(16 + data.len() + padding);
+        out.extend_from_slice(&iv[..16]);
+        out.extend_from_slice(data);
+        out.resize(out.len() + padding, padding as u8);
+
+        let (iv, rest) = out.split_at_mut(16);
+        cbc(&key, iv, rest);
+        out
+    }
+}
+
+/// Draw random bytes from the operating system's secure random number
+/// generator.
+fn random<const N: usize>() -> [u8; N] {
+    let mut bytes = [0; N];
+    getrandom::getrandom(&mut bytes).expect("failed to gather random bytes");
+    bytes
+}
+
+/// The padding for passwords, defined in ISO 32000-1, section 7.6.3.3.
+const PADDING: [u8; 32] = [
+    0x28, 0xBF, 0x4E, 0x5E, 0x4E, 0x75, 0x8A, 0x41, 0x64, 0x00, 0x4E, 0x56, 0xFF, 0xFA,
+    0x01, 0x08, 0x2E, 0x2E, 0x00, 0xB6, 0xD0, 0x68, 0x3E, 0x80, 0x2F, 0x0C, 0xA9, 0xFE,
+    0x64, 0x53, 0x69, 0x7A,
+];
+
+/// Pad or truncate a password to 32 bytes.
+fn pad(password: &str) -> [u8; 32] {
+    let bytes = password.as_bytes();
+    let len = bytes.len().min(32);
+    let mut padded = PADDING;
+    padded[..len].copy_from_slice(&bytes[..len]);
+    padded[len..].copy_from_slice(&PADDING[..32 - len]);
+    padded
+}
+
+/// Truncate a password to the 127 bytes that revision 6 considers.
+fn truncate(password: &str) -> &[u8] {
+    let bytes = password.as_bytes();
+    &bytes[..bytes.len().min(127)]
+}
+
+/// Hash a password as defined in ISO 32000-2, section 7.6.4.3.4
+/// (algorithm 2.B).
+fn hash(password: &[u8], salt: &[u8], user: &[u8]) -> [u8; 32] {
+    let mut k = Sha256::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(user)
+        .finalize()
+        .to_vec();
+
+    let mut round = 0;
+    loop {
+        let mut e = Vec::with_capacity(64 * (password.len() + k.len() + user.len()));
+        for _ in 0..64 {
+            e.extend_from_slice(password);
+            e.extend_from_slice(&k);
+            e.extend_from_slice(user);
+        }
+        cbc(&k[..16], &k[16..32], &mut e);
+
+        // The first 16 bytes as a number modulo 3, which is the same as the
+        // sum of the bytes modulo 3 since 256 % 3 == 1.
+        k = match e[..16].iter().map(|&byte| u32::from(byte)).sum::<u32>() % 3 {
+            0 => Sha256::digest(&e).to_vec(),
+            1 => Sha384::digest(&e).to_vec(),
+            _ => Sha512::digest(&e).to_vec(),
+        };
+
+        round += 1;
+        if round >= 64 && u32::from(e[e.len() - 1]) + 32 <= round {
+            break;
+        }
+    }
+
+    let mut hash = [0; 32];
+    hash.copy_from_slice(&k[..32]);
+    hash
+}
+
+/// Encrypt data in-place with AES in CBC mode.
+///
+/// The key's length selects AES-128 or AES-256. The data's length must be a
+/// multiple of the block size.
+fn cbc(key: &[u8], iv: &[u8], data: &mut [u8]) {
+    if key.len() == 16 {
+        cbc_with(&Aes128::new(GenericArray::from_slice(key)), iv, data);
+    } else {
+        cbc_with(&Aes256::new(GenericArray::from_slice(key)), iv, data);
+    }
+}
+
+/// Encrypt data in-place with a block cipher in CBC mode.
+fn cbc_with(cipher: &impl BlockEncrypt, iv: &[u8], data: &mut [u8]) {
+    let mut prev = [0; 16];
+    prev.copy_from_slice(iv);
+    for block in data.chunks_exact_mut(16) {
+        block.iter_mut().zip(prev).for_each(|(byte, prev)| *byte ^= prev);
+        cipher.encrypt_block(GenericArray::from_mut_slice(block));
+        prev.copy_from_slice(block);
+    }
+}
+
+/// Apply RC4 with the key and then 19 more times with the key's bytes
+/// XOR-ed with the round number, as done for the password entries.
+fn rc4_rounds(key: &[u8; 16], data: &mut [u8]) {
+    rc4(key, data);
+    for round in 1..=19 {
+        rc4(&key.map(|byte| byte ^ round), data);
+    }
+}
+
+/// Encrypt or decrypt data in-place with the RC4 stream cipher.
+fn rc4(key: &[u8], data: &mut [u8]) {
+    let mut state: [u8; 256] = std::array::from_fn(|i| i as u8);
+    let mut j: u8 = 0;
+    for i in 0..256 {
+        j = j.wrapping_add(state[i]).wrapping_add(key[i % key.len()]);
+        state.swap(i, j as usize);
+    }
+
+    let (mut i, mut j) = (0u8, 0u8);
+    for byte in data {
+        i = i.wrapping_add(1);
+        j = j.wrapping_add(state[i as usize]);
+        state.swap(i as usize, j as usize);
+        *byte ^= state[state[i as usize].wrapping_add(state[j as usize]) as usize];
+    }
+}
+
+/// Encode a text string like `pdf-writer` does: In PDFDocEncoding if it is
+/// printable ASCII and in UTF-16BE otherwise.
+fn encode_text(text: &str) -> Vec<u8> {
+    if text.bytes().all(|byte| matches!(byte, 32..=126)) {
+        return text.as_bytes().to_vec();
+    }
+
+    let mut bytes = vec![0xFE, 0xFF];
+    bytes.extend(text.encode_utf16().flat_map(u16::to_be_bytes));
+    bytes
+}
+
+/// Write a parsed object, encrypting its strings and renumbering its
+/// references.
+fn write_value(
+    obj: Obj,
+    value: &Value,
+    handler: &Handler,
+    id: Ref,
+    mapping: &mut dyn FnMut(Ref) -> Ref,
+) {
+    match value {
+        Value::Null => obj.primitive(Null),
+        Value::Bool(v) => obj.primitive(*v),
+        Value::Int(v) => obj.primitive(*v),
+        Value::Real(v) => obj.primitive(*v),
+        Value::Name(name) => obj.primitive(Name(name.as_slice())),
+        Value::Str(string) => obj.primitive(Str(&handler.encrypt(id, string))),
+        Value::Ref(num) => obj.primitive(mapping(Ref::new(*num))),
+        Value::Array(items) => {
+            let mut array = obj.array();
+            for item in items {
+                write_value(array.push(), item, handler, id, mapping);
+            }
+        }
+        Value::Dict(pairs) => {
+            let mut dict = obj.dict();
+            for (key, value) in pairs {
+                write_value(
+                    dict.insert(Name(key.as_slice())),
+                    value,
+                    handler,
+                    id,
+                    mapping,
+                );
+            }
+        }
+    }
+}
+
+/// An indirect object of a chunk.
+struct Object<'a> {
+    /// The object's number.
+    id: i32,
+    /// The object itself, which is the dictionary of a stream.
+    value: Value,
+    /// The data of the stream, if the object is one.
+    stream: Option<&'a [u8]>,
+}
+
+/// A direct object.
+enum Value {
+    Null,
+    Bool(bool),
+    Int(i32),
+    Real(f32),
+    Name(Vec<u8>),
+    Str(Vec<u8>),
+    Ref(i32),
+    Array(Vec<Value>),
+    Dict(Vec<(Vec<u8>, Value)>),
+}
+
+/// Parse the indirect objects of a chunk.
+fn parse_chunk(src: &[u8]) -> Option<Vec<Object>> {
+    let mut p = Parser { src, pos: 0 };
+    let mut objects = vec![];
+    loop {
+        p.skip_whitespace();
+        if p.pos == src.len() {
+            return Some(objects);
+        }
+
+        let id = p.int()?;
+        p.skip_whitespace();
+        p.int()?;
+        p.skip_whitespace();
+        p.expect(b"obj")?;
+        let value = p.value()?;
+        p.skip_whitespace();
+
+        let mut stream = None;
+        if p.eat(b"stream") {
+            p.eat(b"\r");
+            p.expect(b"\n")?;
+            let Value::Dict(pairs) = &value else { return None };
+            let len = pairs.iter().find_map(|(key, value)| match value {
+                Value::Int(len) if key.as_slice() == b"Length" => Some(*len),
+                _ => None,
+            })?;
+            stream = Some(p.take(usize::try_from(len).ok()?)?);
+            p.skip_whitespace();
+            p.expect(b"endstream")?;
+            p.skip_whitespace();
+        }
+
+        p.expect(b"endobj")?;
+        objects.push(Object { id, value, stream });
+    }
+}
+
+/// Parses the objects of a chunk.
+struct Parser<'a> {
+    /// The chunk.
+    src: &'a [u8],
+    /// The position of the next byte.
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// The next byte.
+    fn peek(&self) -> Option<u8> {
+        self.src.get(self.pos).copied()
+    }
+
+    /// Consume the given bytes if they come next.
+    fn eat(&mut self, bytes: &[u8]) -> bool {
+        let found = self.src[self.pos..].starts_with(bytes);
+        if found {
+            self.pos += bytes.len();
+        }
+        found
+    }
+
+    /// Consume the given bytes, which must come next.
+    fn expect(&mut self, bytes: &[u8]) -> Option<()> {
+        self.eat(bytes).then_some(())
+    }
+
+    /// Consume the given number of bytes.
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let src = self.src;
+        let bytes = src.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            match byte {
+                b' ' | b'\n' | b'\r' | b'\t' | b'\x0C' | b'\0' => self.pos += 1,
+                b'%' => {
+                    while !matches!(self.peek(), None | Some(b'\n' | b'\r')) {
+                        self.pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Consume bytes while the predicate holds.
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a [u8] {
+        let src = self.src;
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        &src[start..self.pos]
+    }
+
+    /// Parse a non-negative integer.
+    fn int(&mut self) -> Option<i32> {
+        let digits = self.take_while(|byte| byte.is_ascii_digit());
+        std::str::from_utf8(digits).ok()?.parse().ok()
+    }
+
+    /// Parse a direct object.
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        let src = self.src;
+        let rest = &src[self.pos..];
+        Some(if self.eat(b"<<") {
+            let mut pairs = vec![];
+            loop {
+                self.skip_whitespace();
+                if self.eat(b">>") {
+                    break Value::Dict(pairs);
+                }
+                self.expect(b"/")?;
+                let key = self.name();
+                pairs.push((key, self.value()?));
+            }
+        } else if self.eat(b"[") {
+            let mut items = vec![];
+            loop {
+                self.skip_whitespace();
+                if self.eat(b"]") {
+                    break Value::Array(items);
+                }
+                items.push(self.value()?);
+            }
+        } else if rest.starts_with(b"<") {
+            let (bytes, len) = parse_hex(rest)?;
+            self.pos += len;
+            Value::Str(bytes)
+        } else if rest.starts_with(b"(") {
+            let (bytes, len) = parse_literal(rest)?;
+            self.pos += len;
+            Value::Str(bytes)
+        } else if self.eat(b"/") {
+            Value::Name(self.name())
+        } else if self.eat(b"true") {
+            Value::Bool(true)
+        } else if self.eat(b"false") {
+            Value::Bool(false)
+        } else if self.eat(b"null") {
+            Value::Null
+        } else {
+            self.number()?
+        })
+    }
+
+    /// Parse the rest of a name after its slash.
+    fn name(&mut self) -> Vec<u8> {
+        let raw = self.take_while(|byte| {
+            !byte.is_ascii_whitespace() && !b"()<>[]{}/%".contains(&byte)
+        });
+
+        let mut name = Vec::with_capacity(raw.len());
+        let mut i = 0;
+        while i < raw.len() {
+            let escaped = raw.get(i + 1..i + 3).and_then(|hex| {
+                u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+            });
+            match escaped {
+                Some(byte) if raw[i] == b'#' => {
+                    name.push(byte);
+                    i += 3;
+                }
+                _ => {
+                    name.push(raw[i]);
+                    i += 1;
+                }
+            }
+        }
+        name
+    }
+
+    /// Parse a number or a reference.
+    fn number(&mut self) -> Option<Value> {
+        let text =
+            self.take_while(|byte| matches!(byte, b'0'..=b'9' | b'+' | b'-' | b'.'));
+        let text = std::str::from_utf8(text).ok()?;
+        if text.contains('.') {
+            return text.parse().ok().map(Value::Real);
+        }
+
+        // An integer followed by a generation number and `R` is a reference.
+        let num = text.parse().ok()?;
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.int().is_some() {
+            self.skip_whitespace();
+            if self.eat(b"R") {
+                return Some(Value::Ref(num));
+            }
+        }
+        self.pos = start;
+        Some(Value::Int(num))
+    }
+}
+
+/// Parse a literal string, returning its bytes and its length in the file.
+fn parse_literal(src: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let mut bytes = vec![];
+    let mut depth = 0;
+    let mut i = 0;
+    loop {
+        let c = *src.get(i)?;
+        i += 1;
+        match c {
+            b'(' => {
+                if depth > 0 {
+                    bytes.push(c);
+                }
+                depth += 1;
+            }
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((bytes, i));
+                }
+                bytes.push(c);
+            }
+            b'\\' => {
+                let escaped = *src.get(i)?;
+                i += 1;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'b' => bytes.push(0x08),
+                    b'f' => bytes.push(0x0C),
+                    b'0'..=b'7' => {
+                        let mut value = u32::from(escaped - b'0');
+                        for _ in 0..2 {
+                            match src.get(i) {
+                                Some(&d @ b'0'..=b'7') => {
+                                    value = value * 8 + u32::from(d - b'0');
+                                    i += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    b'\r' => {
+                        if src.get(i) == Some(&b'\n') {
+                            i += 1;
+                        }
+                    }
+                    b'\n' => {}
+                    other => bytes.push(other),
+                }
+            }
+            _ => bytes.push(c),
+        }
+    }
+}
+
+/// Parse a hexadecimal string, returning its bytes and its length in the
+/// file.
+fn parse_hex(src: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let end = src.iter().position(|&c| c == b'>')?;
+    let digits: Vec<u8> = src[1..end]
+        .iter()
+        .filter(|c| !c.is_ascii_whitespace())
+        .map(|&c| (c as char).to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let bytes = digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0))
+        .collect();
+    Some((bytes, end + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::cipher::BlockDecrypt;
+
+    use typst_tests::compile;
+
+    use super::*;
+    use crate::{pdf, PdfOptions};
+
+    #[test]
+    fn test_rc4() {
+        // Test vector from Wikipedia's article on RC4 ("Key" / "Plaintext").
+        let mut data = *b"Plaintext";
+        rc4(b"Key", &mut data);
+        let hex: String = data.iter().map(|byte| format!("{byte:02X}")).collect();
+        assert_eq!(hex, "BBF316E8D940AF0AD3");
+        rc4(b"Key", &mut data);
+        assert_eq!(&data, b"Plaintext");
+    }
+
+    #[test]
+    fn test_parse_literal() {
+        let (bytes, len) = parse_literal(br"(a\(b\) (c)\101\n) rest").unwrap();
+        assert_eq!(bytes, b"a(b) (c)A\n");
+        assert_eq!(len, 18);
+    }
+
+    #[test]
+    fn test_parse_chunk() {
+        let mut chunk = Chunk::new();
+        let mut dict = chunk.indirect(Ref::new(1)).dict();
+        dict.pair(Name(b"A#B"), Ref::new(2));
+        dict.insert(Name(b"C")).array().items([1.5f32, -2.0]);
+        dict.pair(Name(b"D"), Str(b"(x)\n"));
+        dict.finish();
+        chunk.stream(Ref::new(2), b"endstream").pair(Name(b"E"), 7);
+
+        let objects = parse_chunk(chunk.as_bytes()).unwrap();
+        assert_eq!(objects.len(), 2);
+        let Value::Dict(pairs) = &objects[0].value else { panic!() };
+        assert_eq!(pairs[0].0, b"A#B");
+        assert!(matches!(pairs[0].1, Value::Ref(2)));
+        assert!(matches!(&pairs[1].1, Value::Array(items) if items.len() == 2));
+        assert!(matches!(&pairs[2].1, Value::Str(s) if s == b"(x)\n"));
+        assert_eq!(objects[1].stream, Some(&b"endstream"[..]));
+    }
+
+    #[test]
+    fn test_decrypt_aes256() {
+        let pdf = export(PdfCipher::Aes256);
+        let objects = objects(&pdf);
+        let dict = encryption_dict(&objects);
+        assert!(matches!(get(dict, b"R"), Some(Value::Int(6))));
+
+        // Algorithm 11: Authenticate the user password.
+        let u = string(dict, b"U");
+        assert_eq!(hash(b"hunter2", &u[32..40], &[]), u[..32]);
+        assert_ne!(hash(b"hunter3", &u[32..40], &[]), u[..32]);
+
+        // The file encryption key is encrypted with the user password.
+        let mut key = string(dict, b"UE").to_vec();
+        decrypt_cbc(&hash(b"hunter2", &u[40..48], &[]), &[0; 16], &mut key);
+        let mut perms = string(dict, b"Perms").to_vec();
+        decrypt_cbc(&key, &[0; 16], &mut perms);
+        assert_eq!(&perms[9..12], b"adb");
+        assert!(matches!(
+            get(dict, b"P"),
+            Some(Value::Int(p)) if p.to_le_bytes() == perms[..4]
+        ));
+
+        check_decrypted(&pdf, &objects, |_| key.clone());
+    }
+
+    #[test]
+    fn test_decrypt_aes128() {
+        let pdf = export(PdfCipher::Aes128);
+        let objects = objects(&pdf);
+        let dict = encryption_dict(&objects);
+        assert!(matches!(get(dict, b"R"), Some(Value::Int(4))));
+
+        // Algorithm 2: Compute the file encryption key from the user
+        // password and the first part of the file identifier.
+        let trailer = pdf.windows(7).rposition(|w| w == b"trailer").unwrap();
+        let mut p = Parser { src: &pdf[trailer + 7..], pos: 0 };
+        let trailer = p.value().unwrap();
+        let Some(Value::Array(ids)) = get(&trailer, b"ID") else { panic!() };
+        let Value::Str(id) = &ids[0] else { panic!() };
+        let Some(Value::Int(permissions)) = get(dict, b"P") else { panic!() };
+        let mut context = md5::Context::new();
+        context.consume(pad("hunter2"));
+        context.consume(string(dict, b"O"));
+        context.consume(permissions.to_le_bytes());
+        context.consume(id);
+        let mut key = context.compute().0;
+        for _ in 0..50 {
+            key = md5::compute(key).0;
+        }
+
+        // Algorithm 6: Authenticate the user password.
+        let mut context = md5::Context::new();
+        context.consume(PADDING);
+        context.consume(id);
+        let mut u = context.compute().0;
+        rc4_rounds(&key, &mut u);
+        assert_eq!(u, string(dict, b"U")[..16]);
+
+        check_decrypted(&pdf, &objects, |num| {
+            let mut context = md5::Context::new();
+            context.consume(key);
+            context.consume(&num.to_le_bytes()[..3]);
+            context.consume([0u8, 0]);
+            context.consume(b"sAlT");
+            context.compute().0.to_vec()
+        });
+    }
+
+    #[test]
+    fn test_random_values() {
+        let document = compile("Confidential");
+        let encrypt = |reproducible_encryption| {
+            let options = PdfOptions {
+                encryption: Some(PdfEncryption::default()),
+                reproducible_encryption,
+                ..PdfOptions::default()
+            };
+            pdf(&document, &options).unwrap()
+        };
+
+        // The key, the salts and the initialization vectors are random.
+        assert_ne!(encrypt(false), encrypt(false));
+        assert_eq!(encrypt(true), encrypt(true));
+    }
+
+    /// Export a document with text, metadata, a link and an SVG image,
+    /// encrypted with the given cipher.
+    fn export(cipher: PdfCipher) -> Vec<u8> {
+        let document = compile(
+            "#set document(title: \"Secret Report\")\n\
+             Confidential #link(\"https://typst.app\")[text].\n\
+             #image(\"/files/monkey.svg\", width: 1cm)",
+        );
+        let encryption = PdfEncryption {
+            user_password: "hunter2".into(),
+            cipher,
+            ..PdfEncryption::default()
+        };
+        let options = PdfOptions {
+            encryption: Some(encryption),
+            ..PdfOptions::default()
+        };
+        let pdf = pdf(&document, &options).unwrap();
+
+        // Nothing can be read without decrypting it.
+        assert!(crate::tests::streams(&pdf).is_empty());
+        assert!(!pdf.windows(6).any(|w| w == b"Secret"));
+        pdf
+    }
+
+    /// Parse all objects of an exported PDF.
+    fn objects(pdf: &[u8]) -> Vec<Object> {
+        let start = pdf.windows(10).rposition(|w| w == b"startxref\n").unwrap();
+        let mut p = Parser { src: &pdf[start + 10..], pos: 0 };
+        let xref = p.int().unwrap() as usize;
+        parse_chunk(&pdf[..xref]).unwrap()
+    }
+
+    /// Find the encryption dictionary.
+    fn encryption_dict<'a>(objects: &'a [Object]) -> &'a Value {
+        let dict = objects
+            .iter()
+            .map(|object| &object.value)
+            .find(|value| matches!(get(value, b"Filter"), Some(Value::Name(n)) if n == b"Standard"))
+            .unwrap();
+        assert!(matches!(get(dict, b"EncryptMetadata"), Some(Value::Bool(true))));
+        dict
+    }
+
+    /// Decrypt all strings and streams except for those of the encryption
+    /// dictionary. The key of each object is determined by its number.
+    fn check_decrypted(pdf: &[u8], objects: &[Object], key: impl Fn(i32) -> Vec<u8>) {
+        let encryption_dict = encryption_dict(objects);
+        let mut strings = vec![];
+        let mut streams = vec![];
+        for object in objects {
+            if std::ptr::eq(&object.value, encryption_dict) {
+                continue;
+            }
+
+            let key = key(object.id);
+            collect_strings(&object.value, &key, &mut strings);
+            if let Some(data) = object.stream {
+                let data = decrypt(&key, data);
+                let compressed = matches!(
+                    get(&object.value, b"Filter"),
+                    Some(Value::Name(n)) if n == b"FlateDecode"
+                );
+                streams.push(if compressed {
+                    miniz_oxide::inflate::decompress_to_vec_zlib(&data).unwrap()
+                } else {
+                    data
+                });
+            }
+        }
+
+        let contains = |haystack: &[u8], needle: &[u8]| {
+            haystack.windows(needle.len()).any(|w| w == needle)
+        };
+        assert!(strings.iter().any(|s| s == b"Secret Report"));
+        assert!(strings.iter().any(|s| s == b"https://typst.app"));
+        assert!(streams.iter().any(|s| contains(s, b" Tf")));
+        assert!(streams.iter().any(|s| contains(s, b"Secret Report")));
+        assert!(!contains(pdf, b"typst.app"));
+    }
+
+    /// Decrypt all strings in an object.
+    fn collect_strings(value: &Value, key: &[u8], strings: &mut Vec<Vec<u8>>) {
+        match value {
+            Value::Str(string) => strings.push(decrypt(key, string)),
+            Value::Array(items) => {
+                items.iter().for_each(|item| collect_strings(item, key, strings))
+            }
+            Value::Dict(pairs) => pairs
+                .iter()
+                .for_each(|(_, value)| collect_strings(value, key, strings)),
+            _ => {}
+        }
+    }
+
+    /// Look up a key in a dictionary.
+    fn get<'a>(dict: &'a Value, key: &[u8]) -> Option<&'a Value> {
+        let Value::Dict(pairs) = dict else { return None };
+        pairs
+            .iter()
+            .find(|(k, _)| k.as_slice() == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Look up a string in a dictionary.
+    fn string<'a>(dict: &'a Value, key: &[u8]) -> &'a [u8] {
+        match get(dict, key) {
+            Some(Value::Str(string)) => string,
+            _ => panic!("missing string"),
+        }
+    }
+
+    /// Decrypt a string or stream that starts with its initialization vector
+    /// and remove its padding.
+    fn decrypt(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let (iv, data) = data.split_at(16);
+        let mut data = data.to_vec();
+        decrypt_cbc(key, iv, &mut data);
+        let padding = data[data.len() - 1];
+        assert!((1..=16).contains(&padding));
+        assert!(data.ends_with(&vec![padding; padding as usize]));
+        data.truncate(data.len() - padding as usize);
+        data
+    }
+
+    /// Decrypt data in-place with AES in CBC mode.
+    fn decrypt_cbc(key: &[u8], iv: &[u8], data: &mut [u8]) {
+        let mut prev = iv.to_vec();
+        for block in data.chunks_exact_mut(16) {
+            let encrypted = block.to_vec();
+            let array = GenericArray::from_mut_slice(block);
+            if key.len() == 16 {
+                Aes128::new(GenericArray::from_slice(key)).decrypt_block(array);
+            } else {
+                Aes256::new(GenericArray::from_slice(key)).decrypt_block(array);
+            }
+            block.iter_mut().zip(&prev).for_each(|(byte, prev)| *byte ^= prev);
+            prev = encrypted;
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use pdf_writer::{Filter, Finish, Name, Str};
+use typst::diag::{bail, SourceResult};
+use typst::foundations::{NativeElement, StyleChain};
+use typst::pdf::EmbedElem;
+
+use crate::{deflate, pdf_date, PdfContext, PdfStandard};
+
+/// Write the files embedded through `pdf.embed`.
+///
+/// Fails if files are embedded under PDF/A-2, if two files share the same
+/// name, which must uniquely identify a file, or if a file lacks the metadata
+/// that PDF/A-3 requires.
+#[tracing::instrument(skip_all)]
+pub(crate) fn write_embedded_files(ctx: &mut PdfContext) -> SourceResult<()> {
+    let elems = ctx.document.introspector.query(&EmbedElem::elem().select());
+    if elems.is_empty() {
+        return Ok(());
+    }
+
+    // PDF/A-2 only permits embedding files that are themselves PDF/A
+    // documents, which we cannot ensure.
+    if ctx.options.standard == PdfStandard::A2b {
+        bail!(
+            elems[0].span(), "PDF/A-2 does not allow embedding files";
+            hint: "export to PDF/A-3 to embed files"
+        );
+    }
+
+    // PDF/A-3 requires the modification date of each file, for which the
+    // document's date is the best approximation.
+    let tz = ctx.document.date.is_auto();
+    let date = ctx
+        .document
+        .date
+        .unwrap_or(ctx.options.timestamp)
+        .and_then(|date| pdf_date(date, tz));
+
+    let mut seen = HashSet::new();
+    let styles = StyleChain::default();
+    for content in elems.iter() {
+        let Some(embed) = content.to::<EmbedElem>() else { continue };
+        let name = embed.file_name();
+        if !seen.insert(name) {
+            bail!(
+                content.span(), "a file named `{name}` is already embedded";
+                hint: "embedded files are identified by their file name"
+            );
+        }
+
+        let mime_type = embed.mime_type(styles);
+        if ctx.options.standard == PdfStandard::A3b {
+            if mime_type.is_none() {
+                bail!(
+                    content.span(), "PDF/A-3 requires the MIME type of embedded files";
+                    hint: "specify it with the `mime-type` argument"
+                );
+            }
+            if date.is_none() {
+                bail!(
+                    content.span(), "PDF/A-3 requires the date of embedded files";
+                    hint: "set the document's date with `set document(date: ..)`"
+                );
+            }
+        }
+
+        let data = embed.data();
+        let file_ref = ctx.alloc.bump();
+        let compressed = ctx.crypt.data(file_ref, &deflate(data)).into_owned();
+        let mut stream = ctx.pdf.stream(file_ref, &compressed);
+        stream.filter(Filter::FlateDecode);
+        stream.pair(Name(b"Type"), Name(b"EmbeddedFile"));
+        if let Some(mime_type) = mime_type {
+            stream.pair(Name(b"Subtype"), Name(mime_type.as_bytes()));
+        }
+        let mut params = stream.insert(Name(b"Params")).dict();
+        params.pair(Name(b"Size"), data.len() as i32);
+        if let Some(date) = &date {
+            params
+                .pair(Name(b"ModDate"), Str(&ctx.crypt.data(file_ref, date.as_bytes())));
+        }
+        params.finish();
+        stream.finish();
+
+        let spec_ref = ctx.alloc.bump();
+        let mut spec = ctx.pdf.indirect(spec_ref).dict();
+        spec.pair(Name(b"Type"), Name(b"Filespec"));
+        spec.pair(Name(b"F"), Str(&ctx.crypt.data(spec_ref, name.as_bytes())));
+        ctx.crypt.text(&mut spec, spec_ref, Name(b"UF"), name);
+        if let Some(description) = embed.description(styles) {
+            ctx.crypt.text(&mut spec, spec_ref, Name(b"Desc"), description);
+        }
+        spec.pair(Name(b"AFRelationship"), Name(b"Unspecified"));
+        spec.insert(Name(b"EF"))
+            .dict()
+            .pair(Name(b"F"), file_ref)
+            .pair(Name(b"UF"), file_ref);
+        spec.finish();
+
+        ctx.embedded_files.push((name.into(), spec_ref));
+    }
+
+    // The embedded files name tree must be sorted by key.
+    ctx.embedded_files.sort_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(())
+}
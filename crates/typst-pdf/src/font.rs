@@ -31,7 +31,7 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
         let data_ref = ctx.alloc.bump();
         ctx.font_refs.push(type0_ref);
 
-        let glyph_set = ctx.glyph_sets.get_mut(font).unwrap();
+        let glyph_set = &ctx.glyph_sets[font];
         let metrics = font.metrics();
         let ttf = font.ttf();
 
@@ -69,26 +69,21 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
         let mut cid = ctx.pdf.cid_font(cid_ref);
         cid.subtype(if is_cff { CidFontType::Type0 } else { CidFontType::Type2 });
         cid.base_font(Name(base_font.as_bytes()));
-        cid.system_info(SYSTEM_INFO);
+        let registry = ctx.crypt.data(cid_ref, SYSTEM_INFO.registry.0);
+        let ordering = ctx.crypt.data(cid_ref, SYSTEM_INFO.ordering.0);
+        cid.system_info(SystemInfo {
+            registry: Str(&registry),
+            ordering: Str(&ordering),
+            ..SYSTEM_INFO
+        });
         cid.font_descriptor(descriptor_ref);
         cid.default_width(0.0);
         if !is_cff {
             cid.cid_to_gid_map_predefined(Name(b"Identity"));
         }
 
-        // Extract the widths of all glyphs.
-        let mut widths = vec![];
-        for gid in std::iter::once(0).chain(glyph_set.keys().copied()) {
-            let width = ttf.glyph_hor_advance(GlyphId(gid)).unwrap_or(0);
-            let units = font.to_em(width).to_font_units();
-            let cid = glyph_cid(font, gid);
-            if usize::from(cid) >= widths.len() {
-                widths.resize(usize::from(cid) + 1, 0.0);
-                widths[usize::from(cid)] = units;
-            }
-        }
-
         // Write all non-zero glyph widths.
+        let widths = glyph_widths(font, glyph_set);
         let mut first = 0;
         let mut width_writer = cid.widths();
         for (w, group) in widths.group_by_key(|&w| w) {
@@ -146,13 +141,14 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
 
         // Write the /ToUnicode character map, which maps glyph ids back to
         // unicode codepoints to enable copying out of the PDF.
-        let cmap = create_cmap(ttf, glyph_set);
-        ctx.pdf.cmap(cmap_ref, &cmap.finish());
+        let cmap = create_cmap(font, glyph_set);
+        ctx.pdf.cmap(cmap_ref, &ctx.crypt.data(cmap_ref, &cmap));
 
         // Subset and write the font's bytes.
         let glyphs: Vec<_> = glyph_set.keys().copied().collect();
         let data = subset_font(font, &glyphs);
 
+        let data = ctx.crypt.data(data_ref, &data);
         let mut stream = ctx.pdf.stream(data_ref, &data);
         stream.filter(Filter::FlateDecode);
         if is_cff {
@@ -163,6 +159,26 @@ pub(crate) fn write_fonts(ctx: &mut PdfContext) {
     }
 }
 
+/// Extract the widths of the given glyphs, indexed by their CIDs.
+///
+/// This is memoized for the same reason as [`create_cmap`]: It is repeated
+/// for every font on every export, but rarely changes between exports.
+#[comemo::memoize]
+fn glyph_widths(font: &Font, glyph_set: &BTreeMap<u16, EcoString>) -> Arc<Vec<f32>> {
+    let ttf = font.ttf();
+    let mut widths = vec![];
+    for gid in std::iter::once(0).chain(glyph_set.keys().copied()) {
+        let width = ttf.glyph_hor_advance(GlyphId(gid)).unwrap_or(0);
+        let units = font.to_em(width).to_font_units();
+        let cid = glyph_cid(font, gid);
+        if usize::from(cid) >= widths.len() {
+            widths.resize(usize::from(cid) + 1, 0.0);
+            widths[usize::from(cid)] = units;
+        }
+    }
+    Arc::new(widths)
+}
+
 /// Subset a font to the given glyphs.
 ///
 /// - For a font with TrueType outlines, this returns the whole OpenType font.
@@ -197,10 +213,15 @@ fn subset_tag(glyphs: &BTreeMap<u16, EcoString>) -> EcoString {
 }
 
 /// Create a /ToUnicode CMap.
-fn create_cmap(
-    ttf: &ttf_parser::Face,
-    glyph_set: &mut BTreeMap<u16, EcoString>,
-) -> UnicodeCmap {
+///
+/// This is memoized because it walks through the whole cmap table of the font,
+/// which is expensive for fonts with many glyphs (like CJK fonts) and would
+/// otherwise be repeated on every export in watch mode.
+#[comemo::memoize]
+fn create_cmap(font: &Font, glyph_set: &BTreeMap<u16, EcoString>) -> Arc<Vec<u8>> {
+    let ttf = font.ttf();
+    let mut glyph_set = glyph_set.clone();
+
     // For glyphs that have codepoints mapping to them in the font's cmap table,
     // we prefer them over pre-existing text mappings from the document. Only
     // things that don't have a corresponding codepoint (or only a private-use
@@ -232,7 +253,7 @@ fn create_cmap(
         }
     }
 
-    cmap
+    Arc::new(cmap.finish())
 }
 
 /// Get the CID for a glyph id.
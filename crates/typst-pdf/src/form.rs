@@ -0,0 +1,248 @@
+use std::collections::HashSet;
+
+use ecow::EcoString;
+use pdf_writer::writers::Catalog;
+use pdf_writer::{Content, Dict, Finish, Name, Rect, Ref, Str};
+use typst::diag::{bail, SourceResult};
+use typst::introspection::Meta;
+use typst::layout::{Frame, FrameItem};
+use typst::pdf::{FieldKind, FormField};
+
+use crate::crypt::Crypt;
+use crate::PdfContext;
+
+/// The field flag that makes a text field accept multiple lines.
+const MULTILINE: i32 = 1 << 12;
+
+/// The default appearance of text in text fields: Helvetica, sized to fit, in
+/// black.
+const DEFAULT_APPEARANCE: &str = "/Helv 0 Tf 0 g";
+
+/// The largest font size of text in the appearance of text fields.
+const MAX_FONT_SIZE: f32 = 12.0;
+
+/// Check that no two form fields on the exported pages share a name, as PDF
+/// readers identify fields by their name.
+pub(crate) fn check_field_names(ctx: &PdfContext) -> SourceResult<()> {
+    let mut seen = HashSet::new();
+    for (i, frame) in ctx.document.pages.iter().enumerate() {
+        let ranges = &ctx.options.page_ranges;
+        if ranges.as_ref().is_some_and(|ranges| !ranges.includes_page_index(i)) {
+            continue;
+        }
+        check_frame(frame, &mut seen)?;
+    }
+    Ok(())
+}
+
+/// Check the names of the form fields in a frame against those seen so far.
+fn check_frame(frame: &Frame, seen: &mut HashSet<EcoString>) -> SourceResult<()> {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => check_frame(&group.frame, seen)?,
+            FrameItem::Meta(Meta::Field(field), _) => {
+                if !seen.insert(field.name.clone()) {
+                    bail!(
+                        field.span, "a form field named `{}` already exists", field.name;
+                        hint: "form fields are identified by their name"
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Write the widget annotation of a form field on the page with the given
+/// reference.
+///
+/// Each form field has exactly one widget, so the field and its widget share
+/// a single dictionary.
+pub(crate) fn write_widget(
+    ctx: &mut PdfContext,
+    id: Ref,
+    page_ref: Ref,
+    field: &FormField,
+    rect: Rect,
+) {
+    let width = rect.x2 - rect.x1;
+    let height = rect.y2 - rect.y1;
+    let bbox = Rect::new(0.0, 0.0, width, height);
+
+    // Write the appearance streams. Checkboxes need one for each state. Text
+    // fields show their initial value.
+    let on_ref = ctx.alloc.bump();
+    let on = match field.kind {
+        FieldKind::Text => text_appearance(field, width, height),
+        FieldKind::Checkbox => check_mark(width, height),
+        FieldKind::Signature => vec![],
+    };
+    ctx.mark(on_ref);
+    let on = ctx.crypt.data(on_ref, &on);
+    let mut xobject = ctx.pdf.form_xobject(on_ref, &on);
+    xobject.bbox(bbox);
+    if field.kind == FieldKind::Text {
+        write_helvetica(xobject.resources().fonts().insert(Name(b"Helv")).dict());
+    }
+    xobject.finish();
+
+    let off_ref = (field.kind == FieldKind::Checkbox).then(|| ctx.alloc.bump());
+    if let Some(off_ref) = off_ref {
+        ctx.mark(off_ref);
+        ctx.pdf
+            .form_xobject(off_ref, &ctx.crypt.data(off_ref, &[]))
+            .bbox(bbox);
+    }
+
+    ctx.mark(id);
+    let mut widget = ctx.pdf.indirect(id).dict();
+    widget.pair(Name(b"Type"), Name(b"Annot"));
+    widget.pair(Name(b"Subtype"), Name(b"Widget"));
+    widget.pair(Name(b"Rect"), rect);
+    widget.pair(Name(b"P"), page_ref);
+    // Print the field.
+    widget.pair(Name(b"F"), 4);
+    ctx.crypt.text(&mut widget, id, Name(b"T"), &field.name);
+    if let Some(tooltip) = &field.tooltip {
+        ctx.crypt.text(&mut widget, id, Name(b"TU"), tooltip);
+    }
+
+    match field.kind {
+        FieldKind::Text => {
+            widget.pair(Name(b"FT"), Name(b"Tx"));
+            let appearance = ctx.crypt.data(id, DEFAULT_APPEARANCE.as_bytes());
+            widget.pair(Name(b"DA"), Str(&appearance));
+            if field.multiline {
+                widget.pair(Name(b"Ff"), MULTILINE);
+            }
+            if let Some(value) = &field.value {
+                ctx.crypt.text(&mut widget, id, Name(b"V"), value);
+                ctx.crypt.text(&mut widget, id, Name(b"DV"), value);
+            }
+            widget.insert(Name(b"AP")).dict().pair(Name(b"N"), on_ref);
+        }
+        FieldKind::Checkbox => {
+            let state = Name(if field.checked { b"Yes" } else { b"Off" });
+            widget.pair(Name(b"FT"), Name(b"Btn"));
+            widget.pair(Name(b"V"), state);
+            widget.pair(Name(b"DV"), state);
+            widget.pair(Name(b"AS"), state);
+            let mut appearances = widget.insert(Name(b"AP")).dict();
+            let mut states = appearances.insert(Name(b"N")).dict();
+            states.pair(Name(b"Yes"), on_ref);
+            if let Some(off_ref) = off_ref {
+                states.pair(Name(b"Off"), off_ref);
+            }
+            states.finish();
+            appearances.finish();
+        }
+        FieldKind::Signature => {
+            widget.pair(Name(b"FT"), Name(b"Sig"));
+            widget.insert(Name(b"AP")).dict().pair(Name(b"N"), on_ref);
+        }
+    }
+
+    widget.finish();
+    ctx.form_fields.push((id, field.kind));
+}
+
+/// Write the document's interactive form with the given fields into the
+/// catalog with the given reference.
+pub(crate) fn write_acro_form(
+    catalog: &mut Catalog,
+    catalog_ref: Ref,
+    fields: &[(Ref, FieldKind)],
+    crypt: &Crypt,
+) {
+    if fields.is_empty() {
+        return;
+    }
+
+    let mut form = catalog.insert(Name(b"AcroForm")).dict();
+    form.insert(Name(b"Fields"))
+        .array()
+        .items(fields.iter().map(|&(id, _)| id));
+
+    // Signals that the document contains signature fields.
+    if fields.iter().any(|&(_, kind)| kind == FieldKind::Signature) {
+        form.pair(Name(b"SigFlags"), 1);
+    }
+
+    form.pair(Name(b"DA"), Str(&crypt.data(catalog_ref, DEFAULT_APPEARANCE.as_bytes())));
+    let mut resources = form.insert(Name(b"DR")).dict();
+    let mut fonts = resources.insert(Name(b"Font")).dict();
+    write_helvetica(fonts.insert(Name(b"Helv")).dict());
+}
+
+/// Write the dictionary of the Helvetica font that the default appearance
+/// refers to.
+fn write_helvetica(mut font: Dict) {
+    font.pair(Name(b"Type"), Name(b"Font"));
+    font.pair(Name(b"Subtype"), Name(b"Type1"));
+    font.pair(Name(b"BaseFont"), Name(b"Helvetica"));
+    font.pair(Name(b"Encoding"), Name(b"WinAnsiEncoding"));
+}
+
+/// Draw the initial value of a text field with the given size in the way the
+/// default appearance prescribes.
+///
+/// A single line is centered vertically, multiple lines start at the top.
+/// Text that doesn't fit is clipped.
+fn text_appearance(field: &FormField, width: f32, height: f32) -> Vec<u8> {
+    let Some(value) = &field.value else { return vec![] };
+    let padding = 2.0;
+    let size =
+        if field.multiline { MAX_FONT_SIZE } else { (0.7 * height).min(MAX_FONT_SIZE) };
+
+    let mut content = Content::new();
+    content.begin_marked_content(Name(b"Tx"));
+    content.save_state();
+    content.rect(1.0, 1.0, width - 2.0, height - 2.0);
+    content.clip_nonzero();
+    content.end_path();
+    content.begin_text();
+    content.set_font(Name(b"Helv"), size);
+    content.set_fill_gray(0.0);
+    if field.multiline {
+        content.next_line(padding, height - padding - size);
+        for (i, line) in value.lines().enumerate() {
+            if i > 0 {
+                content.next_line(0.0, -1.15 * size);
+            }
+            content.show(Str(&win_ansi(line)));
+        }
+    } else {
+        // Helvetica's capitals are 0.718 em high.
+        let line = value.lines().next().unwrap_or_default();
+        content.next_line(padding, 0.5 * (height - 0.718 * size));
+        content.show(Str(&win_ansi(line)));
+    }
+    content.end_text();
+    content.restore_state();
+    content.end_marked_content();
+    content.finish()
+}
+
+/// Encode text for Helvetica, which uses the WinAnsi encoding.
+///
+/// Characters that the encoding lacks are replaced by question marks.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| match c as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => b'?',
+        })
+        .collect()
+}
+
+/// Draw the check mark of a checked checkbox with the given size.
+fn check_mark(width: f32, height: f32) -> Vec<u8> {
+    let mut content = Content::new();
+    content.set_line_width(0.12 * width.min(height));
+    content.move_to(0.2 * width, 0.5 * height);
+    content.line_to(0.42 * width, 0.25 * height);
+    content.line_to(0.8 * width, 0.78 * height);
+    content.stroke();
+    content.finish()
+}
@@ -103,6 +103,7 @@ pub(crate) fn write_gradients(ctx: &mut PdfContext) {
                 let vertices = compute_vertex_stream(conic, aspect_ratio);
 
                 let stream_shading_id = ctx.alloc.bump();
+                let vertices = ctx.crypt.data(stream_shading_id, &vertices);
                 let mut stream_shading =
                     ctx.pdf.stream_shading(stream_shading_id, &vertices);
 
@@ -3,6 +3,8 @@ use std::io::Cursor;
 
 use image::{DynamicImage, GenericImageView, Rgba};
 use pdf_writer::{Chunk, Filter, Finish, Ref};
+use typst::diag::{bail, SourceResult};
+use typst::syntax::Span;
 use typst::util::Deferred;
 use typst::visualize::{
     ColorSpace, Image, ImageKind, RasterFormat, RasterImage, SvgImage,
@@ -33,7 +35,7 @@ pub fn deferred_image(image: Image) -> Deferred<EncodedImage> {
 
 /// Embed all used images into the PDF.
 #[tracing::instrument(skip_all)]
-pub(crate) fn write_images(ctx: &mut PdfContext) {
+pub(crate) fn write_images(ctx: &mut PdfContext) -> SourceResult<()> {
     for (i, _) in ctx.image_map.items().enumerate() {
         let handle = ctx.image_deferred_map.get(&i).unwrap();
         match handle.wait() {
@@ -49,7 +51,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                 let image_ref = ctx.alloc.bump();
                 ctx.image_refs.push(image_ref);
 
-                let mut image = ctx.pdf.image_xobject(image_ref, data);
+                let data = ctx.crypt.data(image_ref, data);
+                let mut image = ctx.pdf.image_xobject(image_ref, &data);
                 image.filter(*filter);
                 image.width(*width as i32);
                 image.height(*height as i32);
@@ -74,7 +77,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                     image.s_mask(mask_ref);
                     image.finish();
 
-                    let mut mask = ctx.pdf.image_xobject(mask_ref, alpha_data);
+                    let alpha_data = ctx.crypt.data(mask_ref, alpha_data);
+                    let mut mask = ctx.pdf.image_xobject(mask_ref, &alpha_data);
                     mask.filter(*alpha_filter);
                     mask.width(*width as i32);
                     mask.height(*height as i32);
@@ -85,7 +89,8 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
                 }
 
                 if let (Some(icc), Some(icc_ref)) = (icc, icc_ref) {
-                    let mut stream = ctx.pdf.icc_profile(icc_ref, icc);
+                    let icc = ctx.crypt.data(icc_ref, icc);
+                    let mut stream = ctx.pdf.icc_profile(icc_ref, &icc);
                     stream.filter(Filter::FlateDecode);
                     if *has_color {
                         stream.n(3);
@@ -98,13 +103,18 @@ pub(crate) fn write_images(ctx: &mut PdfContext) {
             }
             EncodedImage::Svg(chunk) => {
                 let mut map = HashMap::new();
-                chunk.renumber_into(&mut ctx.pdf, |old| {
+                let renumbered = ctx.crypt.renumber_into(chunk, &mut ctx.pdf, |old| {
                     *map.entry(old).or_insert_with(|| ctx.alloc.bump())
                 });
+                if renumbered.is_none() {
+                    bail!(Span::detached(), "failed to encrypt SVG image");
+                }
                 ctx.image_refs.push(map[&Ref::new(1)]);
             }
         }
     }
+
+    Ok(())
 }
 
 /// Encode an image with a suitable filter and return the data, filter and
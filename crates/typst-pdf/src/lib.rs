@@ -1,74 +1,197 @@
 //! Exporting into PDF documents.
 
 mod color;
+mod crypt;
+mod embed;
 mod extg;
 mod font;
+mod form;
 mod gradient;
 mod image;
+mod named_destination;
 mod outline;
 mod page;
 mod pattern;
+mod stream;
+mod tag;
+#[cfg(test)]
+mod tests;
+
+pub use self::crypt::{PdfCipher, PdfEncryption, PdfPermissions};
 
 use std::cmp::Eq;
 use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
+use std::io::{self, Write};
+use std::num::NonZeroUsize;
 use std::sync::Arc;
 
 use base64::Engine;
 use ecow::{eco_format, EcoString};
 use pdf_writer::types::Direction;
-use pdf_writer::{Finish, Name, Pdf, Ref, TextStr};
-use typst::foundations::Datetime;
-use typst::layout::{Abs, Dir, Em, Transform};
+use pdf_writer::{Filter, Finish, Name, Pdf, Ref, Str, TextStr};
+use typst::diag::{bail, At, SourceResult};
+use typst::foundations::{Datetime, Label};
+use typst::introspection::Location;
+use typst::layout::{Abs, Dir, Em, PageRanges, Transform};
 use typst::model::Document;
+use typst::pdf::FieldKind;
+use typst::syntax::Span;
 use typst::text::{Font, Lang};
 use typst::util::Deferred;
 use typst::visualize::Image;
 use xmp_writer::{DateTime, LangId, RenditionClass, Timezone, XmpWriter};
 
-use crate::color::ColorSpaces;
+use crate::color::{ColorSpaces, SRGB_ICC_DEFLATED};
+use crate::crypt::Crypt;
 use crate::extg::ExtGState;
 use crate::gradient::PdfGradient;
 use crate::image::EncodedImage;
 use crate::page::Page;
 use crate::pattern::PdfPattern;
+use crate::stream::{PdfStream, Trailer};
+use crate::tag::Tags;
 
 /// Export a document into a PDF file.
 ///
-/// Returns the raw bytes making up the PDF file.
+/// Returns the raw bytes making up the PDF file. The output is reproducible:
+/// Given the same document and options, the bytes will be identical. To keep it
+/// this way, the `timestamp` should be fixed, e.g. through `SOURCE_DATE_EPOCH`.
+///
+/// Fails if the options combine encryption with a PDF/A standard, if the
+/// embedded files don't conform to the standard, if two form fields share a
+/// name, or if an image can't be embedded.
+#[tracing::instrument(skip_all)]
+pub fn pdf(document: &Document, options: &PdfOptions) -> SourceResult<Vec<u8>> {
+    let mut ctx = PdfContext::new(document, options)?;
+    form::check_field_names(&ctx)?;
+    page::construct_pages(&mut ctx, &document.pages);
+    named_destination::write_named_destinations(&mut ctx);
+    embed::write_embedded_files(&mut ctx)?;
+    font::write_fonts(&mut ctx);
+    image::write_images(&mut ctx)?;
+    gradient::write_gradients(&mut ctx);
+    extg::write_external_graphics_states(&mut ctx);
+    pattern::write_patterns(&mut ctx);
+    page::write_pages(&mut ctx);
+    page::write_page_tree(&mut ctx);
+    write_catalog(&mut ctx);
+    ctx.finish()
+}
+
+/// Export a document into a PDF file that is written to `out` while it is
+/// being produced.
 ///
-/// The `ident` parameter shall be a string that uniquely and stably identifies
-/// the document. It should not change between compilations of the same
-/// document. Its hash will be used to create a PDF document identifier (the
-/// identifier itself is not leaked). If `ident` is `None`, a hash of the
-/// document is used instead (which means that it _will_ change across
-/// compilations).
+/// Unlike [`pdf`], this writes out each page as soon as it is encoded, so that
+/// the pages of long documents don't have to be kept in memory all at once.
+/// Fonts, images and the other resources shared between pages follow after
+/// the pages, and a cross-reference stream ends the file. The output is just
+/// as reproducible as that of [`pdf`], but not identical to it.
 ///
-/// The `timestamp`, if given, is expected to be the creation date of the
-/// document as a UTC datetime. It will only be used if `set document(date: ..)`
-/// is `auto`.
+/// Fails like [`pdf`] does and additionally if writing to `out` fails.
 #[tracing::instrument(skip_all)]
-pub fn pdf(
+pub fn pdf_stream(
     document: &Document,
-    ident: Option<&str>,
-    timestamp: Option<Datetime>,
-) -> Vec<u8> {
-    let mut ctx = PdfContext::new(document);
-    page::construct_pages(&mut ctx, &document.pages);
+    options: &PdfOptions,
+    out: &mut dyn Write,
+) -> SourceResult<()> {
+    let mut ctx = PdfContext::new(document, options)?;
+    form::check_field_names(&ctx)?;
+    let failed = |err: io::Error| eco_format!("failed to write PDF file ({err})");
+    let mut stream = PdfStream::new(out).map_err(failed).at(Span::detached())?;
+    page::allocate_pages(&mut ctx, &document.pages);
+    named_destination::write_named_destinations(&mut ctx);
+    page::stream_pages(&mut ctx, &document.pages, &mut stream)
+        .map_err(failed)
+        .at(Span::detached())?;
+    embed::write_embedded_files(&mut ctx)?;
     font::write_fonts(&mut ctx);
-    image::write_images(&mut ctx);
+    image::write_images(&mut ctx)?;
     gradient::write_gradients(&mut ctx);
     extg::write_external_graphics_states(&mut ctx);
     pattern::write_patterns(&mut ctx);
     page::write_page_tree(&mut ctx);
-    write_catalog(&mut ctx, ident, timestamp);
-    ctx.pdf.finish()
+    write_catalog(&mut ctx);
+    ctx.finish_stream(stream).map_err(failed).at(Span::detached())
+}
+
+/// Settings for PDF export.
+#[derive(Debug, Default, Clone)]
+pub struct PdfOptions<'a> {
+    /// A string that uniquely and stably identifies the document. It should
+    /// not change between compilations of the same document. Its hash will be
+    /// used to create a PDF document identifier (the identifier itself is not
+    /// leaked). If this is `None`, a hash of the document is used instead
+    /// (which means that it _will_ change across compilations).
+    pub ident: Option<&'a str>,
+    /// The creation date of the document as a UTC datetime. It will only be
+    /// used if `set document(date: ..)` is `auto`.
+    pub timestamp: Option<Datetime>,
+    /// The PDF standard the output should conform to.
+    pub standard: PdfStandard,
+    /// The pages to export. If this is `None`, all pages are exported.
+    ///
+    /// Links and outline entries pointing to pages that are not exported
+    /// have no destination.
+    pub page_ranges: Option<PageRanges>,
+    /// How to encrypt the PDF. If this is `None`, it is not encrypted.
+    ///
+    /// Encryption is not allowed by the PDF/A standards, so export fails if
+    /// it is combined with one of them.
+    pub encryption: Option<PdfEncryption>,
+    /// Whether encrypted output should be reproducible.
+    ///
+    /// By default, the file encryption key, its salts and the initialization
+    /// vectors are random, as the PDF standard requires. With this, they are
+    /// derived from the document identifier and the passwords instead. This
+    /// weakens the encryption: Anyone who knows the identifier and a
+    /// password can predict them.
+    pub reproducible_encryption: bool,
+}
+
+/// A PDF standard that Typst can enforce conformance with.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum PdfStandard {
+    /// Plain PDF 1.7.
+    #[default]
+    V17,
+    /// PDF/A-2b, for long-term archival.
+    ///
+    /// This embeds an sRGB output intent, marks the document as PDF/A in its
+    /// XMP metadata, and makes all annotations printable. As PDF/A-2 does not
+    /// permit arbitrary embedded files, export fails if `pdf.embed` is used.
+    A2b,
+    /// PDF/A-3b, which is like PDF/A-2b, but permits embedding arbitrary
+    /// files. Each embedded file needs a MIME type and the document a date.
+    A3b,
+    /// PDF/UA-1, for accessibility.
+    ///
+    /// This tags the document with a structure tree of its headings,
+    /// paragraphs and figures, which assistive technology like screen readers
+    /// read the document through. Decorative content is marked as such. The
+    /// document should have a title, which is then shown instead of the file
+    /// name.
+    Ua1,
+}
+
+impl PdfStandard {
+    /// Whether this is one of the PDF/A standards.
+    pub fn is_pdfa(self) -> bool {
+        matches!(self, Self::A2b | Self::A3b)
+    }
+
+    /// Whether the document's logical structure is written.
+    pub fn is_tagged(self) -> bool {
+        matches!(self, Self::Ua1)
+    }
 }
 
 /// Context for exporting a whole PDF document.
 struct PdfContext<'a> {
     /// The document that we're currently exporting.
     document: &'a Document,
+    /// The export settings.
+    options: &'a PdfOptions<'a>,
     /// The writer we are writing the PDF into.
     pdf: Pdf,
     /// Content of exported pages.
@@ -82,7 +205,10 @@ struct PdfContext<'a> {
     glyph_sets: HashMap<Font, BTreeMap<u16, EcoString>>,
     /// The number of glyphs for all referenced languages in the document.
     /// We keep track of this to determine the main document language.
-    languages: HashMap<Lang, usize>,
+    ///
+    /// This is a sorted map so that the languages are written in a stable
+    /// order, keeping the output reproducible.
+    languages: BTreeMap<Lang, usize>,
 
     /// Allocator for indirect reference IDs.
     alloc: Ref,
@@ -90,6 +216,14 @@ struct PdfContext<'a> {
     page_tree_ref: Ref,
     /// The IDs of written pages.
     page_refs: Vec<Ref>,
+    /// For each page of the document, the index of its exported page, if it
+    /// is exported.
+    page_indices: PageIndices,
+    /// The first part of the file identifier. It is only known before the
+    /// catalog is written if the document is encrypted.
+    file_id: Vec<u8>,
+    /// Encrypts strings and streams, if the document is encrypted.
+    crypt: Crypt,
     /// The IDs of written fonts.
     font_refs: Vec<Ref>,
     /// The IDs of written images.
@@ -103,6 +237,24 @@ struct PdfContext<'a> {
     /// Handles color space writing.
     colors: ColorSpaces,
 
+    /// The named destinations, sorted by their label.
+    dests: Vec<(Label, Ref)>,
+    /// Maps from locations of labelled elements to their named destination.
+    loc_to_dest: HashMap<Location, Label>,
+    /// The file specifications of embedded files, sorted by their name.
+    embedded_files: Vec<(EcoString, Ref)>,
+    /// The IDs and kinds of written form fields.
+    form_fields: Vec<(Ref, FieldKind)>,
+    /// The logical structure of the document, if it is tagged.
+    tags: Tags,
+    /// Where the objects written since the last page was streamed out start,
+    /// if pages are streamed.
+    offsets: Option<Vec<(Ref, usize)>>,
+    /// Hashes of the pages that were streamed out, in order.
+    streamed: Vec<u128>,
+    /// The entries of the file's trailer, once the catalog is written.
+    trailer: Option<Trailer>,
+
     /// Deduplicates fonts used across the document.
     font_map: Remapper<Font>,
     /// Deduplicates images used across the document.
@@ -118,37 +270,131 @@ struct PdfContext<'a> {
 }
 
 impl<'a> PdfContext<'a> {
-    fn new(document: &'a Document) -> Self {
+    fn new(document: &'a Document, options: &'a PdfOptions<'a>) -> SourceResult<Self> {
+        // The encryption keys depend on the file identifier, so it must be
+        // known before any object is written.
+        let (file_id, crypt) = match &options.encryption {
+            Some(_) if options.standard.is_pdfa() => {
+                bail!(Span::detached(), "PDF/A does not allow encryption")
+            }
+            Some(encryption) => {
+                let file_id = match options.ident {
+                    Some(ident) => hash_base64(&("PDF-1.7", ident)),
+                    None => hash_base64(&document.pages),
+                }
+                .into_bytes();
+                let crypt =
+                    Crypt::new(encryption, &file_id, options.reproducible_encryption);
+                (file_id, crypt)
+            }
+            None => (vec![], Crypt::default()),
+        };
+
         let mut alloc = Ref::new(1);
         let page_tree_ref = alloc.bump();
-        Self {
+        Ok(Self {
             document,
+            options,
             pdf: Pdf::new(),
             pages: vec![],
             glyph_sets: HashMap::new(),
-            languages: HashMap::new(),
+            languages: BTreeMap::new(),
             alloc,
             page_tree_ref,
             page_refs: vec![],
+            page_indices: PageIndices::default(),
+            file_id,
+            crypt,
             font_refs: vec![],
             image_refs: vec![],
             gradient_refs: vec![],
             pattern_refs: vec![],
             ext_gs_refs: vec![],
             colors: ColorSpaces::default(),
+            dests: vec![],
+            loc_to_dest: HashMap::new(),
+            embedded_files: vec![],
+            form_fields: vec![],
+            tags: Tags::default(),
+            offsets: None,
+            streamed: vec![],
+            trailer: None,
             font_map: Remapper::new(),
             image_map: Remapper::new(),
             image_deferred_map: HashMap::default(),
             gradient_map: Remapper::new(),
             pattern_map: Remapper::new(),
             extg_map: Remapper::new(),
+        })
+    }
+}
+
+impl PdfContext<'_> {
+    /// Finish the file, referring to the encryption dictionary from its
+    /// trailer if the document is encrypted.
+    fn finish(mut self) -> SourceResult<Vec<u8>> {
+        let Some(crypt_ref) = self.write_encryption() else {
+            return Ok(self.pdf.finish());
+        };
+
+        let xref = self.pdf.as_bytes().len();
+        match crypt::refer(self.pdf.finish(), xref, crypt_ref) {
+            Some(buf) => Ok(buf),
+            None => {
+                bail!(Span::detached(), "failed to refer to the encryption dictionary")
+            }
+        }
+    }
+
+    /// Write the remaining objects to a stream and end it with a
+    /// cross-reference stream.
+    fn finish_stream(mut self, stream: PdfStream) -> io::Result<()> {
+        let mut trailer = self.trailer.take().expect("catalog should be written");
+        trailer.encrypt = self.write_encryption();
+        let xref = self.pdf.as_bytes().len();
+        stream.finish(&self.pdf.finish(), xref, &trailer)
+    }
+
+    /// Write the encryption dictionary, if the document is encrypted.
+    fn write_encryption(&mut self) -> Option<Ref> {
+        if !self.crypt.is_enabled() {
+            return None;
+        }
+
+        let crypt_ref = self.alloc.bump();
+        self.crypt.write(&mut self.pdf, crypt_ref);
+        Some(crypt_ref)
+    }
+
+    /// Record that an object with the given ID is about to be written, if
+    /// pages are streamed.
+    fn mark(&mut self, id: Ref) {
+        if let Some(offsets) = &mut self.offsets {
+            offsets.push((id, self.pdf.as_bytes().len()));
         }
     }
 }
 
+/// Maps the pages of the document to the exported pages.
+#[derive(Default)]
+struct PageIndices(Vec<Option<usize>>);
+
+impl PageIndices {
+    /// Record the export index of the next page of the document.
+    fn push(&mut self, index: Option<usize>) {
+        self.0.push(index);
+    }
+
+    /// The index of the exported page that shows the page with the given
+    /// one-based number, if that page is exported.
+    fn get(&self, page: NonZeroUsize) -> Option<usize> {
+        self.0.get(page.get() - 1).copied().flatten()
+    }
+}
+
 /// Write the document catalog.
 #[tracing::instrument(skip_all)]
-fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Datetime>) {
+fn write_catalog(ctx: &mut PdfContext) {
     let lang = ctx
         .languages
         .iter()
@@ -168,10 +414,11 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     let page_labels = page::write_page_labels(ctx);
 
     // Write the document information.
-    let mut info = ctx.pdf.document_info(ctx.alloc.bump());
+    let info_ref = ctx.alloc.bump();
+    let mut info = ctx.pdf.document_info(info_ref);
     let mut xmp = XmpWriter::new();
     if let Some(title) = &ctx.document.title {
-        info.title(TextStr(title));
+        ctx.crypt.text(&mut info, info_ref, Name(b"Title"), title);
         xmp.title([(None, title.as_str())]);
     }
 
@@ -194,26 +441,27 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         // bit weird to not use the array (and it makes Acrobat show the author
         // list in quotes), but there's not much we can do about that.
         let joined = authors.join(", ");
-        info.author(TextStr(&joined));
+        ctx.crypt.text(&mut info, info_ref, Name(b"Author"), &joined);
         xmp.creator([joined.as_str()]);
     }
 
     let creator = eco_format!("Typst {}", env!("CARGO_PKG_VERSION"));
-    info.creator(TextStr(&creator));
+    ctx.crypt.text(&mut info, info_ref, Name(b"Creator"), &creator);
     xmp.creator_tool(&creator);
 
     let keywords = &ctx.document.keywords;
     if !keywords.is_empty() {
         let joined = keywords.join(", ");
-        info.keywords(TextStr(&joined));
+        ctx.crypt.text(&mut info, info_ref, Name(b"Keywords"), &joined);
         xmp.pdf_keywords(&joined);
     }
 
-    if let Some(date) = ctx.document.date.unwrap_or(timestamp) {
+    if let Some(date) = ctx.document.date.unwrap_or(ctx.options.timestamp) {
         let tz = ctx.document.date.is_auto();
         if let Some(pdf_date) = pdf_date(date, tz) {
-            info.creation_date(pdf_date);
-            info.modified_date(pdf_date);
+            let pdf_date = ctx.crypt.data(info_ref, pdf_date.as_bytes());
+            info.pair(Name(b"CreationDate"), Str(&pdf_date));
+            info.pair(Name(b"ModDate"), Str(&pdf_date));
         }
         if let Some(xmp_date) = xmp_date(date, tz) {
             xmp.create_date(xmp_date);
@@ -222,43 +470,106 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
     }
 
     info.finish();
-    xmp.num_pages(ctx.document.pages.len() as u32);
+    xmp.num_pages(ctx.pages.len() as u32);
     xmp.format("application/pdf");
     xmp.language(ctx.languages.keys().map(|lang| LangId(lang.as_str())));
 
     // A unique ID for this instance of the document. Changes if anything
     // changes in the frames.
-    let instance_id = hash_base64(&ctx.pdf.as_bytes());
+    let instance_id = if ctx.streamed.is_empty() {
+        hash_base64(&ctx.pdf.as_bytes())
+    } else {
+        hash_base64(&(&ctx.streamed, ctx.pdf.as_bytes()))
+    };
 
-    if let Some(ident) = ident {
+    let file_id = if let Some(ident) = ctx.options.ident {
         // A unique ID for the document that stays stable across compilations.
         let doc_id = hash_base64(&("PDF-1.7", ident));
         xmp.document_id(&doc_id);
         xmp.instance_id(&instance_id);
-        ctx.pdf
-            .set_file_id((doc_id.clone().into_bytes(), instance_id.into_bytes()));
+        ctx.file_id = doc_id.clone().into_bytes();
+        (ctx.file_id.clone(), instance_id.into_bytes())
+    } else if !ctx.file_id.is_empty() {
+        // The first part was fixed up front for the encryption keys.
+        (ctx.file_id.clone(), instance_id.into_bytes())
     } else {
         // This is not spec-compliant, but some PDF readers really want an ID.
         let bytes = instance_id.into_bytes();
-        ctx.pdf.set_file_id((bytes.clone(), bytes));
-    }
+        ctx.file_id = bytes.clone();
+        (bytes.clone(), bytes)
+    };
+    ctx.pdf.set_file_id(file_id.clone());
 
     xmp.rendition_class(RenditionClass::Proof);
     xmp.pdf_version("1.7");
 
-    let xmp_buf = xmp.finish(None);
+    let mut xmp_buf = xmp.finish(None);
+    if ctx.options.standard.is_pdfa() {
+        // Identify the PDF/A part and conformance level in a separate
+        // description, as required by ISO 19005-2, section 6.6.4.
+        let part = if ctx.options.standard == PdfStandard::A3b { 3 } else { 2 };
+        let id = eco_format!(
+            concat!(
+                r#"<rdf:Description rdf:about="" "#,
+                r#"xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">"#,
+                "<pdfaid:part>{}</pdfaid:part>",
+                "<pdfaid:conformance>B</pdfaid:conformance>",
+                "</rdf:Description>",
+            ),
+            part,
+        );
+        if let Some(end) = xmp_buf.find("</rdf:RDF>") {
+            xmp_buf.insert_str(end, &id);
+        }
+    }
+
+    if ctx.options.standard.is_tagged() {
+        // Identify the PDF/UA part, as required by ISO 14289-1, section 5.
+        let id = concat!(
+            r#"<rdf:Description rdf:about="" "#,
+            r#"xmlns:pdfuaid="http://www.aiim.org/pdfua/ns/id/">"#,
+            "<pdfuaid:part>1</pdfuaid:part>",
+            "</rdf:Description>",
+        );
+        if let Some(end) = xmp_buf.find("</rdf:RDF>") {
+            xmp_buf.insert_str(end, id);
+        }
+    }
+
     let meta_ref = ctx.alloc.bump();
     ctx.pdf
-        .stream(meta_ref, xmp_buf.as_bytes())
+        .stream(meta_ref, &ctx.crypt.data(meta_ref, xmp_buf.as_bytes()))
         .pair(Name(b"Type"), Name(b"Metadata"))
         .pair(Name(b"Subtype"), Name(b"XML"));
 
+    // Write the structure tree.
+    let struct_tree_ref = tag::write_structure(ctx);
+
     // Write the document catalog.
-    let mut catalog = ctx.pdf.catalog(ctx.alloc.bump());
+    let intent_ref = ctx.alloc.bump();
+    let catalog_ref = ctx.alloc.bump();
+    ctx.trailer = Some(Trailer {
+        root: catalog_ref,
+        info: info_ref,
+        id: file_id,
+        encrypt: None,
+    });
+    let mut catalog = ctx.pdf.catalog(catalog_ref);
     catalog.pages(ctx.page_tree_ref);
-    catalog.viewer_preferences().direction(dir);
     catalog.metadata(meta_ref);
 
+    let mut preferences = catalog.viewer_preferences();
+    preferences.direction(dir);
+    if struct_tree_ref.is_some() {
+        preferences.pair(Name(b"DisplayDocTitle"), true);
+    }
+    preferences.finish();
+
+    if let Some(struct_tree_ref) = struct_tree_ref {
+        catalog.pair(Name(b"StructTreeRoot"), struct_tree_ref);
+        catalog.insert(Name(b"MarkInfo")).dict().pair(Name(b"Marked"), true);
+    }
+
     // Insert the page labels.
     if !page_labels.is_empty() {
         let mut num_tree = catalog.page_labels();
@@ -272,8 +583,71 @@ fn write_catalog(ctx: &mut PdfContext, ident: Option<&str>, timestamp: Option<Da
         catalog.outlines(outline_root_id);
     }
 
+    // Write the named destination and embedded files trees.
+    if !ctx.dests.is_empty() || !ctx.embedded_files.is_empty() {
+        let mut name_dict = catalog.names();
+        if !ctx.dests.is_empty() {
+            let mut dests_name_tree = name_dict.destinations();
+            let mut names = dests_name_tree.names();
+            for &(label, dest_ref) in &ctx.dests {
+                let name = ctx.crypt.data(catalog_ref, label.as_str().as_bytes());
+                names.insert(Str(&name), dest_ref);
+            }
+            names.finish();
+            dests_name_tree.finish();
+        }
+        if !ctx.embedded_files.is_empty() {
+            let mut files_name_tree = name_dict.insert(Name(b"EmbeddedFiles")).dict();
+            let mut names = files_name_tree.insert(Name(b"Names")).array();
+            for (name, spec_ref) in &ctx.embedded_files {
+                names.item(Str(&ctx.crypt.data(catalog_ref, name.as_bytes())));
+                names.item(*spec_ref);
+            }
+            names.finish();
+            files_name_tree.finish();
+        }
+        name_dict.finish();
+    }
+
+    // PDF/A-3 associates embedded files with the document through the
+    // catalog's /AF array.
+    if !ctx.embedded_files.is_empty() {
+        catalog
+            .insert(Name(b"AF"))
+            .array()
+            .items(ctx.embedded_files.iter().map(|&(_, spec_ref)| spec_ref));
+    }
+
     if let Some(lang) = lang {
-        catalog.lang(TextStr(lang.as_str()));
+        ctx.crypt
+            .text(&mut catalog, catalog_ref, Name(b"Lang"), lang.as_str());
+    }
+
+    form::write_acro_form(&mut catalog, catalog_ref, &ctx.form_fields, &ctx.crypt);
+
+    // PDF/A requires an output intent to give device-independent meaning to
+    // the document's colors.
+    if ctx.options.standard.is_pdfa() {
+        let mut intents = catalog.insert(Name(b"OutputIntents")).array();
+        let mut intent = intents.push().dict();
+        intent.pair(Name(b"Type"), Name(b"OutputIntent"));
+        intent.pair(Name(b"S"), Name(b"GTS_PDFA1"));
+        intent.pair(Name(b"OutputConditionIdentifier"), TextStr("sRGB"));
+        intent.pair(Name(b"RegistryName"), TextStr("http://www.color.org"));
+        intent.pair(Name(b"Info"), TextStr("sRGB IEC61966-2.1"));
+        intent.pair(Name(b"DestOutputProfile"), intent_ref);
+        intent.finish();
+        intents.finish();
+    }
+
+    catalog.finish();
+
+    if ctx.options.standard.is_pdfa() {
+        ctx.pdf
+            .icc_profile(intent_ref, &SRGB_ICC_DEFLATED)
+            .n(3)
+            .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+            .filter(Filter::FlateDecode);
     }
 }
 
@@ -296,34 +670,29 @@ fn hash_base64<T: Hash>(value: &T) -> String {
         .encode(typst::util::hash128(value).to_be_bytes())
 }
 
-/// Converts a datetime to a pdf-writer date.
-fn pdf_date(datetime: Datetime, tz: bool) -> Option<pdf_writer::Date> {
-    let year = datetime.year().filter(|&y| y >= 0)? as u16;
-
-    let mut pdf_date = pdf_writer::Date::new(year);
-
-    if let Some(month) = datetime.month() {
-        pdf_date = pdf_date.month(month);
-    }
-
-    if let Some(day) = datetime.day() {
-        pdf_date = pdf_date.day(day);
-    }
-
-    if let Some(h) = datetime.hour() {
-        pdf_date = pdf_date.hour(h);
-    }
-
-    if let Some(m) = datetime.minute() {
-        pdf_date = pdf_date.minute(m);
-    }
-
-    if let Some(s) = datetime.second() {
-        pdf_date = pdf_date.second(s);
+/// Converts a datetime to a PDF date string.
+///
+/// The string is written like `pdf-writer` writes dates, but can be
+/// encrypted.
+fn pdf_date(datetime: Datetime, tz: bool) -> Option<String> {
+    let year = datetime.year().filter(|&y| y >= 0)?;
+    let mut pdf_date = format!("D:{year:04}");
+
+    let parts = [
+        datetime.month(),
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute(),
+        datetime.second(),
+    ];
+    let known = parts.iter().map_while(|&part| part).count();
+    for part in parts.iter().map_while(|&part| part) {
+        pdf_date.push_str(&format!("{part:02}"));
     }
 
-    if tz {
-        pdf_date = pdf_date.utc_offset_hour(0).utc_offset_minute(0);
+    // The time zone is only given for a complete time.
+    if tz && known == parts.len() {
+        pdf_date.push('Z');
     }
 
     Some(pdf_date)
@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+
+use pdf_writer::writers::Destination;
+use typst::layout::Abs;
+
+use crate::{AbsExt, PdfContext};
+
+/// Write named destinations for all labelled elements.
+///
+/// This makes it possible to link into the document from the outside through
+/// URLs like `document.pdf#label`. If a label occurs multiple times, only its
+/// first occurrence becomes a named destination.
+#[tracing::instrument(skip_all)]
+pub(crate) fn write_named_destinations(ctx: &mut PdfContext) {
+    let mut seen = HashSet::new();
+
+    // Find all elements that have a label and are the first among other
+    // elements with the same label.
+    let mut matches: Vec<_> = ctx
+        .document
+        .introspector
+        .all()
+        .filter_map(|elem| elem.location().zip(elem.label()))
+        .filter(|&(_, label)| seen.insert(label))
+        .collect();
+
+    // Named destinations must be sorted by key.
+    matches.sort_by_key(|&(_, label)| label.as_str());
+
+    for (loc, label) in matches {
+        let pos = ctx.document.introspector.position(loc);
+        let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
+
+        if let Some(index) = ctx.page_indices.get(pos.page) {
+            let dest_ref = ctx.alloc.bump();
+            let x = pos.point.x.to_f32();
+            let height = ctx.document.pages[pos.page.get() - 1].height();
+            let y = (height - y).to_f32();
+            ctx.dests.push((label, dest_ref));
+            ctx.loc_to_dest.insert(loc, label);
+            ctx.pdf
+                .indirect(dest_ref)
+                .start::<Destination>()
+                .page(ctx.page_refs[index])
+                .xyz(x, y, None);
+        }
+    }
+}
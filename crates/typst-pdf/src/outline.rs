@@ -1,6 +1,6 @@
 use std::num::NonZeroUsize;
 
-use pdf_writer::{Finish, Ref, TextStr};
+use pdf_writer::{Finish, Name, Ref};
 use typst::foundations::{Content, NativeElement, Smart};
 use typst::layout::Abs;
 use typst::model::HeadingElem;
@@ -160,12 +160,20 @@ fn write_outline_item(
     }
 
     let body = node.element.expect_field_by_name::<Content>("body");
-    outline.title(TextStr(body.plain_text().trim()));
+    let mut title = body.plain_text().trim().to_owned();
+    if let Some(numbers) = node
+        .element
+        .to::<HeadingElem>()
+        .and_then(|heading| heading.numbers().as_ref())
+    {
+        title = format!("{} {title}", numbers.plain_text().trim());
+    }
+    ctx.crypt.text(&mut outline, id, Name(b"Title"), &title);
 
     let loc = node.element.location().unwrap();
     let pos = ctx.document.introspector.position(loc);
-    let index = pos.page.get() - 1;
-    if let Some(page) = ctx.pages.get(index) {
+    if let Some(index) = ctx.page_indices.get(pos.page) {
+        let page = &ctx.pages[index];
         let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
         outline.dest().page(ctx.page_refs[index]).xyz(
             pos.point.x.to_f32(),
@@ -1,19 +1,23 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::io;
 use std::num::NonZeroUsize;
+use std::sync::Arc;
 
 use ecow::{eco_format, EcoString};
 use pdf_writer::types::{
-    ActionType, AnnotationType, ColorSpaceOperand, LineCapStyle, LineJoinStyle,
-    NumberingStyle,
+    ActionType, AnnotationFlags, AnnotationType, ColorSpaceOperand, LineCapStyle,
+    LineJoinStyle, NumberingStyle, TextRenderingMode,
 };
-use pdf_writer::writers::PageLabel;
-use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str, TextStr};
-use typst::introspection::Meta;
+use pdf_writer::writers::{Annotation, PageLabel};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str};
+use typst::foundations::StyleChain;
+use typst::introspection::{Location, Meta};
 use typst::layout::{
-    Abs, Em, Frame, FrameItem, GroupItem, PdfPageLabel, PdfPageLabelStyle, Point, Ratio,
-    Size, Transform,
+    Abs, Em, Frame, FrameItem, GroupItem, PdfPageLabel, PdfPageLabelStyle, Point,
+    PrintSetup, Ratio, Size, Transform,
 };
-use typst::model::Destination;
+use typst::model::{Destination, HeadingElem};
+use typst::pdf::FormField;
 use typst::text::{Font, TextItem};
 use typst::util::Numeric;
 use typst::visualize::{
@@ -23,34 +27,106 @@ use typst::visualize::{
 use crate::color::PaintEncode;
 use crate::extg::ExtGState;
 use crate::image::deferred_image;
-use crate::{deflate_memoized, AbsExt, EmExt, PdfContext};
+use crate::stream::PdfStream;
+use crate::tag::Role;
+use crate::{deflate, deflate_memoized, form, AbsExt, EmExt, PdfContext};
 
 /// Construct page objects.
 #[tracing::instrument(skip_all)]
 pub(crate) fn construct_pages(ctx: &mut PdfContext, frames: &[Frame]) {
-    for frame in frames {
+    for (i, frame) in frames.iter().enumerate() {
+        let ranges = &ctx.options.page_ranges;
+        if ranges.as_ref().is_some_and(|ranges| !ranges.includes_page_index(i)) {
+            ctx.page_indices.push(None);
+            continue;
+        }
+
+        ctx.page_indices.push(Some(ctx.pages.len()));
         let (page_ref, page) = construct_page(ctx, frame);
         ctx.page_refs.push(page_ref);
         ctx.pages.push(page);
     }
 }
 
+/// Construct and write the pages one at a time, streaming each of them out
+/// before the next one is constructed.
+///
+/// The references of all exported pages must already be allocated through
+/// [`allocate_pages`], as links can point to pages that come later.
+#[tracing::instrument(skip_all)]
+pub(crate) fn stream_pages(
+    ctx: &mut PdfContext,
+    frames: &[Frame],
+    stream: &mut PdfStream,
+) -> io::Result<()> {
+    for (i, frame) in frames.iter().enumerate() {
+        let Some(index) = ctx.page_indices.get(NonZeroUsize::new(1 + i).unwrap()) else {
+            continue;
+        };
+
+        let page_ref = ctx.page_refs[index];
+        let page = encode_page(ctx, frame, page_ref);
+        ctx.pages.push(page);
+
+        // Write the page into a separate file, so that it can be written out
+        // without the objects that were collected so far.
+        let collected = std::mem::replace(&mut ctx.pdf, Pdf::new());
+        ctx.offsets = Some(vec![]);
+        write_page(ctx, index);
+        let pdf = std::mem::replace(&mut ctx.pdf, collected);
+        let offsets = ctx.offsets.take().unwrap_or_default();
+        ctx.streamed.push(typst::util::hash128(pdf.as_bytes()));
+        stream.write(&pdf, &offsets)?;
+
+        // Only the page's metadata is needed from now on.
+        ctx.pages[index].content = vec![];
+    }
+
+    Ok(())
+}
+
+/// Allocate the references of all exported pages.
+pub(crate) fn allocate_pages(ctx: &mut PdfContext, frames: &[Frame]) {
+    for i in 0..frames.len() {
+        let ranges = &ctx.options.page_ranges;
+        if ranges.as_ref().is_some_and(|ranges| !ranges.includes_page_index(i)) {
+            ctx.page_indices.push(None);
+            continue;
+        }
+
+        ctx.page_indices.push(Some(ctx.page_refs.len()));
+        let page_ref = ctx.alloc.bump();
+        ctx.page_refs.push(page_ref);
+    }
+}
+
 /// Construct a page object.
 #[tracing::instrument(skip_all)]
 pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Page) {
     let page_ref = ctx.alloc.bump();
+    (page_ref, encode_page(ctx, frame, page_ref))
+}
 
+/// Encode a frame into the page object with the given reference.
+fn encode_page(ctx: &mut PdfContext, frame: &Frame, page_ref: Ref) -> Page {
+    let page_index = ctx.pages.len();
     let mut ctx = PageContext {
         parent: ctx,
         page_ref,
+        page_index,
         label: None,
+        print: None,
         uses_opacities: false,
         content: Content::new(),
         state: State::new(frame.size()),
         saves: vec![],
         bottom: 0.0,
         links: vec![],
-        resources: HashMap::default(),
+        fields: vec![],
+        marked: vec![],
+        heading: None,
+        artifact: false,
+        resources: BTreeMap::default(),
     };
 
     let size = frame.size();
@@ -69,26 +145,36 @@ pub(crate) fn construct_page(ctx: &mut PdfContext, frame: &Frame) -> (Ref, Page)
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
-    let page = Page {
+    // Add printer's marks outside of the page and its bleed.
+    if let Some(setup) = ctx.print.filter(|setup| setup.marks) {
+        write_marks(&mut ctx, size, setup.bleed);
+    }
+
+    Page {
         size,
         content: ctx.content.finish(),
         id: ctx.page_ref,
         uses_opacities: ctx.uses_opacities,
         links: ctx.links,
+        fields: ctx.fields,
+        marked: ctx.marked,
         label: ctx.label,
+        print: ctx.print,
         resources: ctx.resources,
-    };
-
-    (page_ref, page)
+    }
 }
 
-/// Write the page tree.
+/// Write all constructed pages.
 #[tracing::instrument(skip_all)]
-pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
+pub(crate) fn write_pages(ctx: &mut PdfContext) {
     for i in 0..ctx.pages.len() {
         write_page(ctx, i);
     }
+}
 
+/// Write the page tree.
+#[tracing::instrument(skip_all)]
+pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
     let mut pages = ctx.pdf.pages(ctx.page_tree_ref);
     pages
         .count(ctx.page_refs.len() as i32)
@@ -138,23 +224,47 @@ pub(crate) fn write_page_tree(ctx: &mut PdfContext) {
     pages.finish();
 
     // Write all of the functions used by the document.
-    ctx.colors.write_functions(&mut ctx.pdf);
+    ctx.colors.write_functions(&mut ctx.pdf, &ctx.crypt);
 }
 
 /// Write a page tree node.
 #[tracing::instrument(skip_all)]
 fn write_page(ctx: &mut PdfContext, i: usize) {
+    ctx.mark(ctx.pages[i].id);
     let page = &ctx.pages[i];
     let content_id = ctx.alloc.bump();
+    let field_refs: Vec<_> = page.fields.iter().map(|_| ctx.alloc.bump()).collect();
 
     let mut page_writer = ctx.pdf.page(page.id);
     page_writer.parent(ctx.page_tree_ref);
 
     let w = page.size.x.to_f32();
     let h = page.size.y.to_f32();
-    page_writer.media_box(Rect::new(0.0, 0.0, w, h));
+    match page.print {
+        Some(setup) => {
+            let bleed = setup.bleed.to_f32();
+            let extent = if setup.marks { marks_extent(setup.bleed) } else { bleed };
+            page_writer.media_box(Rect::new(-extent, -extent, w + extent, h + extent));
+            page_writer.bleed_box(Rect::new(-bleed, -bleed, w + bleed, h + bleed));
+            page_writer.trim_box(Rect::new(0.0, 0.0, w, h));
+        }
+        None => {
+            page_writer.media_box(Rect::new(0.0, 0.0, w, h));
+        }
+    }
     page_writer.contents(content_id);
 
+    if !page.marked.is_empty() {
+        page_writer.pair(Name(b"StructParents"), i as i32);
+    }
+
+    // Tagged documents must visit annotations in the order of the structure.
+    if ctx.options.standard.is_tagged()
+        && !(page.links.is_empty() && page.fields.is_empty())
+    {
+        page_writer.pair(Name(b"Tabs"), Name(b"S"));
+    }
+
     if page.uses_opacities {
         page_writer
             .group()
@@ -165,41 +275,71 @@ fn write_page(ctx: &mut PdfContext, i: usize) {
             .srgb();
     }
 
-    let mut annotations = page_writer.annotations();
+    let mut annotations = page_writer.insert(Name(b"Annots")).array();
     for (dest, rect) in &page.links {
-        let mut annotation = annotations.push();
+        let mut annotation = annotations.push().start::<Annotation>();
         annotation.subtype(AnnotationType::Link).rect(*rect);
         annotation.border(0.0, 0.0, 0.0, None);
+        if ctx.options.standard.is_pdfa() {
+            annotation.flags(AnnotationFlags::PRINT);
+        }
 
         let pos = match dest {
             Destination::Url(uri) => {
                 annotation
                     .action()
                     .action_type(ActionType::Uri)
-                    .uri(Str(uri.as_bytes()));
+                    .uri(Str(&ctx.crypt.data(page.id, uri.as_bytes())));
                 continue;
             }
             Destination::Position(pos) => *pos,
-            Destination::Location(loc) => ctx.document.introspector.position(*loc),
+            Destination::Location(loc) => {
+                if let Some(label) = ctx.loc_to_dest.get(loc) {
+                    let label = ctx.crypt.data(page.id, label.as_str().as_bytes());
+                    annotation
+                        .action()
+                        .action_type(ActionType::GoTo)
+                        // The key must be a `Str`, not a `Name`.
+                        .pair(Name(b"D"), Str(&label));
+                    continue;
+                }
+                ctx.document.introspector.position(*loc)
+            }
         };
 
-        let index = pos.page.get() - 1;
         let y = (pos.point.y - Abs::pt(10.0)).max(Abs::zero());
-        if let Some(page) = ctx.pages.get(index) {
+        if let Some(index) = ctx.page_indices.get(pos.page) {
+            let height = ctx.document.pages[pos.page.get() - 1].height();
             annotation
                 .action()
                 .action_type(ActionType::GoTo)
                 .destination()
                 .page(ctx.page_refs[index])
-                .xyz(pos.point.x.to_f32(), (page.size.y - y).to_f32(), None);
+                .xyz(pos.point.x.to_f32(), (height - y).to_f32(), None);
         }
     }
 
+    annotations.items(field_refs.iter().copied());
     annotations.finish();
     page_writer.finish();
 
-    let data = deflate_memoized(&page.content);
-    ctx.pdf.stream(content_id, &data).filter(Filter::FlateDecode);
+    // Streamed pages are not memoized, as the cache would keep all of them
+    // in memory.
+    let data = if ctx.offsets.is_some() {
+        Arc::new(deflate(&page.content))
+    } else {
+        deflate_memoized(&page.content)
+    };
+
+    let page_ref = page.id;
+    let fields = page.fields.clone();
+    ctx.mark(content_id);
+    ctx.pdf
+        .stream(content_id, &ctx.crypt.data(content_id, &data))
+        .filter(Filter::FlateDecode);
+    for ((field, rect), id) in fields.iter().zip(field_refs) {
+        form::write_widget(ctx, id, page_ref, field, *rect);
+    }
 }
 
 /// Write the page labels.
@@ -233,7 +373,7 @@ pub(crate) fn write_page_labels(ctx: &mut PdfContext) -> Vec<(NonZeroUsize, Ref)
         // Only add what is actually provided. Don't add empty prefix string if
         // it wasn't given for example.
         if let Some(prefix) = &label.prefix {
-            entry.prefix(TextStr(prefix));
+            ctx.crypt.text(&mut entry, id, Name(b"P"), prefix);
         }
 
         if let Some(style) = label.style {
@@ -263,14 +403,22 @@ pub struct Page {
     pub uses_opacities: bool,
     /// Links in the PDF coordinate system.
     pub links: Vec<(Destination, Rect)>,
+    /// Form fields in the PDF coordinate system.
+    pub fields: Vec<(FormField, Rect)>,
+    /// For each marked-content sequence of a tagged page, the index of the
+    /// structure element it belongs to.
+    pub marked: Vec<usize>,
     /// The page's PDF label.
     pub label: Option<PdfPageLabel>,
-    /// The page's used resources
-    pub resources: HashMap<PageResource, usize>,
+    /// The page's print production settings.
+    pub print: Option<PrintSetup>,
+    /// The page's used resources, sorted so that they are written in a
+    /// stable order.
+    pub resources: BTreeMap<PageResource, usize>,
 }
 
 /// Represents a resource being used in a PDF page by its name.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PageResource {
     kind: ResourceKind,
     name: EcoString,
@@ -283,7 +431,7 @@ impl PageResource {
 }
 
 /// A kind of resource being used in a PDF page.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ResourceKind {
     XObject,
     Font,
@@ -328,15 +476,24 @@ impl PageResource {
 pub struct PageContext<'a, 'b> {
     pub(crate) parent: &'a mut PdfContext<'b>,
     page_ref: Ref,
+    page_index: usize,
     label: Option<PdfPageLabel>,
+    print: Option<PrintSetup>,
     pub content: Content,
     state: State,
     saves: Vec<State>,
     bottom: f32,
     uses_opacities: bool,
     links: Vec<(Destination, Rect)>,
+    fields: Vec<(FormField, Rect)>,
+    /// The structure element of each marked-content sequence so far.
+    marked: Vec<usize>,
+    /// The heading whose content is currently being written.
+    heading: Option<(Location, NonZeroUsize)>,
+    /// Whether decorative content is currently being written.
+    artifact: bool,
     /// Keep track of the resources being used in the page.
-    pub resources: HashMap<PageResource, usize>,
+    pub resources: BTreeMap<PageResource, usize>,
 }
 
 /// A simulated graphics state used to deduplicate graphics state changes and
@@ -355,6 +512,7 @@ struct State {
     external_graphics_state: Option<ExtGState>,
     stroke: Option<FixedStroke>,
     stroke_space: Option<Name<'static>>,
+    text_rendering_mode: TextRenderingMode,
 }
 
 impl State {
@@ -370,6 +528,7 @@ impl State {
             external_graphics_state: None,
             stroke: None,
             stroke_space: None,
+            text_rendering_mode: TextRenderingMode::Fill,
         }
     }
 
@@ -555,10 +714,70 @@ impl PageContext<'_, '_> {
     pub fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    fn set_text_rendering_mode(&mut self, mode: TextRenderingMode) {
+        if self.state.text_rendering_mode != mode {
+            self.content.set_text_rendering_mode(mode);
+            self.state.text_rendering_mode = mode;
+        }
+    }
+
+    /// The role of the text that is currently being written.
+    fn text_role(&self) -> Role {
+        match self.heading {
+            Some((loc, level)) => Role::Heading(loc, level),
+            None => Role::Paragraph,
+        }
+    }
+
+    /// Start a marked-content sequence for content with the given role and add
+    /// it to the structure tree, if the document is tagged.
+    ///
+    /// Returns whether a sequence was started.
+    fn begin_tag(&mut self, role: Role) -> bool {
+        if !self.parent.options.standard.is_tagged() || self.artifact {
+            return false;
+        }
+
+        let mcid = self.marked.len() as i32;
+        let name = role.name();
+        let elem = self.parent.tags.push(self.page_index, mcid, role);
+        self.marked.push(elem);
+
+        let mut sequence = self.content.begin_marked_content_with_properties(name);
+        sequence.properties().pair(Name(b"MCID"), mcid);
+        true
+    }
+
+    /// Start a marked-content sequence for decorative content, if the document
+    /// is tagged.
+    ///
+    /// Returns whether a sequence was started.
+    fn begin_artifact(&mut self) -> bool {
+        if !self.parent.options.standard.is_tagged() || self.artifact {
+            return false;
+        }
+
+        self.content.begin_marked_content(Name(b"Artifact"));
+        true
+    }
 }
 
 /// Encode a frame into the content stream.
 fn write_frame(ctx: &mut PageContext, frame: &Frame) {
+    // The content of a heading's frames belongs to the heading.
+    let outer = ctx.heading;
+    if ctx.parent.options.standard.is_tagged() {
+        if let Some(heading) = frame.items().find_map(|(_, item)| match item {
+            FrameItem::Meta(Meta::Elem(elem), _) => elem.to::<HeadingElem>(),
+            _ => None,
+        }) {
+            if let Some(loc) = heading.location() {
+                ctx.heading = Some((loc, heading.level(StyleChain::default())));
+            }
+        }
+    }
+
     for &(pos, ref item) in frame.items() {
         let x = pos.x.to_f32();
         let y = pos.y.to_f32();
@@ -567,16 +786,118 @@ fn write_frame(ctx: &mut PageContext, frame: &Frame) {
             FrameItem::Group(group) => write_group(ctx, pos, group),
             FrameItem::Text(text) => write_text(ctx, pos, text),
             FrameItem::Shape(shape, _) => write_shape(ctx, pos, shape),
-            FrameItem::Image(image, size, _) => write_image(ctx, x, y, image, *size),
+            FrameItem::Image(image, size, opacity, _) => {
+                write_image(ctx, x, y, image, *size, *opacity)
+            }
             FrameItem::Meta(meta, size) => match meta {
                 Meta::Link(dest) => write_link(ctx, pos, dest, *size),
                 Meta::Elem(_) => {}
                 Meta::Hide => {}
                 Meta::PageNumbering(_) => {}
                 Meta::PdfPageLabel(label) => ctx.label = Some(label.clone()),
+                Meta::PrintSetup(setup) => ctx.print = Some(*setup),
+                Meta::Field(field) => write_field(ctx, pos, field, *size),
+                Meta::Artifact => {}
             },
         }
     }
+
+    ctx.heading = outer;
+}
+
+/// The length of crop marks (5mm).
+const MARK_LENGTH: Abs = Abs::raw(5.0 * 72.0 / 25.4);
+
+/// The minimum distance of printer's marks from the page's edges (3mm).
+const MARK_OFFSET: Abs = Abs::raw(3.0 * 72.0 / 25.4);
+
+/// How far the printer's marks for a page with the given bleed extend beyond
+/// the page's edges.
+fn marks_extent(bleed: Abs) -> f32 {
+    (bleed.max(MARK_OFFSET) + MARK_LENGTH * 1.5).to_f32()
+}
+
+/// Encode crop marks, registration marks, and a color bar around a page.
+///
+/// The marks are drawn in registration color (all process inks), so that
+/// they appear on every separation.
+fn write_marks(ctx: &mut PageContext, size: Size, bleed: Abs) {
+    let w = size.x.to_f32();
+    let h = size.y.to_f32();
+    let offset = bleed.max(MARK_OFFSET).to_f32();
+    let len = MARK_LENGTH.to_f32();
+    let cmyk = !ctx.parent.options.standard.is_pdfa();
+
+    ctx.save_state();
+    let content = &mut ctx.content;
+    content.set_line_width(0.25);
+    if cmyk {
+        content.set_stroke_cmyk(1.0, 1.0, 1.0, 1.0);
+    } else {
+        content.set_stroke_gray(0.0);
+    }
+
+    // Crop marks at the corners.
+    for (x, y) in [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)] {
+        let dx = if x == 0.0 { -1.0 } else { 1.0 };
+        let dy = if y == 0.0 { -1.0 } else { 1.0 };
+        content.move_to(x + dx * offset, y);
+        content.line_to(x + dx * (offset + len), y);
+        content.move_to(x, y + dy * offset);
+        content.line_to(x, y + dy * (offset + len));
+    }
+    content.stroke();
+
+    // Registration marks at the centers of the edges.
+    let r = len / 4.0;
+    let d = offset + len / 2.0;
+    for (cx, cy) in [(w / 2.0, -d), (w / 2.0, h + d), (-d, h / 2.0), (w + d, h / 2.0)] {
+        content.move_to(cx - 2.0 * r, cy);
+        content.line_to(cx + 2.0 * r, cy);
+        content.move_to(cx, cy - 2.0 * r);
+        content.line_to(cx, cy + 2.0 * r);
+        write_circle(content, cx, cy, r);
+    }
+    content.stroke();
+
+    // A color bar with the process colors and tints of black, placed below
+    // the page and next to its bottom left crop mark.
+    let cell = len * 0.8;
+    let colors = [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0, 0.0, 0.75],
+        [0.0, 0.0, 0.0, 0.5],
+        [0.0, 0.0, 0.0, 0.25],
+    ];
+    if cmyk {
+        let top = h + d - cell / 2.0;
+        for (i, [c, m, yellow, k]) in colors.into_iter().enumerate() {
+            let x = len + i as f32 * cell;
+            if x + cell > w / 2.0 - 2.0 * r {
+                break;
+            }
+            content.set_fill_cmyk(c, m, yellow, k);
+            content.rect(x, top, cell, cell);
+            content.fill_nonzero();
+        }
+    }
+
+    ctx.restore_state();
+}
+
+/// Add a circle to the current path.
+fn write_circle(content: &mut Content, cx: f32, cy: f32, r: f32) {
+    // The distance of the control points for approximating a quarter circle
+    // with a cubic Bézier curve.
+    let k = 0.552_284_8 * r;
+    content.move_to(cx + r, cy);
+    content.cubic_to(cx + r, cy + k, cx + k, cy + r, cx, cy + r);
+    content.cubic_to(cx - k, cy + r, cx - r, cy + k, cx - r, cy);
+    content.cubic_to(cx - r, cy - k, cx - k, cy - r, cx, cy - r);
+    content.cubic_to(cx + k, cy - r, cx + r, cy - k, cx + r, cy);
 }
 
 /// Encode a group into the content stream.
@@ -603,7 +924,25 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
         ctx.content.end_path();
     }
 
+    // Mark decorative content so that assistive technology can skip it.
+    let artifact = group
+        .frame
+        .items()
+        .any(|(_, item)| matches!(item, FrameItem::Meta(Meta::Artifact, _)));
+
+    let marked = artifact && !ctx.artifact;
+    if marked {
+        ctx.content.begin_marked_content(Name(b"Artifact"));
+    }
+
+    let outer = std::mem::replace(&mut ctx.artifact, ctx.artifact || artifact);
     write_frame(ctx, &group.frame);
+    ctx.artifact = outer;
+
+    if marked {
+        ctx.content.end_marked_content();
+    }
+
     ctx.restore_state();
 }
 
@@ -620,9 +959,20 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
         glyph_set.entry(g.id).or_insert_with(|| segment.into());
     }
 
+    let stroke = text.stroke.as_ref().filter(|stroke| stroke.thickness.to_f32() > 0.0);
+
     ctx.set_fill(&text.fill, true, ctx.state.transforms(Size::zero(), pos));
+    if let Some(stroke) = stroke {
+        ctx.set_stroke(stroke, ctx.state.transforms(Size::zero(), pos));
+        ctx.set_text_rendering_mode(TextRenderingMode::FillStroke);
+    } else {
+        ctx.set_text_rendering_mode(TextRenderingMode::Fill);
+    }
+
     ctx.set_font(&text.font, text.size);
-    ctx.set_opacities(None, Some(&text.fill));
+    ctx.set_opacities(stroke, Some(&text.fill));
+
+    let tagged = ctx.begin_tag(ctx.text_role());
     ctx.content.begin_text();
 
     // Positiosn the text.
@@ -665,6 +1015,10 @@ fn write_text(ctx: &mut PageContext, pos: Point, text: &TextItem) {
     items.finish();
     positioned.finish();
     ctx.content.end_text();
+
+    if tagged {
+        ctx.content.end_marked_content();
+    }
 }
 
 /// Encode a geometrical shape into the content stream.
@@ -694,6 +1048,10 @@ fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
 
     ctx.set_opacities(stroke, shape.fill.as_ref());
 
+    // Shapes are lines, backgrounds and the like, which only decorate the
+    // actual content.
+    let artifact = ctx.begin_artifact();
+
     match shape.geometry {
         Geometry::Line(target) => {
             let dx = target.x.to_f32();
@@ -719,6 +1077,10 @@ fn write_shape(ctx: &mut PageContext, pos: Point, shape: &Shape) {
         (None, Some(_)) => ctx.content.stroke(),
         (Some(_), Some(_)) => ctx.content.fill_nonzero_and_stroke(),
     };
+
+    if artifact {
+        ctx.content.end_marked_content();
+    }
 }
 
 /// Encode a bezier path into the content stream.
@@ -745,7 +1107,14 @@ fn write_path(ctx: &mut PageContext, x: f32, y: f32, path: &Path) {
 }
 
 /// Encode a vector or raster image into the content stream.
-fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size) {
+fn write_image(
+    ctx: &mut PageContext,
+    x: f32,
+    y: f32,
+    image: &Image,
+    size: Size,
+    opacity: Ratio,
+) {
     let index = ctx.parent.image_map.insert(image.clone());
     ctx.parent
         .image_deferred_map
@@ -758,7 +1127,17 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
     ctx.content.save_state();
     ctx.content.transform([w, 0.0, 0.0, -h, x, y + h]);
 
-    if let Some(alt) = image.alt() {
+    // Images are painted with the fill opacity.
+    let fill_opacity = (opacity.get() * 255.0).round() as u8;
+    if fill_opacity != 255 {
+        ctx.set_external_graphics_state(&ExtGState { stroke_opacity: 255, fill_opacity });
+    }
+
+    // In tagged documents, the description is part of the structure tree.
+    if ctx.begin_tag(Role::Figure(image.alt().map(Into::into))) {
+        ctx.content.x_object(Name(name.as_bytes()));
+        ctx.content.end_marked_content();
+    } else if let Some(alt) = image.alt() {
         let mut image_span =
             ctx.content.begin_marked_content_with_properties(Name(b"Span"));
         let mut image_alt = image_span.properties();
@@ -779,12 +1158,31 @@ fn write_image(ctx: &mut PageContext, x: f32, y: f32, image: &Image, size: Size)
 
 /// Save a link for later writing in the annotations dictionary.
 fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size) {
+    let (min, max) = bounding_box(ctx, pos, size);
+    let x1 = min.x.to_f32();
+    let x2 = max.x.to_f32();
+    let y1 = max.y.to_f32();
+    let y2 = min.y.to_f32();
+    let rect = Rect::new(x1, y1, x2, y2);
+
+    ctx.links.push((dest.clone(), rect));
+}
+
+/// Save a form field for later writing in the annotations dictionary.
+fn write_field(ctx: &mut PageContext, pos: Point, field: &FormField, size: Size) {
+    let (min, max) = bounding_box(ctx, pos, size);
+    let rect = Rect::new(min.x.to_f32(), min.y.to_f32(), max.x.to_f32(), max.y.to_f32());
+    ctx.fields.push((field.clone(), rect));
+}
+
+/// Compute the bounding box of an area with the given size and position
+/// after it is transformed into the PDF coordinate system.
+fn bounding_box(ctx: &PageContext, pos: Point, size: Size) -> (Point, Point) {
     let mut min_x = Abs::inf();
     let mut min_y = Abs::inf();
     let mut max_x = -Abs::inf();
     let mut max_y = -Abs::inf();
 
-    // Compute the bounding box of the transformed link.
     for point in [
         pos,
         pos + Point::with_x(size.x),
@@ -798,13 +1196,7 @@ fn write_link(ctx: &mut PageContext, pos: Point, dest: &Destination, size: Size)
         max_y.set_max(t.y);
     }
 
-    let x1 = min_x.to_f32();
-    let x2 = max_x.to_f32();
-    let y1 = max_y.to_f32();
-    let y2 = min_y.to_f32();
-    let rect = Rect::new(x1, y1, x2, y2);
-
-    ctx.links.push((dest.clone(), rect));
+    (Point::new(min_x, min_y), Point::new(max_x, max_y))
 }
 
 fn to_pdf_line_cap(cap: LineCap) -> LineCapStyle {
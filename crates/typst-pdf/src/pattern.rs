@@ -17,6 +17,7 @@ pub(crate) fn write_patterns(ctx: &mut PdfContext) {
         ctx.pattern_refs.push(tiling);
 
         let content = deflate_memoized(content);
+        let content = ctx.crypt.data(tiling, &content);
         let mut tiling_pattern = ctx.pdf.tiling_pattern(tiling, &content);
         tiling_pattern
             .tiling_type(TilingType::ConstantSpacing)
@@ -0,0 +1,194 @@
+use std::io::{self, Write};
+
+use pdf_writer::{Chunk, Filter, Finish, Name, Pdf, Ref, Str};
+
+use crate::deflate;
+
+/// Writes the objects of a PDF file to an output as soon as they are
+/// complete, and finally a cross-reference stream that indexes all of them.
+pub(crate) struct PdfStream<'w> {
+    /// Where the file is written to.
+    out: &'w mut dyn Write,
+    /// The length of the header that `pdf-writer` puts in front of each file.
+    header: usize,
+    /// How many bytes were written so far.
+    written: usize,
+    /// The byte offsets of all written objects.
+    offsets: Vec<(Ref, usize)>,
+}
+
+impl<'w> PdfStream<'w> {
+    /// Start a file by writing its header.
+    pub fn new(out: &'w mut dyn Write) -> io::Result<Self> {
+        let header = Pdf::new();
+        out.write_all(header.as_bytes())?;
+        let len = header.as_bytes().len();
+        Ok(Self { out, header: len, written: len, offsets: vec![] })
+    }
+
+    /// Write the objects of a PDF file that hasn't been finished, along with
+    /// the offsets of the objects within it.
+    pub fn write(&mut self, pdf: &Pdf, offsets: &[(Ref, usize)]) -> io::Result<()> {
+        let body = &pdf.as_bytes()[self.header..];
+        let base = self.written - self.header;
+        self.offsets
+            .extend(offsets.iter().map(|&(id, offset)| (id, base + offset)));
+        self.out.write_all(body)?;
+        self.written += body.len();
+        Ok(())
+    }
+
+    /// Write the objects of a finished PDF file, whose cross-reference table
+    /// starts at `xref`, and end the output with a cross-reference stream in
+    /// place of the file's own table and trailer.
+    pub fn finish(
+        mut self,
+        pdf: &[u8],
+        xref: usize,
+        trailer: &Trailer,
+    ) -> io::Result<()> {
+        let invalid = || {
+            io::Error::new(io::ErrorKind::InvalidData, "unexpected structure of PDF file")
+        };
+        let table = pdf.get(xref..).and_then(|table| table.strip_prefix(b"xref"));
+        let offsets = table.and_then(parse_xref).ok_or_else(invalid)?;
+
+        let base = self.written - self.header;
+        self.offsets
+            .extend(offsets.iter().enumerate().filter_map(|(num, offset)| {
+                offset.map(|offset| (Ref::new(num as i32), base + offset))
+            }));
+        let body = pdf.get(self.header..xref).ok_or_else(invalid)?;
+        self.out.write_all(body)?;
+        self.written += body.len();
+
+        // The cross-reference stream is the last object. Each entry consists
+        // of a type byte, an eight byte offset and a two byte generation.
+        let max = self.offsets.iter().map(|(id, _)| id.get() as usize).max();
+        let num = max.unwrap_or(0) + 1;
+        self.offsets.push((Ref::new(num as i32), self.written));
+        self.offsets.sort();
+
+        let mut entries = Vec::with_capacity(11 * (num + 1));
+        let mut offsets = self.offsets.iter().peekable();
+        for i in 0..=num {
+            match offsets.next_if(|(id, _)| id.get() as usize == i) {
+                Some(&(_, offset)) => {
+                    entries.push(1);
+                    entries.extend((offset as u64).to_be_bytes());
+                    entries.extend([0, 0]);
+                }
+                None => {
+                    let generation: u16 = if i == 0 { 65535 } else { 0 };
+                    entries.push(0);
+                    entries.extend([0; 8]);
+                    entries.extend(generation.to_be_bytes());
+                }
+            }
+        }
+
+        // The stream's dictionary takes over the entries of the trailer.
+        let data = deflate(&entries);
+        let mut chunk = Chunk::new();
+        let mut stream = chunk.stream(Ref::new(num as i32), &data);
+        stream.filter(Filter::FlateDecode);
+        stream.pair(Name(b"Type"), Name(b"XRef"));
+        stream.pair(Name(b"Size"), (num + 1) as i32);
+        stream.insert(Name(b"W")).array().items([1, 8, 2]);
+        stream.pair(Name(b"Root"), trailer.root);
+        stream.pair(Name(b"Info"), trailer.info);
+        stream
+            .insert(Name(b"ID"))
+            .array()
+            .item(Str(&trailer.id.0))
+            .item(Str(&trailer.id.1));
+        if let Some(encrypt) = trailer.encrypt {
+            stream.pair(Name(b"Encrypt"), encrypt);
+        }
+        stream.finish();
+
+        self.out.write_all(chunk.as_bytes())?;
+        write!(self.out, "startxref\n{}\n%%EOF", self.written)?;
+        self.out.flush()
+    }
+}
+
+/// The entries of a file's trailer.
+pub(crate) struct Trailer {
+    /// The document catalog.
+    pub root: Ref,
+    /// The document information dictionary.
+    pub info: Ref,
+    /// The two parts of the file identifier.
+    pub id: (Vec<u8>, Vec<u8>),
+    /// The encryption dictionary, if the document is encrypted.
+    pub encrypt: Option<Ref>,
+}
+
+/// Parse the entries of a cross-reference table, returning the offsets of
+/// the objects that are in use.
+fn parse_xref(table: &[u8]) -> Option<Vec<Option<usize>>> {
+    let text = std::str::from_utf8(table.get(..table.len().min(64))?).ok()?;
+    let line = text.trim_start().lines().next()?;
+    let (first, count) = line.split_once(' ')?;
+    let first: usize = first.parse().ok()?;
+    let count: usize = count.trim().parse().ok()?;
+
+    let start = find(table, b"\n")? + 1;
+    let start = start + find(&table[start..], b"\n")? + 1;
+    let mut offsets = vec![None; first + count];
+    for (k, entry) in table.get(start..start + 20 * count)?.chunks(20).enumerate() {
+        let entry = std::str::from_utf8(entry).ok()?;
+        if entry.get(17..18)? == "n" {
+            offsets[first + k] = Some(entry.get(..10)?.parse().ok()?);
+        }
+    }
+
+    Some(offsets)
+}
+
+/// The position of the first occurrence of the needle.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use pdf_writer::TextStr;
+
+    use super::*;
+    use crate::tests::{check_xref_stream, contains};
+
+    #[test]
+    fn test_stream_offsets() {
+        let mut out = vec![];
+        let mut stream = PdfStream::new(&mut out).unwrap();
+
+        let mut page = Pdf::new();
+        let offset = page.as_bytes().len();
+        page.indirect(Ref::new(2)).primitive(42);
+        stream.write(&page, &[(Ref::new(2), offset)]).unwrap();
+
+        let mut rest = Pdf::new();
+        rest.indirect(Ref::new(1)).dict().pair(Name(b"Kid"), Ref::new(2));
+        rest.catalog(Ref::new(3)).pages(Ref::new(1));
+        rest.document_info(Ref::new(4)).title(TextStr("Test"));
+        let xref = rest.as_bytes().len();
+        let trailer = Trailer {
+            root: Ref::new(3),
+            info: Ref::new(4),
+            id: (b"a".to_vec(), b"b".to_vec()),
+            encrypt: None,
+        };
+        stream.finish(&rest.finish(), xref, &trailer).unwrap();
+
+        // The cross-reference stream comes last and takes over the trailer.
+        let (object, entries) = check_xref_stream(&out);
+        assert!(object.starts_with(b"5 0 obj"));
+        assert!(contains(object, "/Root 3 0 R"));
+        assert!(contains(object, "/Info 4 0 R"));
+        assert!(contains(object, "/ID [(a) (b)]"));
+        assert_eq!(entries.len(), 6 * 11);
+        assert!(entries.chunks(11).skip(1).all(|entry| entry[0] == 1));
+    }
+}
@@ -0,0 +1,134 @@
+use std::num::NonZeroUsize;
+
+use ecow::EcoString;
+use pdf_writer::{Finish, Name, Ref};
+use typst::introspection::Location;
+
+use crate::PdfContext;
+
+/// The logical structure of a tagged document, collected while its pages are
+/// encoded.
+#[derive(Default)]
+pub(crate) struct Tags {
+    /// The structure elements, in reading order.
+    elems: Vec<StructElem>,
+}
+
+impl Tags {
+    /// Add the marked-content sequence with the given identifier on the
+    /// exported page with the given index to the structure.
+    ///
+    /// Consecutive sequences with the same role on the same page share one
+    /// structure element, except for figures. Returns the index of the
+    /// structure element the sequence was added to.
+    pub fn push(&mut self, page: usize, mcid: i32, role: Role) -> usize {
+        match self.elems.last_mut() {
+            Some(last)
+                if last.page == page
+                    && last.role == role
+                    && !matches!(role, Role::Figure(_)) =>
+            {
+                last.mcids.push(mcid);
+            }
+            _ => self.elems.push(StructElem { role, page, mcids: vec![mcid] }),
+        }
+        self.elems.len() - 1
+    }
+}
+
+/// A structure element with content on a single page.
+struct StructElem {
+    /// What kind of content the element holds.
+    role: Role,
+    /// The index of the exported page the element's content is on.
+    page: usize,
+    /// The identifiers of the element's marked-content sequences.
+    mcids: Vec<i32>,
+}
+
+/// The role of a structure element.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Role {
+    /// A heading at the given location with the given level.
+    Heading(Location, NonZeroUsize),
+    /// Running text.
+    Paragraph,
+    /// An image with its description.
+    Figure(Option<EcoString>),
+}
+
+impl Role {
+    /// The standard structure type of the role.
+    pub fn name(&self) -> Name<'static> {
+        match self {
+            Self::Heading(_, level) => match level.get() {
+                1 => Name(b"H1"),
+                2 => Name(b"H2"),
+                3 => Name(b"H3"),
+                4 => Name(b"H4"),
+                5 => Name(b"H5"),
+                _ => Name(b"H6"),
+            },
+            Self::Paragraph => Name(b"P"),
+            Self::Figure(_) => Name(b"Figure"),
+        }
+    }
+}
+
+/// Write the structure tree and return the reference of its root, if the
+/// document is tagged.
+#[tracing::instrument(skip_all)]
+pub(crate) fn write_structure(ctx: &mut PdfContext) -> Option<Ref> {
+    if !ctx.options.standard.is_tagged() {
+        return None;
+    }
+
+    let root_ref = ctx.alloc.bump();
+    let doc_ref = ctx.alloc.bump();
+    let parent_tree_ref = ctx.alloc.bump();
+    let elem_refs: Vec<_> = ctx.tags.elems.iter().map(|_| ctx.alloc.bump()).collect();
+
+    let mut root = ctx.pdf.indirect(root_ref).dict();
+    root.pair(Name(b"Type"), Name(b"StructTreeRoot"));
+    root.pair(Name(b"K"), doc_ref);
+    root.pair(Name(b"ParentTree"), parent_tree_ref);
+    root.finish();
+
+    let mut doc = ctx.pdf.indirect(doc_ref).dict();
+    doc.pair(Name(b"Type"), Name(b"StructElem"));
+    doc.pair(Name(b"S"), Name(b"Document"));
+    doc.pair(Name(b"P"), root_ref);
+    doc.insert(Name(b"K")).array().items(elem_refs.iter().copied());
+    doc.finish();
+
+    for (elem, &elem_ref) in ctx.tags.elems.iter().zip(&elem_refs) {
+        let mut dict = ctx.pdf.indirect(elem_ref).dict();
+        dict.pair(Name(b"Type"), Name(b"StructElem"));
+        dict.pair(Name(b"S"), elem.role.name());
+        dict.pair(Name(b"P"), doc_ref);
+        dict.pair(Name(b"Pg"), ctx.page_refs[elem.page]);
+        if let Role::Figure(Some(alt)) = &elem.role {
+            ctx.crypt.text(&mut dict, elem_ref, Name(b"Alt"), alt);
+        }
+        dict.insert(Name(b"K")).array().items(elem.mcids.iter().copied());
+        dict.finish();
+    }
+
+    // Maps from the marked-content sequences of each page to the structure
+    // elements they belong to. The keys are the pages' /StructParents.
+    let mut parent_tree = ctx.pdf.indirect(parent_tree_ref).dict();
+    let mut nums = parent_tree.insert(Name(b"Nums")).array();
+    for (i, page) in ctx.pages.iter().enumerate() {
+        if page.marked.is_empty() {
+            continue;
+        }
+        nums.item(i as i32);
+        nums.push()
+            .array()
+            .items(page.marked.iter().map(|&elem| elem_refs[elem]));
+    }
+    nums.finish();
+    parent_tree.finish();
+
+    Some(root_ref)
+}
@@ -0,0 +1,271 @@
+use typst::foundations::Datetime;
+use typst_tests::compile;
+
+use crate::{pdf, pdf_stream, PdfEncryption, PdfOptions, PdfStandard};
+
+/// Whether the bytes of a PDF file contain the given sequence.
+pub fn contains(pdf: &[u8], needle: &str) -> bool {
+    pdf.windows(needle.len()).any(|window| window == needle.as_bytes())
+}
+
+/// The position of the first occurrence of the needle.
+pub fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// The position of the last occurrence of the needle.
+pub fn find_last(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).rposition(|window| window == needle)
+}
+
+/// Inflate all compressed streams of a PDF file.
+pub fn streams(pdf: &[u8]) -> Vec<Vec<u8>> {
+    let mut streams = vec![];
+    let mut rest = pdf;
+    while let Some(start) = find(rest, b"stream\n") {
+        let data = &rest[start + b"stream\n".len()..];
+        let Some(end) = find(data, b"\nendstream") else { break };
+        if let Ok(inflated) = miniz_oxide::inflate::decompress_to_vec_zlib(&data[..end]) {
+            streams.push(inflated);
+        }
+        rest = &data[end + b"\nendstream".len()..];
+    }
+    streams
+}
+
+/// Check that every entry of the cross-reference stream that ends a streamed
+/// PDF file points to the object it belongs to.
+///
+/// Returns the cross-reference stream object and its decoded entries.
+pub fn check_xref_stream(pdf: &[u8]) -> (&[u8], Vec<u8>) {
+    let start = find_last(pdf, b"startxref").expect("file should end with startxref");
+    let text = std::str::from_utf8(&pdf[start + b"startxref".len()..]).unwrap();
+    let xref: usize = text.split_whitespace().next().unwrap().parse().unwrap();
+    let object = &pdf[xref..];
+    assert!(contains(object, "/Type /XRef"));
+
+    let data_start = find(object, b"stream\n").unwrap() + b"stream\n".len();
+    let data_end = find_last(object, b"\nendstream").unwrap();
+    let entries =
+        miniz_oxide::inflate::decompress_to_vec_zlib(&object[data_start..data_end])
+            .unwrap();
+    for (num, entry) in entries.chunks(11).enumerate() {
+        if entry[0] == 1 {
+            let offset = u64::from_be_bytes(entry[1..9].try_into().unwrap()) as usize;
+            assert!(pdf[offset..].starts_with(format!("{num} 0 obj").as_bytes()));
+        }
+    }
+    (object, entries)
+}
+
+#[test]
+fn test_tagged_structure() {
+    let document = compile(
+        "= Introduction\n\
+         Some text.\n\
+         #image(\"/files/tiger.jpg\", alt: \"A tiger\", width: 1cm)\n\
+         #pdf.artifact(line(length: 1cm))",
+    );
+
+    let options = PdfOptions {
+        standard: PdfStandard::Ua1,
+        ..PdfOptions::default()
+    };
+    let tagged = pdf(&document, &options).unwrap();
+    assert!(contains(&tagged, "/Type /StructTreeRoot"));
+    assert!(contains(&tagged, "/S /Document"));
+    assert!(contains(&tagged, "/S /H1"));
+    assert!(contains(&tagged, "/S /P"));
+    assert!(contains(&tagged, "/S /Figure"));
+    assert!(contains(&tagged, "/Alt (A tiger)"));
+    assert!(contains(&tagged, "/StructParents 0"));
+    assert!(contains(&tagged, "/Marked true"));
+    assert!(contains(&tagged, "<pdfuaid:part>1</pdfuaid:part>"));
+
+    let plain = pdf(&document, &PdfOptions::default()).unwrap();
+    assert!(!contains(&plain, "/StructTreeRoot"));
+    assert!(!contains(&plain, "/StructParents"));
+}
+
+#[test]
+fn test_text_stroke() {
+    let document = compile("#text(stroke: 2pt + red)[Stroked] plain");
+    let streams = streams(&pdf(&document, &PdfOptions::default()).unwrap());
+    let content = streams
+        .iter()
+        .find(|stream| contains(stream, " Tf"))
+        .expect("page should have a content stream");
+
+    // The stroked run switches to fill-and-stroke and the plain one back.
+    let text = String::from_utf8_lossy(content);
+    let stroked = text.find("2 Tr").expect("text should be stroked");
+    let plain = text.find("0 Tr").expect("text should be filled again");
+    assert!(stroked < plain);
+    assert!(contains(content, "2 w"));
+
+    let document = compile("plain");
+    let streams = streams(&pdf(&document, &PdfOptions::default()).unwrap());
+    let content = streams.iter().find(|stream| contains(stream, " Tf")).unwrap();
+    assert!(!contains(content, " Tr"));
+}
+
+#[test]
+fn test_page_bleed() {
+    let page = "#set page(width: 80pt, height: 60pt, fill: aqua";
+    let plain = pdf(&compile(&format!("{page})")), &PdfOptions::default()).unwrap();
+    assert!(contains(&plain, "/MediaBox [0 0 80 60]"));
+    assert!(!contains(&plain, "/TrimBox"));
+
+    // The media box grows by the bleed, which the fill extends into.
+    let bled =
+        pdf(&compile(&format!("{page}, bleed: 10pt)")), &PdfOptions::default()).unwrap();
+    assert!(contains(&bled, "/MediaBox [-10 -10 90 70]"));
+    assert!(contains(&bled, "/BleedBox [-10 -10 90 70]"));
+    assert!(contains(&bled, "/TrimBox [0 0 80 60]"));
+
+    // Marks need more room beyond the bleed.
+    let marked = format!("{page}, bleed: 10pt, marks: true)");
+    let marked = pdf(&compile(&marked), &PdfOptions::default()).unwrap();
+    assert!(contains(&marked, "/BleedBox [-10 -10 90 70]"));
+    assert!(!contains(&marked, "/MediaBox [-10 -10 90 70]"));
+}
+
+#[test]
+fn test_watermark() {
+    let page = "#set page(width: 100pt, height: 100pt, background: watermark(";
+    let stamp = "image(\"/files/tiger.jpg\", width: 20pt, opacity: 50%)";
+    let count = |source: &str| {
+        let document = compile(source);
+        let streams = streams(&pdf(&document, &PdfOptions::default()).unwrap());
+        let content = streams.iter().find(|stream| contains(stream, " Do")).unwrap();
+        content.windows(3).filter(|w| w == b" Do").count()
+    };
+
+    // The stamp is repeated across the page, or placed once.
+    assert!(count(&format!("{page}{stamp}))")) > 4);
+    assert_eq!(count(&format!("{page}tiled: false, {stamp}))")), 1);
+
+    // The image is painted with its opacity.
+    let document = compile(&format!("{page}{stamp}))"));
+    let stamped = pdf(&document, &PdfOptions::default()).unwrap();
+    assert!(contains(&stamped, "/ca 0.5"));
+    let document = compile("#image(\"/files/tiger.jpg\", width: 20pt)");
+    assert!(!contains(&pdf(&document, &PdfOptions::default()).unwrap(), "/ca 0.5"));
+}
+
+#[test]
+fn test_endnote_links() {
+    let document = compile(
+        "#set footnote(placement: \"end\")\n\
+         Text#footnote[Note]\n\
+         #pagebreak()\n\
+         #footnote.endnotes()",
+    );
+    assert_eq!(document.pages.len(), 2);
+
+    // The reference links to the entry on the second page and the entry links
+    // back to the reference on the first one.
+    let out = pdf(&document, &PdfOptions::default()).unwrap();
+    let text = String::from_utf8_lossy(&out);
+    let targets: Vec<&str> = text
+        .match_indices("/D [")
+        .map(|(i, _)| text[i + 4..].split(' ').next().unwrap())
+        .collect();
+    assert_eq!(targets.len(), 2);
+    assert_ne!(targets[0], targets[1]);
+}
+
+#[test]
+fn test_embedded_files() {
+    let options = PdfOptions {
+        standard: PdfStandard::A3b,
+        timestamp: Datetime::from_ymd(2024, 1, 2),
+        ..PdfOptions::default()
+    };
+    let document = compile(
+        "#pdf.embed(\"/files/data.csv\", description: \"Raw data\", mime-type: \"text/csv\")",
+    );
+    let embedded = pdf(&document, &options).unwrap();
+    assert!(contains(&embedded, "/Type /EmbeddedFile"));
+    assert!(contains(&embedded, "/Subtype /text#2Fcsv"));
+    assert!(contains(&embedded, "/ModDate (D:20240102)"));
+    assert!(contains(&embedded, "/UF (data.csv)"));
+    assert!(contains(&embedded, "/Desc (Raw data)"));
+    assert!(contains(&embedded, "/AFRelationship /Unspecified"));
+
+    // PDF/A-2 does not permit embedded files at all.
+    let a2b = PdfOptions { standard: PdfStandard::A2b, ..options.clone() };
+    let errors = pdf(&document, &a2b).unwrap_err();
+    assert_eq!(errors[0].message, "PDF/A-2 does not allow embedding files");
+
+    // PDF/A-3 requires the MIME type.
+    let document = compile("#pdf.embed(\"/files/hello.txt\")");
+    let errors = pdf(&document, &options).unwrap_err();
+    assert_eq!(errors[0].message, "PDF/A-3 requires the MIME type of embedded files");
+    assert!(!contains(&pdf(&document, &PdfOptions::default()).unwrap(), "/ModDate"));
+
+    // Files are identified by their name.
+    let document =
+        compile("#pdf.embed(\"/files/data.csv\")\n#pdf.embed(\"/files/data.csv\")");
+    let errors = pdf(&document, &PdfOptions::default()).unwrap_err();
+    assert_eq!(errors[0].message, "a file named `data.csv` is already embedded");
+}
+
+#[test]
+fn test_form_fields() {
+    let document = compile(
+        "#form.field(\"name\", value: \"Jane\")\n\
+         #form.field(\"newsletter\", kind: \"checkbox\")",
+    );
+    let form = pdf(&document, &PdfOptions::default()).unwrap();
+    assert!(contains(&form, "/T (name)"));
+    assert!(contains(&form, "/V (Jane)"));
+    assert!(contains(&form, "(Jane) Tj"));
+    assert!(contains(&form, "/AS /Off"));
+    assert!(!contains(&form, "/NeedAppearances"));
+
+    // The text field has one appearance, the checkbox one for each state.
+    let appearances = form.windows(14).filter(|w| w == b"/Subtype /Form").count();
+    assert_eq!(appearances, 3);
+
+    // Fields are identified by their name.
+    let document = compile("#form.field(\"a\")\n#form.field(\"a\")");
+    let errors = pdf(&document, &PdfOptions::default()).unwrap_err();
+    assert_eq!(errors[0].message, "a form field named `a` already exists");
+}
+
+#[test]
+fn test_pdf_stream() {
+    let document = compile(
+        "= Introduction\n\
+         #lorem(100)\n\
+         #pagebreak()\n\
+         #image(\"/files/tiger.jpg\", width: 1cm)",
+    );
+    let mut out = vec![];
+    pdf_stream(&document, &PdfOptions::default(), &mut out).unwrap();
+
+    // The file ends with a cross-reference stream instead of a table.
+    let (object, _) = check_xref_stream(&out);
+    assert!(contains(object, "/Root"));
+    assert!(contains(object, "/Info"));
+    assert!(contains(object, "/ID"));
+    assert!(!contains(object, "/Encrypt"));
+    assert!(!contains(&out, "trailer"));
+
+    let count = |needle: &str| {
+        out.windows(needle.len()).filter(|w| *w == needle.as_bytes()).count()
+    };
+    assert_eq!(count("/Type /Page") - count("/Type /Pages"), 2);
+    assert_eq!(count("/Subtype /Image"), 1);
+
+    // The encryption dictionary is referred to from the stream's dictionary.
+    let options = PdfOptions {
+        encryption: Some(PdfEncryption::default()),
+        ..PdfOptions::default()
+    };
+    let mut out = vec![];
+    pdf_stream(&document, &options, &mut out).unwrap();
+    let (object, _) = check_xref_stream(&out);
+    assert!(contains(object, "/Encrypt"));
+}
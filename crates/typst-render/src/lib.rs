@@ -15,8 +15,8 @@ use typst::layout::{
 };
 use typst::text::{Font, TextItem};
 use typst::visualize::{
-    Color, FixedStroke, Geometry, Gradient, Image, ImageKind, LineCap, LineJoin, Paint,
-    Path, PathItem, Pattern, RasterFormat, RelativeTo, Shape,
+    Color, DashPattern, FixedStroke, Geometry, Gradient, Image, ImageKind, LineCap,
+    LineJoin, Paint, Path, PathItem, Pattern, RasterFormat, RelativeTo, Shape,
 };
 use usvg::{NodeExt, TreeParsing};
 
@@ -158,14 +158,17 @@ fn render_frame(canvas: &mut sk::Pixmap, state: State, frame: &Frame) {
             FrameItem::Shape(shape, _) => {
                 render_shape(canvas, state.pre_translate(*pos), shape);
             }
-            FrameItem::Image(image, size, _) => {
-                render_image(canvas, state.pre_translate(*pos), image, *size);
+            FrameItem::Image(image, size, opacity, _) => {
+                render_image(canvas, state.pre_translate(*pos), image, *size, *opacity);
             }
             FrameItem::Meta(meta, _) => match meta {
                 Meta::Link(_) => {}
                 Meta::Elem(_) => {}
                 Meta::PageNumbering(_) => {}
                 Meta::PdfPageLabel(_) => {}
+                Meta::PrintSetup(_) => {}
+                Meta::Field(_) => {}
+                Meta::Artifact => {}
                 Meta::Hide => {}
             },
         }
@@ -376,8 +379,13 @@ fn render_outline_glyph(
 
     // Render a glyph directly as a path. This only happens when the fast glyph
     // rasterization can't be used due to very large text size or weird
-    // scale/skewing transforms.
-    if ppem > 100.0 || ts.kx != 0.0 || ts.ky != 0.0 || ts.sx != ts.sy {
+    // scale/skewing transforms, or when the glyph is stroked.
+    if ppem > 100.0
+        || ts.kx != 0.0
+        || ts.ky != 0.0
+        || ts.sx != ts.sy
+        || text.stroke.is_some()
+    {
         let path = {
             let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
             text.font.ttf().outline_glyph(id, &mut builder)?;
@@ -403,6 +411,43 @@ fn render_outline_glyph(
         // system is Y-up.
         let ts = ts.pre_scale(scale, -scale);
         canvas.fill_path(&path, &paint, rule, ts, state.mask);
+
+        if let Some(FixedStroke {
+            paint,
+            thickness,
+            line_cap,
+            line_join,
+            dash_pattern,
+            miter_limit,
+        }) = &text.stroke
+        {
+            // Don't draw zero-pt stroke.
+            if thickness.to_f32() > 0.0 {
+                // The path is in font units, so the stroke must be, too.
+                let dash = dash_pattern
+                    .as_ref()
+                    .and_then(|pattern| to_sk_dash_pattern(pattern, 1.0 / scale));
+                let mut pixmap = None;
+                let paint = to_sk_paint(
+                    paint,
+                    state.pre_concat(sk::Transform::from_scale(scale, -scale)),
+                    Size::zero(),
+                    true,
+                    None,
+                    &mut pixmap,
+                    None,
+                );
+                let stroke = sk::Stroke {
+                    width: thickness.to_f32() / scale,
+                    line_cap: to_sk_line_cap(*line_cap),
+                    line_join: to_sk_line_join(*line_join),
+                    dash,
+                    miter_limit: miter_limit.get() as f32,
+                };
+                canvas.stroke_path(&path, &paint, &stroke, ts, state.mask);
+            }
+        }
+
         return Some(());
     }
 
@@ -581,17 +626,9 @@ fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<
 
         // Don't draw zero-pt stroke.
         if width > 0.0 {
-            let dash = dash_pattern.as_ref().and_then(|pattern| {
-                // tiny-skia only allows dash patterns with an even number of elements,
-                // while pdf allows any number.
-                let pattern_len = pattern.array.len();
-                let len =
-                    if pattern_len % 2 == 1 { 2 * pattern_len } else { pattern_len };
-                let dash_array =
-                    pattern.array.iter().map(|l| l.to_f32()).cycle().take(len).collect();
-
-                sk::StrokeDash::new(dash_array, pattern.phase.to_f32())
-            });
+            let dash = dash_pattern
+                .as_ref()
+                .and_then(|pattern| to_sk_dash_pattern(pattern, 1.0));
 
             let bbox = shape.geometry.bbox_size();
             let offset_bbox = (!matches!(shape.geometry, Geometry::Line(..)))
@@ -644,6 +681,27 @@ fn render_shape(canvas: &mut sk::Pixmap, state: State, shape: &Shape) -> Option<
     Some(())
 }
 
+/// Convert a Typst dash pattern into a tiny-skia one, scaling its lengths by
+/// the given factor.
+fn to_sk_dash_pattern(
+    pattern: &DashPattern<Abs, Abs>,
+    scale: f32,
+) -> Option<sk::StrokeDash> {
+    // tiny-skia only allows dash patterns with an even number of elements,
+    // while pdf allows any number.
+    let pattern_len = pattern.array.len();
+    let len = if pattern_len % 2 == 1 { 2 * pattern_len } else { pattern_len };
+    let dash_array = pattern
+        .array
+        .iter()
+        .map(|l| l.to_f32() * scale)
+        .cycle()
+        .take(len)
+        .collect();
+
+    sk::StrokeDash::new(dash_array, pattern.phase.to_f32() * scale)
+}
+
 /// Convert a Typst path into a tiny-skia path.
 fn convert_path(path: &Path) -> Option<sk::Path> {
     let mut builder = sk::PathBuilder::new();
@@ -679,6 +737,7 @@ fn render_image(
     state: State,
     image: &Image,
     size: Size,
+    opacity: Ratio,
 ) -> Option<()> {
     let ts = state.transform;
     let view_width = size.x.to_f32();
@@ -708,7 +767,7 @@ fn render_image(
             (*pixmap).as_ref(),
             sk::SpreadMode::Pad,
             sk::FilterQuality::Nearest,
-            1.0,
+            opacity.get() as f32,
             sk::Transform::from_scale(paint_scale_x, paint_scale_y),
         ),
         ..Default::default()
@@ -25,9 +25,14 @@ use xmlwriter::XmlWriter;
 const CONIC_SEGMENT: usize = 360;
 
 /// Export a frame into a SVG file.
+///
+/// Glyphs are exported as paths or images, so that the result looks the same
+/// everywhere without embedding fonts. Unless disabled in the `options`, each
+/// text run is additionally accompanied by an invisible `<text>` element,
+/// which keeps the text selectable and searchable.
 #[tracing::instrument(skip_all)]
-pub fn svg(frame: &Frame) -> String {
-    let mut renderer = SVGRenderer::new();
+pub fn svg(frame: &Frame, options: &SvgOptions) -> String {
+    let mut renderer = SVGRenderer::new(options);
     renderer.write_header(frame.size());
 
     let state = State::new(frame.size(), Transform::identity());
@@ -39,13 +44,13 @@ pub fn svg(frame: &Frame) -> String {
 ///
 /// The padding will be added around and between the individual frames.
 #[tracing::instrument(skip_all)]
-pub fn svg_merged(frames: &[Frame], padding: Abs) -> String {
+pub fn svg_merged(frames: &[Frame], padding: Abs, options: &SvgOptions) -> String {
     let width = 2.0 * padding
         + frames.iter().map(|frame| frame.width()).max().unwrap_or_default();
     let height = padding + frames.iter().map(|page| page.height() + padding).sum::<Abs>();
     let size = Size::new(width, height);
 
-    let mut renderer = SVGRenderer::new();
+    let mut renderer = SVGRenderer::new(options);
     renderer.write_header(size);
 
     let [x, mut y] = [padding; 2];
@@ -59,10 +64,26 @@ pub fn svg_merged(frames: &[Frame], padding: Abs) -> String {
     renderer.finalize()
 }
 
+/// Settings for SVG export.
+#[derive(Debug, Clone)]
+pub struct SvgOptions {
+    /// Whether to write an invisible text layer above the glyphs. Without it,
+    /// the text can't be selected or searched, but the files are smaller.
+    pub text_layer: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self { text_layer: true }
+    }
+}
+
 /// Renders one or multiple frames to an SVG file.
 struct SVGRenderer {
     /// The internal XML writer.
     xml: XmlWriter,
+    /// Whether to write a text layer for each text run.
+    text_layer: bool,
     /// Prepared glyphs.
     glyphs: Deduplicator<RenderedGlyph>,
     /// Clip paths are used to clip a group. A clip path is a path that defines
@@ -216,9 +237,10 @@ enum RenderedGlyph {
 
 impl SVGRenderer {
     /// Create a new SVG renderer with empty glyph and clip path.
-    fn new() -> Self {
+    fn new(options: &SvgOptions) -> Self {
         SVGRenderer {
             xml: XmlWriter::new(xmlwriter::Options::default()),
+            text_layer: options.text_layer,
             glyphs: Deduplicator::new('g'),
             clip_paths: Deduplicator::new('c'),
             gradient_refs: Deduplicator::new('g'),
@@ -289,7 +311,9 @@ impl SVGRenderer {
                 FrameItem::Shape(shape, _) => {
                     self.render_shape(state.pre_translate(*pos), shape)
                 }
-                FrameItem::Image(image, size, _) => self.render_image(image, size),
+                FrameItem::Image(image, size, opacity, _) => {
+                    self.render_image(image, size, *opacity)
+                }
                 FrameItem::Meta(_, _) => unreachable!(),
             };
 
@@ -355,6 +379,31 @@ impl SVGRenderer {
         }
 
         self.xml.end_element();
+        if self.text_layer {
+            self.render_text_layer(text, x);
+        }
+    }
+
+    /// Render an invisible text layer on top of the glyphs of a text item.
+    ///
+    /// Since the glyphs themselves are paths or images, this is what makes the
+    /// text selectable and searchable when the SVG is embedded in a web page.
+    /// The text is stretched to the total advance `width` of the glyphs so that
+    /// the selection roughly lines up with the visible glyphs.
+    fn render_text_layer(&mut self, text: &TextItem, width: f64) {
+        if text.text.trim().is_empty() || width <= 0.0 {
+            return;
+        }
+
+        self.xml.start_element("text");
+        self.xml.write_attribute("class", "typst-text-layer");
+        self.xml.write_attribute("font-size", &text.size.to_pt());
+        self.xml.write_attribute("textLength", &width);
+        self.xml.write_attribute("lengthAdjust", "spacingAndGlyphs");
+        self.xml.write_attribute("fill-opacity", "0");
+        self.xml.write_attribute("xml:space", "preserve");
+        self.xml.write_text(&text.text);
+        self.xml.end_element();
     }
 
     /// Render a glyph defined by an SVG.
@@ -452,6 +501,13 @@ impl SVGRenderer {
             Size::new(Abs::pt(width), Abs::pt(height)),
             self.text_paint_transform(state, &text.fill),
         );
+        if let Some(stroke) = &text.stroke {
+            self.write_stroke(
+                stroke,
+                Size::new(Abs::pt(width), Abs::pt(height)),
+                self.text_paint_transform(state, &stroke.paint),
+            );
+        }
         self.xml.end_element();
 
         Some(())
@@ -681,13 +737,16 @@ impl SVGRenderer {
     }
 
     /// Render an image element.
-    fn render_image(&mut self, image: &Image, size: &Axes<Abs>) {
+    fn render_image(&mut self, image: &Image, size: &Axes<Abs>, opacity: Ratio) {
         let url = convert_image_to_base64_url(image);
         self.xml.start_element("image");
         self.xml.write_attribute("xlink:href", &url);
         self.xml.write_attribute("width", &size.x.to_pt());
         self.xml.write_attribute("height", &size.y.to_pt());
         self.xml.write_attribute("preserveAspectRatio", "none");
+        if opacity != Ratio::one() {
+            self.xml.write_attribute("opacity", &opacity.get());
+        }
         self.xml.end_element();
     }
 
@@ -1493,3 +1552,54 @@ impl ColorEncode for Color {
 fn correct_pattern_pos(x: f32) -> f32 {
     (x + 0.5) / 2.0
 }
+
+#[cfg(test)]
+mod tests {
+    use typst::layout::Em;
+    use typst::syntax::Span;
+    use typst::text::{Glyph, Lang};
+
+    use super::*;
+
+    const FONT: &[u8] = include_bytes!("../../../assets/fonts/LinLibertine_R.ttf");
+
+    /// A frame with a single text run.
+    fn text_frame() -> Frame {
+        let font = Font::new(FONT.into(), 0).unwrap();
+        let id = font.ttf().glyph_index('A').unwrap().0;
+        let glyph = Glyph {
+            id,
+            x_advance: font.advance(id).unwrap(),
+            x_offset: Em::zero(),
+            range: 0..1,
+            span: (Span::detached(), 0),
+        };
+
+        let text = TextItem {
+            font,
+            size: Abs::pt(10.0),
+            fill: Color::BLACK.into(),
+            stroke: None,
+            lang: Lang::ENGLISH,
+            text: "A".into(),
+            glyphs: vec![glyph],
+        };
+
+        let mut frame = Frame::soft(Size::splat(Abs::pt(20.0)));
+        frame.push(Point::with_y(Abs::pt(10.0)), FrameItem::Text(text));
+        frame
+    }
+
+    #[test]
+    fn test_text_layer() {
+        let frame = text_frame();
+
+        let with = svg(&frame, &SvgOptions::default());
+        assert!(with.contains(r#"class="typst-text""#));
+        assert!(with.contains(r#"class="typst-text-layer""#));
+
+        let without = svg(&frame, &SvgOptions { text_layer: false });
+        assert!(without.contains(r#"class="typst-text""#));
+        assert!(!without.contains("<text"));
+    }
+}
@@ -290,7 +290,7 @@ impl Lexer<'_> {
     }
 
     fn label(&mut self) -> SyntaxKind {
-        let label = self.s.eat_while(|c| is_id_continue(c) || matches!(c, ':' | '.'));
+        let label = self.s.eat_while(is_valid_in_label_literal);
         if label.is_empty() {
             return self.error("label cannot be empty");
         }
@@ -734,6 +734,18 @@ pub fn is_ident(string: &str) -> bool {
         .map_or(false, |c| is_id_start(c) && chars.all(is_id_continue))
 }
 
+/// Whether a string can be written as a label literal, i.e. `<name>`.
+#[inline]
+pub fn is_valid_label_literal(string: &str) -> bool {
+    !string.is_empty() && string.chars().all(is_valid_in_label_literal)
+}
+
+/// Whether a character can be part of a label literal's name.
+#[inline]
+fn is_valid_in_label_literal(c: char) -> bool {
+    is_id_continue(c) || matches!(c, ':' | '.')
+}
+
 /// Whether a character can start an identifier.
 #[inline]
 pub fn is_id_start(c: char) -> bool {
@@ -16,7 +16,8 @@ pub use self::file::{FileId, PackageSpec, PackageVersion, VirtualPath};
 pub use self::highlight::{highlight, highlight_html, Tag};
 pub use self::kind::SyntaxKind;
 pub use self::lexer::{
-    is_id_continue, is_id_start, is_ident, is_newline, link_prefix, split_newlines,
+    is_id_continue, is_id_start, is_ident, is_newline, is_valid_label_literal,
+    link_prefix, split_newlines,
 };
 pub use self::node::{LinkedChildren, LinkedNode, SyntaxError, SyntaxNode};
 pub use self::parser::{parse, parse_code, parse_math};
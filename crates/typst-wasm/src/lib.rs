@@ -0,0 +1,365 @@
+//! WebAssembly bindings for Typst.
+//!
+//! This crate exposes a [`TypstCompiler`] to JavaScript through
+//! `wasm-bindgen`. It keeps all sources, files, and fonts in memory, so it
+//! doesn't need any access to the file system or the network and runs on
+//! `wasm32-unknown-unknown`. It powers interactive examples in the browser:
+//!
+//! ```js
+//! const compiler = new TypstCompiler();
+//! compiler.addFont(fontBytes);
+//! compiler.setInput("edition", "teacher");
+//! compiler.addSource("/main.typ", "Hello *world*!");
+//! const svg = compiler.compileSvg("/main.typ");
+//! console.log(compiler.warnings());
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use comemo::{Prehashed, Track};
+use ecow::{eco_format, EcoVec};
+use typst::diag::{FileError, FileResult, Severity, SourceDiagnostic};
+use typst::eval::{eval_selector, Tracer};
+use typst::foundations::sys::Target;
+use typst::foundations::{Bytes, Datetime, Dict, IntoValue};
+use typst::layout::Abs;
+use typst::model::Document;
+use typst::syntax::{FileId, Source, Span, VirtualPath};
+use typst::text::{Font, FontBook, Lang, Region};
+use typst::{Compiler, Library, World};
+use typst_pdf::PdfOptions;
+use typst_svg::SvgOptions;
+use wasm_bindgen::prelude::*;
+
+/// A compiler that keeps its sources, files, and fonts in memory.
+#[wasm_bindgen]
+pub struct TypstCompiler {
+    world: MemoryWorld,
+    lang: Option<(Lang, Option<Region>)>,
+    inputs: Dict,
+    target: Target,
+    svg: SvgOptions,
+    warnings: EcoVec<SourceDiagnostic>,
+}
+
+#[wasm_bindgen]
+impl TypstCompiler {
+    /// Create a new compiler without any fonts or files.
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self {
+            world: MemoryWorld {
+                library: Prehashed::new(Library::build()),
+                book: Prehashed::new(FontBook::new()),
+                fonts: vec![],
+                sources: HashMap::new(),
+                files: HashMap::new(),
+                main: None,
+                today: None,
+            },
+            lang: None,
+            inputs: Dict::new(),
+            target: Target::default(),
+            svg: SvgOptions::default(),
+            warnings: EcoVec::new(),
+        }
+    }
+
+    /// Add all fonts contained in a font file or collection.
+    #[wasm_bindgen(js_name = addFont)]
+    pub fn add_font(&mut self, data: Vec<u8>) {
+        let fonts: Vec<_> = Font::iter(Bytes::from(data)).collect();
+        self.world.book.update(|book| {
+            for font in &fonts {
+                book.push(font.info().clone());
+            }
+        });
+        self.world.fonts.extend(fonts);
+    }
+
+    /// Add or replace a source file at the given path.
+    #[wasm_bindgen(js_name = addSource)]
+    pub fn add_source(&mut self, path: &str, text: String) {
+        let id = file_id(path);
+        match self.world.sources.get_mut(&id) {
+            Some(source) => {
+                source.replace(&text);
+            }
+            None => {
+                self.world.sources.insert(id, Source::new(id, text));
+            }
+        }
+    }
+
+    /// Add or replace a binary file (like an image) at the given path.
+    #[wasm_bindgen(js_name = addFile)]
+    pub fn add_file(&mut self, path: &str, data: Vec<u8>) {
+        self.world.files.insert(file_id(path), Bytes::from(data));
+    }
+
+    /// Remove all sources and files, but keep the fonts.
+    pub fn reset(&mut self) {
+        self.world.sources.clear();
+        self.world.files.clear();
+    }
+
+    /// Drop all results cached between compilations, to release memory.
+    #[wasm_bindgen(js_name = flushCaches)]
+    pub fn flush_caches(&mut self) {
+        typst::memory::flush();
+    }
+
+    /// Set the date returned by `datetime.today()`.
+    #[wasm_bindgen(js_name = setToday)]
+    pub fn set_today(&mut self, year: i32, month: u8, day: u8) {
+        self.world.today = Datetime::from_ymd(year, month, day);
+    }
+
+    /// Set the language of error messages, like `zh-CN`.
+    ///
+    /// Messages without a translation stay in English.
+    #[wasm_bindgen(js_name = setLang)]
+    pub fn set_lang(&mut self, tag: &str) -> Result<(), JsError> {
+        let (lang, region) = match tag.split_once('-') {
+            Some((lang, region)) => (lang, Some(region)),
+            None => (tag, None),
+        };
+
+        let lang = lang.parse::<Lang>().map_err(JsError::new)?;
+        let region =
+            region.map(str::parse::<Region>).transpose().map_err(JsError::new)?;
+        self.lang = Some((lang, region));
+        Ok(())
+    }
+
+    /// Add or replace a string visible through `sys.inputs`.
+    #[wasm_bindgen(js_name = setInput)]
+    pub fn set_input(&mut self, key: &str, value: &str) {
+        self.inputs.insert(key.into(), value.into_value());
+        self.rebuild_library();
+    }
+
+    /// Remove all strings visible through `sys.inputs`.
+    #[wasm_bindgen(js_name = clearInputs)]
+    pub fn clear_inputs(&mut self) {
+        self.inputs = Dict::new();
+        self.rebuild_library();
+    }
+
+    /// Set whether SVG output has an invisible layer of selectable text.
+    ///
+    /// This is enabled by default.
+    #[wasm_bindgen(js_name = setSvgTextLayer)]
+    pub fn set_svg_text_layer(&mut self, enabled: bool) {
+        self.svg.text_layer = enabled;
+    }
+
+    /// The warnings of the last compilation, in the same format as errors.
+    ///
+    /// Returns an empty string if there were none.
+    pub fn warnings(&self) -> String {
+        self.format(&self.warnings)
+    }
+
+    /// Compile the file at the given path into one SVG containing all pages.
+    #[wasm_bindgen(js_name = compileSvg)]
+    pub fn compile_svg(&mut self, main: &str) -> Result<String, JsError> {
+        let document = self.compile(main, Target::Svg)?;
+        Ok(typst_svg::svg_merged(&document.pages, Abs::pt(2.0), &self.svg))
+    }
+
+    /// Compile the file at the given path into one SVG per page.
+    #[wasm_bindgen(js_name = compileSvgPages)]
+    pub fn compile_svg_pages(&mut self, main: &str) -> Result<Vec<JsValue>, JsError> {
+        let document = self.compile(main, Target::Svg)?;
+        Ok(document
+            .pages
+            .iter()
+            .map(|frame| JsValue::from_str(&typst_svg::svg(frame, &self.svg)))
+            .collect())
+    }
+
+    /// Compile the file at the given path into a PDF.
+    #[wasm_bindgen(js_name = compilePdf)]
+    pub fn compile_pdf(&mut self, main: &str) -> Result<Vec<u8>, JsError> {
+        let document = self.compile(main, Target::Pdf)?;
+        let options = PdfOptions {
+            ident: Some(main),
+            timestamp: self.world.today,
+            ..PdfOptions::default()
+        };
+        typst_pdf::pdf(&document, &options)
+            .map_err(|errors| JsError::new(&self.format(&errors)))
+    }
+
+    /// Compile the file at the given path and find all elements matching the
+    /// selector, like `heading.where(level: 1)` or `<intro>`.
+    ///
+    /// Returns a JSON array of objects with the element's fields under
+    /// `element` and its page number under `page`.
+    pub fn query(&mut self, main: &str, selector: &str) -> Result<String, JsError> {
+        let document = self.compile(main, Target::default())?;
+        let world: &dyn World = &self.world;
+        let selector =
+            eval_selector(world.track(), selector).map_err(|err| JsError::new(&err))?;
+        serde_json::to_string(&document.query(&selector))
+            .map_err(|err| JsError::new(&err.to_string()))
+    }
+}
+
+impl TypstCompiler {
+    /// Rebuild the standard library with the current inputs and target.
+    fn rebuild_library(&mut self) {
+        let library = Library::builder()
+            .with_inputs(self.inputs.clone())
+            .with_target(self.target)
+            .build();
+        self.world.library = Prehashed::new(library);
+    }
+
+    /// Compile the file at the given path into a document for the given
+    /// export target, which is visible through `sys.target`.
+    fn compile(&mut self, main: &str, target: Target) -> Result<Document, JsError> {
+        if self.target != target {
+            self.target = target;
+            self.rebuild_library();
+        }
+
+        self.warnings = EcoVec::new();
+        let id = file_id(main);
+        if !self.world.sources.contains_key(&id) {
+            return Err(JsError::new(&format!("source file {main} does not exist")));
+        }
+
+        self.world.main = Some(id);
+        let mut tracer = Tracer::new();
+        let mut compiler = Compiler::new();
+        if let Some((lang, region)) = self.lang {
+            compiler = compiler.with_lang(lang, region);
+        }
+
+        let result = compiler.compile(&self.world, &mut tracer);
+        self.warnings = tracer.warnings();
+        typst::memory::evict(10);
+        result.map_err(|errors| JsError::new(&self.format(&errors)))
+    }
+
+    /// Format diagnostics into a human-readable message.
+    fn format(&self, diags: &EcoVec<SourceDiagnostic>) -> String {
+        let mut out = String::new();
+        for diag in diags {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+
+            let severity = match diag.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+
+            let position = self.position(diag.span);
+            write!(out, "{position}{severity}: {}", diag.message).unwrap();
+            for hint in &diag.hints {
+                write!(out, "\n  hint: {hint}").unwrap();
+            }
+
+            // The chain of calls and imports that led to the error.
+            for point in &diag.trace {
+                let (tracepoint, count) = &point.v;
+                write!(out, "\n  {}{tracepoint}", self.position(point.span)).unwrap();
+                if *count > 1 {
+                    write!(out, " (repeated {count} times)").unwrap();
+                }
+            }
+        }
+        out
+    }
+
+    /// Format the position of a span as a `path:line:column: ` prefix.
+    fn position(&self, span: Span) -> String {
+        let position = span.id().and_then(|id| {
+            let source = self.world.sources.get(&id)?;
+            let range = source.range(span)?;
+            let line = source.byte_to_line(range.start)?;
+            let column = source.byte_to_column(range.start)?;
+            Some((id, line, column))
+        });
+
+        let Some((id, line, column)) = position else { return String::new() };
+        let path = id.vpath().as_rootless_path().display();
+        format!("{path}:{}:{}: ", line + 1, column + 1)
+    }
+}
+
+/// A world that keeps everything in memory.
+struct MemoryWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    fonts: Vec<Font>,
+    sources: HashMap<FileId, Source>,
+    files: HashMap<FileId, Bytes>,
+    main: Option<FileId>,
+    today: Option<Datetime>,
+}
+
+impl World for MemoryWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.main
+            .and_then(|id| self.sources.get(&id))
+            .cloned()
+            .unwrap_or_else(|| Source::detached(""))
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.sources.get(&id).cloned().ok_or_else(|| not_found(id))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        if let Some(bytes) = self.files.get(&id) {
+            return Ok(bytes.clone());
+        }
+
+        // Source files can also be read as bytes, e.g. through `read`.
+        self.sources
+            .get(&id)
+            .map(|source| Bytes::from(source.text().as_bytes()))
+            .ok_or_else(|| not_found(id))
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index).cloned()
+    }
+
+    fn files(&self) -> Vec<FileId> {
+        self.sources.keys().chain(self.files.keys()).copied().collect()
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        self.today
+    }
+}
+
+/// Create a file id for a path in the in-memory project.
+fn file_id(path: &str) -> FileId {
+    FileId::new(None, VirtualPath::new(path))
+}
+
+/// The error for a file that wasn't added to the world.
+fn not_found(id: FileId) -> FileError {
+    match id.package() {
+        Some(spec) => FileError::Other(Some(eco_format!(
+            "packages are not available in the browser ({spec})"
+        ))),
+        None => FileError::NotFound(id.vpath().as_rootless_path().into()),
+    }
+}
@@ -0,0 +1,37 @@
+# English diagnostic messages.
+#
+# Messages are identified by diagnostic code. They are not used for display,
+# but to extract the variables from a diagnostic so that it can be rendered in
+# another language. A code can have multiple forms, which are distinguished
+# by a suffix, as in `unexpected-argument-named`. Hints are given as the
+# attributes of a message.
+
+argument-is-positional = the argument `{ $what }` is positional
+    .hint = try removing `{ $name }:`
+
+compilation-cancelled = compilation was cancelled
+    .hint = a newer compilation was probably started
+
+empty-emph = no text within underscores
+    .hint = using multiple consecutive underscores (e.g. __) has no additional effect
+
+empty-strong = no text within stars
+    .hint = using multiple consecutive stars (e.g. **) has no additional effect
+
+layout-did-not-converge = layout did not converge within { $max } attempts
+    .hint = check if any states or queries are updating themselves
+
+maximum-layout-depth = maximum layout depth exceeded
+    .hint = try to reduce the amount of nesting in your layout
+
+missing-argument = missing argument: { $what }
+
+unexpected-argument = unexpected argument
+unexpected-argument-named = unexpected argument: { $name }
+
+unknown-font-family = unknown font family: { $family }
+
+unnecessary-import-rename = unnecessary import rename to same name
+
+# Labels of secondary spans.
+label-closure-defined-here = closure defined here
@@ -0,0 +1,30 @@
+# Simplified Chinese diagnostic messages.
+
+argument-is-positional = 参数 `{ $what }` 是位置参数
+    .hint = 尝试删除 `{ $name }:`
+
+compilation-cancelled = 编译已取消
+    .hint = 可能已经开始了一次新的编译
+
+empty-emph = 下划线之间没有文本
+    .hint = 连续使用多个下划线（例如 __）没有额外效果
+
+empty-strong = 星号之间没有文本
+    .hint = 连续使用多个星号（例如 **）没有额外效果
+
+layout-did-not-converge = 布局在 { $max } 次尝试内未能收敛
+    .hint = 检查是否有状态或查询在更新自身
+
+maximum-layout-depth = 超出最大布局深度
+    .hint = 尝试减少布局中的嵌套层数
+
+missing-argument = 缺少参数：{ $what }
+
+unexpected-argument = 意外的参数
+unexpected-argument-named = 意外的参数：{ $name }
+
+unknown-font-family = 未知的字体族：{ $family }
+
+unnecessary-import-rename = 不必要的导入重命名：新名称与原名称相同
+
+label-closure-defined-here = 闭包在此处定义
@@ -8,8 +8,10 @@ use std::string::FromUtf8Error;
 
 use comemo::Tracked;
 use ecow::{eco_vec, EcoVec};
+use once_cell::sync::Lazy;
 
 use crate::syntax::{PackageSpec, Span, Spanned, SyntaxError};
+use crate::text::{Lang, Region};
 use crate::{World, WorldExt};
 
 /// Early-return with a [`StrResult`] or [`SourceResult`].
@@ -132,11 +134,32 @@ pub struct SourceDiagnostic {
     pub span: Span,
     /// A diagnostic message describing the problem.
     pub message: EcoString,
-    /// The trace of function calls leading to the problem.
-    pub trace: EcoVec<Spanned<Tracepoint>>,
+    /// The trace of function calls leading to the problem. Each tracepoint
+    /// comes with the number of times it occurred in a row, which is larger
+    /// than one for recursive calls.
+    pub trace: EcoVec<Spanned<(Tracepoint, usize)>>,
     /// Additional hints to the user, indicating how this problem could be avoided
     /// or worked around.
     pub hints: EcoVec<EcoString>,
+    /// A stable, kebab-case code identifying the kind of problem, like
+    /// `missing-argument`. Can be used to look up a longer explanation.
+    pub code: Option<&'static str>,
+    /// Secondary spans with labels that provide context, like the definition
+    /// of a closure whose call failed.
+    pub labels: EcoVec<Spanned<EcoString>>,
+    /// Fixes for the problem that can be applied mechanically.
+    pub suggestions: EcoVec<Suggestion>,
+}
+
+/// A fix for a [`SourceDiagnostic`] that can be applied mechanically.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Suggestion {
+    /// The span whose text should be replaced.
+    pub span: Span,
+    /// The text to replace it with.
+    pub replacement: EcoString,
+    /// A short description of the fix.
+    pub message: EcoString,
 }
 
 /// The severity of a [`SourceDiagnostic`].
@@ -157,6 +180,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            code: None,
+            labels: eco_vec![],
+            suggestions: eco_vec![],
         }
     }
 
@@ -168,6 +194,9 @@ impl SourceDiagnostic {
             trace: eco_vec![],
             message: message.into(),
             hints: eco_vec![],
+            code: None,
+            labels: eco_vec![],
+            suggestions: eco_vec![],
         }
     }
 
@@ -187,6 +216,33 @@ impl SourceDiagnostic {
         self.hints.extend(hints);
         self
     }
+
+    /// Sets the stable code of the diagnostic.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Adds a secondary span with a label to the diagnostic.
+    pub fn with_label(mut self, span: Span, label: impl Into<EcoString>) -> Self {
+        self.labels.push(Spanned::new(label.into(), span));
+        self
+    }
+
+    /// Adds a mechanically applicable fix to the diagnostic.
+    pub fn with_suggestion(
+        mut self,
+        span: Span,
+        replacement: impl Into<EcoString>,
+        message: impl Into<EcoString>,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            replacement: replacement.into(),
+            message: message.into(),
+        });
+        self
+    }
 }
 
 impl From<SyntaxError> for SourceDiagnostic {
@@ -197,6 +253,9 @@ impl From<SyntaxError> for SourceDiagnostic {
             message: error.message,
             trace: eco_vec![],
             hints: error.hints,
+            code: Some("syntax-error"),
+            labels: eco_vec![],
+            suggestions: eco_vec![],
         }
     }
 }
@@ -257,7 +316,17 @@ impl<T> Trace<T> for SourceResult<T> {
                     }
                 }
 
-                error.trace.push(Spanned::new(make_point(), span));
+                // Recursive calls pass through the same call site over and
+                // over again, which would bloat the trace. Instead, we count
+                // the repetitions.
+                if let Some(last) = error.trace.make_mut().last_mut() {
+                    if last.span == span {
+                        last.v.1 += 1;
+                        continue;
+                    }
+                }
+
+                error.trace.push(Spanned::new((make_point(), 1), span));
             }
             errors
         })
@@ -504,3 +573,252 @@ pub fn format_xml_like_error(format: &str, error: roxmltree::Error) -> EcoString
         err => eco_format!("failed to parse {format} ({err})"),
     }
 }
+
+/// Translate diagnostics into the given language, where possible.
+///
+/// Only diagnostics with a [code](SourceDiagnostic::code) can be translated:
+/// Their message and hints are matched against the English catalog to extract
+/// the variable parts, which are then filled into the translated catalog.
+/// Diagnostics without a matching translation are left untouched, so that
+/// they still show up in English. Codes and spans are never changed.
+///
+/// Currently, only Simplified Chinese is available.
+pub fn localize(diags: &mut [SourceDiagnostic], lang: Lang, region: Option<Region>) {
+    let Some(catalog) = Catalog::get(lang, region) else { return };
+    for diag in diags {
+        catalog.localize(diag);
+    }
+}
+
+/// The English diagnostic catalog, which is used for matching.
+static EN: Lazy<Catalog> =
+    Lazy::new(|| Catalog::parse(include_str!("../assets/i18n/en.ftl")));
+
+/// The Simplified Chinese diagnostic catalog.
+static ZH_CN: Lazy<Catalog> =
+    Lazy::new(|| Catalog::parse(include_str!("../assets/i18n/zh-CN.ftl")));
+
+/// A catalog of diagnostic messages in a small subset of the Fluent syntax:
+/// Messages with attributes and variable placeables like `{ $name }`.
+struct Catalog(Vec<Message>);
+
+/// A message in a [`Catalog`].
+struct Message {
+    id: EcoString,
+    value: Pattern,
+    attrs: Vec<(EcoString, Pattern)>,
+}
+
+/// A message pattern, consisting of text and variables.
+struct Pattern(Vec<Piece>);
+
+/// A part of a [`Pattern`].
+enum Piece {
+    Text(EcoString),
+    Var(EcoString),
+}
+
+impl Catalog {
+    /// The catalog for the given language, if any.
+    ///
+    /// Chinese without a region is assumed to be Simplified Chinese as used
+    /// in mainland China. Other regions, like Taiwan, use Traditional Chinese,
+    /// for which there is no catalog yet.
+    fn get(lang: Lang, region: Option<Region>) -> Option<&'static Self> {
+        match lang {
+            Lang::CHINESE if region.map_or(true, |region| region == "CN") => Some(&ZH_CN),
+            _ => None,
+        }
+    }
+
+    /// Parse a catalog.
+    fn parse(text: &str) -> Self {
+        let mut messages: Vec<Message> = vec![];
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with(' ') {
+                let Some((name, value)) =
+                    line.trim().strip_prefix('.').and_then(|attr| attr.split_once('='))
+                else {
+                    continue;
+                };
+
+                if let Some(message) = messages.last_mut() {
+                    let pattern = Pattern::parse(value.trim());
+                    message.attrs.push((name.trim().into(), pattern));
+                }
+            } else if let Some((id, value)) = line.split_once('=') {
+                messages.push(Message {
+                    id: id.trim().into(),
+                    value: Pattern::parse(value.trim()),
+                    attrs: vec![],
+                });
+            }
+        }
+        Self(messages)
+    }
+
+    /// Find a message by its identifier.
+    fn message(&self, id: &str) -> Option<&Message> {
+        self.0.iter().find(|message| message.id == id)
+    }
+
+    /// Translate a diagnostic in-place.
+    fn localize(&self, diag: &mut SourceDiagnostic) {
+        if let Some(code) = diag.code {
+            // A code can have multiple forms, distinguished by a suffix.
+            let forms = EN.0.iter().filter(|message| {
+                message.id == code
+                    || message
+                        .id
+                        .strip_prefix(code)
+                        .is_some_and(|suffix| suffix.starts_with('-'))
+            });
+
+            for en in forms {
+                let Some(vars) = en.value.matches(&diag.message) else { continue };
+                let Some(target) = self.message(&en.id) else { break };
+                diag.message = target.value.render(&vars);
+                for hint in diag.hints.make_mut() {
+                    self.localize_hint(en, target, hint);
+                }
+                break;
+            }
+        }
+
+        for label in diag.labels.make_mut() {
+            let labels = EN.0.iter().filter(|message| message.id.starts_with("label-"));
+            for en in labels {
+                let Some(vars) = en.value.matches(&label.v) else { continue };
+                if let Some(target) = self.message(&en.id) {
+                    label.v = target.value.render(&vars);
+                }
+                break;
+            }
+        }
+    }
+
+    /// Translate a hint through the attributes of a message.
+    fn localize_hint(&self, en: &Message, target: &Message, hint: &mut EcoString) {
+        for (name, pattern) in &en.attrs {
+            let Some(vars) = pattern.matches(hint) else { continue };
+            if let Some((_, translated)) = target.attrs.iter().find(|(n, _)| n == name) {
+                *hint = translated.render(&vars);
+            }
+            break;
+        }
+    }
+}
+
+impl Pattern {
+    /// Parse a pattern from the value of a message or attribute.
+    fn parse(mut text: &str) -> Self {
+        let mut pieces = vec![];
+        while let Some(start) = text.find('{') {
+            let Some(end) = text[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+
+            if start > 0 {
+                pieces.push(Piece::Text(text[..start].into()));
+            }
+
+            // A placeable is either a variable or a quoted string literal.
+            let inner = text[start + 1..end].trim();
+            if let Some(var) = inner.strip_prefix('$') {
+                pieces.push(Piece::Var(var.into()));
+            } else {
+                pieces.push(Piece::Text(inner.trim_matches('"').into()));
+            }
+
+            text = &text[end + 1..];
+        }
+
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text.into()));
+        }
+
+        Self(pieces)
+    }
+
+    /// Match a string against the pattern and extract its variables.
+    fn matches(&self, mut text: &str) -> Option<Vec<(EcoString, EcoString)>> {
+        let mut vars = vec![];
+        let mut pieces = self.0.iter().peekable();
+        while let Some(piece) = pieces.next() {
+            match piece {
+                Piece::Text(literal) => text = text.strip_prefix(literal.as_str())?,
+                Piece::Var(name) => {
+                    let value = match pieces.peek() {
+                        Some(Piece::Text(next)) => {
+                            let end = text.find(next.as_str())?;
+                            let (value, rest) = text.split_at(end);
+                            text = rest;
+                            value
+                        }
+                        _ => std::mem::take(&mut text),
+                    };
+                    vars.push((name.clone(), value.into()));
+                }
+            }
+        }
+        text.is_empty().then_some(vars)
+    }
+
+    /// Render the pattern with the given variables.
+    fn render(&self, vars: &[(EcoString, EcoString)]) -> EcoString {
+        let mut out = EcoString::new();
+        for piece in &self.0 {
+            match piece {
+                Piece::Text(literal) => out.push_str(literal),
+                Piece::Var(name) => {
+                    if let Some((_, value)) = vars.iter().find(|(n, _)| n == name) {
+                        out.push_str(value);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localize_round_trip() {
+        let mut diags = [
+            error!(
+                Span::detached(), "the argument `x` is positional";
+                hint: "try removing `x:`"
+            )
+            .with_code("argument-is-positional"),
+            error!(Span::detached(), "unexpected argument: fill")
+                .with_code("unexpected-argument"),
+            error!(Span::detached(), "unexpected argument: fill"),
+        ];
+
+        localize(&mut diags, Lang::CHINESE, None);
+        assert_eq!(diags[0].message, "参数 `x` 是位置参数");
+        assert_eq!(diags[0].hints[0], "尝试删除 `x:`");
+        assert_eq!(diags[0].code, Some("argument-is-positional"));
+        assert_eq!(diags[1].message, "意外的参数：fill");
+        assert_eq!(diags[2].message, "unexpected argument: fill");
+    }
+
+    #[test]
+    fn test_localize_chinese_regions() {
+        let mut diags = [error!(Span::detached(), "missing argument: body")
+            .with_code("missing-argument")];
+
+        localize(&mut diags, Lang::CHINESE, Some("TW".parse().unwrap()));
+        assert_eq!(diags[0].message, "missing argument: body");
+
+        localize(&mut diags, Lang::CHINESE, Some("CN".parse().unwrap()));
+        assert_eq!(diags[0].message, "缺少参数：body");
+    }
+}
@@ -29,7 +29,8 @@ impl Eval for ast::FuncCall<'_> {
         let trailing_comma = args.trailing_comma();
 
         if !vm.engine.route.within(Route::MAX_CALL_DEPTH) {
-            bail!(span, "maximum function call depth exceeded");
+            bail!(error!(span, "maximum function call depth exceeded")
+                .with_code("maximum-call-depth"));
         }
 
         // Try to evaluate as a call to an associated function or field.
@@ -250,7 +251,7 @@ pub(crate) fn call_closure(
     route: Tracked<Route>,
     locator: Tracked<Locator>,
     tracer: TrackedMut<Tracer>,
-    mut args: Args,
+    args: Args,
 ) -> SourceResult<Value> {
     let node = closure.node.cast::<ast::Closure>().unwrap();
 
@@ -277,7 +278,34 @@ pub(crate) fn call_closure(
         vm.define(name, Value::Func(func.clone()));
     }
 
-    // Parse the arguments according to the parameter list.
+    // Parse the arguments according to the parameter list. If that fails,
+    // point to the closure's definition for context.
+    bind_params(&mut vm, node, closure, args).map_err(|errors| {
+        errors
+            .into_iter()
+            .map(|error| error.with_label(node.span(), "closure defined here"))
+            .collect::<EcoVec<_>>()
+    })?;
+
+    // Handle control flow.
+    let output = node.body().eval(&mut vm)?;
+    match vm.flow {
+        Some(FlowEvent::Return(_, Some(explicit))) => return Ok(explicit),
+        Some(FlowEvent::Return(_, None)) => {}
+        Some(flow) => bail!(flow.forbidden()),
+        None => {}
+    }
+
+    Ok(output)
+}
+
+/// Bind the arguments of a closure call to the closure's parameters.
+fn bind_params(
+    vm: &mut Vm,
+    node: ast::Closure,
+    closure: &Closure,
+    mut args: Args,
+) -> SourceResult<()> {
     let num_pos_params = node
         .params()
         .children()
@@ -299,7 +327,7 @@ pub(crate) fn call_closure(
                 ast::Pattern::Normal(_) => unreachable!(),
                 pattern => {
                     crate::eval::destructure(
-                        &mut vm,
+                        vm,
                         pattern,
                         args.expect::<Value>("pattern parameter")?,
                     )?;
@@ -330,18 +358,12 @@ pub(crate) fn call_closure(
     }
 
     // Ensure all arguments have been used.
-    args.finish()?;
-
-    // Handle control flow.
-    let output = node.body().eval(&mut vm)?;
-    match vm.flow {
-        Some(FlowEvent::Return(_, Some(explicit))) => return Ok(explicit),
-        Some(FlowEvent::Return(_, None)) => {}
-        Some(flow) => bail!(flow.forbidden()),
-        None => {}
-    }
+    args.finish_among(node.params().children().filter_map(|param| match param {
+        ast::Param::Named(named) => Some(named.name().as_str()),
+        _ => None,
+    }))?;
 
-    Ok(output)
+    Ok(())
 }
 
 fn in_math(expr: ast::Expr) -> bool {
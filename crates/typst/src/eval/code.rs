@@ -142,7 +142,7 @@ impl Eval for ast::Ident<'_> {
 
     #[tracing::instrument(name = "Ident::eval", skip_all)]
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get(&self).cloned().at(self.span())
+        vm.scopes.get_at(&self, self.span()).cloned()
     }
 }
 
@@ -38,10 +38,13 @@ impl Eval for ast::ModuleImport<'_> {
             if let ast::Expr::Ident(ident) = self.source() {
                 if ident.as_str() == new_name.as_str() {
                     // Warn on `import x as x`
-                    vm.engine.tracer.warn(warning!(
-                        new_name.span(),
-                        "unnecessary import rename to same name",
-                    ));
+                    vm.engine.tracer.warn(
+                        warning!(
+                            new_name.span(),
+                            "unnecessary import rename to same name",
+                        )
+                        .with_code("unnecessary-import-rename"),
+                    );
                 }
             }
 
@@ -73,10 +76,13 @@ impl Eval for ast::ModuleImport<'_> {
                             if renamed_item.original_name().as_str()
                                 == renamed_item.new_name().as_str()
                             {
-                                vm.engine.tracer.warn(warning!(
-                                    renamed_item.new_name().span(),
-                                    "unnecessary import rename to same name",
-                                ));
+                                vm.engine.tracer.warn(
+                                    warning!(
+                                        renamed_item.new_name().span(),
+                                        "unnecessary import rename to same name",
+                                    )
+                                    .with_code("unnecessary-import-rename"),
+                                );
                             }
                         }
 
@@ -144,7 +144,7 @@ impl Eval for ast::Strong<'_> {
                 .warn(warning!(
                     self.span(), "no text within stars";
                     hint: "using multiple consecutive stars (e.g. **) has no additional effect",
-                ));
+                ).with_code("empty-strong"));
         }
 
         Ok(StrongElem::new(body.eval(vm)?).pack())
@@ -163,7 +163,7 @@ impl Eval for ast::Emph<'_> {
                 .warn(warning!(
                     self.span(), "no text within underscores";
                     hint: "using multiple consecutive underscores (e.g. __) has no additional effect"
-                ));
+                ).with_code("empty-emph"));
         }
 
         Ok(EmphElem::new(body.eval(vm)?).pack())
@@ -25,7 +25,7 @@ impl Eval for ast::MathIdent<'_> {
 
     #[tracing::instrument(name = "MathIdent::eval", skip_all)]
     fn eval(self, vm: &mut Vm) -> SourceResult<Self::Output> {
-        vm.scopes.get_in_math(&self).cloned().at(self.span())
+        vm.scopes.get_in_math_at(&self, self.span()).cloned()
     }
 }
 
@@ -25,9 +25,13 @@ pub(crate) use self::flow::*;
 
 use comemo::{Track, Tracked, TrackedMut};
 
-use crate::diag::{bail, SourceResult};
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::{Engine, Route};
-use crate::foundations::{Cast, Module, NativeElement, Scope, Scopes, Value};
+use crate::foundations::{
+    Cast, LocatableSelector, Module, NativeElement, Scope, Scopes, Selector, Value,
+};
 use crate::introspection::{Introspector, Locator};
 use crate::math::EquationElem;
 use crate::syntax::{ast, parse, parse_code, parse_math, Source, Span};
@@ -35,7 +39,7 @@ use crate::World;
 
 /// Evaluate a source file and return the resulting module.
 #[comemo::memoize]
-#[tracing::instrument(skip_all)]
+#[tracing::instrument(skip_all, fields(file = ?source.id().vpath()))]
 pub fn eval(
     world: Tracked<dyn World + '_>,
     route: Tracked<Route>,
@@ -153,6 +157,25 @@ pub fn eval_string(
     Ok(output)
 }
 
+/// Evaluate a string as a selector for locatable elements, like
+/// `{heading.where(level: 1)}` or `{<intro>}`.
+pub fn eval_selector(
+    world: Tracked<dyn World + '_>,
+    string: &str,
+) -> StrResult<Selector> {
+    let value =
+        eval_string(world, string, Span::detached(), EvalMode::Code, Scope::new())
+            .map_err(|errors| {
+                let mut message = EcoString::from("failed to evaluate selector");
+                for (i, error) in errors.into_iter().enumerate() {
+                    message.push_str(if i == 0 { ": " } else { ", " });
+                    message.push_str(&error.message);
+                }
+                message
+            })?;
+    Ok(value.cast::<LocatableSelector>()?.0)
+}
+
 /// In which mode to evaluate a string.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
 pub enum EvalMode {
@@ -1,13 +1,14 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
-use ecow::EcoVec;
+use ecow::{EcoString, EcoVec};
 
 use crate::diag::SourceDiagnostic;
 use crate::foundations::Value;
 use crate::syntax::{FileId, Span};
 use crate::util::hash128;
 
-/// Traces warnings and which values existed for an expression at a span.
+/// Traces warnings, dependencies, glyph coverage, and which values existed for
+/// an expression at a span.
 #[derive(Default, Clone)]
 pub struct Tracer {
     inspected: Option<Span>,
@@ -15,6 +16,9 @@ pub struct Tracer {
     warnings_set: HashSet<u128>,
     delayed: EcoVec<SourceDiagnostic>,
     values: EcoVec<Value>,
+    dependencies: EcoVec<Dependency>,
+    coverage: GlyphCoverage,
+    passes: usize,
 }
 
 impl Tracer {
@@ -36,6 +40,16 @@ impl Tracer {
         self.warnings
     }
 
+    /// Mutable access to the warnings, e.g. to localize them.
+    pub fn warnings_mut(&mut self) -> &mut [SourceDiagnostic] {
+        self.warnings.make_mut()
+    }
+
+    /// Retain only the warnings for which the predicate returns `true`.
+    pub fn retain_warnings(&mut self, f: impl FnMut(&SourceDiagnostic) -> bool) {
+        self.warnings.retain(f);
+    }
+
     /// Mark a span as inspected. All values observed for this span can be
     /// retrieved via `values` later.
     pub fn inspect(&mut self, span: Span) {
@@ -46,6 +60,39 @@ impl Tracer {
     pub fn values(self) -> EcoVec<Value> {
         self.values
     }
+
+    /// The files and fonts that the last compilation accessed through the
+    /// world, in the order they were first accessed.
+    ///
+    /// This includes files that failed to load, so that a watcher can notice
+    /// when they are created.
+    pub fn dependencies(&self) -> &[Dependency] {
+        &self.dependencies
+    }
+
+    /// Replace the recorded dependencies.
+    pub(crate) fn set_dependencies(&mut self, dependencies: EcoVec<Dependency>) {
+        self.dependencies = dependencies;
+    }
+
+    /// Which characters of the last compilation's text needed font fallback
+    /// and which had no glyph in any font.
+    pub fn coverage(&self) -> &GlyphCoverage {
+        &self.coverage
+    }
+
+    /// How many layout passes the last compilation needed until
+    /// introspection converged (or the compiler gave up).
+    ///
+    /// This is zero if the compilation failed before layout started.
+    pub fn passes(&self) -> usize {
+        self.passes
+    }
+
+    /// Record the number of layout passes.
+    pub(crate) fn set_passes(&mut self, passes: usize) {
+        self.passes = passes;
+    }
 }
 
 #[comemo::track]
@@ -73,6 +120,17 @@ impl Tracer {
         }
     }
 
+    /// Record that a character was shaped with a font from the given family
+    /// because none of the requested fonts has a glyph for it.
+    pub fn fallback(&mut self, c: char, family: EcoString) {
+        self.coverage.fallbacks.entry(c).or_insert(family);
+    }
+
+    /// Record that no font has a glyph for a character.
+    pub fn missing(&mut self, c: char) {
+        self.coverage.missing.insert(c);
+    }
+
     /// Trace a value for the span.
     pub fn value(&mut self, v: Value) {
         if self.values.len() < Self::MAX_VALUES {
@@ -80,3 +138,31 @@ impl Tracer {
         }
     }
 }
+
+/// Something a compilation accessed through the world.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Dependency {
+    /// A source file or binary file, possibly in a package.
+    File(FileId),
+    /// The font with the given index in the font book.
+    Font(usize),
+}
+
+/// Which characters of a document's text are not covered by the requested
+/// fonts.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct GlyphCoverage {
+    /// Characters that were shaped with a fallback font, along with the family
+    /// of the first font they fell back to.
+    pub fallbacks: BTreeMap<char, EcoString>,
+    /// Characters that no available font has a glyph for. These are shown as
+    /// "tofus".
+    pub missing: BTreeSet<char>,
+}
+
+impl GlyphCoverage {
+    /// Whether all characters were covered by the requested fonts.
+    pub fn is_complete(&self) -> bool {
+        self.fallbacks.is_empty() && self.missing.is_empty()
+    }
+}
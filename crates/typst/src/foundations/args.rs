@@ -7,6 +7,7 @@ use crate::foundations::{
     func, repr, scope, ty, Array, Dict, FromValue, IntoValue, Repr, Str, Value,
 };
 use crate::syntax::{Span, Spanned};
+use crate::util::closest;
 
 /// Captured arguments to a function.
 ///
@@ -134,11 +135,12 @@ impl Args {
                     item.span,
                     "the argument `{what}` is positional";
                     hint: "try removing `{}:`", name,
-                );
+                )
+                .with_code("argument-is-positional");
             }
         }
 
-        error!(self.span, "missing argument: {what}")
+        error!(self.span, "missing argument: {what}").with_code("missing-argument")
     }
 
     /// Find and consume the first castable positional argument.
@@ -225,11 +227,27 @@ impl Args {
     /// Return an "unexpected argument" error if there is any remaining
     /// argument.
     pub fn finish(self) -> SourceResult<()> {
+        self.finish_among(std::iter::empty())
+    }
+
+    /// Like [`finish`](Self::finish), but suggests a similarly named
+    /// parameter if an unexpected named argument looks like a typo.
+    pub fn finish_among<'a>(
+        self,
+        params: impl IntoIterator<Item = &'a str>,
+    ) -> SourceResult<()> {
         if let Some(arg) = self.items.first() {
-            match &arg.name {
-                Some(name) => bail!(arg.span, "unexpected argument: {name}"),
-                _ => bail!(arg.span, "unexpected argument"),
-            }
+            let error = match &arg.name {
+                Some(name) => {
+                    let mut error = error!(arg.span, "unexpected argument: {name}");
+                    if let Some(similar) = closest(name, params) {
+                        error.hint(eco_format!("did you mean `{similar}`?"));
+                    }
+                    error
+                }
+                _ => error!(arg.span, "unexpected argument"),
+            };
+            bail!(error.with_code("unexpected-argument"));
         }
         Ok(())
     }
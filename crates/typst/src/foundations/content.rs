@@ -209,6 +209,12 @@ impl Content {
         self
     }
 
+    /// Set a field to the content, failing if the value does not fit the
+    /// field.
+    pub(crate) fn try_set_field(&mut self, id: u8, value: Value) -> StrResult<()> {
+        self.make_mut().set_field(id, value)
+    }
+
     /// Create a new sequence element from multiples elements.
     pub fn sequence(iter: impl IntoIterator<Item = Self>) -> Self {
         let mut iter = iter.into_iter();
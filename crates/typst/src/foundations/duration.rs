@@ -1,10 +1,11 @@
-use std::fmt::{self, Debug, Formatter};
+use std::fmt::{self, Debug, Formatter, Write};
 use std::ops::{Add, Div, Mul, Neg, Sub};
 
 use ecow::{eco_format, EcoString};
 use time::ext::NumericalDuration;
 
-use crate::foundations::{func, repr, scope, ty, Repr};
+use crate::diag::{bail, StrResult};
+use crate::foundations::{func, repr, scope, ty, Repr, Smart, Str};
 
 /// Represents a positive or negative span of time.
 #[ty(scope)]
@@ -108,6 +109,133 @@ impl Duration {
     pub fn weeks(&self) -> f64 {
         self.seconds() / 604_800.0
     }
+
+    /// Displays the duration in a specified format.
+    ///
+    /// The format can contain the components `[[weeks]]`, `[[days]]`,
+    /// `[[hours]]`, `[[minutes]]` and `[[seconds]]`. The largest component in
+    /// the format receives the whole duration, while the others only show what
+    /// remains, e.g. the minutes that don't make up a full hour. Minutes and
+    /// seconds that are not the largest component are padded to two digits. A
+    /// literal opening bracket is written as two opening brackets. The default
+    /// format is `[[hours]:[minutes]:[seconds]]`.
+    ///
+    /// ```example
+    /// #let talk = duration(hours: 1, minutes: 5)
+    /// #talk.display() \
+    /// #talk.display("[minutes] min") \
+    /// #let start = datetime(hour: 9, minute: 30, second: 0)
+    /// #(start + talk).display("[hour]:[minute]")
+    /// ```
+    #[func]
+    pub fn display(
+        &self,
+        /// The format used to display the duration.
+        #[default]
+        pattern: Smart<Str>,
+    ) -> StrResult<EcoString> {
+        let pattern = match &pattern {
+            Smart::Auto => "[hours]:[minutes]:[seconds]",
+            Smart::Custom(pattern) => pattern.as_str(),
+        };
+
+        let items = parse_pattern(pattern)?;
+        let largest = items.iter().filter_map(|item| item.unit()).max();
+
+        let mut remaining = self.0.whole_seconds().unsigned_abs();
+        let mut values = [0; 5];
+        for unit in (0..5).rev() {
+            if items.iter().any(|item| item.unit() == Some(unit)) {
+                values[unit] = remaining / UNITS[unit].1;
+                remaining %= UNITS[unit].1;
+            }
+        }
+
+        let mut out = EcoString::new();
+        if self.0.is_negative() {
+            out.push('-');
+        }
+
+        for item in items {
+            match item {
+                PatternItem::Literal(text) => out.push_str(text),
+                PatternItem::Unit(unit) => {
+                    let value = values[unit];
+                    if unit <= 1 && Some(unit) != largest {
+                        write!(out, "{value:02}").unwrap();
+                    } else {
+                        write!(out, "{value}").unwrap();
+                    }
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+/// The components of a duration format, from small to large, along with their
+/// length in seconds.
+const UNITS: [(&str, u64); 5] = [
+    ("seconds", 1),
+    ("minutes", 60),
+    ("hours", 3_600),
+    ("days", 86_400),
+    ("weeks", 604_800),
+];
+
+/// A part of a duration format.
+enum PatternItem<'a> {
+    /// Text that is displayed as is.
+    Literal(&'a str),
+    /// The index of a component in [`UNITS`].
+    Unit(usize),
+}
+
+impl PatternItem<'_> {
+    /// The component this item displays, if any.
+    fn unit(&self) -> Option<usize> {
+        match self {
+            Self::Literal(_) => None,
+            Self::Unit(unit) => Some(*unit),
+        }
+    }
+}
+
+/// Parse a duration format into its parts.
+fn parse_pattern(pattern: &str) -> StrResult<Vec<PatternItem>> {
+    let mut items = vec![];
+    let mut rest = pattern;
+    while let Some(start) = rest.find('[') {
+        if start > 0 {
+            items.push(PatternItem::Literal(&rest[..start]));
+        }
+
+        rest = &rest[start + 1..];
+        if let Some(after) = rest.strip_prefix('[') {
+            items.push(PatternItem::Literal("["));
+            rest = after;
+            continue;
+        }
+
+        let Some(end) = rest.find(']') else {
+            bail!("unclosed component in duration format");
+        };
+
+        let name = &rest[..end];
+        let Some(unit) = UNITS.iter().position(|&(unit, _)| unit == name) else {
+            bail!("unknown duration component `{name}`");
+        };
+
+        items.push(PatternItem::Unit(unit));
+        rest = &rest[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        items.push(PatternItem::Literal(rest));
+    }
+
+    Ok(items)
 }
 
 impl Debug for Duration {
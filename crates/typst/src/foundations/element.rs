@@ -75,9 +75,10 @@ impl Element {
 
     /// Execute the set rule for the element and return the resulting style map.
     pub fn set(self, engine: &mut Engine, mut args: Args) -> SourceResult<Styles> {
+        let named = args.to_named();
         let styles = (self.0.set)(engine, &mut args)?;
         args.finish()?;
-        Ok(styles)
+        Ok(styles.sourced(&named))
     }
 
     /// Whether the element has the given capability.
@@ -0,0 +1,158 @@
+//! Binary-to-text encodings.
+
+use ::base64::Engine;
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{func, Bytes, Module, Scope, Str};
+use crate::loading::Readable;
+
+/// A module with binary-to-text encodings.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_func::<base64>();
+    scope.define_func::<base64_decode>();
+    scope.define_func::<hex>();
+    scope.define_func::<hex_decode>();
+    scope.define_func::<url>();
+    scope.define_func::<url_decode>();
+    Module::new("encoding", scope)
+}
+
+/// Encodes a string or bytes with Base64.
+///
+/// ```example
+/// #encoding.base64("Typst")
+/// ```
+#[func(title = "Base64")]
+pub fn base64(
+    /// The data to encode. Strings are encoded in UTF-8.
+    data: Readable,
+) -> Str {
+    ::base64::engine::general_purpose::STANDARD
+        .encode(data.as_slice())
+        .into()
+}
+
+/// Decodes Base64-encoded bytes.
+///
+/// ```example
+/// #str(encoding.base64-decode("VHlwc3Q="))
+/// ```
+#[func(title = "Base64 Decode")]
+pub fn base64_decode(
+    /// The Base64 text to decode.
+    text: Str,
+) -> StrResult<Bytes> {
+    ::base64::engine::general_purpose::STANDARD
+        .decode(text.as_bytes())
+        .map(Bytes::from)
+        .map_err(|_| "text is not valid base64".into())
+}
+
+/// Encodes a string or bytes as lowercase hexadecimal digits.
+///
+/// ```example
+/// #encoding.hex("Typst")
+/// ```
+#[func(title = "Hexadecimal")]
+pub fn hex(
+    /// The data to encode. Strings are encoded in UTF-8.
+    data: Readable,
+) -> Str {
+    let mut out = EcoString::with_capacity(2 * data.as_slice().len());
+    for byte in data.as_slice() {
+        out.push_str(&eco_format!("{byte:02x}"));
+    }
+    out.into()
+}
+
+/// Decodes hexadecimal digits into bytes.
+///
+/// Both lowercase and uppercase digits are accepted.
+///
+/// ```example
+/// #str(encoding.hex-decode("5479707374"))
+/// ```
+#[func(title = "Hexadecimal Decode")]
+pub fn hex_decode(
+    /// The hexadecimal text to decode.
+    text: Str,
+) -> StrResult<Bytes> {
+    if text.len() % 2 != 0 {
+        bail!("hexadecimal text must have an even number of digits");
+    }
+
+    text.as_bytes()
+        .chunks(2)
+        .map(parse_hex_pair)
+        .collect::<Option<Vec<u8>>>()
+        .map(Bytes::from)
+        .ok_or_else(|| "text is not valid hexadecimal".into())
+}
+
+/// Percent-encodes a string for use in a URL.
+///
+/// All characters except ASCII letters, digits, and `-`, `.`, `_`, and `~`
+/// are encoded, so the result can be used as a part of a path or a query.
+///
+/// ```example
+/// #let query = encoding.url("排版 & typst")
+/// #link("https://www.bing.com/search?q=" + query)
+/// ```
+#[func(title = "URL Encode")]
+pub fn url(
+    /// The text to encode.
+    text: Str,
+) -> Str {
+    let mut out = EcoString::with_capacity(text.len());
+    for &byte in text.as_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            out.push(byte as char);
+        } else {
+            out.push_str(&eco_format!("%{byte:02X}"));
+        }
+    }
+    out.into()
+}
+
+/// Decodes a percent-encoded part of a URL.
+///
+/// ```example
+/// #encoding.url-decode("%E6%8E%92%E7%89%88")
+/// ```
+#[func(title = "URL Decode")]
+pub fn url_decode(
+    /// The text to decode.
+    text: Str,
+) -> StrResult<Str> {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = bytes
+                .get(i + 1..i + 3)
+                .and_then(parse_hex_pair)
+                .ok_or("invalid percent-encoding")?;
+            out.push(byte);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(out)
+        .map(Into::into)
+        .map_err(|_| "decoded text is not valid utf-8".into())
+}
+
+/// Parse two hexadecimal digits into a byte.
+fn parse_hex_pair(pair: &[u8]) -> Option<u8> {
+    let digit = |byte: u8| (byte as char).to_digit(16);
+    match *pair {
+        [high, low] => Some((digit(high)? * 16 + digit(low)?) as u8),
+        _ => None,
+    }
+}
@@ -51,6 +51,11 @@ impl f64 {
 
 impl Repr for f64 {
     fn repr(&self) -> EcoString {
+        // Infinity has no literal, so we refer to the constant instead.
+        if self.is_infinite() {
+            let sign = if self.is_sign_negative() { repr::MINUS_SIGN } else { "" };
+            return eco_format!("{sign}calc.inf");
+        }
         repr::format_float(*self, None, "")
     }
 }
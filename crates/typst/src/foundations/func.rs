@@ -191,6 +191,15 @@ impl Func {
         }
     }
 
+    /// The names of the parameters that can be passed by name.
+    fn named_params(&self) -> impl Iterator<Item = &'static str> {
+        self.params()
+            .into_iter()
+            .flatten()
+            .filter(|param| param.named)
+            .map(|param| param.name)
+    }
+
     /// Get the parameter info for a parameter with the given name if it exist.
     pub fn param(&self, name: &str) -> Option<&'static ParamInfo> {
         self.params()?.iter().find(|param| param.name == name)
@@ -266,12 +275,12 @@ impl Func {
         match &self.repr {
             Repr::Native(native) => {
                 let value = (native.function)(engine, &mut args)?;
-                args.finish()?;
+                args.finish_among(self.named_params())?;
                 Ok(value)
             }
             Repr::Element(func) => {
                 let value = func.construct(engine, &mut args)?;
-                args.finish()?;
+                args.finish_among(self.named_params())?;
                 Ok(Value::Content(value))
             }
             Repr::Closure(closure) => crate::eval::call_closure(
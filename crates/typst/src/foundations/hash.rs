@@ -0,0 +1,46 @@
+//! Cryptographic hash functions.
+
+use sha2::{Digest, Sha256};
+
+use crate::foundations::{func, Bytes, Module, Scope};
+use crate::loading::Readable;
+
+/// A module with hash functions.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define_func::<sha256>();
+    scope.define_func::<md5>();
+    Module::new("hash", scope)
+}
+
+/// Computes the SHA-256 hash of a string or bytes.
+///
+/// The hash is returned as 32 bytes. Use [`encoding.hex`]($encoding.hex) to
+/// turn it into the usual hexadecimal form.
+///
+/// ```example
+/// #encoding.hex(hash.sha256("Typst"))
+/// ```
+#[func(title = "SHA-256")]
+pub fn sha256(
+    /// The data to hash. Strings are hashed in their UTF-8 encoding.
+    data: Readable,
+) -> Bytes {
+    Bytes::from(Sha256::digest(data.as_slice()).to_vec())
+}
+
+/// Computes the MD5 hash of a string or bytes.
+///
+/// The hash is returned as 16 bytes. MD5 is not secure anymore, but still
+/// used to derive short identifiers and by older data formats.
+///
+/// ```example
+/// #encoding.hex(hash.md5("Typst"))
+/// ```
+#[func(title = "MD5")]
+pub fn md5(
+    /// The data to hash. Strings are hashed in their UTF-8 encoding.
+    data: Readable,
+) -> Bytes {
+    Bytes::from(::md5::compute(data.as_slice()).0.to_vec())
+}
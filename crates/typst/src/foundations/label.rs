@@ -1,6 +1,7 @@
 use ecow::{eco_format, EcoString};
 
 use crate::foundations::{func, scope, ty, Repr};
+use crate::syntax::is_valid_label_literal;
 use crate::util::PicoStr;
 
 /// A label for an element.
@@ -12,6 +13,10 @@ use crate::util::PicoStr;
 /// A labelled element can be [referenced]($ref), [queried]($query) for, and
 /// [styled]($styling) through its label.
 ///
+/// When exporting to PDF, the first element with a given label also becomes a
+/// named destination. This means that you can link to it from the outside
+/// with a URL like `document.pdf#label`.
+///
 /// # Example
 /// ```example
 /// #show <a>: set text(blue)
@@ -64,7 +69,12 @@ impl Label {
 
 impl Repr for Label {
     fn repr(&self) -> EcoString {
-        eco_format!("<{}>", self.as_str())
+        let name = self.as_str();
+        if is_valid_label_literal(name) {
+            eco_format!("<{name}>")
+        } else {
+            eco_format!("label({})", name.repr())
+        }
     }
 }
 
@@ -1,6 +1,9 @@
 //! Foundational types and functions.
 
 pub mod calc;
+pub mod encoding;
+pub mod hash;
+pub mod random;
 pub mod repr;
 pub mod sys;
 
@@ -23,6 +26,7 @@ mod label;
 mod methods;
 mod module;
 mod none;
+mod number;
 mod plugin;
 mod scope;
 mod selector;
@@ -50,6 +54,7 @@ pub use self::label::*;
 pub use self::methods::*;
 pub use self::module::*;
 pub use self::none::*;
+pub use self::number::*;
 pub use self::plugin::*;
 pub use self::repr::Repr;
 pub use self::scope::*;
@@ -83,7 +88,7 @@ use crate::syntax::Spanned;
 pub static FOUNDATIONS: Category;
 
 /// Hook up all `foundations` definitions.
-pub(super) fn define(global: &mut Scope) {
+pub(super) fn define(global: &mut Scope, inputs: Dict, target: sys::Target) {
     global.category(FOUNDATIONS);
     global.define_type::<bool>();
     global.define_type::<i64>();
@@ -105,12 +110,16 @@ pub(super) fn define(global: &mut Scope) {
     global.define_type::<Version>();
     global.define_type::<Plugin>();
     global.define_func::<repr::repr>();
+    global.define_func::<format_number>();
     global.define_func::<panic>();
     global.define_func::<assert>();
     global.define_func::<eval>();
     global.define_func::<style>();
     global.define_module(calc::module());
-    global.define_module(sys::module());
+    global.define_module(random::module());
+    global.define_module(encoding::module());
+    global.define_module(hash::module());
+    global.define_module(sys::module(inputs, target));
 }
 
 /// Fails with an error.
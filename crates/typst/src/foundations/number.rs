@@ -0,0 +1,175 @@
+use chinese_number::{ChineseCase, ChineseCountMethod, ChineseVariant, NumberToChinese};
+use ecow::EcoString;
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::calc::Num;
+use crate::foundations::repr::{self, MINUS_SIGN};
+use crate::foundations::{func, Cast, Str};
+
+/// Formats a number as a string.
+///
+/// Unlike [`str`]($str), this function can round to a fixed number of
+/// decimal places, group the digits of large numbers and write numbers in
+/// other styles, like percentages or Chinese numerals.
+///
+/// # Example
+/// ```example
+/// #format-number(1234567.891, decimals: 2, grouping: ",") \
+/// #format-number(0.256, decimals: 1, style: "percent") \
+/// #format-number(6.02214076e23, decimals: 3, style: "scientific") \
+/// #format-number(123, style: "chinese") \
+/// #format-number(12345.6, decimals: 2, style: "chinese-financial")
+/// ```
+#[func]
+pub fn format_number(
+    /// The number to format.
+    number: Num,
+    /// The number of digits after the decimal point. The number is rounded if
+    /// it has more digits and padded with zeros if it has fewer.
+    ///
+    /// If this is `{none}`, integers are written without and floats with as
+    /// many digits as needed.
+    #[named]
+    #[default]
+    decimals: Option<usize>,
+    /// A separator to put between each group of three digits before the
+    /// decimal point, like `{","}`. Chinese numerals ignore this.
+    #[named]
+    #[default]
+    grouping: Option<Str>,
+    /// How to write the number.
+    #[named]
+    #[default(NumberStyle::Decimal)]
+    style: NumberStyle,
+) -> StrResult<Str> {
+    let number = match style {
+        NumberStyle::Percent => match number {
+            Num::Int(v) => {
+                v.checked_mul(100).map_or(Num::Float(v as f64 * 100.0), Num::Int)
+            }
+            Num::Float(v) => Num::Float(v * 100.0),
+        },
+        _ => number,
+    };
+
+    if let Num::Float(v) = number {
+        if !v.is_finite() {
+            let formatted = repr::format_float(v, None, "");
+            if matches!(style, NumberStyle::Chinese | NumberStyle::ChineseFinancial) {
+                bail!("cannot write {formatted} in Chinese numerals");
+            }
+            return Ok(formatted.into());
+        }
+    }
+
+    if style == NumberStyle::Scientific {
+        let formatted = match (number, decimals) {
+            (Num::Int(v), Some(d)) => format!("{v:.d$e}"),
+            (Num::Int(v), None) => format!("{v:e}"),
+            (Num::Float(v), Some(d)) => format!("{v:.d$e}"),
+            (Num::Float(v), None) => format!("{v:e}"),
+        };
+        return Ok(formatted.replace('-', MINUS_SIGN).into());
+    }
+
+    let formatted = match (number, decimals) {
+        (Num::Int(v), Some(d)) if d > 0 => {
+            format!("{}.{}", v.unsigned_abs(), "0".repeat(d))
+        }
+        (Num::Int(v), _) => v.unsigned_abs().to_string(),
+        (Num::Float(v), Some(d)) => format!("{:.d$}", v.abs()),
+        (Num::Float(v), None) => v.abs().to_string(),
+    };
+
+    let (int, frac) = formatted.split_once('.').unwrap_or((&formatted, ""));
+    let zero = formatted.chars().all(|c| c == '0' || c == '.');
+    let negative = !zero
+        && match number {
+            Num::Int(v) => v < 0,
+            Num::Float(v) => v < 0.0,
+        };
+
+    let mut out = EcoString::new();
+    match style {
+        NumberStyle::Chinese | NumberStyle::ChineseFinancial => {
+            let case = if style == NumberStyle::Chinese {
+                ChineseCase::Lower
+            } else {
+                ChineseCase::Upper
+            };
+            let int = int
+                .parse::<u64>()
+                .ok()
+                .and_then(|int| {
+                    let method = ChineseCountMethod::TenThousand;
+                    int.to_chinese(ChineseVariant::Simple, case, method).ok()
+                })
+                .ok_or("number is too large to be written in Chinese numerals")?;
+
+            if negative {
+                out.push('负');
+            }
+            out.push_str(&int);
+            if !frac.is_empty() {
+                let digits = if style == NumberStyle::Chinese {
+                    LOWER_DIGITS
+                } else {
+                    UPPER_DIGITS
+                };
+                out.push('点');
+                for digit in frac.bytes() {
+                    out.push(digits[(digit - b'0') as usize]);
+                }
+            }
+        }
+        _ => {
+            if negative {
+                out.push_str(MINUS_SIGN);
+            }
+            match &grouping {
+                Some(separator) => {
+                    for (i, digit) in int.chars().enumerate() {
+                        if i > 0 && (int.len() - i) % 3 == 0 {
+                            out.push_str(separator);
+                        }
+                        out.push(digit);
+                    }
+                }
+                None => out.push_str(int),
+            }
+            if !frac.is_empty() {
+                out.push('.');
+                out.push_str(frac);
+            }
+            if style == NumberStyle::Percent {
+                out.push('%');
+            }
+        }
+    }
+
+    Ok(out.into())
+}
+
+/// How to write a number.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum NumberStyle {
+    /// With decimal digits, like `1234.5`.
+    Decimal,
+    /// As a percentage of the number multiplied by 100, like `12.5%`.
+    Percent,
+    /// In scientific notation, like `1.2345e3`.
+    Scientific,
+    /// With Chinese numerals, like `一千二百三十四点五`.
+    Chinese,
+    /// With the uppercase Chinese numerals used in finance, like
+    /// `壹仟贰佰叁拾肆点伍`.
+    ChineseFinancial,
+}
+
+/// The Chinese numerals for the digits after the decimal point.
+const LOWER_DIGITS: [char; 10] =
+    ['零', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// The uppercase Chinese numerals for the digits after the decimal point.
+const UPPER_DIGITS: [char; 10] =
+    ['零', '壹', '贰', '叁', '肆', '伍', '陆', '柒', '捌', '玖'];
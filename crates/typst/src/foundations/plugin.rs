@@ -42,6 +42,12 @@ use crate::World;
 /// particular, if a plugin function is called twice with the same arguments,
 /// Typst might cache the results and call your function only once.
 ///
+/// # Limits
+/// Each call to a plugin function may execute roughly one billion WebAssembly
+/// instructions. If it takes more than that, for example because it is stuck
+/// in an infinite loop, it is aborted with an error. This keeps a faulty
+/// plugin from hanging the compiler.
+///
 /// # Example
 /// ```example
 /// #let myplugin = plugin("hello.wasm")
@@ -132,6 +138,10 @@ struct Repr {
 /// Owns all data associated with the WebAssembly module.
 type Store = wasmi::Store<StoreData>;
 
+/// How much fuel a plugin can use up per call. Executing an instruction uses
+/// up about one unit.
+const FUEL: u64 = 1_000_000_000;
+
 /// If there was an error reading/writing memory, keep the offset + length to
 /// display an error message.
 struct MemoryError {
@@ -168,7 +178,9 @@ impl Plugin {
     /// Create a new plugin from raw WebAssembly bytes.
     #[comemo::memoize]
     pub fn new(bytes: Bytes) -> StrResult<Self> {
-        let engine = wasmi::Engine::default();
+        let mut config = wasmi::Config::default();
+        config.consume_fuel(true);
+        let engine = wasmi::Engine::new(&config);
         let module = wasmi::Module::new(&engine, bytes.as_slice())
             .map_err(|err| format!("failed to load WebAssembly module ({err})"))?;
 
@@ -189,10 +201,17 @@ impl Plugin {
             .unwrap();
 
         let mut store = Store::new(&engine, StoreData::default());
+        refuel(&mut store);
         let instance = linker
             .instantiate(&mut store, &module)
             .and_then(|pre_instance| pre_instance.start(&mut store))
-            .map_err(|e| eco_format!("{e}"))?;
+            .map_err(|err| {
+                if out_of_fuel(&err) {
+                    eco_format!("plugin took too long to start")
+                } else {
+                    eco_format!("{err}")
+                }
+            })?;
 
         // Ensure that the plugin exports its memory.
         if !matches!(
@@ -261,10 +280,17 @@ impl Plugin {
         // Store the input data.
         store.data_mut().args = args;
 
-        // Call the function.
+        // Call the function with a fresh budget.
+        refuel(&mut store);
         let mut code = wasmi::Value::I32(-1);
         func.call(store.as_context_mut(), &lengths, std::slice::from_mut(&mut code))
-            .map_err(|err| eco_format!("plugin panicked: {err}"))?;
+            .map_err(|err| {
+                if out_of_fuel(&err) {
+                    eco_format!("plugin function `{name}` took too long and was aborted")
+                } else {
+                    eco_format!("plugin panicked: {err}")
+                }
+            })?;
         if let Some(MemoryError { offset, length, write }) =
             store.data_mut().memory_error.take()
         {
@@ -298,6 +324,27 @@ impl Plugin {
     }
 }
 
+/// Top the store's fuel back up to the budget of a single call.
+///
+/// Fuel that is left over from an earlier call is kept, so that the budget
+/// never exceeds [`FUEL`].
+fn refuel(store: &mut Store) {
+    let remaining = store.consume_fuel(0).unwrap_or(0);
+    store
+        .add_fuel(FUEL.saturating_sub(remaining))
+        .expect("fuel metering is enabled");
+}
+
+/// Whether the plugin's execution trapped because it used up all of its
+/// fuel, as opposed to a trap raised by the plugin itself.
+fn out_of_fuel(err: &wasmi::Error) -> bool {
+    matches!(
+        err,
+        wasmi::Error::Trap(trap)
+            if trap.trap_code() == Some(wasmi::core::TrapCode::OutOfFuel)
+    )
+}
+
 impl Debug for Plugin {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.pad("Plugin(..)")
@@ -0,0 +1,207 @@
+//! Reproducible random numbers.
+
+use std::cmp::Ordering;
+use std::hash::Hasher;
+
+use siphasher::sip::SipHasher13;
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{cast, func, Array, Module, Scope, Str, Value};
+use crate::syntax::Span;
+
+/// A module with functions for random numbers.
+///
+/// All functions are deterministic: without a `seed`, they derive one from
+/// the location of the call in its file. This means that the same call yields
+/// the same result in every compilation, but also in every iteration of a
+/// loop. Pass a seed that depends on the loop variable to get different
+/// results.
+pub fn module() -> Module {
+    let mut scope = Scope::deduplicating();
+    scope.define_func::<uniform>();
+    scope.define_func::<integer>();
+    scope.define_func::<shuffle>();
+    scope.define_func::<choice>();
+    Module::new("random", scope)
+}
+
+/// Generates a random float between `low` (inclusive) and `high` (exclusive).
+///
+/// ```example
+/// #random.uniform(seed: 1) \
+/// #random.uniform(10, 20, seed: 1)
+/// ```
+#[func]
+pub fn uniform(
+    /// The call site.
+    span: Span,
+    /// The lower bound.
+    #[default(0.0)]
+    low: f64,
+    /// The upper bound.
+    #[default(1.0)]
+    high: f64,
+    /// The seed to generate the number from.
+    #[named]
+    #[default]
+    seed: Option<Seed>,
+) -> StrResult<f64> {
+    if low.partial_cmp(&high) != Some(Ordering::Less) {
+        bail!("`low` must be smaller than `high`");
+    }
+
+    let mut rng = Rng::new(seed, span);
+    Ok(low + rng.float() * (high - low))
+}
+
+/// Generates a random integer between `start` (inclusive) and `end`
+/// (exclusive).
+///
+/// ```example
+/// #random.integer(1, 7, seed: "die")
+/// ```
+#[func]
+pub fn integer(
+    /// The call site.
+    span: Span,
+    /// The smallest possible integer.
+    start: i64,
+    /// The integer after the largest possible one.
+    end: i64,
+    /// The seed to generate the integer from.
+    #[named]
+    #[default]
+    seed: Option<Seed>,
+) -> StrResult<i64> {
+    if end <= start {
+        bail!("range must not be empty");
+    }
+
+    let mut rng = Rng::new(seed, span);
+    let len = (end as i128 - start as i128) as u64;
+    Ok((start as i128 + rng.below(len) as i128) as i64)
+}
+
+/// Shuffles the items of an array into a random order.
+///
+/// ```example
+/// #random.shuffle(range(1, 10), seed: 1)
+/// ```
+#[func]
+pub fn shuffle(
+    /// The call site.
+    span: Span,
+    /// The array to shuffle.
+    array: Array,
+    /// The seed to generate the order from.
+    #[named]
+    #[default]
+    seed: Option<Seed>,
+) -> Array {
+    let mut rng = Rng::new(seed, span);
+    let mut items: Vec<Value> = array.into_iter().collect();
+    for i in (1..items.len()).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        items.swap(i, j);
+    }
+    items.into_iter().collect()
+}
+
+/// Picks a random item of an array.
+///
+/// ```example
+/// #random.choice(("A", "B", "C", "D"), seed: 1)
+/// ```
+#[func]
+pub fn choice(
+    /// The call site.
+    span: Span,
+    /// The array to pick from.
+    array: Array,
+    /// The seed to pick the item with.
+    #[named]
+    #[default]
+    seed: Option<Seed>,
+) -> StrResult<Value> {
+    if array.is_empty() {
+        bail!("array is empty");
+    }
+
+    let mut rng = Rng::new(seed, span);
+    let index = rng.below(array.len() as u64) as usize;
+    Ok(array.as_slice()[index].clone())
+}
+
+/// A seed for random numbers.
+///
+/// Seeds are turned into numbers in the same way on all platforms, so that
+/// a document looks the same wherever it is compiled.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Seed(u64);
+
+impl Seed {
+    /// Derive a seed from the location of a call.
+    ///
+    /// Only the file's path and the span's number go into the seed, so that
+    /// it does not depend on the order in which files are loaded.
+    fn from_span(span: Span) -> Self {
+        let mut state = SipHasher13::new();
+        if let Some(id) = span.id() {
+            if let Some(package) = id.package() {
+                state.write(package.to_string().as_bytes());
+            }
+            let path = id.vpath().as_rootless_path().to_string_lossy();
+            state.write(path.replace('\\', "/").as_bytes());
+        }
+        state.write(&span.number().to_le_bytes());
+        Self(state.finish())
+    }
+}
+
+cast! {
+    Seed,
+    v: i64 => Self(v as u64),
+    v: Str => {
+        let mut state = SipHasher13::new();
+        state.write(v.as_bytes());
+        Self(state.finish())
+    },
+    v: Array => {
+        let mut state = SipHasher13::new();
+        for item in v {
+            state.write(&item.cast::<Seed>()?.0.to_le_bytes());
+        }
+        Self(state.finish())
+    },
+}
+
+/// A small, fast, and deterministic random number generator (SplitMix64).
+///
+/// It is not suitable for cryptography.
+struct Rng(u64);
+
+impl Rng {
+    /// Create a generator from a seed, or from the call site if there is none.
+    fn new(seed: Option<Seed>, span: Span) -> Self {
+        Self(seed.unwrap_or_else(|| Seed::from_span(span)).0)
+    }
+
+    /// The next random 64-bit number.
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// A random float between 0 (inclusive) and 1 (exclusive).
+    fn float(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// A random integer below `n`, which must not be zero.
+    fn below(&mut self, n: u64) -> u64 {
+        ((self.next_u64() as u128 * n as u128) >> 64) as u64
+    }
+}
@@ -13,6 +13,10 @@ pub const MINUS_SIGN: &str = "\u{2212}";
 /// in monospace with syntax-highlighting. The exceptions are `{none}`,
 /// integers, floats, strings, content, and functions.
 ///
+/// For most values, the representation is valid Typst code that
+/// [evaluates]($eval) back to an equal value. This does not hold for content,
+/// functions, and a few other values that have no literal syntax.
+///
 /// **Note:** This function is for debugging purposes. Its output should not be
 /// considered stable and may change at any time!
 ///
@@ -1,15 +1,18 @@
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 
-use ecow::{eco_format, EcoString};
+use ecow::{eco_format, EcoString, EcoVec};
 use indexmap::IndexMap;
 
-use crate::diag::{bail, HintedStrResult, HintedString, StrResult};
+use crate::diag::{
+    bail, At, HintedStrResult, HintedString, SourceDiagnostic, SourceResult, StrResult,
+};
 use crate::foundations::{
     Element, Func, IntoValue, Module, NativeElement, NativeFunc, NativeFuncData,
     NativeType, Type, Value,
 };
-use crate::util::Static;
+use crate::syntax::Span;
+use crate::util::{closest, Static};
 use crate::Library;
 
 #[doc(inline)]
@@ -50,7 +53,7 @@ impl<'a> Scopes<'a> {
             .chain(self.scopes.iter().rev())
             .chain(self.base.map(|base| base.global.scope()))
             .find_map(|scope| scope.get(var))
-            .ok_or_else(|| unknown_variable(var))
+            .ok_or_else(|| unknown_variable(var, self.names(false)))
     }
 
     /// Try to access a variable immutably in math.
@@ -59,26 +62,88 @@ impl<'a> Scopes<'a> {
             .chain(self.scopes.iter().rev())
             .chain(self.base.map(|base| base.math.scope()))
             .find_map(|scope| scope.get(var))
-            .ok_or_else(|| unknown_variable(var))
+            .ok_or_else(|| unknown_variable(var, self.names(true)))
+    }
+
+    /// Access a variable immutably, failing with an error at the given span.
+    ///
+    /// In contrast to [`get`](Self::get), the error suggests a similarly named
+    /// variable as a fix.
+    pub fn get_at(&self, var: &str, span: Span) -> SourceResult<&Value> {
+        self.get(var)
+            .at(span)
+            .map_err(|errors| self.unknown(errors, var, span, false))
+    }
+
+    /// Access a variable immutably in math, failing with an error at the
+    /// given span.
+    pub fn get_in_math_at(&self, var: &str, span: Span) -> SourceResult<&Value> {
+        self.get_in_math(var)
+            .at(span)
+            .map_err(|errors| self.unknown(errors, var, span, true))
     }
 
     /// Try to access a variable mutably.
     pub fn get_mut(&mut self, var: &str) -> HintedStrResult<&mut Value> {
+        let defined = std::iter::once(&self.top)
+            .chain(self.scopes.iter())
+            .any(|scope| scope.get(var).is_some());
+
+        if !defined {
+            return Err(match self.base.and_then(|base| base.global.scope().get(var)) {
+                Some(_) => eco_format!("cannot mutate a constant: {}", var).into(),
+                _ => unknown_variable(var, self.names(false)),
+            });
+        }
+
         std::iter::once(&mut self.top)
             .chain(&mut self.scopes.iter_mut().rev())
             .find_map(|scope| scope.get_mut(var))
-            .ok_or_else(|| {
-                match self.base.and_then(|base| base.global.scope().get(var)) {
-                    Some(_) => eco_format!("cannot mutate a constant: {}", var).into(),
-                    _ => unknown_variable(var),
+            .expect("variable is defined")
+    }
+
+    /// Add a code and, if there is a similarly named variable, a fix to the
+    /// errors for an unknown variable.
+    fn unknown(
+        &self,
+        errors: EcoVec<SourceDiagnostic>,
+        var: &str,
+        span: Span,
+        math: bool,
+    ) -> EcoVec<SourceDiagnostic> {
+        let similar = closest(var, self.names(math));
+        errors
+            .into_iter()
+            .map(|error| {
+                let error = error.with_code("unknown-variable");
+                match similar {
+                    Some(similar) => error.with_suggestion(
+                        span,
+                        similar,
+                        eco_format!("replace with `{similar}`"),
+                    ),
+                    None => error,
                 }
-            })?
+            })
+            .collect()
+    }
+
+    /// The names of all variables that are visible from the top scope.
+    fn names(&self, math: bool) -> impl Iterator<Item = &str> {
+        let base = self.base.map(|base| if math { &base.math } else { &base.global });
+        std::iter::once(&self.top)
+            .chain(self.scopes.iter().rev())
+            .chain(base.map(|module| module.scope()))
+            .flat_map(|scope| scope.iter().map(|(name, _)| name.as_str()))
     }
 }
 
 /// The error message when a variable is not found.
 #[cold]
-fn unknown_variable(var: &str) -> HintedString {
+fn unknown_variable<'a>(
+    var: &str,
+    names: impl IntoIterator<Item = &'a str>,
+) -> HintedString {
     let mut res = HintedString {
         message: eco_format!("unknown variable: {}", var),
         hints: vec![],
@@ -94,6 +159,10 @@ fn unknown_variable(var: &str) -> HintedString {
         ));
     }
 
+    if let Some(similar) = closest(var, names) {
+        res.hints.push(eco_format!("did you mean `{similar}`?"));
+    }
+
     res
 }
 
@@ -404,17 +404,21 @@ cast! {
 
 impl FromValue for ShowableSelector {
     fn from_value(value: Value) -> StrResult<Self> {
-        fn validate(selector: &Selector) -> StrResult<()> {
+        fn validate(selector: &Selector, nested: bool) -> StrResult<()> {
             match selector {
                 Selector::Elem(_, _) => {}
                 Selector::Label(_) => {}
-                Selector::Regex(_) => {}
-                Selector::Or(_)
-                | Selector::And(_)
-                | Selector::Location(_)
-                | Selector::Can(_)
-                | Selector::Before { .. }
-                | Selector::After { .. } => {
+                Selector::Regex(_) if !nested => {}
+                Selector::Location(_) => {}
+                Selector::Or(list) | Selector::And(list) => {
+                    for selector in list {
+                        validate(selector, true)?;
+                    }
+                }
+                Selector::Regex(_) => {
+                    bail!("regex selectors cannot be combined in show rules")
+                }
+                Selector::Can(_) | Selector::Before { .. } | Selector::After { .. } => {
                     bail!("this selector cannot be used with show")
                 }
             }
@@ -426,7 +430,7 @@ impl FromValue for ShowableSelector {
         }
 
         let selector = Selector::from_value(value)?;
-        validate(&selector)?;
+        validate(&selector, false)?;
         Ok(Self(selector))
     }
 }
@@ -5,13 +5,15 @@ use std::ops::{Add, AddAssign, Deref, Range};
 
 use ecow::EcoString;
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::diag::{bail, At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, dict, func, repr, scope, ty, Array, Bytes, Dict, Func, IntoValue, Label, Repr,
-    Type, Value, Version,
+    cast, dict, func, repr, scope, ty, Array, Bytes, Cast, Dict, Func, IntoValue, Label,
+    Repr, Type, Value, Version,
 };
 use crate::layout::Align;
 use crate::syntax::{Span, Spanned};
@@ -596,6 +598,93 @@ impl Str {
         }
         s.into()
     }
+
+    /// Converts the string into a Unicode normal form.
+    ///
+    /// Strings that look the same can consist of different codepoints: An
+    /// accented letter can be a single precomposed codepoint or a letter
+    /// followed by a combining accent. Normalizing makes such strings compare
+    /// equal.
+    ///
+    /// ```example
+    /// #let decomposed = "e\u{0301}"
+    /// #(decomposed == "é") \
+    /// #(decomposed.normalize() == "é")
+    /// ```
+    #[func]
+    pub fn normalize(
+        &self,
+        /// The normal form to convert to.
+        #[named]
+        #[default(UnicodeNormalForm::Nfc)]
+        form: UnicodeNormalForm,
+    ) -> Str {
+        match form {
+            UnicodeNormalForm::Nfc => self.as_str().nfc().collect(),
+            UnicodeNormalForm::Nfd => self.as_str().nfd().collect(),
+            UnicodeNormalForm::Nfkc => self.as_str().nfkc().collect(),
+            UnicodeNormalForm::Nfkd => self.as_str().nfkd().collect(),
+        }
+    }
+
+    /// Converts ASCII letters, digits, punctuation, and spaces into their
+    /// full-width forms.
+    ///
+    /// ```example
+    /// #"Typst 2023!".to-fullwidth()
+    /// ```
+    #[func]
+    pub fn to_fullwidth(&self) -> Str {
+        self.chars()
+            .map(|c| match c {
+                ' ' => '\u{3000}',
+                '!'..='~' => char::from_u32(c as u32 + FULLWIDTH_OFFSET).unwrap(),
+                _ => c,
+            })
+            .collect()
+    }
+
+    /// Converts full-width letters, digits, punctuation, and spaces into their
+    /// ASCII forms.
+    ///
+    /// ```example
+    /// #"Ｔｙｐｓｔ　２０２３！".to-halfwidth()
+    /// ```
+    #[func]
+    pub fn to_halfwidth(&self) -> Str {
+        self.chars()
+            .map(|c| match c {
+                '\u{3000}' => ' ',
+                '！'..='～' => char::from_u32(c as u32 - FULLWIDTH_OFFSET).unwrap(),
+                _ => c,
+            })
+            .collect()
+    }
+
+    /// The number of columns the string takes up in a monospaced terminal.
+    ///
+    /// Wide characters like Chinese characters and full-width punctuation
+    /// take up two columns, combining marks none.
+    ///
+    /// ```example
+    /// #"Typst".display-width() \
+    /// #"排版".display-width()
+    /// ```
+    #[func]
+    pub fn display_width(
+        &self,
+        /// Whether characters with an ambiguous width, like `“`, take up two
+        /// columns, as they do in CJK contexts.
+        #[named]
+        #[default(false)]
+        cjk: bool,
+    ) -> usize {
+        if cjk {
+            self.as_str().width_cjk()
+        } else {
+            self.as_str().width()
+        }
+    }
 }
 
 impl Deref for Str {
@@ -940,6 +1029,23 @@ cast! {
     },
 }
 
+/// A Unicode normalization form.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum UnicodeNormalForm {
+    /// Canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+    /// Compatibility composition, which also replaces characters like `ﬁ` or
+    /// full-width letters with their plain counterparts.
+    Nfkc,
+    /// Compatibility decomposition.
+    Nfkd,
+}
+
+/// The distance between an ASCII character and its full-width form.
+const FULLWIDTH_OFFSET: u32 = 0xFEE0;
+
 /// A replacement for a matched [`Str`]
 pub enum Replacement {
     /// A string a match is replaced with.
@@ -5,24 +5,25 @@ use std::hash::{Hash, Hasher};
 use std::{iter, mem, ptr};
 
 use comemo::Prehashed;
-use ecow::{eco_vec, EcoString, EcoVec};
+use ecow::{eco_format, eco_vec, EcoString, EcoVec};
 use once_cell::sync::Lazy;
 use smallvec::SmallVec;
 
-use crate::diag::{SourceResult, Trace, Tracepoint};
+use crate::diag::{SourceResult, StrResult, Trace, Tracepoint};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, func, ty, Content, Element, Func, NativeElement, Repr, Selector, Show,
+    cast, elem, func, scope, ty, Content, Dict, Element, Func, NativeElement, Repr,
+    Selector, Show, Str, Value,
 };
 use crate::syntax::Span;
 use crate::text::{FontFamily, FontList, TextElem};
 
 /// Provides access to active styles.
 ///
-/// The styles are currently opaque and only useful in combination with the
-/// [`measure`]($measure) function. See its documentation for more details. In
-/// the future, the provided styles might also be directly accessed to look up
-/// styles defined by [set rules]($styling/#set-rules).
+/// The styles are mostly useful in combination with the [`measure`]($measure)
+/// function. See its documentation for more details. Properties defined by
+/// [set rules]($styling/#set-rules) can also be looked up directly with the
+/// [`get`]($styles.get) method.
 ///
 /// ```example
 /// #let thing(body) = style(styles => {
@@ -62,7 +63,7 @@ impl Show for StyleElem {
 }
 
 /// A list of style properties.
-#[ty]
+#[ty(scope)]
 #[derive(Default, PartialEq, Clone, Hash)]
 pub struct Styles(EcoVec<Prehashed<Style>>);
 
@@ -119,6 +120,26 @@ impl Styles {
         self
     }
 
+    /// Record the arguments of the set rule the contained properties stem
+    /// from, so that they can be looked up again with [`get`](Self::get).
+    pub fn sourced(mut self, args: &Dict) -> Self {
+        if args.is_empty() {
+            return self;
+        }
+
+        for entry in self.0.make_mut() {
+            entry.update(|entry| {
+                if let Style::Property(property) = entry {
+                    let name = property.elem.field_name(property.id);
+                    if let Some(value) = name.and_then(|name| args.get(name).ok()) {
+                        property.source = Some(value.clone());
+                    }
+                }
+            });
+        }
+        self
+    }
+
     /// Returns `Some(_)` with an optional span if this list contains
     /// styles for the given element.
     pub fn interruption<T: NativeElement>(&self) -> Option<Option<Span>> {
@@ -140,6 +161,53 @@ impl Styles {
     }
 }
 
+#[scope]
+impl Styles {
+    /// Looks up the value of an element's property in the style chain.
+    ///
+    /// This returns the argument of the innermost
+    /// [set rule]($styling/#set-rules) in the chain that sets the property,
+    /// exactly as it was passed to that rule. It is not combined with outer
+    /// set rules or resolved: For properties that fold, like the text size,
+    /// this is only the innermost part, and relative lengths stay relative.
+    /// If no set rule in the chain sets the property, the `default` is
+    /// returned, which itself defaults to `{none}`. The element's own default
+    /// value is never returned.
+    ///
+    /// ```example
+    /// #set text(fill: blue)
+    /// #style(styles => {
+    ///   let fill = styles.get(text, "fill")
+    ///   [The text is #repr(fill).]
+    /// })
+    /// ```
+    #[func]
+    pub fn get(
+        &self,
+        /// The element the property belongs to.
+        element: Element,
+        /// The name of the property.
+        field: Str,
+        /// A default value to return if no set rule defines the property.
+        #[named]
+        default: Option<Value>,
+    ) -> StrResult<Value> {
+        let id = element.field_id(&field).ok_or_else(|| {
+            eco_format!("element `{}` does not have field `{}`", element.name(), field)
+        })?;
+
+        Ok(self
+            .0
+            .iter()
+            .rev()
+            .filter_map(|entry| entry.property())
+            .find(|property| property.is(element, id))
+            .and_then(|property| property.source.clone())
+            .or(default)
+            .unwrap_or_default())
+    }
+}
+
 impl From<Style> for Styles {
     fn from(entry: Style) -> Self {
         Self(eco_vec![Prehashed::new(entry)])
@@ -218,6 +286,8 @@ pub struct Property {
     value: Block,
     /// The span of the set rule the property stems from.
     span: Option<Span>,
+    /// The argument of the set rule the property stems from, as written.
+    source: Option<Value>,
 }
 
 impl Property {
@@ -226,7 +296,13 @@ impl Property {
     where
         T: Debug + Clone + Hash + Send + Sync + 'static,
     {
-        Self { elem, id, value: Block::new(value), span: None }
+        Self {
+            elem,
+            id,
+            value: Block::new(value),
+            span: None,
+            source: None,
+        }
     }
 
     /// Whether this property is the given one.
@@ -1,9 +1,9 @@
 //! System-related things.
 
-use crate::foundations::{Module, Scope, Version};
+use crate::foundations::{Cast, Dict, Module, Scope, Version};
 
 /// A module with system-related things.
-pub fn module() -> Module {
+pub fn module(inputs: Dict, target: Target) -> Module {
     let mut scope = Scope::deduplicating();
     scope.define(
         "version",
@@ -13,5 +13,27 @@ pub fn module() -> Module {
             env!("CARGO_PKG_VERSION_PATCH").parse::<u32>().unwrap(),
         ]),
     );
+    scope.define("inputs", inputs);
+    scope.define("target", target);
     Module::new("sys", scope)
 }
+
+/// The format that a document is compiled for, visible through `sys.target`.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Target {
+    /// A PDF file.
+    #[default]
+    Pdf,
+    /// Raster images.
+    Png,
+    /// Vector images.
+    Svg,
+    /// A web page.
+    Html,
+    /// An e-book.
+    Epub,
+    /// A Markdown file.
+    Md,
+    /// A plain text file.
+    Txt,
+}
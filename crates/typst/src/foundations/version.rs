@@ -2,11 +2,12 @@ use std::cmp::Ordering;
 use std::fmt::{self, Display, Formatter, Write};
 use std::hash::Hash;
 use std::iter::repeat;
+use std::str::FromStr;
 
 use ecow::{eco_format, EcoString, EcoVec};
 
 use crate::diag::{bail, StrResult};
-use crate::foundations::{cast, func, repr, scope, ty, Repr};
+use crate::foundations::{cast, func, repr, scope, ty, Repr, Str};
 
 /// A version with an arbitrary number of components.
 ///
@@ -39,11 +40,11 @@ impl Version {
     /// Always non-negative. Returns `0` if the version isn't specified to the
     /// necessary length.
     pub fn component(&self, name: &str) -> StrResult<i64> {
-        self.0
+        let index = Self::COMPONENTS
             .iter()
-            .zip(Self::COMPONENTS)
-            .find_map(|(&i, s)| (s == name).then_some(i as i64))
-            .ok_or_else(|| "unknown version component".into())
+            .position(|&s| s == name)
+            .ok_or("unknown version component")?;
+        Ok(self.0.get(index).copied().unwrap_or_default() as i64)
     }
 
     /// Push a component to the end of this version.
@@ -61,18 +62,21 @@ impl Version {
 impl Version {
     /// Creates a new version.
     ///
-    /// It can have any number of components (even zero).
+    /// It can have any number of components (even zero). Strings like
+    /// `{"0.12.0"}` are parsed into their components.
     ///
     /// ```example
     /// #version() \
     /// #version(1) \
     /// #version(1, 2, 3, 4) \
     /// #version((1, 2, 3, 4)) \
-    /// #version((1, 2), 3)
+    /// #version((1, 2), 3) \
+    /// #version("0.12.0")
     /// ```
     #[func(constructor)]
     pub fn construct(
-        /// The components of the version (array arguments are flattened)
+        /// The components of the version (array arguments are flattened and
+        /// strings are parsed)
         #[variadic]
         components: Vec<VersionComponents>,
     ) -> Version {
@@ -118,6 +122,23 @@ impl Version {
     }
 }
 
+impl FromStr for Version {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            bail!("version must not be empty");
+        }
+
+        s.split('.')
+            .map(|part| {
+                part.parse::<u32>()
+                    .map_err(|_| eco_format!("`{s}` is not a valid version"))
+            })
+            .collect()
+    }
+}
+
 impl FromIterator<u32> for Version {
     fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
         Self(EcoVec::from_iter(iter))
@@ -196,5 +217,6 @@ pub enum VersionComponents {
 cast! {
     VersionComponents,
     v: u32 => Self::Single(v),
-    v: Vec<u32> => Self::Multiple(v)
+    v: Vec<u32> => Self::Multiple(v),
+    v: Str => Self::Multiple(v.parse::<Version>()?.into_iter().collect()),
 }
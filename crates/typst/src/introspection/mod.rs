@@ -29,8 +29,9 @@ use crate::foundations::{
     cast, category, elem, ty, Behave, Behaviour, Category, Content, Repr, Scope,
     Unlabellable,
 };
-use crate::layout::PdfPageLabel;
+use crate::layout::{PdfPageLabel, PrintSetup};
 use crate::model::{Destination, Numbering};
+use crate::pdf::FormField;
 
 /// Interactions between document parts.
 ///
@@ -83,6 +84,12 @@ pub enum Meta {
     PageNumbering(Option<Numbering>),
     /// A PDF page label of the current page.
     PdfPageLabel(PdfPageLabel),
+    /// The print production settings of the current page.
+    PrintSetup(PrintSetup),
+    /// A fillable form field covering the area this metadata is attached to.
+    Field(FormField),
+    /// Marks the frame that contains this metadata as decorative.
+    Artifact,
     /// Indicates that content should be hidden. This variant doesn't appear
     /// in the final frames as it is removed alongside the content that should
     /// be hidden.
@@ -100,6 +107,9 @@ impl Debug for Meta {
             Self::Elem(content) => write!(f, "Elem({:?})", content.func()),
             Self::PageNumbering(value) => write!(f, "PageNumbering({value:?})"),
             Self::PdfPageLabel(label) => write!(f, "PdfPageLabel({label:?})"),
+            Self::PrintSetup(setup) => write!(f, "PrintSetup({setup:?})"),
+            Self::Field(field) => write!(f, "Field({field:?})"),
+            Self::Artifact => f.pad("Artifact"),
             Self::Hide => f.pad("Hide"),
         }
     }
@@ -1,12 +1,17 @@
+use ecow::eco_format;
+use unicode_segmentation::UnicodeSegmentation;
+
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, AutoValue, Content, NativeElement, Resolve, Smart, StyleChain, Value,
+    cast, elem, AutoValue, Cast, Content, NativeElement, Resolve, Smart, Str, StyleChain,
+    Value,
 };
 use crate::layout::{
     Abs, Axes, Corners, Em, Fr, Fragment, FrameKind, Layout, Length, Ratio, Regions, Rel,
-    Sides, Size, Spacing, VElem,
+    Sides, Size, Spacing, VAlign, VElem,
 };
+use crate::text::{families, variant, TextElem, TextSize};
 use crate::util::Numeric;
 use crate::visualize::{clip_rect, Paint, Stroke};
 
@@ -45,13 +50,34 @@ pub struct BoxElem {
     /// The height of the box.
     pub height: Smart<Rel<Length>>,
 
-    /// An amount to shift the box's baseline by.
+    /// An amount to shift the box's baseline by or how to align the box with
+    /// the surrounding text.
     ///
     /// ```example
     /// Image: #box(baseline: 40%, image("tiger.jpg", width: 2cm)).
     /// ```
-    #[resolve]
-    pub baseline: Rel<Length>,
+    ///
+    /// Instead of a shift, this can also be a vertical alignment:
+    /// - `{top}` aligns the top of the box with the text's
+    ///   [top edge]($text.top-edge).
+    /// - `{horizon}` centers the box between the text's top and
+    ///   [bottom edge]($text.bottom-edge).
+    /// - `{bottom}` aligns the bottom of the box with the text's bottom edge.
+    ///
+    /// This keeps small images and icons in a line of text from jumping.
+    ///
+    /// ```example
+    /// #let icon = box.with(
+    ///   width: 8pt,
+    ///   height: 8pt,
+    ///   fill: eastern,
+    /// )
+    ///
+    /// Top #icon(baseline: top) \
+    /// Horizon #icon(baseline: horizon) \
+    /// Bottom #icon(baseline: bottom)
+    /// ```
+    pub baseline: Baseline,
 
     /// The box's background color. See the
     /// [rectangle's documentation]($rect.fill) for more details.
@@ -104,6 +130,43 @@ pub struct BoxElem {
     #[default(false)]
     pub clip: bool,
 
+    /// How to make content fit that is too large for the box's fixed width or
+    /// height.
+    ///
+    /// - `{none}`: The content overflows the box. Combine this with
+    ///   [`clip`]($box.clip) to cut it off at the box's edges.
+    /// - `{"ellipsis"}`: The content is shortened and ends with the
+    ///   [`ellipsis`]($box.ellipsis). Only the content's plain text is kept,
+    ///   so its formatting should be applied to the box instead. If the
+    ///   box's height is `{auto}`, the text is kept on a single line.
+    /// - `{"shrink"}`: The text is made smaller, down to half of its size.
+    ///
+    /// This is useful for form fields and table cells of a fixed size whose
+    /// content is not known in advance.
+    ///
+    /// ```example
+    /// #let field = box.with(
+    ///   width: 80pt,
+    ///   height: 1.4em,
+    ///   inset: (x: 2pt),
+    ///   stroke: 0.5pt,
+    /// )
+    ///
+    /// 姓名：#field(overflow: "shrink")[欧阳慕容司马]
+    ///
+    /// 地址：#field(overflow: "ellipsis")[北京市海淀区中关村大街一号]
+    ///
+    /// #box(width: 80pt, overflow: "ellipsis", ellipsis: "。。。")[
+    ///   北京市海淀区中关村大街一号
+    /// ]
+    /// ```
+    pub overflow: Option<Overflow>,
+
+    /// The text that marks shortened content if the
+    /// [`overflow`]($box.overflow) is `{"ellipsis"}`.
+    #[default("…".into())]
+    pub ellipsis: Str,
+
     /// The contents of the box.
     #[positional]
     pub body: Option<Content>,
@@ -132,24 +195,52 @@ impl Layout for BoxElem {
             .unwrap_or(regions.base());
 
         // Apply inset.
-        let mut body = self.body(styles).unwrap_or_default();
         let inset = self.inset(styles);
-        if inset.iter().any(|v| !v.is_zero()) {
-            body = body.padded(inset.map(|side| side.map(Length::from)));
+        let pad = |body: Content| {
+            if inset.iter().any(|v| !v.is_zero()) {
+                body.padded(inset.map(|side| side.map(Length::from)))
+            } else {
+                body
+            }
+        };
+
+        // Make overflowing content fit.
+        let mut body = self.body(styles).unwrap_or_default();
+        if let Some(overflow) = self.overflow(styles) {
+            let fit = Fit { styles, size, fixed: expand, pad: &pad };
+            body = match overflow {
+                Overflow::Ellipsis => {
+                    fit.truncate(engine, body, &self.ellipsis(styles))?
+                }
+                Overflow::Shrink => fit.shrink(engine, body)?,
+            };
         }
 
         // Select the appropriate base and expansion for the child depending
         // on whether it is automatically or relatively sized.
         let pod = Regions::one(size, expand);
-        let mut frame = body.layout(engine, styles, pod)?.into_frame();
+        let mut frame = pad(body).layout(engine, styles, pod)?.into_frame();
 
         // Enforce correct size.
         *frame.size_mut() = expand.select(size, frame.size());
 
-        // Apply baseline shift.
-        let shift = self.baseline(styles).relative_to(frame.height());
-        if !shift.is_zero() {
-            frame.set_baseline(frame.baseline() - shift);
+        // Apply baseline shift or alignment.
+        match self.baseline(styles) {
+            Baseline::Shift(shift) => {
+                let shift = shift.resolve(styles).relative_to(frame.height());
+                if !shift.is_zero() {
+                    frame.set_baseline(frame.baseline() - shift);
+                }
+            }
+            Baseline::Align(align) => {
+                let (top, bottom) = text_edges(engine, styles);
+                let height = frame.height();
+                frame.set_baseline(match align {
+                    VAlign::Top => top,
+                    VAlign::Horizon => (height + top - bottom) / 2.0,
+                    VAlign::Bottom => height - bottom,
+                });
+            }
         }
 
         // Prepare fill and stroke.
@@ -179,6 +270,148 @@ impl Layout for BoxElem {
     }
 }
 
+/// How to make content fit into a box.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Overflow {
+    /// Shorten the content and end it with an ellipsis.
+    Ellipsis,
+    /// Make the text smaller.
+    Shrink,
+}
+
+/// Makes content fit into a box's fixed size.
+struct Fit<'a, F> {
+    styles: StyleChain<'a>,
+    /// The size of the box.
+    size: Size,
+    /// Which axes of the box have a fixed size.
+    fixed: Axes<bool>,
+    /// Applies the box's inset.
+    pad: &'a F,
+}
+
+impl<F: Fn(Content) -> Content> Fit<'_, F> {
+    /// Shorten the body's plain text until it fits with the ellipsis.
+    fn truncate(
+        &self,
+        engine: &mut Engine,
+        body: Content,
+        ellipsis: &str,
+    ) -> SourceResult<Content> {
+        let single = !self.fixed.y;
+        if self.fits(engine, &body, single)? {
+            return Ok(body);
+        }
+
+        // Find the longest prefix that still fits.
+        let text = body.plain_text();
+        let graphemes: Vec<&str> = text.trim().graphemes(true).collect();
+        let shortened = |n: usize| {
+            let prefix = graphemes[..n].concat();
+            TextElem::packed(eco_format!("{}{ellipsis}", prefix.trim_end()))
+        };
+
+        let (mut lo, mut hi) = (0, graphemes.len());
+        while lo < hi {
+            let mid = (lo + hi + 1) / 2;
+            if self.fits(engine, &shortened(mid), single)? {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        Ok(shortened(lo))
+    }
+
+    /// Make the body's text smaller until it fits.
+    fn shrink(&self, engine: &mut Engine, body: Content) -> SourceResult<Content> {
+        const MIN_SCALE: f64 = 0.5;
+
+        if self.fits(engine, &body, false)? {
+            return Ok(body);
+        }
+
+        let scaled = |scale: f64| {
+            body.clone()
+                .styled(TextElem::set_size(TextSize(Em::new(scale).into())))
+        };
+
+        // Search for the largest scale that fits.
+        let (mut lo, mut hi) = (MIN_SCALE, 1.0);
+        while hi - lo > 0.01 {
+            let mid = (lo + hi) / 2.0;
+            if self.fits(engine, &scaled(mid), false)? {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+
+        Ok(scaled(lo))
+    }
+
+    /// Whether the body fits into the fixed axes of the box. If `single` is
+    /// true, the body is measured without line breaks.
+    fn fits(
+        &self,
+        engine: &mut Engine,
+        body: &Content,
+        single: bool,
+    ) -> SourceResult<bool> {
+        let width = if single { Abs::inf() } else { self.size.x };
+        let pod = Regions::one(Size::new(width, Abs::inf()), Axes::splat(false));
+        let body = (self.pad)(body.clone());
+        let frame = body.layout(engine, self.styles, pod)?.into_frame();
+        Ok((!self.fixed.x || frame.width().fits(self.size.x))
+            && (!self.fixed.y || frame.height().fits(self.size.y)))
+    }
+}
+
+/// How to position a box relative to the baseline of the surrounding text.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub enum Baseline {
+    /// Shift the box's baseline by an amount.
+    Shift(Rel<Length>),
+    /// Align the box with the edges of the surrounding text.
+    Align(VAlign),
+}
+
+impl Default for Baseline {
+    fn default() -> Self {
+        Self::Shift(Rel::zero())
+    }
+}
+
+cast! {
+    Baseline,
+    self => match self {
+        Self::Shift(shift) => shift.into_value(),
+        Self::Align(align) => align.into_value(),
+    },
+    v: Rel<Length> => Self::Shift(v),
+    v: VAlign => Self::Align(v),
+}
+
+/// The top and bottom edges of the surrounding text, measured upwards and
+/// downwards from the baseline.
+fn text_edges(engine: &Engine, styles: StyleChain) -> (Abs, Abs) {
+    let world = engine.world;
+    let variant = variant(styles);
+    let Some(font) = families(styles).find_map(|family| {
+        let id = world.book().select(family, variant)?;
+        world.font(id)
+    }) else {
+        return (Abs::zero(), Abs::zero());
+    };
+
+    let size = TextElem::size_in(styles);
+    let overrides = TextElem::metrics_in(styles);
+    let top = TextElem::top_edge_in(styles).resolve(size, &font, &overrides, None);
+    let bottom = -TextElem::bottom_edge_in(styles).resolve(size, &font, &overrides, None);
+    (top, bottom)
+}
+
 /// A block-level container.
 ///
 /// Such a container can be used to separate content, size it, and give it a
@@ -9,7 +9,7 @@ use crate::layout::{
     Frame, FrameItem, Layout, PlaceElem, Point, Regions, Rel, Size, Spacing, VAlign,
     VElem,
 };
-use crate::model::{FootnoteElem, FootnoteEntry, ParElem};
+use crate::model::{FootnoteElem, FootnoteEntry, FootnotePlacement, ParElem};
 use crate::util::Numeric;
 use crate::visualize::{
     CircleElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem, RectElem,
@@ -723,6 +723,10 @@ fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
                 if !notes.iter().any(|note| note.location() == content.location()) =>
             {
                 let Some(footnote) = content.to::<FootnoteElem>() else { continue };
+                // Endnotes are listed elsewhere.
+                if footnote.placement(StyleChain::default()) == FootnotePlacement::End {
+                    continue;
+                }
                 notes.push(footnote.clone());
             }
             _ => {}
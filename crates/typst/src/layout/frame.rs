@@ -9,7 +9,7 @@ use ecow::{eco_format, EcoString};
 use crate::foundations::{cast, dict, Dict, Repr, StyleChain, Value};
 use crate::introspection::{Meta, MetaElem};
 use crate::layout::{
-    Abs, Axes, Corners, FixedAlign, Length, Point, Rel, Sides, Size, Transform,
+    Abs, Axes, Corners, FixedAlign, Length, Point, Ratio, Rel, Sides, Size, Transform,
 };
 use crate::syntax::Span;
 use crate::text::TextItem;
@@ -137,6 +137,107 @@ impl Frame {
     pub fn items(&self) -> std::slice::Iter<'_, (Point, FrameItem)> {
         self.items.iter()
     }
+
+    /// Find the positions of all items that stem from the given span, relative
+    /// to the top-left of the frame.
+    ///
+    /// For text, this is the start of the first matching glyph of each run on
+    /// its baseline. For shapes and images, it is their top-left corner.
+    pub fn positions_of(&self, span: Span) -> Vec<Point> {
+        let mut positions = vec![];
+        if span.is_detached() {
+            return positions;
+        }
+
+        for (pos, item) in &self.items {
+            match item {
+                FrameItem::Group(group) => {
+                    positions.extend(
+                        group
+                            .frame
+                            .positions_of(span)
+                            .into_iter()
+                            .map(|point| point.transform(group.transform) + *pos),
+                    );
+                }
+                FrameItem::Text(text) => {
+                    let mut x = pos.x;
+                    for glyph in &text.glyphs {
+                        if glyph.span.0 == span {
+                            positions.push(Point::new(x, pos.y));
+                            break;
+                        }
+                        x += glyph.x_advance.at(text.size);
+                    }
+                }
+                FrameItem::Shape(_, s) | FrameItem::Image(_, _, _, s) if *s == span => {
+                    positions.push(*pos);
+                }
+                _ => {}
+            }
+        }
+
+        positions
+    }
+
+    /// Find the source span of the topmost item at the given point, relative
+    /// to the top-left of the frame.
+    ///
+    /// Groups are searched with their transformation applied and only within
+    /// their clip path. A glyph is hit within its advance and one font size
+    /// above its baseline. Items with detached spans are skipped, so that a
+    /// click on text with a highlight behind it still finds the text.
+    pub fn hit(&self, point: Point) -> Option<Span> {
+        let within = |size: Size, point: Point| {
+            point.x >= Abs::zero()
+                && point.y >= Abs::zero()
+                && point.x <= size.x
+                && point.y <= size.y
+        };
+
+        for (pos, item) in self.items.iter().rev() {
+            let point = point - *pos;
+            let span = match item {
+                FrameItem::Group(group) => {
+                    let Some(inverse) = group.transform.invert() else { continue };
+                    let point = point.transform(inverse);
+                    if let Some(clip_path) = &group.clip_path {
+                        if !within(clip_path.bbox_size(), point) {
+                            continue;
+                        }
+                    }
+                    group.frame.hit(point)
+                }
+                FrameItem::Text(text) => {
+                    let mut x = Abs::zero();
+                    text.glyphs.iter().find_map(|glyph| {
+                        let width = glyph.x_advance.at(text.size);
+                        let offset = Point::new(x, -text.size);
+                        x += width;
+                        within(Size::new(width, text.size), point - offset)
+                            .then_some(glyph.span.0)
+                    })
+                }
+                FrameItem::Shape(shape, span) => match &shape.geometry {
+                    Geometry::Rect(size) => within(*size, point).then_some(*span),
+                    Geometry::Path(path) => {
+                        within(path.bbox_size(), point).then_some(*span)
+                    }
+                    Geometry::Line(_) => None,
+                },
+                FrameItem::Image(_, size, _, span) => {
+                    within(*size, point).then_some(*span)
+                }
+                FrameItem::Meta(..) => None,
+            };
+
+            if let Some(span) = span.filter(|span| !span.is_detached()) {
+                return Some(span);
+            }
+        }
+
+        None
+    }
 }
 
 /// Insert items and subframes.
@@ -204,8 +305,13 @@ impl Frame {
 
     /// Whether the given frame should be inlined.
     fn should_inline(&self, frame: &Frame) -> bool {
-        // We do not inline big frames and hard frames.
-        frame.kind().is_soft() && (self.items.is_empty() || frame.items.len() <= 5)
+        // We do not inline big frames and hard frames. Neither do we inline
+        // decorative frames, so that the exporter can mark them as a whole.
+        frame.kind().is_soft()
+            && (self.items.is_empty() || frame.items.len() <= 5)
+            && !frame
+                .items()
+                .any(|(_, item)| matches!(item, FrameItem::Meta(Meta::Artifact, _)))
     }
 
     /// Inline a frame at the given layer.
@@ -469,8 +575,8 @@ pub enum FrameItem {
     Text(TextItem),
     /// A geometric shape with optional fill and stroke.
     Shape(Shape, Span),
-    /// An image and its size.
-    Image(Image, Size, Span),
+    /// An image, its size, and its opacity.
+    Image(Image, Size, Ratio, Span),
     /// Meta information and the region it applies to.
     Meta(Meta, Size),
 }
@@ -481,7 +587,7 @@ impl Debug for FrameItem {
             Self::Group(group) => group.fmt(f),
             Self::Text(text) => write!(f, "{text:?}"),
             Self::Shape(shape, _) => write!(f, "{shape:?}"),
-            Self::Image(image, _, _) => write!(f, "{image:?}"),
+            Self::Image(image, _, _, _) => write!(f, "{image:?}"),
             Self::Meta(meta, _) => write!(f, "{meta:?}"),
         }
     }
@@ -585,3 +691,51 @@ pub enum PdfPageLabelStyle {
     /// `AA` to `ZZ` and so on for the next).
     UpperAlpha,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::Ratio;
+    use crate::syntax::Source;
+
+    #[test]
+    fn test_frame_hit_transformed_group() {
+        let source = Source::detached("Hello");
+        let span = source.root().span();
+        let size = Size::splat(Abs::pt(10.0));
+
+        let mut inner = Frame::soft(size);
+        let shape = Geometry::Rect(size).filled(Color::BLACK.into());
+        inner.push(Point::zero(), FrameItem::Shape(shape, span));
+
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::scale(Ratio::new(2.0), Ratio::new(2.0));
+
+        let mut frame = Frame::soft(Size::splat(Abs::pt(100.0)));
+        frame.push(Point::splat(Abs::pt(50.0)), FrameItem::Group(group));
+
+        assert_eq!(frame.hit(Point::splat(Abs::pt(65.0))), Some(span));
+        assert_eq!(frame.hit(Point::splat(Abs::pt(75.0))), None);
+        assert_eq!(frame.hit(Point::splat(Abs::pt(45.0))), None);
+    }
+
+    #[test]
+    fn test_frame_positions_of_transformed_group() {
+        let source = Source::detached("Hello");
+        let span = source.root().span();
+        let size = Size::splat(Abs::pt(10.0));
+
+        let mut inner = Frame::soft(size);
+        let shape = Geometry::Rect(size).filled(Color::BLACK.into());
+        inner.push(Point::splat(Abs::pt(5.0)), FrameItem::Shape(shape, span));
+
+        let mut group = GroupItem::new(inner);
+        group.transform = Transform::scale(Ratio::new(2.0), Ratio::new(2.0));
+
+        let mut frame = Frame::soft(Size::splat(Abs::pt(100.0)));
+        frame.push(Point::splat(Abs::pt(50.0)), FrameItem::Group(group));
+
+        assert_eq!(frame.positions_of(span), vec![Point::splat(Abs::pt(60.0))]);
+        assert_eq!(frame.positions_of(Span::detached()), vec![]);
+    }
+}
@@ -118,6 +118,30 @@ pub struct GridElem {
     #[borrowed]
     pub row_gutter: TrackSizings,
 
+    /// The number of rows at the top of the grid that form its header.
+    ///
+    /// When the grid breaks across multiple pages or columns, its header rows
+    /// are repeated at the top of each of them.
+    ///
+    /// ```example
+    /// #set page(height: 120pt)
+    /// #grid(
+    ///   columns: 2,
+    ///   gutter: 6pt,
+    ///   header-rows: 1,
+    ///   [*Name*], [*Value*],
+    ///   ..range(12).map(str),
+    /// )
+    /// ```
+    pub header_rows: usize,
+
+    /// The number of rows at the bottom of the grid that form its footer.
+    ///
+    /// When the grid breaks across multiple pages or columns, its footer rows
+    /// are repeated at the bottom of each of them. Repeated footer rows never
+    /// break and fractional footer rows are only laid out at the very end.
+    pub footer_rows: usize,
+
     /// The contents of the grid cells.
     ///
     /// The cells are populated in row-major order.
@@ -146,7 +170,9 @@ impl Layout for GridElem {
             regions,
             styles,
             self.span(),
-        );
+        )
+        .with_header(self.header_rows(styles))
+        .with_footer(self.footer_rows(styles));
 
         // Measure the columns and layout the grid row-by-row.
         Ok(layouter.layout(engine)?.fragment)
@@ -193,6 +219,17 @@ pub struct GridLayouter<'a> {
     initial: Size,
     /// Frames for finished regions.
     finished: Vec<Frame>,
+    /// The number of rows, including gutter rows, that form the header.
+    header: usize,
+    /// Whether the header is complete and should be repeated in new regions.
+    repeat_header: bool,
+    /// The index of the first row, including gutter rows, of the footer.
+    footer: usize,
+    /// The prepared footer rows that end each region until the footer itself
+    /// is reached.
+    footer_frames: Vec<(Frame, usize)>,
+    /// The combined height of `footer_frames`.
+    footer_height: Abs,
     /// The span of the grid element.
     span: Span,
 }
@@ -307,15 +344,49 @@ impl<'a> GridLayouter<'a> {
             lrows: vec![],
             initial: regions.size,
             finished: vec![],
+            header: 0,
+            repeat_header: false,
+            footer: usize::MAX,
+            footer_frames: vec![],
+            footer_height: Abs::zero(),
             span,
         }
     }
 
+    /// Repeat the first `rows` content rows at the top of each region.
+    pub fn with_header(mut self, rows: usize) -> Self {
+        let rows = if self.has_gutter { 2 * rows } else { rows };
+        self.header = rows.min(self.rows.len());
+        self
+    }
+
+    /// Repeat the last `rows` content rows at the bottom of each region.
+    ///
+    /// The footer never overlaps with a header set before.
+    pub fn with_footer(mut self, rows: usize) -> Self {
+        let rows = if self.has_gutter { 2 * rows } else { rows };
+        self.footer = self.rows.len() - rows.min(self.rows.len() - self.header);
+        self
+    }
+
     /// Determines the columns sizes and then layouts the grid row-by-row.
     pub fn layout(mut self, engine: &mut Engine) -> SourceResult<GridLayout> {
         self.measure_columns(engine)?;
+        self.prepare_footer(engine)?;
 
         for y in 0..self.rows.len() {
+            // From now on, new regions start with the header.
+            if self.header > 0 && y == self.header {
+                self.repeat_header = true;
+            }
+
+            // The footer itself is reached, so it no longer needs its space
+            // reserved.
+            if y == self.footer && !self.footer_frames.is_empty() {
+                self.footer_frames.clear();
+                self.regions.size.y += self.footer_height;
+            }
+
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
             if self.regions.is_full() && (!self.has_gutter || y % 2 == 0) {
@@ -329,6 +400,7 @@ impl<'a> GridLayouter<'a> {
             }
         }
 
+        self.repeat_header = false;
         self.finish_region(engine)?;
 
         Ok(GridLayout {
@@ -669,6 +741,22 @@ impl<'a> GridLayouter<'a> {
 
     /// Finish rows for one region.
     fn finish_region(&mut self, engine: &mut Engine) -> SourceResult<()> {
+        // Don't leave the header alone at the end of a region. Instead, move
+        // it to the next region together with the first body row.
+        let orphaned = self.repeat_header
+            && !self.regions.in_last()
+            && !self.lrows.is_empty()
+            && self.lrows.iter().all(|row| match row {
+                Row::Frame(_, y) | Row::Fr(_, y) => *y < self.header,
+            });
+
+        let carried = if orphaned { std::mem::take(&mut self.lrows) } else { vec![] };
+        if !orphaned {
+            for (frame, y) in &self.footer_frames {
+                self.lrows.push(Row::Frame(frame.clone(), *y));
+            }
+        }
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -712,10 +800,69 @@ impl<'a> GridLayouter<'a> {
         self.rrows.push(rrows);
         self.regions.next();
         self.initial = self.regions.size;
+        if !self.footer_frames.is_empty() {
+            self.regions.size.y -= self.footer_height;
+        }
+
+        if orphaned {
+            for row in carried {
+                match row {
+                    Row::Frame(frame, y) => self.push_row(frame, y),
+                    row => self.lrows.push(row),
+                }
+            }
+        } else if self.repeat_header {
+            for y in 0..self.header {
+                match self.layout_repeated_row(engine, y)? {
+                    Some(Row::Frame(frame, y)) => self.push_row(frame, y),
+                    Some(row) => self.lrows.push(row),
+                    None => {}
+                }
+            }
+        }
 
         Ok(())
     }
 
+    /// Layout the footer rows once and reserve space for them in the first
+    /// region.
+    fn prepare_footer(&mut self, engine: &mut Engine) -> SourceResult<()> {
+        for y in self.footer..self.rows.len() {
+            if let Some(Row::Frame(frame, y)) = self.layout_repeated_row(engine, y)? {
+                self.footer_height += frame.height();
+                self.footer_frames.push((frame, y));
+            }
+        }
+
+        if !self.footer_frames.is_empty() {
+            self.regions.size.y -= self.footer_height;
+        }
+
+        Ok(())
+    }
+
+    /// Layout a header or footer row that is repeated in each region.
+    ///
+    /// Repeated rows do not break across regions. An auto row that would break
+    /// is cut to the height of its first region.
+    fn layout_repeated_row(
+        &mut self,
+        engine: &mut Engine,
+        y: usize,
+    ) -> SourceResult<Option<Row>> {
+        let height = match self.rows[y] {
+            Sizing::Auto => match self.measure_auto_row(engine, y, false)? {
+                Some(resolved) if !resolved.is_empty() => resolved[0],
+                _ => return Ok(None),
+            },
+            Sizing::Rel(v) => v.resolve(self.styles).relative_to(self.regions.base().y),
+            Sizing::Fr(v) => return Ok(Some(Row::Fr(v, y))),
+        };
+
+        let frame = self.layout_single_row(engine, height, y)?;
+        Ok(Some(Row::Frame(frame, y)))
+    }
+
     /// Get the content of the cell in column `x` and row `y`.
     ///
     /// Returns `None` if it's a gutter cell.
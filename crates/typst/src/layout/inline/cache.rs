@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+use rustybuzz::{GlyphBuffer, GlyphInfo, GlyphPosition};
+
+use crate::memory::ShapingStats;
+
+/// The global cache for shaped runs of text.
+static CACHE: Lazy<Mutex<Cache>> = Lazy::new(|| Mutex::new(Cache::new(DEFAULT_LIMIT)));
+
+/// The maximum number of cached runs, unless configured otherwise.
+///
+/// A run is typically a word or a short phrase, so this is enough for the
+/// paragraphs of a few thousand pages while bounding the cache to some ten
+/// megabytes.
+const DEFAULT_LIMIT: usize = 100_000;
+
+/// A cache of shaped runs, keyed by a hash of their inputs.
+struct Cache {
+    /// The cached runs.
+    entries: HashMap<u128, Entry>,
+    /// How often a run was found in the cache.
+    hits: u64,
+    /// How often a run had to be shaped.
+    misses: u64,
+    /// Counts lookups, to determine which runs were used least recently.
+    clock: u64,
+    /// The maximum number of cached runs.
+    limit: usize,
+}
+
+impl Cache {
+    /// Create an empty cache with the given maximum number of runs.
+    fn new(limit: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            hits: 0,
+            misses: 0,
+            clock: 0,
+            limit,
+        }
+    }
+
+    /// Look up the run with the given key and mark it as used.
+    fn get(&mut self, key: u128) -> Option<Arc<ShapedRun>> {
+        self.clock += 1;
+        let Some(entry) = self.entries.get_mut(&key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        entry.age = 0;
+        entry.used = self.clock;
+        Some(entry.run.clone())
+    }
+
+    /// Cache a run that was just shaped, dropping the least recently used
+    /// runs if the limit is exceeded.
+    fn insert(&mut self, key: u128, run: Arc<ShapedRun>) {
+        self.entries.insert(key, Entry { run, age: 0, used: self.clock });
+        if self.entries.len() > self.limit {
+            self.shrink();
+        }
+    }
+
+    /// Change the maximum number of cached runs.
+    fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.entries.len() > limit {
+            self.shrink();
+        }
+    }
+
+    /// Drop the least recently used runs until at most three quarters of the
+    /// limit remain, so that the cache doesn't have to be shrunk on every
+    /// insertion once it is full.
+    fn shrink(&mut self) {
+        let keep = self.limit - self.limit / 4;
+        if keep == 0 {
+            self.entries.clear();
+            return;
+        }
+
+        let mut used: Vec<u64> = self.entries.values().map(|entry| entry.used).collect();
+        used.sort_unstable();
+        let threshold = used[used.len() - keep];
+        self.entries.retain(|_, entry| entry.used >= threshold);
+    }
+
+    /// Drop the runs that haven't been used in the last `max_age` calls to
+    /// this function.
+    fn evict(&mut self, max_age: usize) {
+        self.entries.retain(|_, entry| {
+            entry.age += 1;
+            entry.age <= max_age
+        });
+    }
+
+    /// Statistics of the cache since its creation.
+    fn stats(&self) -> ShapingStats {
+        ShapingStats {
+            hits: self.hits,
+            misses: self.misses,
+            entries: self.entries.len(),
+            bytes: self
+                .entries
+                .values()
+                .map(|entry| std::mem::size_of::<ShapedRun>() + entry.run.heap_size())
+                .sum(),
+        }
+    }
+}
+
+/// An entry in the shaping cache.
+struct Entry {
+    /// The shaped run.
+    run: Arc<ShapedRun>,
+    /// How many evictions have passed since the run was last used.
+    age: usize,
+    /// The lookup in which the run was last used.
+    used: u64,
+}
+
+/// The output of shaping a run of text with a single font.
+pub(super) struct ShapedRun {
+    /// The shaped glyphs.
+    pub infos: Vec<GlyphInfo>,
+    /// The positions of the shaped glyphs.
+    pub positions: Vec<GlyphPosition>,
+}
+
+impl ShapedRun {
+    /// The number of bytes the run occupies on the heap.
+    fn heap_size(&self) -> usize {
+        self.infos.len() * std::mem::size_of::<GlyphInfo>()
+            + self.positions.len() * std::mem::size_of::<GlyphPosition>()
+    }
+}
+
+impl From<GlyphBuffer> for ShapedRun {
+    fn from(buffer: GlyphBuffer) -> Self {
+        Self {
+            infos: buffer.glyph_infos().to_vec(),
+            positions: buffer.glyph_positions().to_vec(),
+        }
+    }
+}
+
+/// Look up the run with the given key or shape and cache it.
+///
+/// The key must hash everything that the shaping output depends on: The
+/// font, the features, the text itself, and the buffer's language, script,
+/// and direction.
+///
+/// Paragraphs are laid out again in every introspection pass and whenever
+/// their region changes, while the runs in them rarely change. Caching the
+/// runs on this level saves most of the reshaping, which is especially
+/// costly for CJK text.
+pub(super) fn shape_cached(
+    key: u128,
+    shape: impl FnOnce() -> ShapedRun,
+) -> Arc<ShapedRun> {
+    if let Some(run) = CACHE.lock().unwrap().get(key) {
+        return run;
+    }
+
+    // Shape without holding the lock, so that other threads can continue to
+    // use the cache in the meantime.
+    let run = Arc::new(shape());
+    CACHE.lock().unwrap().insert(key, run.clone());
+    run
+}
+
+/// Limit the number of cached runs, or lift the limit with `None`.
+///
+/// Once the limit is exceeded, the least recently used runs are dropped.
+pub(crate) fn set_shaping_cache_limit(max_entries: Option<usize>) {
+    CACHE.lock().unwrap().set_limit(max_entries.unwrap_or(usize::MAX));
+}
+
+/// Evict shaped runs that haven't been used in the last `max_age` calls to
+/// this function.
+///
+/// This is called alongside [`comemo::evict`] by [`crate::memory::evict`].
+pub(crate) fn evict_shaping_cache(max_age: usize) {
+    CACHE.lock().unwrap().evict(max_age);
+}
+
+/// Statistics of the shaping cache since the start of the process.
+pub(crate) fn shaping_stats() -> ShapingStats {
+    CACHE.lock().unwrap().stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run() -> ShapedRun {
+        ShapedRun { infos: vec![], positions: vec![] }
+    }
+
+    #[test]
+    fn test_shaping_cache_hits() {
+        let mut cache = Cache::new(10);
+        assert!(cache.get(1).is_none());
+        cache.insert(1, Arc::new(run()));
+        assert!(cache.get(1).is_some());
+        assert!(cache.get(1).is_some());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 1, 1));
+        assert_eq!(stats.bytes, std::mem::size_of::<ShapedRun>());
+        assert!((stats.hit_rate() - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_shaping_cache_eviction() {
+        let mut cache = Cache::new(usize::MAX);
+        cache.insert(1, Arc::new(run()));
+        cache.insert(2, Arc::new(run()));
+        cache.evict(1);
+        assert!(cache.get(2).is_some());
+        cache.evict(1);
+
+        // Only the run that was used in between survives.
+        assert!(cache.get(1).is_none());
+        assert!(cache.get(2).is_some());
+        cache.evict(0);
+        assert_eq!(cache.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_shaping_cache_limit() {
+        let mut cache = Cache::new(4);
+        for key in 0..4 {
+            assert!(cache.get(key).is_none());
+            cache.insert(key, Arc::new(run()));
+        }
+        assert!(cache.get(0).is_some());
+
+        // Exceeding the limit keeps the three most recently used runs.
+        assert!(cache.get(4).is_none());
+        cache.insert(4, Arc::new(run()));
+        assert_eq!(cache.stats().entries, 3);
+        assert!(cache.get(0).is_some());
+        assert!(cache.get(1).is_none());
+        assert!(cache.get(4).is_some());
+
+        cache.set_limit(1);
+        assert_eq!(cache.stats().entries, 1);
+        cache.set_limit(0);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
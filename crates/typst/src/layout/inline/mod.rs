@@ -1,6 +1,11 @@
+mod cache;
 mod linebreak;
 mod shaping;
 
+pub(crate) use self::cache::{
+    evict_shaping_cache, set_shaping_cache_limit, shaping_stats,
+};
+
 use comemo::{Prehashed, Tracked, TrackedMut};
 use unicode_bidi::{BidiInfo, Level as BidiLevel};
 use unicode_script::{Script, UnicodeScript};
@@ -10,7 +15,7 @@ use self::shaping::{
     is_gb_style, is_of_cj_script, shape, ShapedGlyph, ShapedText, BEGIN_PUNCT_PAT,
     END_PUNCT_PAT,
 };
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, warning, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{Content, Resolve, Smart, StyleChain};
@@ -23,7 +28,8 @@ use crate::math::EquationElem;
 use crate::model::{Linebreaks, ParElem};
 use crate::syntax::Span;
 use crate::text::{
-    Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem, TextElem,
+    Font, Lang, LinebreakElem, SmartQuoteElem, SmartQuoter, SmartQuotes, SpaceElem,
+    TextElem,
 };
 use crate::util::Numeric;
 use crate::World;
@@ -121,6 +127,8 @@ struct Preparation<'a> {
     align: FixedAlign,
     /// Whether to justify the paragraph.
     justify: bool,
+    /// Whether to also justify the last line and lines before forced breaks.
+    justify_last_line: bool,
     /// The paragraph's hanging indent.
     hang: Abs,
     /// Whether to add spacing between CJK and Latin characters.
@@ -384,6 +392,24 @@ impl<'a> Line<'a> {
         self.items().filter_map(Item::text).map(|s| s.shrinkability()).sum()
     }
 
+    /// How much can the line shrink in addition by compressing the gaps
+    /// between CJK characters.
+    fn cjk_shrinkability(&self) -> Abs {
+        self.items()
+            .filter_map(Item::text)
+            .map(|s| s.cjk_shrinkability(self.ends_with(s)))
+            .sum()
+    }
+
+    /// Whether the text item is the last one of the line. A CJK character at
+    /// its end has no gap after it that could shrink.
+    fn ends_with(&self, text: &ShapedText) -> bool {
+        self.items()
+            .filter_map(Item::text)
+            .last()
+            .is_some_and(|last| std::ptr::eq(last, text))
+    }
+
     /// The sum of fractions in the line.
     fn fr(&self) -> Fr {
         self.items()
@@ -439,7 +465,9 @@ fn collect<'a>(
         } else if let Some(elem) = child.to::<TextElem>() {
             let prev = full.len();
             if let Some(case) = TextElem::case_in(styles) {
-                full.push_str(&case.apply(elem.text()));
+                let continuing =
+                    full.chars().next_back().is_some_and(char::is_alphanumeric);
+                full.push_str(&case.apply_continuing(elem.text(), continuing));
             } else {
                 full.push_str(elem.text());
             }
@@ -528,6 +556,7 @@ fn collect<'a>(
 
 /// Prepare paragraph layout by shaping the whole paragraph and layouting all
 /// contained inline-level content.
+#[tracing::instrument(name = "shape paragraph", skip_all, fields(len = text.len()))]
 fn prepare<'a>(
     engine: &mut Engine,
     children: &'a [Prehashed<Content>],
@@ -592,6 +621,8 @@ fn prepare<'a>(
         cursor = end;
     }
 
+    record_coverage(engine, &items);
+
     let cjk_latin_spacing = TextElem::cjk_latin_spacing_in(styles).is_auto();
     if cjk_latin_spacing {
         add_cjk_latin_spacing(&mut items);
@@ -605,6 +636,7 @@ fn prepare<'a>(
         lang: shared_get(styles, children, TextElem::lang_in),
         align: AlignElem::alignment_in(styles).resolve(styles).x,
         justify: ParElem::justify_in(styles),
+        justify_last_line: ParElem::justify_last_line_in(styles),
         hang: ParElem::hanging_indent_in(styles),
         cjk_latin_spacing,
         fallback: TextElem::fallback_in(styles),
@@ -614,6 +646,59 @@ fn prepare<'a>(
     })
 }
 
+/// Record which characters needed font fallback and which have no glyph at
+/// all.
+///
+/// Missing glyphs are also reported as warnings, but only when font fallback
+/// is enabled: Otherwise, they are expected and other fonts might still have
+/// them.
+fn record_coverage(engine: &mut Engine, items: &[Item]) {
+    for shaped in items.iter().filter_map(Item::text) {
+        let requested = TextElem::font_in(shaped.styles);
+        let warn = TextElem::fallback_in(shaped.styles);
+
+        // Consecutive glyphs mostly share a font, so we only check whether
+        // a font was requested when it changes.
+        let mut last: Option<(&Font, bool)> = None;
+        for glyph in shaped.glyphs.iter() {
+            if glyph.glyph_id == 0 {
+                engine.tracer.missing(glyph.c);
+                if warn {
+                    engine.tracer.warn(
+                        warning!(
+                            glyph.span.0,
+                            "no font has a glyph for `{}` (U+{:04X})",
+                            glyph.c,
+                            glyph.c as u32;
+                            hint: "the character is shown as a tofu";
+                            hint: "try adding a font that covers it to `text(font: ..)`",
+                        )
+                        .with_code("missing-glyph"),
+                    );
+                }
+                continue;
+            }
+
+            let fallback = match last {
+                Some((font, fallback)) if *font == glyph.font => fallback,
+                _ => {
+                    let family = glyph.font.info().family.to_lowercase();
+                    let fallback =
+                        !requested.into_iter().any(|f| f.as_str() == family.as_str());
+                    last = Some((&glyph.font, fallback));
+                    fallback
+                }
+            };
+
+            if fallback {
+                engine
+                    .tracer
+                    .fallback(glyph.c, glyph.font.info().family.as_str().into());
+            }
+        }
+    }
+}
+
 /// Add some spacing between Han characters and western characters.
 /// See Requirements for Chinese Text Layout, Section 3.2.2 Mixed Text Composition in Horizontal
 /// Written Mode
@@ -761,6 +846,7 @@ fn linebreak<'a>(engine: &Engine, p: &'a Preparation<'a>, width: Abs) -> Vec<Lin
     match linebreaks {
         Linebreaks::Simple => linebreak_simple(engine, p, width),
         Linebreaks::Optimized => linebreak_optimized(engine, p, width),
+        Linebreaks::Balanced => linebreak_balanced(engine, p, width),
     }
 }
 
@@ -810,6 +896,41 @@ fn linebreak_simple<'a>(
     lines
 }
 
+/// Perform line breaking with lines of about equal length. This searches for
+/// the smallest width at which simple line breaking still produces the same
+/// number of lines.
+fn linebreak_balanced<'a>(
+    engine: &Engine,
+    p: &'a Preparation<'a>,
+    width: Abs,
+) -> Vec<Line<'a>> {
+    const MAX_LINES: usize = 10;
+
+    let lines = linebreak_simple(engine, p, width);
+    let count = lines.len();
+    if count < 2 || count > MAX_LINES || !width.is_finite() {
+        return lines;
+    }
+
+    // The lines can't be narrower than if the text was distributed evenly.
+    let total = lines.iter().map(|line| line.width).sum::<Abs>();
+    let mut lo = total / count as f64;
+    let mut hi = width;
+    let mut best = lines;
+    while hi - lo > Abs::pt(0.5) {
+        let mid = (lo + hi) / 2.0;
+        let attempt = linebreak_simple(engine, p, mid);
+        if attempt.len() <= count {
+            hi = mid;
+            best = attempt;
+        } else {
+            lo = mid;
+        }
+    }
+
+    best
+}
+
 /// Perform line breaking in optimized Knuth-Plass style. Here, we use more
 /// context to determine the line breaks than in the simple first-fit style. For
 /// example, we might choose to cut a line short even though there is still a
@@ -878,7 +999,7 @@ fn linebreak_optimized<'a>(
             let adjust = if delta >= Abs::zero() {
                 attempt.stretchability()
             } else {
-                attempt.shrinkability()
+                attempt.shrinkability() + attempt.cjk_shrinkability()
             };
             // Ideally, the ratio should between -1.0 and 1.0, but sometimes a value above 1.0
             // is possible, in which case the line is underfull.
@@ -984,8 +1105,9 @@ fn line<'a>(
     breakpoint: Breakpoint,
 ) -> Line<'a> {
     let end = range.end;
-    let mut justify =
-        p.justify && end < p.bidi.text.len() && breakpoint != Breakpoint::Mandatory;
+    let mut justify = p.justify
+        && (p.justify_last_line
+            || (end < p.bidi.text.len() && breakpoint != Breakpoint::Mandatory));
 
     if range.is_empty() {
         return Line {
@@ -1256,13 +1378,23 @@ fn commit(
     let fr = line.fr();
     let mut justification_ratio = 0.0;
     let mut extra_justification = Abs::zero();
+    let mut cjk_ratio = 0.0;
 
     let shrink = line.shrinkability();
+    let cjk_shrink = line.cjk_shrinkability();
     let stretch = line.stretchability();
-    if remaining < Abs::zero() && shrink > Abs::zero() {
+    if remaining < Abs::zero() && (shrink > Abs::zero() || cjk_shrink > Abs::zero()) {
         // Attempt to reduce the length of the line, using shrinkability.
-        justification_ratio = (remaining / shrink).max(-1.0);
-        remaining = (remaining + shrink).min(Abs::zero());
+        if shrink > Abs::zero() {
+            justification_ratio = (remaining / shrink).max(-1.0);
+            remaining = (remaining + shrink).min(Abs::zero());
+        }
+
+        // Only if that isn't enough, compress the gaps between CJK characters.
+        if remaining < Abs::zero() && cjk_shrink > Abs::zero() {
+            cjk_ratio = (remaining / cjk_shrink).max(-1.0);
+            remaining = (remaining + cjk_shrink).min(Abs::zero());
+        }
     } else if line.justify && fr.is_zero() {
         // Attempt to increase the length of the line, using stretchability.
         if stretch > Abs::zero() {
@@ -1309,8 +1441,13 @@ fn commit(
                 }
             }
             Item::Text(shaped) => {
-                let frame =
-                    shaped.build(engine, justification_ratio, extra_justification);
+                let frame = shaped.build(
+                    engine,
+                    justification_ratio,
+                    extra_justification,
+                    cjk_ratio,
+                    line.ends_with(shaped),
+                );
                 push(&mut offset, frame);
             }
             Item::Frame(frame) | Item::Meta(frame) => {
@@ -8,6 +8,7 @@ use ecow::EcoString;
 use rustybuzz::{Tag, UnicodeBuffer};
 use unicode_script::{Script, UnicodeScript};
 
+use super::cache::shape_cached;
 use super::SpanMapper;
 use crate::engine::Engine;
 use crate::foundations::StyleChain;
@@ -17,7 +18,8 @@ use crate::text::{
     decorate, families, features, variant, Font, FontVariant, Glyph, Lang, Region,
     TextElem, TextItem,
 };
-use crate::util::SliceExt;
+use crate::util::{hash128, SliceExt};
+use crate::visualize::Stroke;
 use crate::World;
 
 /// The result of shaping text.
@@ -212,11 +214,17 @@ impl<'a> ShapedText<'a> {
     ///
     /// The `justification` defines how much extra advance width each
     /// [justifiable glyph](ShapedGlyph::is_justifiable) will get.
+    ///
+    /// The `cjk_ratio` defines how much of the [`cjk_shrink`](TextElem::cjk_shrink)
+    /// the gap after each CJK character loses. If the text ends the line, its
+    /// last character keeps its gap, as it lies beyond the line's edge.
     pub fn build(
         &self,
         engine: &Engine,
         justification_ratio: f64,
         extra_justification: Abs,
+        cjk_ratio: f64,
+        line_end: bool,
     ) -> Frame {
         let (top, bottom) = self.measure(engine);
         let size = Size::new(self.width, top + bottom);
@@ -228,7 +236,10 @@ impl<'a> ShapedText<'a> {
         let shift = TextElem::baseline_in(self.styles);
         let lang = TextElem::lang_in(self.styles);
         let decos = TextElem::deco_in(self.styles);
+        let overrides = TextElem::metrics_in(self.styles);
         let fill = TextElem::fill_in(self.styles);
+        let stroke = TextElem::stroke_in(self.styles).map(Stroke::unwrap_or_default);
+        let cjk_shrink = Em::from_length(TextElem::cjk_shrink_in(self.styles), self.size);
 
         for ((font, y_offset), group) in
             self.glyphs.as_ref().group_by_key(|g| (g.font.clone(), g.y_offset))
@@ -261,6 +272,9 @@ impl<'a> ShapedText<'a> {
                         justification_right +=
                             Em::from_length(extra_justification, self.size)
                     }
+                    if glyph.is_cj_script() && !(line_end && self.is_last(glyph)) {
+                        justification_right += cjk_shrink * cjk_ratio;
+                    }
 
                     frame.size_mut().x += justification_left.at(self.size)
                         + justification_right.at(self.size);
@@ -283,6 +297,7 @@ impl<'a> ShapedText<'a> {
                 size: self.size,
                 lang,
                 fill: fill.clone(),
+                stroke: stroke.clone(),
                 text: self.text[range.start - self.base..range.end - self.base].into(),
                 glyphs,
             };
@@ -294,7 +309,7 @@ impl<'a> ShapedText<'a> {
                 // Apply line decorations.
                 frame.push(pos, FrameItem::Text(item.clone()));
                 for deco in &decos {
-                    decorate(&mut frame, deco, &item, width, shift, pos);
+                    decorate(&mut frame, deco, &item, &overrides, width, shift, pos);
                 }
             }
 
@@ -314,11 +329,12 @@ impl<'a> ShapedText<'a> {
 
         let top_edge = TextElem::top_edge_in(self.styles);
         let bottom_edge = TextElem::bottom_edge_in(self.styles);
+        let overrides = TextElem::metrics_in(self.styles);
 
         // Expand top and bottom by reading the font's vertical metrics.
         let mut expand = |font: &Font, bbox: Option<ttf_parser::Rect>| {
-            top.set_max(top_edge.resolve(self.size, font, bbox));
-            bottom.set_max(-bottom_edge.resolve(self.size, font, bbox));
+            top.set_max(top_edge.resolve(self.size, font, &overrides, bbox));
+            bottom.set_max(-bottom_edge.resolve(self.size, font, &overrides, bbox));
         };
 
         if self.glyphs.is_empty() {
@@ -382,6 +398,24 @@ impl<'a> ShapedText<'a> {
             .at(self.size)
     }
 
+    /// How much the gaps after CJK characters can shrink once spaces and
+    /// punctuation are fully compressed.
+    ///
+    /// If the text ends the line, only the gaps between its characters count.
+    pub fn cjk_shrinkability(&self, line_end: bool) -> Abs {
+        let count = self
+            .glyphs
+            .iter()
+            .filter(|g| g.is_cj_script() && !(line_end && self.is_last(g)))
+            .count();
+        TextElem::cjk_shrink_in(self.styles) * count as f64
+    }
+
+    /// Whether the glyph is the last one of the text.
+    fn is_last(&self, glyph: &ShapedGlyph) -> bool {
+        self.glyphs.last().is_some_and(|last| std::ptr::eq(last, glyph))
+    }
+
     /// Reshape a range of the shaped text, reusing information from this
     /// shaping process if possible.
     ///
@@ -658,25 +692,44 @@ fn shape_segment<'a>(
 
     ctx.used.push(font.clone());
 
-    // Fill the buffer with our text.
-    let mut buffer = UnicodeBuffer::new();
-    buffer.push_str(text);
-    buffer.set_language(language(ctx.styles));
-    if let Some(script) = TextElem::script_in(ctx.styles).as_custom().and_then(|script| {
+    let language = language(ctx.styles);
+    let script = TextElem::script_in(ctx.styles).as_custom().and_then(|script| {
         rustybuzz::Script::from_iso15924_tag(Tag::from_bytes(script.as_bytes()))
-    }) {
-        buffer.set_script(script)
-    }
-    buffer.set_direction(match ctx.dir {
-        Dir::LTR => rustybuzz::Direction::LeftToRight,
-        Dir::RTL => rustybuzz::Direction::RightToLeft,
-        _ => unimplemented!("vertical text layout"),
     });
 
-    // Shape!
-    let buffer = rustybuzz::shape(font.rusty(), &ctx.features, buffer);
-    let infos = buffer.glyph_infos();
-    let pos = buffer.glyph_positions();
+    // Hash everything that the shaping output depends on.
+    let key = hash128(&(
+        &font,
+        ctx.features
+            .iter()
+            .map(|feature| (feature.tag, feature.value, feature.start, feature.end))
+            .collect::<Vec<_>>(),
+        text,
+        language.as_str(),
+        script.map(|script| script.tag()),
+        ctx.dir,
+    ));
+
+    // Shape! Or reuse the glyphs from the last time we shaped this run.
+    let run = shape_cached(key, || {
+        // Fill the buffer with our text.
+        let mut buffer = UnicodeBuffer::new();
+        buffer.push_str(text);
+        buffer.set_language(language);
+        if let Some(script) = script {
+            buffer.set_script(script)
+        }
+        buffer.set_direction(match ctx.dir {
+            Dir::LTR => rustybuzz::Direction::LeftToRight,
+            Dir::RTL => rustybuzz::Direction::RightToLeft,
+            _ => unimplemented!("vertical text layout"),
+        });
+
+        rustybuzz::shape(font.rusty(), &ctx.features, buffer).into()
+    });
+
+    let infos = &run.infos;
+    let pos = &run.positions;
     let ltr = ctx.dir.is_positive();
 
     // Collect the shaped glyphs, doing fallback and shaping parts again with
@@ -1,15 +1,16 @@
 use crate::diag::SourceResult;
 use crate::engine::Engine;
-use crate::foundations::{dict, func, Content, Dict, StyleChain, Styles};
-use crate::layout::{Abs, Axes, Layout, Regions, Size};
+use crate::foundations::{dict, func, Content, Dict, Resolve, Smart, StyleChain, Styles};
+use crate::layout::{Abs, Axes, Layout, Length, Regions, Size};
 
 /// Measures the layouted size of content.
 ///
-/// The `measure` function lets you determine the layouted size of content. Note
-/// that an infinite space is assumed, therefore the measured height/width may
-/// not necessarily match the final height/width of the measured content. If you
-/// want to measure in the current layout dimensions, you can combine `measure`
-/// and [`layout`]($layout).
+/// The `measure` function lets you determine the layouted size of content. By
+/// default, an infinite space is assumed, therefore the measured height/width
+/// may not necessarily match the final height/width of the measured content.
+/// You can limit the available space with the `width` and `height` parameters.
+/// If you want to measure in the current layout dimensions, you can combine
+/// `measure` and [`layout`]($layout).
 ///
 /// # Example
 /// The same content can have a different size depending on the styles that
@@ -37,6 +38,18 @@ use crate::layout::{Abs, Axes, Layout, Regions, Size};
 /// #thing[Welcome]
 /// ```
 ///
+/// With a limited width, text wraps just like it would in a box of that
+/// width. This lets you, for example, find out how tall a paragraph will be in
+/// a sidebar:
+///
+/// ```example
+/// #let body = lorem(12)
+/// #style(styles => {
+///   let size = measure(body, styles, width: 4cm)
+///   [Height in a 4cm column: #size.height]
+/// })
+/// ```
+///
 /// The measure function returns a dictionary with the entries `width` and
 /// `height`, both of type [`length`]($length).
 #[func]
@@ -47,9 +60,25 @@ pub fn measure(
     content: Content,
     /// The styles with which to layout the content.
     styles: Styles,
+    /// The width available to layout the content.
+    ///
+    /// Setting this to `{auto}` indicates infinite available width.
+    #[named]
+    #[default(Smart::Auto)]
+    width: Smart<Length>,
+    /// The height available to layout the content.
+    ///
+    /// Setting this to `{auto}` indicates infinite available height.
+    #[named]
+    #[default(Smart::Auto)]
+    height: Smart<Length>,
 ) -> SourceResult<Dict> {
-    let pod = Regions::one(Axes::splat(Abs::inf()), Axes::splat(false));
     let styles = StyleChain::new(&styles);
+    let available = Axes::new(
+        width.map(|v| v.resolve(styles)).unwrap_or(Abs::inf()),
+        height.map(|v| v.resolve(styles)).unwrap_or(Abs::inf()),
+    );
+    let pod = Regions::one(available, Axes::splat(false));
     let frame = content.measure(engine, styles, pod)?.into_frame();
     let Size { x, y } = frame.size();
     Ok(dict! { "width" => x, "height" => y })
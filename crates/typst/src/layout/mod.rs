@@ -34,6 +34,7 @@ mod size;
 mod spacing;
 mod stack;
 mod transform;
+mod watermark;
 
 pub use self::abs::*;
 pub use self::align::*;
@@ -66,12 +67,13 @@ pub use self::size::*;
 pub use self::spacing::*;
 pub use self::stack::*;
 pub use self::transform::*;
+pub use self::watermark::*;
 
 pub(crate) use self::inline::*;
 
 use comemo::{Tracked, TrackedMut};
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::eval::Tracer;
 use crate::foundations::{category, Category, Content, Scope, StyleChain};
@@ -113,7 +115,9 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<MoveElem>();
     global.define_elem::<ScaleElem>();
     global.define_elem::<RotateElem>();
+    global.define_elem::<SkewElem>();
     global.define_elem::<HideElem>();
+    global.define_elem::<WatermarkElem>();
     global.define_func::<measure>();
     global.define_func::<layout>();
 }
@@ -238,10 +242,11 @@ impl Layout for Content {
             };
 
             if !engine.route.within(Route::MAX_LAYOUT_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum layout depth exceeded";
                     hint: "try to reduce the amount of nesting in your layout",
-                );
+                )
+                .with_code("maximum-layout-depth"));
             }
 
             let scratch = Scratch::default();
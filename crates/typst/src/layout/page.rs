@@ -1,8 +1,11 @@
 use std::borrow::Cow;
 use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
 use std::ptr;
 use std::str::FromStr;
 
+use ecow::{eco_format, EcoString};
+
 use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
@@ -11,15 +14,15 @@ use crate::foundations::{
 };
 use crate::introspection::{Counter, CounterKey, ManualPageCounter, Meta};
 use crate::layout::{
-    Abs, Align, AlignElem, Axes, ColumnsElem, Dir, Fragment, Frame, HAlign, Layout,
-    Length, Point, Ratio, Regions, Rel, Sides, Size, VAlign,
+    Abs, Align, AlignElem, Axes, ColumnsElem, Dir, Fragment, Frame, FrameItem, HAlign,
+    Layout, Length, Point, Ratio, Regions, Rel, Sides, Size, VAlign,
 };
 
 use crate::model::Numbering;
-use crate::syntax::Spanned;
+use crate::syntax::{Span, Spanned};
 use crate::text::TextElem;
 use crate::util::{NonZeroExt, Numeric, Scalar};
-use crate::visualize::Paint;
+use crate::visualize::{Geometry, Paint};
 
 /// Layouts its child onto one or multiple pages.
 ///
@@ -188,6 +191,46 @@ pub struct PageElem {
     #[borrowed]
     pub fill: Option<Paint>,
 
+    /// How far the page's background extends beyond its edges.
+    ///
+    /// Print shops cut printed sheets down to the page size, which is never
+    /// perfectly precise. To avoid white slivers at the edges, the page's
+    /// `fill` as well as any content that is placed beyond the page's edges
+    /// are printed into this additional area, which is later cut off. Typical
+    /// values are `{3mm}` or `{0.125in}`.
+    ///
+    /// The PDF exporter records the page's size as its trim box and the page
+    /// extended by the bleed as its bleed box. Other exporters ignore the
+    /// bleed.
+    ///
+    /// ```typ
+    /// #set page(bleed: 3mm, fill: aqua)
+    /// ```
+    #[resolve]
+    #[parse({
+        let bleed: Option<Spanned<Length>> = args.named("bleed")?;
+        if let Some(Spanned { v, span }) = bleed {
+            if v.abs < Abs::zero() || v.em.get() < 0.0 {
+                bail!(span, "bleed must not be negative");
+            }
+        }
+        bleed.map(|spanned| spanned.v)
+    })]
+    pub bleed: Length,
+
+    /// Whether to add printer's marks around the page in PDF output.
+    ///
+    /// This adds crop marks at the corners of the page, registration marks at
+    /// the centers of its edges, and a color bar below it. All of these are
+    /// placed outside of the page and its bleed. Other exporters ignore this
+    /// property.
+    ///
+    /// ```typ
+    /// #set page(bleed: 3mm, marks: true)
+    /// ```
+    #[default(false)]
+    pub marks: bool,
+
     /// How to [number]($numbering) the pages.
     ///
     /// If an explicit `footer` (or `header` for top-aligned numbering) is
@@ -202,6 +245,22 @@ pub struct PageElem {
     ///
     /// #lorem(48)
     /// ```
+    ///
+    /// A document can switch between numberings. The front matter of a
+    /// thesis, for example, is often numbered with Roman numerals, while the
+    /// main text starts again at 1. To switch, change the numbering and reset
+    /// the [page counter]($counter) on the first page of the main text.
+    /// Headers, footers, outline entries, and PDF page labels all use the
+    /// numbering of their page.
+    ///
+    /// ```example
+    /// #set page(height: 80pt, numbering: "I")
+    /// = 摘要
+    ///
+    /// #set page(numbering: "1")
+    /// #counter(page).update(1)
+    /// = 绪论
+    /// ```
     #[borrowed]
     pub numbering: Option<Numbering>,
 
@@ -407,6 +466,8 @@ impl PageElem {
         }
 
         let fill = self.fill(styles);
+        let bleed = self.bleed(styles);
+        let marks = self.marks(styles);
         let foreground = Cow::Borrowed(self.foreground(styles));
         let background = Cow::Borrowed(self.background(styles));
         let header_ascent = self.header_ascent(styles);
@@ -444,7 +505,9 @@ impl PageElem {
 
         // Post-process pages.
         for frame in frames.iter_mut() {
-            tracing::info!("Layouting page #{}", page_counter.physical());
+            let number = page_counter.physical().get();
+            let _span = tracing::info_span!("page", number).entered();
+            tracing::info!("Layouting page #{}", number);
 
             // The padded width of the page's content without margins.
             let pw = frame.width();
@@ -508,7 +571,19 @@ impl PageElem {
             }
 
             if let Some(fill) = fill {
-                frame.fill(fill.clone());
+                if bleed > Abs::zero() {
+                    let rect = Geometry::Rect(size + Size::splat(2.0 * bleed))
+                        .filled(fill.clone());
+                    let item = FrameItem::Shape(rect, Span::detached());
+                    frame.prepend(Point::splat(-bleed), item);
+                } else {
+                    frame.fill(fill.clone());
+                }
+            }
+
+            if bleed > Abs::zero() || marks {
+                let setup = PrintSetup { bleed, marks };
+                frame.push_positionless_meta(Meta::PrintSetup(setup));
             }
 
             page_counter.visit(engine, frame)?;
@@ -527,6 +602,15 @@ impl PageElem {
     }
 }
 
+/// Print production settings of a page, as set up by the PDF exporter.
+#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+pub struct PrintSetup {
+    /// How far the page's background extends beyond its edges.
+    pub bleed: Abs,
+    /// Whether to add printer's marks around the page.
+    pub marks: bool,
+}
+
 /// Specification of the page's margins.
 #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Margin {
@@ -750,6 +834,78 @@ impl Parity {
     }
 }
 
+/// A list of page ranges, e.g. to export only some pages of a document.
+///
+/// Page numbers are one-based and ranges are inclusive. A range without a
+/// start or end extends to the first or last page, respectively. It can be
+/// parsed from a comma-separated list like `1-3,7,9-`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PageRanges(Vec<PageRange>);
+
+/// A range of pages, as part of [`PageRanges`].
+pub type PageRange = RangeInclusive<Option<NonZeroUsize>>;
+
+impl PageRanges {
+    /// Create new page ranges.
+    pub fn new(ranges: Vec<PageRange>) -> Self {
+        Self(ranges)
+    }
+
+    /// Whether the page with the given one-based number is included.
+    pub fn includes_page(&self, page: NonZeroUsize) -> bool {
+        self.0.iter().any(|range| {
+            range.start().map_or(true, |start| start <= page)
+                && range.end().map_or(true, |end| page <= end)
+        })
+    }
+
+    /// Whether the page with the given zero-based index is included.
+    pub fn includes_page_index(&self, index: usize) -> bool {
+        self.includes_page(NonZeroUsize::new(index + 1).unwrap())
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |part: &str| -> Result<Option<NonZeroUsize>, EcoString> {
+            let part = part.trim();
+            if part.is_empty() {
+                return Ok(None);
+            }
+            match part.parse::<usize>() {
+                Ok(0) => Err("page numbers start at one".into()),
+                Ok(number) => Ok(NonZeroUsize::new(number)),
+                Err(_) => Err(eco_format!("invalid page number: {part}")),
+            }
+        };
+
+        let mut ranges = vec![];
+        for part in s.split(',') {
+            let range = match part.split_once('-') {
+                Some((start, end)) => parse(start)?..=parse(end)?,
+                None => {
+                    let page = parse(part)?.ok_or("page range must not be empty")?;
+                    Some(page)..=Some(page)
+                }
+            };
+
+            if let (Some(start), Some(end)) = (range.start(), range.end()) {
+                if start > end {
+                    return Err(eco_format!(
+                        "page range {start}-{end} ends before it starts"
+                    ));
+                }
+            }
+
+            ranges.push(range);
+        }
+
+        Ok(Self(ranges))
+    }
+}
+
 /// Specification of a paper.
 #[derive(Debug, Copy, Clone, Hash)]
 pub struct Paper {
@@ -963,3 +1119,22 @@ papers! {
     (PRESENTATION_16_9:    297.0, 167.0625, "presentation-16-9")
     (PRESENTATION_4_3:     280.0,    210.0, "presentation-4-3")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_ranges_parse() {
+        let ranges: PageRanges = "1-3, 7,9-".parse().unwrap();
+        let included: Vec<_> = (0..12)
+            .filter(|&i| ranges.includes_page_index(i))
+            .map(|i| i + 1)
+            .collect();
+        assert_eq!(included, [1, 2, 3, 7, 9, 10, 11, 12]);
+        assert!("-2".parse::<PageRanges>().unwrap().includes_page_index(1));
+        assert!("0".parse::<PageRanges>().is_err());
+        assert!("3-1".parse::<PageRanges>().is_err());
+        assert!("1,,2".parse::<PageRanges>().is_err());
+    }
+}
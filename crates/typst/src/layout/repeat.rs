@@ -2,13 +2,15 @@ use crate::diag::{bail, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{elem, Content, NativeElement, Resolve, StyleChain};
 use crate::layout::{
-    Abs, AlignElem, Axes, Fragment, Frame, Layout, Point, Regions, Size,
+    Abs, AlignElem, Axes, Fragment, Frame, Layout, Length, Point, Regions, Size,
 };
 use crate::util::Numeric;
 
 /// Repeats content to the available space.
 ///
 /// This can be useful when implementing a custom index, reference, or outline.
+/// Together with [fractional spacing]($h), it also creates the dotted leaders
+/// of price lists and signature lines.
 ///
 /// Space may be inserted between the instances of the body parameter, so be
 /// sure to include negative space if you need the instances to overlap.
@@ -32,6 +34,27 @@ pub struct RepeatElem {
     /// The content to repeat.
     #[required]
     pub body: Content,
+
+    /// The gap between each instance of the body.
+    ///
+    /// ```example
+    /// Tea #box(width: 1fr, repeat(gap: 3pt)[.]) 2.50 € \
+    /// Coffee #box(width: 1fr, repeat(gap: 3pt)[.]) 3.00 €
+    /// ```
+    #[resolve]
+    pub gap: Length,
+
+    /// Whether to increase the gap between instances to completely fill the
+    /// available space.
+    ///
+    /// If this is `{false}`, the instances are placed with exactly the given
+    /// gap and aligned according to the current [alignment]($align).
+    ///
+    /// ```example
+    /// #box(width: 1fr, repeat(justify: false)[- ])
+    /// ```
+    #[default(true)]
+    pub justify: bool,
 }
 
 impl Layout for RepeatElem {
@@ -46,11 +69,16 @@ impl Layout for RepeatElem {
         let piece = self.body().layout(engine, styles, pod)?.into_frame();
         let align = AlignElem::alignment_in(styles).resolve(styles);
 
+        let gap = self.gap(styles);
+        let justify = self.justify(styles);
+
+        // Fit as many instances as possible, each but the last followed by
+        // the gap.
         let fill = regions.size.x;
         let width = piece.width();
-        let count = (fill / width).floor();
-        let remaining = fill % width;
-        let apart = remaining / (count - 1.0);
+        let count = ((fill + gap) / (width + gap)).floor();
+        let remaining = (fill + gap) % (width + gap);
+        let apart = if justify { gap + remaining / (count - 1.0) } else { gap };
 
         let size = Size::new(regions.size.x, piece.height());
 
@@ -64,11 +92,11 @@ impl Layout for RepeatElem {
         }
 
         let mut offset = Abs::zero();
-        if count == 1.0 {
+        if count == 1.0 || !justify {
             offset += align.x.position(remaining);
         }
 
-        if width > Abs::zero() {
+        if width + gap > Abs::zero() {
             for _ in 0..(count as usize).min(1000) {
                 frame.push_frame(Point::with_x(offset), piece.clone());
                 offset += piece.width() + apart;
@@ -2,8 +2,8 @@ use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{elem, Content, Resolve, StyleChain};
 use crate::layout::{
-    Abs, Align, Angle, Axes, FixedAlign, Fragment, HAlign, Layout, Length, Ratio,
-    Regions, Rel, VAlign,
+    Abs, Align, Angle, Axes, FixedAlign, Fragment, Frame, HAlign, Layout, Length, Point,
+    Ratio, Regions, Rel, Size, VAlign,
 };
 
 /// Moves content without affecting layout.
@@ -57,7 +57,7 @@ impl Layout for MoveElem {
 /// Rotates content without affecting layout.
 ///
 /// Rotates an element by a given angle. The layout will act as if the element
-/// was not rotated.
+/// was not rotated unless you specify `{reflow: true}`.
 ///
 /// # Example
 /// ```example
@@ -98,6 +98,18 @@ pub struct RotateElem {
     #[default(HAlign::Center + VAlign::Horizon)]
     pub origin: Align,
 
+    /// Whether the rotation impacts the layout.
+    ///
+    /// If set to `{false}`, the rotated content will retain the bounding box of
+    /// the original content. If set to `{true}`, the bounding box will take the
+    /// rotation of the content into account and adjust the layout accordingly.
+    ///
+    /// ```example
+    /// Hello #rotate(90deg, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
     /// The content to rotate.
     #[required]
     pub body: Content,
@@ -112,22 +124,18 @@ impl Layout for RotateElem {
         regions: Regions,
     ) -> SourceResult<Fragment> {
         let pod = Regions::one(regions.base(), Axes::splat(false));
-        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
-        let Axes { x, y } = self
-            .origin(styles)
-            .resolve(styles)
-            .zip_map(frame.size(), FixedAlign::position);
-        let ts = Transform::translate(x, y)
-            .pre_concat(Transform::rotate(self.angle(styles)))
-            .pre_concat(Transform::translate(-x, -y));
-        frame.transform(ts);
-        Ok(Fragment::frame(frame))
+        let frame = self.body().layout(engine, styles, pod)?.into_frame();
+        let origin = self.origin(styles).resolve(styles);
+        let ts = Transform::rotate(self.angle(styles));
+        Ok(Fragment::frame(transform_frame(frame, origin, ts, self.reflow(styles))))
     }
 }
 
 /// Scales content without affecting layout.
 ///
 /// Lets you mirror content by specifying a negative scale on a single axis.
+/// The layout will act as if the element was not scaled unless you specify
+/// `{reflow: true}`.
 ///
 /// # Example
 /// ```example
@@ -163,6 +171,18 @@ pub struct ScaleElem {
     #[default(HAlign::Center + VAlign::Horizon)]
     pub origin: Align,
 
+    /// Whether the scaling impacts the layout.
+    ///
+    /// If set to `{false}`, the scaled content will be allowed to overlap
+    /// other content. If set to `{true}`, it will compute the new size of
+    /// the scaled content and adjust the layout accordingly.
+    ///
+    /// ```example
+    /// Hello #scale(x: 20%, y: 40%, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
     /// The content to scale.
     #[required]
     pub body: Content,
@@ -177,19 +197,120 @@ impl Layout for ScaleElem {
         regions: Regions,
     ) -> SourceResult<Fragment> {
         let pod = Regions::one(regions.base(), Axes::splat(false));
-        let mut frame = self.body().layout(engine, styles, pod)?.into_frame();
-        let Axes { x, y } = self
-            .origin(styles)
-            .resolve(styles)
-            .zip_map(frame.size(), FixedAlign::position);
-        let transform = Transform::translate(x, y)
-            .pre_concat(Transform::scale(self.x(styles), self.y(styles)))
-            .pre_concat(Transform::translate(-x, -y));
-        frame.transform(transform);
-        Ok(Fragment::frame(frame))
+        let frame = self.body().layout(engine, styles, pod)?.into_frame();
+        let origin = self.origin(styles).resolve(styles);
+        let ts = Transform::scale(self.x(styles), self.y(styles));
+        Ok(Fragment::frame(transform_frame(frame, origin, ts, self.reflow(styles))))
     }
 }
 
+/// Skews content without affecting layout.
+///
+/// Skews an element in horizontal and/or vertical direction. The layout will
+/// act as if the element was not skewed unless you specify `{reflow: true}`.
+///
+/// # Example
+/// ```example
+/// #skew(ax: -12deg)[
+///   This is some fake italic text.
+/// ]
+/// ```
+#[elem(Layout)]
+pub struct SkewElem {
+    /// The horizontal skewing angle.
+    ///
+    /// ```example
+    /// #skew(ax: 30deg)[Skewed]
+    /// ```
+    pub ax: Angle,
+
+    /// The vertical skewing angle.
+    ///
+    /// ```example
+    /// #skew(ay: 30deg)[Skewed]
+    /// ```
+    pub ay: Angle,
+
+    /// The origin of the skew transformation.
+    ///
+    /// ```example
+    /// X #box(skew(ax: -30deg, origin: center + horizon)[X]) X \
+    /// X #box(skew(ax: -30deg, origin: bottom + left)[X]) X
+    /// ```
+    #[fold]
+    #[default(HAlign::Center + VAlign::Horizon)]
+    pub origin: Align,
+
+    /// Whether the skew transformation impacts the layout.
+    ///
+    /// If set to `{false}`, the skewed content will retain the bounding box of
+    /// the original content. If set to `{true}`, the bounding box will take the
+    /// transformation of the content into account and adjust the layout
+    /// accordingly.
+    ///
+    /// ```example
+    /// Hello #skew(ay: 30deg, reflow: true)[World]!
+    /// ```
+    #[default(false)]
+    pub reflow: bool,
+
+    /// The content to skew.
+    #[required]
+    pub body: Content,
+}
+
+impl Layout for SkewElem {
+    #[tracing::instrument(name = "SkewElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let pod = Regions::one(regions.base(), Axes::splat(false));
+        let frame = self.body().layout(engine, styles, pod)?.into_frame();
+        let origin = self.origin(styles).resolve(styles);
+        let ts = Transform::skew(self.ax(styles), self.ay(styles));
+        Ok(Fragment::frame(transform_frame(frame, origin, ts, self.reflow(styles))))
+    }
+}
+
+/// Applies a transformation around an origin to a frame.
+///
+/// With `reflow`, the result is sized to the bounding box of the transformed
+/// frame, so that the layout takes the transformation into account.
+pub(crate) fn transform_frame(
+    mut frame: Frame,
+    origin: Axes<FixedAlign>,
+    transform: Transform,
+    reflow: bool,
+) -> Frame {
+    let Axes { x, y } = origin.zip_map(frame.size(), FixedAlign::position);
+    let mut ts = Transform::translate(x, y)
+        .pre_concat(transform)
+        .pre_concat(Transform::translate(-x, -y));
+
+    if !reflow {
+        frame.transform(ts);
+        return frame;
+    }
+
+    // Compute the bounding box of the transformed frame and move its top-left
+    // corner to the origin of the output.
+    let size = frame.size();
+    let corners =
+        [Point::zero(), Point::with_x(size.x), Point::with_y(size.y), size.to_point()]
+            .map(|corner| corner.transform(ts));
+    let min = corners.into_iter().reduce(Point::min).unwrap();
+    let max = corners.into_iter().reduce(Point::max).unwrap();
+    ts = Transform::translate(-min.x, -min.y).pre_concat(ts);
+    frame.transform(ts);
+
+    let mut output = Frame::soft(Size::new(max.x - min.x, max.y - min.y));
+    output.push_frame(Point::zero(), frame);
+    output
+}
+
 /// A scale-skew-translate transformation.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Transform {
@@ -237,6 +358,15 @@ impl Transform {
         }
     }
 
+    /// A skew transform.
+    pub fn skew(ax: Angle, ay: Angle) -> Self {
+        Self {
+            kx: Ratio::new(ax.tan()),
+            ky: Ratio::new(ay.tan()),
+            ..Self::identity()
+        }
+    }
+
     /// Whether this is the identity transformation.
     pub fn is_identity(self) -> bool {
         self == Self::identity()
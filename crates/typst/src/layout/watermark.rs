@@ -0,0 +1,113 @@
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, StyleChain};
+use crate::layout::{
+    transform_frame, Abs, Angle, Axes, Em, FixedAlign, Fragment, Frame, Layout, Length,
+    Point, Regions, Size, Transform,
+};
+use crate::visualize::Path;
+
+/// A watermark that covers the available space.
+///
+/// The watermark's body is rotated and repeated in staggered rows across the
+/// whole area. It is meant to be used as the [background]($page.background)
+/// of a page, so that it is placed behind the page's content. Since set rules
+/// apply to all following pages, a watermark can be added to some of the
+/// pages only.
+///
+/// To make the watermark unobtrusive, give it a light or
+/// [transparent]($color.transparentize) color. Image stamps can be made
+/// transparent with their [`opacity`]($image.opacity).
+///
+/// # Example
+/// ```example
+/// #set page(background: watermark(
+///   text(20pt, fill: red.transparentize(80%))[*机密*]
+/// ))
+///
+/// = 年度预算
+/// 本文件仅供内部传阅。
+///
+/// #set page(background: watermark(
+///   tiled: false,
+///   text(48pt, fill: luma(220))[DRAFT]
+/// ))
+/// #pagebreak()
+/// = Appendix
+/// ```
+#[elem(Layout)]
+pub struct WatermarkElem {
+    /// The content to repeat, like some text or an image.
+    #[required]
+    pub body: Content,
+
+    /// The angle by which to rotate the body. Negative angles rotate it
+    /// counterclockwise, so that text rises from left to right.
+    #[default(Angle::deg(-30.0))]
+    pub angle: Angle,
+
+    /// Whether to repeat the body across the whole area. If this is
+    /// `{false}`, the body is placed once, centered in the area.
+    #[default(true)]
+    pub tiled: bool,
+
+    /// The gap between the repeated instances of the body.
+    #[resolve]
+    #[default(Em::new(4.0).into())]
+    pub gap: Length,
+}
+
+impl Layout for WatermarkElem {
+    #[tracing::instrument(name = "WatermarkElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = regions.base();
+        if !size.is_finite() {
+            bail!(self.span(), "watermark with no size restrictions");
+        }
+
+        let pod = Regions::one(size, Axes::splat(false));
+        let piece = self.body().layout(engine, styles, pod)?.into_frame();
+        let origin = Axes::splat(FixedAlign::Center);
+        let ts = Transform::rotate(self.angle(styles));
+        let piece = transform_frame(piece, origin, ts, true);
+
+        let mut frame = Frame::hard(size);
+        if !self.tiled(styles) {
+            let pos = (size - piece.size()).to_point() / 2.0;
+            frame.push_frame(pos, piece);
+            return Ok(Fragment::frame(frame));
+        }
+
+        // Fill the area with rows of instances that are centered in it. Every
+        // other row is shifted by half an instance.
+        let gap = self.gap(styles);
+        let step = piece.size() + Size::splat(gap);
+        if step.x <= Abs::zero() || step.y <= Abs::zero() {
+            return Ok(Fragment::frame(frame));
+        }
+
+        let cols = (size.x / step.x).ceil() as usize + 1;
+        let rows = (size.y / step.y).ceil() as usize + 1;
+        let start = Point::new(
+            (size.x - step.x * cols as f64 + gap) / 2.0,
+            (size.y - step.y * rows as f64 + gap) / 2.0,
+        );
+
+        for row in 0..rows.min(1000) {
+            let shift = if row % 2 == 1 { step.x / 2.0 } else { Abs::zero() };
+            for col in 0..cols.min(1000) {
+                let pos =
+                    start + Point::new(shift + step.x * col as f64, step.y * row as f64);
+                frame.push_frame(pos, piece.clone());
+            }
+        }
+
+        frame.clip(Path::rect(size));
+        Ok(Fragment::frame(frame))
+    }
+}
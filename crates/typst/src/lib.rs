@@ -47,7 +47,11 @@ pub mod introspection;
 pub mod layout;
 pub mod loading;
 pub mod math;
+pub mod memory;
 pub mod model;
+pub mod pdf;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod profile;
 pub mod realize;
 pub mod symbols;
 pub mod text;
@@ -57,22 +61,27 @@ pub mod visualize;
 pub use typst_syntax as syntax;
 
 use std::collections::HashSet;
+use std::fmt::{self, Debug, Formatter};
 use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use comemo::{Prehashed, Track, Tracked, Validate};
 use ecow::{EcoString, EcoVec};
+use indexmap::IndexSet;
 
-use crate::diag::{warning, FileResult, SourceDiagnostic, SourceResult};
+use crate::diag::{bail, error, warning, FileResult, SourceDiagnostic, SourceResult};
 use crate::engine::{Engine, Route};
-use crate::eval::Tracer;
+use crate::eval::{Dependency, Tracer};
+use crate::foundations::sys::Target;
 use crate::foundations::{
-    Array, Bytes, Content, Datetime, Module, Scope, StyleChain, Styles,
+    Array, Bytes, Content, Datetime, Dict, Module, Scope, StyleChain, Styles,
 };
 use crate::introspection::{Introspector, Locator};
 use crate::layout::{Align, Dir, LayoutRoot};
 use crate::model::Document;
-use crate::syntax::{FileId, PackageSpec, Source, Span};
-use crate::text::{Font, FontBook};
+use crate::syntax::{FileId, LinkedNode, PackageSpec, Source, Span, SyntaxKind};
+use crate::text::{Font, FontBook, Lang, Region};
 use crate::visualize::Color;
 
 /// Compile a source file into a fully layouted document.
@@ -85,10 +94,23 @@ use crate::visualize::Color;
 /// `tracer.warnings()` after compilation will return all compiler warnings.
 #[tracing::instrument(skip_all)]
 pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document> {
-    // Call `track` on the world just once to keep comemo's ID stable.
-    let world = world.track();
+    Compiler::new().compile(world, tracer)
+}
 
-    // Try to evaluate the source file into a module.
+/// Evaluate the main file and apply its show rules, without layouting it.
+///
+/// Semantic elements like headings, lists, and tables are preserved, see
+/// [`realize_structure`](crate::realize::realize_structure). This is used by
+/// exporters that work on the structure of a document instead of its
+/// layout. Introspection, e.g. through counters and queries, is answered from
+/// the already compiled `document`.
+#[tracing::instrument(skip_all)]
+pub fn structure(
+    world: &dyn World,
+    tracer: &mut Tracer,
+    document: &Document,
+) -> SourceResult<Content> {
+    let world = world.track();
     let module = crate::eval::eval(
         world,
         Route::default().track(),
@@ -97,12 +119,222 @@ pub fn compile(world: &dyn World, tracer: &mut Tracer) -> SourceResult<Document>
     )
     .map_err(deduplicate)?;
 
-    // Typeset the module's content, relayouting until convergence.
-    typeset(world, tracer, &module.content()).map_err(deduplicate)
+    let library = world.library();
+    let styles = StyleChain::new(&library.styles);
+    let mut locator = Locator::new();
+    let mut engine = Engine {
+        world,
+        route: Route::default(),
+        tracer: tracer.track_mut(),
+        locator: &mut locator,
+        introspector: document.introspector.track(),
+    };
+
+    crate::realize::realize_structure(&mut engine, &module.content(), styles)
+        .map_err(deduplicate)
+}
+
+/// A compiler that can be cancelled and that reports its progress.
+///
+/// This is useful for editor integrations: When the user keeps typing, a
+/// stale compilation can be aborted through a [`CancelToken`] instead of
+/// waiting for it to finish, see [`compile_cancellable`]. Since compilation is
+/// memoized, the work done before cancellation is not lost for the next
+/// compilation.
+///
+/// [`compile_cancellable`]: Self::compile_cancellable
+///
+/// Cancellation is checked between the phases of compilation and between
+/// layout iterations. Exporting happens outside of the compiler and is thus
+/// not covered.
+#[derive(Default)]
+pub struct Compiler {
+    progress: Option<Box<dyn Fn(Phase) + Send + Sync>>,
+    lang: Option<(Lang, Option<Region>)>,
+    max_passes: Option<usize>,
+}
+
+impl Compiler {
+    /// The default maximum number of layout passes.
+    pub const MAX_PASSES: usize = 5;
+
+    /// Create a new compiler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give up relayouting after the given number of passes, instead of
+    /// [`MAX_PASSES`](Self::MAX_PASSES).
+    ///
+    /// At least one pass is always performed. The number of passes a
+    /// compilation actually needed is available through
+    /// [`Tracer::passes`].
+    pub fn with_max_passes(mut self, passes: usize) -> Self {
+        self.max_passes = Some(passes.max(1));
+        self
+    }
+
+    /// Report the start of each phase to the given callback.
+    pub fn with_progress(mut self, f: impl Fn(Phase) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(f));
+        self
+    }
+
+    /// Translate diagnostics into the given language, where possible.
+    ///
+    /// See [`diag::localize`] for details.
+    pub fn with_lang(mut self, lang: Lang, region: Option<Region>) -> Self {
+        self.lang = Some((lang, region));
+        self
+    }
+
+    /// Compile a source file into a fully layouted document.
+    ///
+    /// Behaves like [`compile`], but with the compiler's configuration.
+    pub fn compile(
+        &self,
+        world: &dyn World,
+        tracer: &mut Tracer,
+    ) -> SourceResult<Document> {
+        self.compile_cancellable(world, tracer, &CancelToken::new())
+    }
+
+    /// Compile a source file like [`compile`](Self::compile), but fail with an
+    /// error once the given token is cancelled.
+    ///
+    /// Each compilation should get its own token, so that cancelling a stale
+    /// compilation cannot affect the one that replaces it.
+    pub fn compile_cancellable(
+        &self,
+        world: &dyn World,
+        tracer: &mut Tracer,
+        token: &CancelToken,
+    ) -> SourceResult<Document> {
+        let recorder = Recorder::new(world);
+        let mut result = self.compile_impl(&recorder, tracer, token);
+        tracer.set_dependencies(recorder.finish());
+
+        if let Some((lang, region)) = self.lang {
+            crate::diag::localize(tracer.warnings_mut(), lang, region);
+            if let Err(errors) = &mut result {
+                crate::diag::localize(errors.make_mut(), lang, region);
+            }
+        }
+
+        result
+    }
+
+    /// Compile a source file like [`compile`](Self::compile) and record how
+    /// long the compilation spent in its phases.
+    ///
+    /// The returned profile can be turned into a flamegraph, see
+    /// [`Profile::collapsed`](crate::profile::Profile::collapsed).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn profile(
+        &self,
+        world: &dyn World,
+        tracer: &mut Tracer,
+    ) -> (SourceResult<Document>, crate::profile::Profile) {
+        let profiler = Arc::new(crate::profile::Profiler::default());
+        let result = tracing::subscriber::with_default(profiler.clone(), || {
+            self.compile(world, tracer)
+        });
+        (result, profiler.finish())
+    }
+
+    fn compile_impl(
+        &self,
+        world: &dyn World,
+        tracer: &mut Tracer,
+        token: &CancelToken,
+    ) -> SourceResult<Document> {
+        // Call `track` on the world just once to keep comemo's ID stable.
+        let world = world.track();
+
+        // Try to evaluate the source file into a module.
+        self.enter(token, Phase::Eval)?;
+        let module = crate::eval::eval(
+            world,
+            Route::default().track(),
+            tracer.track_mut(),
+            &world.main(),
+        )
+        .map_err(deduplicate)?;
+
+        // Typeset the module's content, relayouting until convergence.
+        let result = typeset(self, token, world, tracer, &module.content());
+
+        // Drop warnings that were suppressed in the source code.
+        tracer.retain_warnings(|warning| !is_allowed(world, warning));
+
+        result.map_err(deduplicate)
+    }
+
+    /// Report the start of a phase, unless we were cancelled.
+    fn enter(&self, token: &CancelToken, phase: Phase) -> SourceResult<()> {
+        if token.is_cancelled() {
+            bail!(error!(
+                Span::detached(), "compilation was cancelled";
+                hint: "a newer compilation was probably started"
+            )
+            .with_code("compilation-cancelled"));
+        }
+
+        if let Some(progress) = &self.progress {
+            progress(phase);
+        }
+
+        Ok(())
+    }
+}
+
+impl Debug for Compiler {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Compiler")
+            .field("lang", &self.lang)
+            .field("max_passes", &self.max_passes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Cancels a running compilation.
+///
+/// Clones of a token share their state, so one clone can be handed to the
+/// compilation while another one cancels it.
+#[derive(Debug, Default, Clone)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the cancellation of the compilation.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A phase of compilation, as reported by [`Compiler::with_progress`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Phase {
+    /// The main file is evaluated (parsing happens on demand in this phase).
+    Eval,
+    /// The content is layouted. There can be multiple iterations until
+    /// introspection converges, which are counted from zero.
+    Layout(usize),
 }
 
 /// Relayout until introspection converges.
 fn typeset(
+    compiler: &Compiler,
+    token: &CancelToken,
     world: Tracked<dyn World + '_>,
     tracer: &mut Tracer,
     content: &Content,
@@ -110,13 +342,16 @@ fn typeset(
     let library = world.library();
     let styles = StyleChain::new(&library.styles);
 
+    let max = compiler.max_passes.unwrap_or(Compiler::MAX_PASSES);
     let mut iter = 0;
     let mut document = Document::default();
+    tracer.set_passes(0);
 
     // Relayout until all introspections stabilize.
-    // If that doesn't happen within five attempts, we give up.
+    // If that doesn't happen within the maximum number of passes, we give up.
     loop {
         tracing::info!("Layout iteration {iter}");
+        compiler.enter(token, Phase::Layout(iter))?;
 
         // Clear delayed errors.
         tracer.delayed();
@@ -135,20 +370,26 @@ fn typeset(
         document = content.layout_root(&mut engine, styles)?;
         document.introspector.rebuild(&document.pages);
         iter += 1;
+        tracer.set_passes(iter);
 
         if document.introspector.validate(&constraint) {
             break;
         }
 
-        if iter >= 5 {
-            tracer.warn(warning!(
-                Span::detached(), "layout did not converge within 5 attempts";
-                hint: "check if any states or queries are updating themselves"
-            ));
+        if iter >= max {
+            tracer.warn(
+                warning!(
+                    Span::detached(), "layout did not converge within {max} attempts";
+                    hint: "check if any states or queries are updating themselves"
+                )
+                .with_code("layout-did-not-converge"),
+            );
             break;
         }
     }
 
+    model::warn_unlisted_endnotes(&document.introspector, tracer);
+
     // Promote delayed errors.
     let delayed = tracer.delayed();
     if !delayed.is_empty() {
@@ -158,6 +399,48 @@ fn typeset(
     Ok(document)
 }
 
+/// Whether a warning was suppressed through an `// @allow(code)` comment in
+/// the line of the warning or the line before it.
+///
+/// Multiple codes can be allowed at once, separated by commas:
+/// `// @allow(unknown-font-family, empty-strong)`.
+fn is_allowed(world: Tracked<dyn World + '_>, warning: &SourceDiagnostic) -> bool {
+    let Some(code) = warning.code else { return false };
+    let Some(id) = warning.span.id() else { return false };
+    let Ok(source) = world.source(id) else { return false };
+    let Some(line) = source
+        .range(warning.span)
+        .and_then(|range| source.byte_to_line(range.start))
+    else {
+        return false;
+    };
+
+    let root = LinkedNode::new(source.root());
+    [line.checked_sub(1), Some(line)].into_iter().flatten().any(|line| {
+        source
+            .line_to_range(line)
+            .is_some_and(|range| allows(&root, &range, code))
+    })
+}
+
+/// Whether a line comment starting in the given range of the node allows the
+/// code.
+fn allows(node: &LinkedNode, range: &Range<usize>, code: &str) -> bool {
+    if node.range().end <= range.start || node.offset() >= range.end {
+        return false;
+    }
+
+    if node.kind() == SyntaxKind::LineComment {
+        let Some(rest) = node.text()[2..].trim_start().strip_prefix("@allow(") else {
+            return false;
+        };
+        let Some((codes, _)) = rest.split_once(')') else { return false };
+        return codes.split(',').any(|allowed| allowed.trim() == code);
+    }
+
+    node.children().any(|child| allows(&child, range, code))
+}
+
 /// Deduplicate diagnostics.
 fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic> {
     let mut unique = HashSet::new();
@@ -183,6 +466,16 @@ fn deduplicate(mut diags: EcoVec<SourceDiagnostic>) -> EcoVec<SourceDiagnostic>
 /// clients like language servers can also retain the source files and
 /// [edit](Source::edit) them in-place to benefit from better incremental
 /// performance.
+///
+/// The world is the compiler's only connection to its environment: It
+/// doesn't touch the file system, the clock, or the network by itself. This
+/// means that embedders (the CLI, a language server, a web playground, or the
+/// documentation generator) are free to serve sources, files, and fonts from
+/// memory or from a virtual file system. This also includes packages: A
+/// [`FileId`] can belong to a package and it's up to the world how to resolve
+/// it, e.g. by downloading the package or by looking it up in a bundle.
+///
+/// [`FileId`]: syntax::FileId
 #[comemo::track]
 pub trait World {
     /// The standard library.
@@ -223,6 +516,83 @@ pub trait World {
     fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
         &[]
     }
+
+    /// A list of the files in the project.
+    ///
+    /// This function is optional to implement. It enhances the user experience
+    /// by enabling autocompletion for file paths, e.g. in imports and images.
+    fn files(&self) -> Vec<FileId> {
+        vec![]
+    }
+}
+
+/// A world that records which files and fonts are accessed through it.
+///
+/// Memoized calls revalidate their constraints against the world, so
+/// accesses are recorded even when results come from the cache.
+struct Recorder<'a> {
+    world: &'a dyn World,
+    dependencies: Mutex<IndexSet<Dependency>>,
+}
+
+impl<'a> Recorder<'a> {
+    /// Wrap a world.
+    fn new(world: &'a dyn World) -> Self {
+        Self { world, dependencies: Mutex::new(IndexSet::new()) }
+    }
+
+    /// Note an access.
+    fn record(&self, dependency: Dependency) {
+        self.dependencies.lock().unwrap().insert(dependency);
+    }
+
+    /// Return everything that was accessed.
+    fn finish(self) -> EcoVec<Dependency> {
+        self.dependencies.into_inner().unwrap().into_iter().collect()
+    }
+}
+
+impl World for Recorder<'_> {
+    fn library(&self) -> &Prehashed<Library> {
+        self.world.library()
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        self.world.book()
+    }
+
+    fn main(&self) -> Source {
+        let main = self.world.main();
+        self.record(Dependency::File(main.id()));
+        main
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        self.record(Dependency::File(id));
+        self.world.source(id)
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        self.record(Dependency::File(id));
+        self.world.file(id)
+    }
+
+    fn font(&self, index: usize) -> Option<Font> {
+        self.record(Dependency::Font(index));
+        self.world.font(index)
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        self.world.today(offset)
+    }
+
+    fn packages(&self) -> &[(PackageSpec, Option<EcoString>)] {
+        self.world.packages()
+    }
+
+    fn files(&self) -> Vec<FileId> {
+        self.world.files()
+    }
 }
 
 /// Helper methods on [`World`] implementations.
@@ -254,9 +624,12 @@ pub struct Library {
 impl Library {
     /// Construct the standard library.
     pub fn build() -> Self {
-        let math = math::module();
-        let global = global(math.clone());
-        Self { global, math, styles: Styles::new() }
+        Self::builder().build()
+    }
+
+    /// Create a new builder for a library.
+    pub fn builder() -> LibraryBuilder {
+        LibraryBuilder::default()
     }
 }
 
@@ -266,12 +639,47 @@ impl Default for Library {
     }
 }
 
+/// Configurable builder for the standard library.
+///
+/// This struct is created by [`Library::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct LibraryBuilder {
+    inputs: Option<Dict>,
+    target: Target,
+}
+
+impl LibraryBuilder {
+    /// Configure the inputs visible through `sys.inputs`.
+    pub fn with_inputs(mut self, inputs: Dict) -> Self {
+        self.inputs = Some(inputs);
+        self
+    }
+
+    /// Configure the export target visible through `sys.target`.
+    ///
+    /// Defaults to [`Target::Pdf`].
+    pub fn with_target(mut self, target: Target) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Consumes the builder and returns a `Library`.
+    pub fn build(self) -> Library {
+        let math = math::module();
+        let inputs = self.inputs.unwrap_or_default();
+        let global = global(math.clone(), inputs, self.target);
+        Library { global, math, styles: Styles::new() }
+    }
+}
+
 /// Construct the module with global definitions.
 #[tracing::instrument(skip_all)]
-fn global(math: Module) -> Module {
+fn global(math: Module, inputs: Dict, target: Target) -> Module {
     let mut global = Scope::deduplicating();
-    self::foundations::define(&mut global);
+    self::foundations::define(&mut global, inputs, target);
     self::model::define(&mut global);
+    global.define_module(self::pdf::module());
+    global.define_module(self::pdf::form());
     self::text::define(&mut global);
     global.reset_category();
     global.define_module(math);
@@ -56,10 +56,11 @@ impl csv {
         delimiter: Delimiter,
     ) -> SourceResult<Array> {
         let Spanned { v: data, span } = data;
+        let text = data.as_text().at(span)?;
         let mut builder = ::csv::ReaderBuilder::new();
         builder.has_headers(false);
         builder.delimiter(delimiter.0 as u8);
-        let mut reader = builder.from_reader(data.as_slice());
+        let mut reader = builder.from_reader(text.as_bytes());
         let mut array = Array::new();
 
         for (line, result) in reader.records().enumerate() {
@@ -66,7 +66,8 @@ impl json {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_json::from_slice(data.as_slice())
+        let text = data.as_text().at(span)?;
+        serde_json::from_str(text)
             .map_err(|err| eco_format!("failed to parse JSON ({err})"))
             .at(span)
     }
@@ -23,12 +23,17 @@ pub use self::toml_::*;
 pub use self::xml_::*;
 pub use self::yaml_::*;
 
+use crate::diag::StrResult;
 use crate::foundations::{cast, category, Bytes, Category, Scope, Str};
 
 /// Data loading from external files.
 ///
 /// These functions help you with loading and embedding data, for example from
 /// the results of an experiment.
+///
+/// Text-based formats are decoded as UTF-8. To load a file that is encoded as
+/// GBK instead, first [read]($read) it with `{encoding: "gbk"}` and then pass
+/// the resulting string to the loading function.
 #[category]
 pub static DATA_LOADING: Category;
 
@@ -54,12 +59,27 @@ pub enum Readable {
 }
 
 impl Readable {
-    fn as_slice(&self) -> &[u8] {
+    /// The data as raw bytes, with strings encoded as UTF-8.
+    pub fn as_slice(&self) -> &[u8] {
         match self {
             Readable::Bytes(v) => v,
             Readable::Str(v) => v.as_bytes(),
         }
     }
+
+    /// The data as text.
+    ///
+    /// Bytes are decoded as UTF-8, skipping a byte order mark. Other encodings
+    /// are only decoded when asked for through the `read` function.
+    fn as_text(&self) -> StrResult<&str> {
+        let data = match self {
+            Readable::Str(v) => return Ok(v.as_str()),
+            Readable::Bytes(v) => v.as_slice(),
+        };
+
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        std::str::from_utf8(data).map_err(|_| "file is not valid utf-8".into())
+    }
 }
 
 cast! {
@@ -10,6 +10,8 @@ use crate::World;
 /// Reads plain text or data from a file.
 ///
 /// By default, the file will be read as UTF-8 and returned as a [string]($str).
+/// Files saved by older tools on Chinese systems are often encoded as GBK
+/// instead, which you can read with `{encoding: "gbk"}`.
 ///
 /// If you specify `{encoding: none}`, this returns raw [bytes]($bytes) instead.
 ///
@@ -46,6 +48,13 @@ pub fn read(
                 .at(span)?
                 .into(),
         ),
+        Some(Encoding::Gbk) => Readable::Str(
+            encoding_rs::GBK
+                .decode_without_bom_handling_and_without_replacement(&data)
+                .ok_or("file is not valid gbk")
+                .at(span)?
+                .into(),
+        ),
     })
 }
 
@@ -54,4 +63,6 @@ pub fn read(
 pub enum Encoding {
     /// The Unicode UTF-8 encoding.
     Utf8,
+    /// The GBK encoding for simplified Chinese, a superset of GB 2312.
+    Gbk,
 }
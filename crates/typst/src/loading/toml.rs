@@ -49,9 +49,7 @@ impl toml {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        let raw = std::str::from_utf8(data.as_slice())
-            .map_err(|_| "file is not valid utf-8")
-            .at(span)?;
+        let raw = data.as_text().at(span)?;
         ::toml::from_str(raw)
             .map_err(|err| format_toml_error(err, raw))
             .at(span)
@@ -1,6 +1,6 @@
 use ecow::EcoString;
 
-use crate::diag::{format_xml_like_error, At, FileError, SourceResult};
+use crate::diag::{format_xml_like_error, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{dict, func, scope, Array, Dict, IntoValue, Str, Value};
 use crate::loading::Readable;
@@ -77,9 +77,7 @@ impl xml {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        let text = std::str::from_utf8(data.as_slice())
-            .map_err(FileError::from)
-            .at(span)?;
+        let text = data.as_text().at(span)?;
         let document =
             roxmltree::Document::parse(text).map_err(format_xml_error).at(span)?;
         Ok(convert_xml(document.root()))
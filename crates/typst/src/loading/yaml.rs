@@ -58,7 +58,8 @@ impl yaml {
         data: Spanned<Readable>,
     ) -> SourceResult<Value> {
         let Spanned { v: data, span } = data;
-        serde_yaml::from_slice(data.as_slice())
+        let text = data.as_text().at(span)?;
+        serde_yaml::from_str(text)
             .map_err(|err| eco_format!("failed to parse YAML ({err})"))
             .at(span)
     }
@@ -202,9 +202,11 @@ impl Layout for EquationElem {
         } else {
             let font_size = TextElem::size_in(styles);
             let slack = ParElem::leading_in(styles) * 0.7;
-            let top_edge = TextElem::top_edge_in(styles).resolve(font_size, &font, None);
-            let bottom_edge =
-                -TextElem::bottom_edge_in(styles).resolve(font_size, &font, None);
+            let overrides = TextElem::metrics_in(styles);
+            let top_edge =
+                TextElem::top_edge_in(styles).resolve(font_size, &font, &overrides, None);
+            let bottom_edge = -TextElem::bottom_edge_in(styles)
+                .resolve(font_size, &font, &overrides, None);
 
             let ascent = top_edge.max(frame.ascent() - slack);
             let descent = bottom_edge.max(frame.descent() - slack);
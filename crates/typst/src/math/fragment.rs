@@ -11,7 +11,7 @@ use crate::layout::{Abs, Corner, Em, Frame, FrameItem, Point, Size};
 use crate::math::{Limits, MathContext, MathStyle, Scaled};
 use crate::syntax::Span;
 use crate::text::{Font, Glyph, Lang, TextElem, TextItem};
-use crate::visualize::Paint;
+use crate::visualize::{FixedStroke, Paint, Stroke};
 
 #[derive(Debug, Clone)]
 pub enum MathFragment {
@@ -194,6 +194,7 @@ pub struct GlyphFragment {
     pub font: Font,
     pub lang: Lang,
     pub fill: Paint,
+    pub stroke: Option<FixedStroke>,
     pub shift: Abs,
     pub width: Abs,
     pub ascent: Abs,
@@ -233,6 +234,7 @@ impl GlyphFragment {
             font: ctx.font.clone(),
             lang: TextElem::lang_in(ctx.styles()),
             fill: TextElem::fill_in(ctx.styles()).as_decoration(),
+            stroke: TextElem::stroke_in(ctx.styles()).map(Stroke::unwrap_or_default),
             shift: TextElem::baseline_in(ctx.styles()),
             style: ctx.style,
             font_size: ctx.size,
@@ -306,6 +308,7 @@ impl GlyphFragment {
             font: self.font.clone(),
             size: self.font_size,
             fill: self.fill,
+            stroke: self.stroke,
             lang: self.lang,
             text: self.c.into(),
             glyphs: vec![Glyph {
@@ -0,0 +1,605 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::eval::{eval_string, EvalMode};
+use crate::foundations::{func, Content, NativeElement, Scope, Str};
+use crate::math::EquationElem;
+use crate::syntax::Spanned;
+
+/// Converts a LaTeX formula into an equation.
+///
+/// This helps with moving existing documents to Typst: formulas can be
+/// pasted as they are instead of being rewritten by hand. The most common
+/// LaTeX math commands are supported, including fractions, roots, scripts,
+/// Greek letters, operators, arrows, accents, font styles, `\text`, and the
+/// `matrix`, `pmatrix`, `bmatrix`, `vmatrix`, `cases`, and `aligned`
+/// environments. Commands that Typst knows by the same name, like `\sin` or
+/// `\alpha`, are passed through.
+///
+/// Since backslashes need to be escaped in strings, raw text is the most
+/// convenient way to write the formula.
+///
+/// # Example
+/// ```example
+/// #math.latex(`x = \frac{-b \pm \sqrt{b^2 - 4ac}}{2a}`.text)
+///
+/// #math.latex(block: true, `
+///   f(x) = \begin{cases}
+///     x^2 & \text{若 } x \geq 0 \\
+///     -x & \text{否则}
+///   \end{cases}
+/// `.text)
+/// ```
+#[func(title = "LaTeX")]
+pub fn latex(
+    /// The engine.
+    engine: &mut Engine,
+    /// The LaTeX formula, without surrounding dollar signs.
+    source: Spanned<Str>,
+    /// Whether the equation is displayed as a separate block.
+    #[named]
+    #[default(false)]
+    block: bool,
+) -> SourceResult<Content> {
+    let typst = convert(&source.v).at(source.span)?;
+    let span = source.span;
+    let content =
+        eval_string(engine.world, &typst, span, EvalMode::Math, Scope::new())?.display();
+    Ok(match content.to::<EquationElem>() {
+        Some(equation) if block => {
+            EquationElem::new(equation.body().clone()).with_block(true).pack()
+        }
+        _ => content,
+    })
+}
+
+/// Convert a LaTeX formula into Typst math markup.
+fn convert(source: &str) -> StrResult<EcoString> {
+    let mut converter = Converter {
+        src: source,
+        cursor: 0,
+        out: EcoString::new(),
+        env: Env::None,
+    };
+    converter.seq(Stop::Eof)?;
+    Ok(converter.out)
+}
+
+/// Converts LaTeX math into Typst math markup.
+struct Converter<'a> {
+    /// The LaTeX source.
+    src: &'a str,
+    /// The byte position in the source.
+    cursor: usize,
+    /// The Typst markup written so far.
+    out: EcoString,
+    /// The innermost environment, which decides how `&` and `\\` are written.
+    env: Env,
+}
+
+/// Where a sequence of LaTeX math ends.
+#[derive(Copy, Clone)]
+enum Stop<'a> {
+    /// At the end of the source.
+    Eof,
+    /// At a closing brace or bracket.
+    Char(char),
+    /// At the end of an environment.
+    End(&'a str),
+}
+
+/// A LaTeX environment that affects how alignment points and line breaks are
+/// written.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Env {
+    /// No environment, or one that has alignment points and line breaks.
+    None,
+    /// A matrix, in which `&` separates cells and `\\` rows.
+    Matrix,
+    /// Cases, in which `\\` separates the cases.
+    Cases,
+}
+
+impl Converter<'_> {
+    /// Convert items until the sequence ends.
+    fn seq(&mut self, stop: Stop) -> StrResult<()> {
+        loop {
+            self.skip_trivia();
+            if let Stop::End(name) = stop {
+                if self.rest().starts_with("\\end") {
+                    self.cursor += "\\end".len();
+                    let end = self.raw_arg()?;
+                    if end != name {
+                        bail!("expected `\\end{{{name}}}`, found `\\end{{{end}}}`");
+                    }
+                    return Ok(());
+                }
+            }
+
+            match (self.peek(), stop) {
+                (None, Stop::Eof) => return Ok(()),
+                (None, Stop::Char(c)) => bail!("expected `{c}`"),
+                (None, Stop::End(name)) => bail!("expected `\\end{{{name}}}`"),
+                (Some(c), Stop::Char(end)) if c == end => {
+                    self.eat();
+                    return Ok(());
+                }
+                _ => self.item()?,
+            }
+        }
+    }
+
+    /// Convert a single item.
+    fn item(&mut self) -> StrResult<()> {
+        let Some(c) = self.eat() else { return Ok(()) };
+        match c {
+            '\\' => self.command()?,
+            '{' => {
+                let group = self.nested(|s| s.seq(Stop::Char('}')))?;
+                self.skip_trivia();
+                if group.is_empty() {
+                    self.push("\"\"");
+                } else if group.contains(' ') && matches!(self.peek(), Some('^' | '_')) {
+                    // Typst would attach the script to the group's last token
+                    // only.
+                    self.push(&eco_format!("({group})"));
+                } else {
+                    self.push(&group);
+                }
+            }
+            '}' => bail!("unexpected `}}`"),
+            '^' | '_' => {
+                let arg = self.arg()?;
+                if self.out.is_empty() {
+                    self.out.push_str("\"\"");
+                }
+                self.out.push(c);
+                self.out.push('(');
+                self.out.push_str(&arg);
+                self.out.push(')');
+            }
+            '\'' => self.out.push('\''),
+            '&' if self.env == Env::Matrix => self.out.push(','),
+            '~' => self.push("space"),
+            '0'..='9' => {
+                let start = self.cursor - 1;
+                while let Some(c) = self.peek() {
+                    let dot = c == '.'
+                        && self.rest()[1..].starts_with(|c: char| c.is_ascii_digit());
+                    if !c.is_ascii_digit() && !dot {
+                        break;
+                    }
+                    self.eat();
+                }
+                let src = self.src;
+                self.push(&src[start..self.cursor]);
+            }
+            c if c.is_ascii_alphabetic() => self.push(c.encode_utf8(&mut [0; 4])),
+            c if c.is_alphabetic() => {
+                // Typst would read a run of letters as a variable name, so
+                // write them as text.
+                let start = self.cursor - c.len_utf8();
+                while self.peek().is_some_and(|c| c.is_alphabetic() && !c.is_ascii()) {
+                    self.eat();
+                }
+                let text = quote(&self.src[start..self.cursor]);
+                self.push(&text);
+            }
+            ',' | ';' | '/' | '"' | '#' | '$' | '@' | '*' | '`' => {
+                self.push(&eco_format!("\\{c}"));
+            }
+            c => self.push(c.encode_utf8(&mut [0; 4])),
+        }
+        Ok(())
+    }
+
+    /// Convert a command after its backslash.
+    fn command(&mut self) -> StrResult<()> {
+        let start = self.cursor;
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                while self.peek().is_some_and(|c| c.is_ascii_alphabetic()) {
+                    self.eat();
+                }
+            }
+            Some(_) => {
+                self.eat();
+            }
+            None => bail!("expected command after `\\`"),
+        }
+
+        let src = self.src;
+        let name = &src[start..self.cursor];
+        match name {
+            "\\" => match self.env {
+                Env::Matrix => self.out.push(';'),
+                Env::Cases => self.out.push(','),
+                Env::None => self.push("\\"),
+            },
+            "," => self.push("thin"),
+            ":" | ">" => self.push("med"),
+            ";" => self.push("thick"),
+            " " => self.push("space"),
+            "!" => {}
+            "{" | "}" => self.push(name),
+            "|" => self.push("‖"),
+            "%" | "&" | "_" | "#" | "$" => self.push(&eco_format!("\\{name}")),
+            "frac" | "dfrac" | "tfrac" | "cfrac" => self.call("frac", 2)?,
+            "binom" | "dbinom" | "tbinom" => self.call("binom", 2)?,
+            "sqrt" => {
+                self.skip_trivia();
+                if self.peek() == Some('[') {
+                    self.eat();
+                    let index = self.nested(|s| s.seq(Stop::Char(']')))?;
+                    let radicand = self.arg()?;
+                    self.push(&eco_format!("root({index}, {radicand})"));
+                } else {
+                    self.call("sqrt", 1)?;
+                }
+            }
+            "overset" | "stackrel" => {
+                let top = self.arg()?;
+                let base = self.arg()?;
+                self.push(&eco_format!("attach({base}, t: {top})"));
+            }
+            "underset" => {
+                let bottom = self.arg()?;
+                let base = self.arg()?;
+                self.push(&eco_format!("attach({base}, b: {bottom})"));
+            }
+            "text" | "textrm" | "textnormal" | "mbox" | "textup" => {
+                let text = quote(&self.raw_arg()?);
+                self.push(&text);
+            }
+            "textbf" => {
+                let text = quote(&self.raw_arg()?);
+                self.push(&eco_format!("bold({text})"));
+            }
+            "textit" => {
+                let text = quote(&self.raw_arg()?);
+                self.push(&eco_format!("italic({text})"));
+            }
+            "operatorname" => {
+                let text = quote(&self.raw_arg()?);
+                self.push(&eco_format!("op({text})"));
+            }
+            "not" => {
+                self.skip_trivia();
+                let negated = self.nested(Self::item)?;
+                if negated.is_empty() {
+                    bail!("expected symbol after `\\not`");
+                }
+                match negation(&negated) {
+                    Some(symbol) => self.push(symbol),
+                    None => self.push(&eco_format!("cancel({negated})")),
+                }
+            }
+            "begin" => self.environment()?,
+            "end" => bail!("unexpected `\\end`"),
+            // Typst sizes delimiters automatically.
+            "left" | "right" | "big" | "Big" | "bigg" | "Bigg" | "bigl" | "bigr"
+            | "Bigl" | "Bigr" | "biggl" | "biggr" | "Biggl" | "Biggr" => {
+                self.skip_trivia();
+                if self.rest().starts_with('.') {
+                    self.eat();
+                }
+            }
+            "displaystyle" | "textstyle" | "scriptstyle" | "limits" | "nolimits"
+            | "nonumber" | "notag" => {}
+            _ => {
+                if let Some(func) = function(name) {
+                    self.call(func, 1)?;
+                } else {
+                    self.push(symbol(name).unwrap_or(name));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert an environment after `\begin`.
+    fn environment(&mut self) -> StrResult<()> {
+        let name = self.raw_arg()?;
+        let (env, call) = match name.as_str() {
+            "matrix" | "smallmatrix" => (Env::Matrix, "mat(delim: #none, "),
+            "pmatrix" => (Env::Matrix, "mat(delim: \"(\", "),
+            "bmatrix" => (Env::Matrix, "mat(delim: \"[\", "),
+            "Bmatrix" => (Env::Matrix, "mat(delim: \"{\", "),
+            "vmatrix" => (Env::Matrix, "mat(delim: \"|\", "),
+            "Vmatrix" => (Env::Matrix, "mat(delim: \"||\", "),
+            "array" => {
+                // Skip the column specification.
+                self.raw_arg()?;
+                (Env::Matrix, "mat(delim: #none, ")
+            }
+            "cases" => (Env::Cases, "cases("),
+            "aligned" | "align" | "align*" | "gathered" | "gather" | "gather*"
+            | "split" | "equation" | "equation*" | "multline" | "multline*" => {
+                (Env::None, "")
+            }
+            _ => bail!("unsupported LaTeX environment `{name}`"),
+        };
+
+        let prev = std::mem::replace(&mut self.env, env);
+        let body = self.nested(|s| s.seq(Stop::End(&name)));
+        self.env = prev;
+
+        // Drop a trailing line break.
+        let mut body = body?;
+        let trimmed = body.trim_end_matches([' ', ';', ',', '\\']).len();
+        body.truncate(trimmed);
+
+        if call.is_empty() {
+            self.push(&body);
+        } else {
+            self.push(&eco_format!("{call}{body})"));
+        }
+
+        Ok(())
+    }
+
+    /// Convert the arguments of a command into a call of a Typst function.
+    fn call(&mut self, func: &str, count: usize) -> StrResult<()> {
+        let mut call = EcoString::from(func);
+        call.push('(');
+        for i in 0..count {
+            if i > 0 {
+                call.push_str(", ");
+            }
+            call.push_str(&self.arg()?);
+        }
+        call.push(')');
+        self.push(&call);
+        Ok(())
+    }
+
+    /// Convert the argument of a command or script: a group or a single item.
+    fn arg(&mut self) -> StrResult<EcoString> {
+        self.skip_trivia();
+        match self.peek() {
+            Some('{') => {
+                self.eat();
+                self.nested(|s| s.seq(Stop::Char('}')))
+            }
+            Some(_) => self.nested(Self::item),
+            None => bail!("expected argument"),
+        }
+    }
+
+    /// Read a group of text as it is, like the argument of `\text`.
+    fn raw_arg(&mut self) -> StrResult<EcoString> {
+        self.skip_trivia();
+        if self.peek() != Some('{') {
+            bail!("expected `{{`");
+        }
+
+        self.eat();
+        let start = self.cursor;
+        let mut depth = 0;
+        loop {
+            match self.eat() {
+                Some('{') => depth += 1,
+                Some('}') if depth == 0 => break,
+                Some('}') => depth -= 1,
+                Some('\\') => {
+                    self.eat();
+                }
+                Some(_) => {}
+                None => bail!("expected `}}`"),
+            }
+        }
+
+        Ok(self.src[start..self.cursor - 1].into())
+    }
+
+    /// Convert into a fresh output and return it.
+    fn nested(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> StrResult<()>,
+    ) -> StrResult<EcoString> {
+        let prev = std::mem::take(&mut self.out);
+        let result = f(self);
+        let nested = std::mem::replace(&mut self.out, prev);
+        result.map(|()| nested)
+    }
+
+    /// Write a token, separated from the previous one by a space.
+    fn push(&mut self, token: &str) {
+        if token.is_empty() {
+            return;
+        }
+        if !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(token);
+    }
+
+    /// Skip whitespace and comments.
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.eat();
+                }
+                Some('%') => {
+                    while self.peek().is_some_and(|c| c != '\n') {
+                        self.eat();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// The source after the cursor.
+    fn rest(&self) -> &str {
+        &self.src[self.cursor..]
+    }
+
+    /// The next character.
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Consume the next character.
+    fn eat(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.cursor += c.len_utf8();
+        Some(c)
+    }
+}
+
+/// Write text as a Typst string literal.
+fn quote(text: &str) -> EcoString {
+    let mut quoted = EcoString::from("\"");
+    for c in text.chars() {
+        if matches!(c, '"' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The Typst function for a LaTeX command with one argument.
+fn function(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "mathbf" | "boldsymbol" | "bm" => "bold",
+        "mathrm" => "upright",
+        "mathit" => "italic",
+        "mathbb" => "bb",
+        "mathcal" | "mathscr" => "cal",
+        "mathsf" => "sans",
+        "mathtt" => "mono",
+        "mathfrak" => "frak",
+        "hat" | "widehat" => "hat",
+        "tilde" | "widetilde" => "tilde",
+        "bar" => "macron",
+        "vec" | "overrightarrow" => "arrow",
+        "dot" => "dot",
+        "ddot" => "dot.double",
+        "acute" => "acute",
+        "grave" => "grave",
+        "breve" => "breve",
+        "check" => "caron",
+        "mathring" => "circle",
+        "overline" => "overline",
+        "underline" => "underline",
+        "overbrace" => "overbrace",
+        "underbrace" => "underbrace",
+        "cancel" => "cancel",
+        _ => return None,
+    })
+}
+
+/// The negated form of a Typst symbol, for LaTeX's `\not`.
+fn negation(symbol: &str) -> Option<&'static str> {
+    Some(match symbol {
+        "=" => "eq.not",
+        "<" => "lt.not",
+        ">" => "gt.not",
+        "lt.eq" => "lt.eq.not",
+        "gt.eq" => "gt.eq.not",
+        "equiv" => "equiv.not",
+        "tilde.op" => "tilde.not",
+        "tilde.eq" => "tilde.eq.not",
+        "tilde.equiv" => "tilde.equiv.not",
+        "in" => "in.not",
+        "in.rev" => "in.rev.not",
+        "subset" => "subset.not",
+        "supset" => "supset.not",
+        "subset.eq" => "subset.eq.not",
+        "supset.eq" => "supset.eq.not",
+        "exists" => "exists.not",
+        "|" => "divides.not",
+        "parallel" => "parallel.not",
+        _ => return None,
+    })
+}
+
+/// The Typst symbol for a LaTeX command that Typst calls differently.
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "epsilon" => "epsilon.alt",
+        "varepsilon" => "epsilon",
+        "vartheta" => "theta.alt",
+        "varkappa" => "kappa.alt",
+        "varpi" => "pi.alt",
+        "varrho" => "rho.alt",
+        "varsigma" => "sigma.alt",
+        "phi" => "phi.alt",
+        "varphi" => "phi",
+        "cdot" => "dot.op",
+        "pm" => "plus.minus",
+        "mp" => "minus.plus",
+        "ast" => "ast.op",
+        "star" => "star.op",
+        "circ" => "compose",
+        "oplus" => "plus.circle",
+        "otimes" => "times.circle",
+        "odot" => "dot.circle",
+        "leq" | "le" => "lt.eq",
+        "geq" | "ge" => "gt.eq",
+        "neq" | "ne" => "eq.not",
+        "ll" => "lt.double",
+        "gg" => "gt.double",
+        "sim" => "tilde.op",
+        "simeq" => "tilde.eq",
+        "cong" => "tilde.equiv",
+        "propto" => "prop",
+        "infty" => "infinity",
+        "partial" => "diff",
+        "notin" => "in.not",
+        "ni" => "in.rev",
+        "subseteq" => "subset.eq",
+        "supseteq" => "supset.eq",
+        "cup" => "union",
+        "cap" => "sect",
+        "bigcup" => "union.big",
+        "bigcap" => "sect.big",
+        "setminus" => "without",
+        "emptyset" | "varnothing" => "nothing",
+        "nexists" => "exists.not",
+        "neg" | "lnot" => "not",
+        "land" | "wedge" => "and",
+        "lor" | "vee" => "or",
+        "to" | "rightarrow" => "arrow.r",
+        "leftarrow" | "gets" => "arrow.l",
+        "leftrightarrow" => "arrow.l.r",
+        "Rightarrow" | "implies" => "arrow.r.double",
+        "Leftarrow" => "arrow.l.double",
+        "Leftrightarrow" | "iff" => "arrow.l.r.double",
+        "longrightarrow" => "arrow.r.long",
+        "mapsto" => "arrow.r.bar",
+        "uparrow" => "arrow.t",
+        "downarrow" => "arrow.b",
+        "ldots" | "dots" => "dots.h",
+        "cdots" => "dots.h.c",
+        "vdots" => "dots.v",
+        "ddots" => "dots.down",
+        "prod" => "product",
+        "coprod" => "product.co",
+        "int" => "integral",
+        "iint" => "integral.double",
+        "iiint" => "integral.triple",
+        "oint" => "integral.cont",
+        "langle" => "angle.l",
+        "rangle" => "angle.r",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "vert" | "lvert" | "rvert" | "mid" => "|",
+        "Vert" | "lVert" | "rVert" => "‖",
+        "colon" => ":",
+        "hbar" => "planck.reduce",
+        "ddagger" => "dagger.double",
+        "triangle" => "triangle.stroked.t",
+        "bmod" => "mod",
+        "qquad" => "wide",
+        _ => return None,
+    })
+}
@@ -10,6 +10,7 @@ mod class;
 mod equation;
 mod frac;
 mod fragment;
+mod latex;
 mod lr;
 mod matrix;
 mod op;
@@ -27,6 +28,7 @@ pub use self::cancel::*;
 pub use self::class::*;
 pub use self::equation::*;
 pub use self::frac::*;
+pub use self::latex::*;
 pub use self::lr::*;
 pub use self::matrix::*;
 pub use self::op::*;
@@ -200,6 +202,7 @@ pub fn module() -> Module {
     math.define_func::<inline>();
     math.define_func::<script>();
     math.define_func::<sscript>();
+    math.define_func::<latex>();
 
     // Text operators, spacings, and symbols.
     op::define(&mut math);
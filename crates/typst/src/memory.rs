@@ -0,0 +1,197 @@
+//! Memory usage accounting and cache control.
+//!
+//! Compilation keeps results around between runs to make recompilation fast:
+//! Memoized evaluation and layout results, shaped runs of text, and whatever
+//! the [`World`] caches itself. Long-running processes, like a preview
+//! server, should call [`evict`] after each compilation and can use
+//! [`MemoryStats`] to decide when to [`flush`] everything. The cache of shaped
+//! runs is additionally bounded by [`set_shaping_limit`].
+
+use std::mem::{size_of, size_of_val};
+
+use crate::eval::{Dependency, Tracer};
+use crate::foundations::{Content, Value};
+use crate::introspection::Meta;
+use crate::layout::{Frame, FrameItem, Point};
+use crate::model::Document;
+use crate::syntax::SyntaxNode;
+use crate::World;
+
+/// Approximate memory usage of a compilation, in bytes.
+///
+/// These are estimates that count the heap allocations of the relevant data
+/// structures. Data that is shared between multiple places, like identical
+/// frames, may be counted more than once.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct MemoryStats {
+    /// The text and syntax trees of the source files the compilation
+    /// depended on.
+    pub sources: usize,
+    /// The page frames of the resulting document.
+    pub frames: usize,
+    /// The values that the document's frames keep alive for introspection,
+    /// i.e. the elements that can be queried and their fields.
+    pub values: usize,
+    /// The global cache of shaped runs of text.
+    pub shaping: usize,
+}
+
+impl MemoryStats {
+    /// Measure the memory held by the last compilation traced by `tracer`
+    /// and, if it succeeded, by its `document`.
+    ///
+    /// Values that only live during evaluation are not measured: They are
+    /// reference-counted and mostly shared with the syntax trees and the
+    /// content they produce. Only the values that outlive the compilation in
+    /// the document are counted.
+    pub fn measure(
+        world: &dyn World,
+        tracer: &Tracer,
+        document: Option<&Document>,
+    ) -> Self {
+        let sources = tracer
+            .dependencies()
+            .iter()
+            .filter_map(|dependency| match dependency {
+                Dependency::File(id) => world.source(*id).ok(),
+                Dependency::Font(_) => None,
+            })
+            .map(|source| source.text().len() + node_size(source.root()))
+            .sum();
+
+        let pages =
+            document.map(|document| document.pages.as_slice()).unwrap_or_default();
+        Self {
+            sources,
+            frames: pages.iter().map(frame_size).sum(),
+            values: pages.iter().map(frame_values_size).sum(),
+            shaping: shaping_stats().bytes,
+        }
+    }
+
+    /// The sum of all measured sizes.
+    pub fn total(&self) -> usize {
+        self.sources + self.frames + self.values + self.shaping
+    }
+}
+
+/// Statistics of the shaping cache, for profiling.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ShapingStats {
+    /// How often a run of text was found in the cache.
+    pub hits: u64,
+    /// How often a run of text had to be shaped.
+    pub misses: u64,
+    /// How many runs are currently cached.
+    pub entries: usize,
+    /// The approximate number of bytes occupied by the cached runs.
+    pub bytes: usize,
+}
+
+impl ShapingStats {
+    /// The share of lookups that were found in the cache, between zero and
+    /// one.
+    pub fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total == 0 {
+            return 0.0;
+        }
+        self.hits as f64 / total as f64
+    }
+}
+
+/// Statistics of the cache of shaped runs of text since the start of the
+/// process.
+pub fn shaping_stats() -> ShapingStats {
+    crate::layout::shaping_stats()
+}
+
+/// Limit the number of cached runs of shaped text, or lift the limit with
+/// `None`.
+///
+/// Once the limit is exceeded, the least recently used runs are dropped. By
+/// default, at most 100 000 runs are cached. This bounds the memory of
+/// long-running processes that compile many unrelated documents, where
+/// eviction by age alone keeps too many runs around.
+pub fn set_shaping_limit(max_entries: Option<usize>) {
+    crate::layout::set_shaping_cache_limit(max_entries);
+}
+
+/// Evict cached results that haven't been used in the last `max_age` calls to
+/// this function.
+///
+/// This evicts both comemo's memoization cache and the cache of shaped runs.
+/// Call it after each compilation with a small age, e.g. `10`.
+pub fn evict(max_age: usize) {
+    comemo::evict(max_age);
+    crate::layout::evict_shaping_cache(max_age);
+}
+
+/// Drop all cached results.
+///
+/// The next compilation will have to start from scratch.
+pub fn flush() {
+    evict(0);
+}
+
+/// The approximate size of a syntax tree.
+fn node_size(node: &SyntaxNode) -> usize {
+    size_of::<SyntaxNode>() + node.children().map(node_size).sum::<usize>()
+}
+
+/// The approximate size of a frame and its items.
+fn frame_size(frame: &Frame) -> usize {
+    let items = frame.items().map(|(_, item)| match item {
+        FrameItem::Group(group) => frame_size(&group.frame),
+        FrameItem::Text(text) => size_of_val(text.glyphs.as_slice()) + text.text.len(),
+        _ => 0,
+    });
+    let len = frame.items().len();
+    size_of::<Frame>() + len * size_of::<(Point, FrameItem)>() + items.sum::<usize>()
+}
+
+/// The approximate size of the values in a frame's metadata.
+fn frame_values_size(frame: &Frame) -> usize {
+    frame
+        .items()
+        .map(|(_, item)| match item {
+            FrameItem::Group(group) => frame_values_size(&group.frame),
+            FrameItem::Meta(Meta::Elem(content), _) => content_size(content),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// The approximate size of a value.
+fn value_size(value: &Value) -> usize {
+    size_of::<Value>()
+        + match value {
+            Value::Str(string) => string.len(),
+            Value::Bytes(bytes) => bytes.len(),
+            Value::Content(content) => content_size(content),
+            Value::Array(array) => array.iter().map(value_size).sum(),
+            Value::Dict(dict) => dict.iter().map(|(k, v)| k.len() + value_size(v)).sum(),
+            _ => 0,
+        }
+}
+
+/// The approximate size of an element's fields.
+fn content_size(content: &Content) -> usize {
+    content.fields().iter().map(|(k, v)| k.len() + value_size(v)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::foundations::IntoValue;
+
+    #[test]
+    fn test_value_size() {
+        let base = size_of::<Value>();
+        assert_eq!(value_size(&Value::Int(1)), base);
+        assert_eq!(value_size(&"hello".into_value()), base + 5);
+
+        let array = Value::Array(["ab".into_value(), Value::None].into_iter().collect());
+        assert_eq!(value_size(&array), 3 * base + 2);
+    }
+}
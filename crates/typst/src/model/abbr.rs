@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use comemo::Tracked;
+use ecow::EcoString;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, Content, Finalize, NativeElement, Show, Smart, Str, StyleChain,
+};
+use crate::introspection::{Introspector, Locatable, Location};
+use crate::model::{HeadingElem, TermItem, TermsElem};
+use crate::text::{Lang, LocalName, Region, TextElem};
+use crate::util::NonZeroExt;
+
+/// An abbreviation that is expanded on its first use.
+///
+/// The first time an abbreviation is used in the document, it is written out
+/// in full, followed by the abbreviation in parentheses. All later uses only
+/// show the abbreviation. The long form only needs to be given once; other
+/// uses can just refer to the abbreviation.
+///
+/// All abbreviations of a document can be listed with
+/// [`abbr.list`]($abbr.list).
+///
+/// # Example
+/// ```example
+/// #abbr("PDF", [Portable Document Format])
+/// files are widely used.
+/// Typst compiles to #abbr("PDF").
+/// ```
+#[elem(scope, title = "Abbreviation", Locatable, Show)]
+pub struct AbbrElem {
+    /// The abbreviation.
+    #[required]
+    pub short: Str,
+
+    /// The written-out form of the abbreviation. If multiple uses of the same
+    /// abbreviation have a long form, the first one is used.
+    #[positional]
+    pub long: Option<Content>,
+}
+
+#[scope]
+impl AbbrElem {
+    #[elem]
+    type AbbrList;
+}
+
+impl Show for AbbrElem {
+    #[tracing::instrument(name = "AbbrElem::show", skip_all)]
+    fn show(&self, engine: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        let uses = first_uses(engine.introspector);
+        let short = TextElem::packed(self.short().clone());
+        let Some((first, long)) = uses.get(self.short()) else {
+            // The abbreviation wasn't introspected yet.
+            return Ok(short);
+        };
+
+        let Some(long) = long.clone() else {
+            bail!(self.span(), "abbreviation `{}` has no long form", self.short());
+        };
+
+        if self.location() != Some(*first) {
+            return Ok(short);
+        }
+
+        Ok(Content::sequence([
+            long,
+            TextElem::packed(" ("),
+            short,
+            TextElem::packed(")"),
+        ]))
+    }
+}
+
+/// Find the location of the first use and the first long form of each
+/// abbreviation.
+///
+/// This is shared by all uses, so that showing them doesn't need a query per
+/// use.
+#[comemo::memoize]
+fn first_uses(
+    introspector: Tracked<Introspector>,
+) -> Arc<HashMap<Str, (Location, Option<Content>)>> {
+    let mut uses = HashMap::new();
+    for elem in introspector.query(&AbbrElem::elem().select()).iter() {
+        let abbr = elem.to::<AbbrElem>().unwrap();
+        let (_, long) = uses
+            .entry(abbr.short().clone())
+            .or_insert_with(|| (elem.location().unwrap(), None));
+        if long.is_none() {
+            *long = abbr.long(StyleChain::default());
+        }
+    }
+    Arc::new(uses)
+}
+
+/// A list of all abbreviations in the document.
+///
+/// Each abbreviation is listed once, sorted alphabetically, along with its
+/// long form.
+///
+/// ```example
+/// #abbr.list()
+///
+/// #abbr("CJK", [Chinese, Japanese, and Korean])
+/// text needs #abbr("PDF", [Portable Document Format])
+/// fonts with #abbr("CJK") glyphs.
+/// ```
+#[elem(name = "list", title = "Abbreviation List", Show, Finalize, LocalName)]
+pub struct AbbrList {
+    /// The title of the list.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the list will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl Show for AbbrList {
+    #[tracing::instrument(name = "AbbrList::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        // List each abbreviation with the first long form given for it.
+        let mut seen = HashSet::new();
+        let mut abbrs: Vec<(EcoString, Str, Content)> = vec![];
+        for elem in engine.introspector.query(&AbbrElem::elem().select()).iter() {
+            let abbr = elem.to::<AbbrElem>().unwrap();
+            let Some(long) = abbr.long(StyleChain::default()) else {
+                continue;
+            };
+            if seen.insert(abbr.short().clone()) {
+                let short = abbr.short().clone();
+                abbrs.push((short.to_lowercase().into(), short, long));
+            }
+        }
+        abbrs.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+
+        let items = abbrs
+            .into_iter()
+            .map(|(_, short, long)| TermItem::new(TextElem::packed(short), long))
+            .collect();
+        seq.push(TermsElem::new(items).pack().spanned(self.span()));
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl Finalize for AbbrList {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized.styled(HeadingElem::set_numbering(None))
+    }
+}
+
+impl LocalName for AbbrList {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::CHINESE => "缩略语",
+            Lang::DANISH => "Forkortelser",
+            Lang::DUTCH => "Afkortingen",
+            Lang::FRENCH => "Abréviations",
+            Lang::GERMAN => "Abkürzungsverzeichnis",
+            Lang::ITALIAN => "Abbreviazioni",
+            Lang::JAPANESE => "略語",
+            Lang::POLISH => "Skróty",
+            Lang::PORTUGUESE => "Abreviaturas",
+            Lang::RUSSIAN => "Сокращения",
+            Lang::SPANISH => "Abreviaturas",
+            Lang::ENGLISH | _ => "Abbreviations",
+        }
+    }
+}
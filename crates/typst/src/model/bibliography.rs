@@ -3,6 +3,7 @@ use std::ffi::OsStr;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::num::NonZeroUsize;
+use std::ops::Range;
 use std::path::Path;
 use std::sync::Arc;
 
@@ -19,7 +20,7 @@ use once_cell::sync::Lazy;
 use smallvec::{smallvec, SmallVec};
 use typed_arena::Arena;
 
-use crate::diag::{bail, error, At, FileError, SourceResult, StrResult};
+use crate::diag::{bail, error, warning, At, FileError, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::eval::{eval_string, EvalMode};
 use crate::foundations::{
@@ -125,6 +126,22 @@ pub struct BibliographyElem {
     #[default(CslStyle::from_name("ieee").unwrap())]
     pub style: CslStyle,
 
+    /// Path(s) to [CSL locale files](https://github.com/citation-style-language/locales).
+    ///
+    /// Locale files define how terms like "and", "edited by", or "page" are
+    /// written in a language. The locale matching the
+    /// [text language]($text.lang) and region is used. The given files take
+    /// precedence over the built-in locales for the same language.
+    ///
+    /// ```example
+    /// #set text(lang: "zh", region: "CN")
+    /// 如 @netwok 所述。
+    ///
+    /// #bibliography("works.bib", locales: "locales-zh-CN.xml")
+    /// ```
+    #[parse(CslLocales::parse(engine, args)?)]
+    pub locales: CslLocales,
+
     /// The loaded bibliography.
     #[internal]
     #[required]
@@ -200,6 +217,7 @@ impl Synthesize for BibliographyElem {
     fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
         self.push_full(self.full(styles));
         self.push_style(self.style(styles));
+        self.push_locales(self.locales(styles));
         self.push_lang(TextElem::lang_in(styles));
         self.push_region(TextElem::region_in(styles));
         Ok(())
@@ -336,16 +354,24 @@ impl Bibliography {
             .collect::<SourceResult<Vec<Bytes>>>()?;
 
         // Parse.
-        let bibliography = Self::load(&paths, &data).at(span)?;
+        let (bibliography, warnings) = Self::load(&paths, &data).at(span)?;
+        for message in warnings {
+            engine.tracer.warn(warning!(span, "{message}"));
+        }
 
         Ok((paths, bibliography))
     }
 
-    /// Load bibliography entries from paths.
+    /// Load bibliography entries from paths. Also returns warnings about
+    /// skipped entries.
     #[comemo::memoize]
-    fn load(paths: &BibliographyPaths, data: &[Bytes]) -> StrResult<Bibliography> {
+    fn load(
+        paths: &BibliographyPaths,
+        data: &[Bytes],
+    ) -> StrResult<(Bibliography, EcoVec<EcoString>)> {
         let mut map = IndexMap::new();
         let mut duplicates = Vec::<EcoString>::new();
+        let mut warnings = EcoVec::new();
 
         // We might have multiple bib/yaml files
         for (path, bytes) in paths.0.iter().zip(data) {
@@ -357,10 +383,10 @@ impl Bibliography {
                 .unwrap_or_default();
 
             let library = match ext.to_lowercase().as_str() {
-                "yml" | "yaml" => hayagriva::io::from_yaml_str(src)
-                    .map_err(|err| eco_format!("failed to parse YAML ({err})"))?,
-                "bib" => hayagriva::io::from_biblatex_str(src)
-                    .map_err(|errors| format_biblatex_error(path, src, errors))?,
+                "yml" | "yaml" => hayagriva::io::from_yaml_str(src).map_err(|err| {
+                    eco_format!("failed to parse YAML file ({path}: {err})")
+                })?,
+                "bib" => parse_biblatex(path, src, &mut warnings)?,
                 _ => bail!("unknown bibliography format (must be .yml/.yaml or .bib)"),
             };
 
@@ -380,10 +406,12 @@ impl Bibliography {
             bail!("duplicate bibliography keys: {}", duplicates.join(", "));
         }
 
-        Ok(Bibliography {
+        let bibliography = Bibliography {
             map: Arc::new(map),
             hash: crate::util::hash128(data),
-        })
+        };
+
+        Ok((bibliography, warnings))
     }
 
     fn has(&self, key: impl Into<PicoStr>) -> bool {
@@ -417,18 +445,79 @@ cast! {
     type Bibliography,
 }
 
-/// Format a BibLaTeX loading error.
-fn format_biblatex_error(path: &str, src: &str, errors: Vec<BibLaTeXError>) -> EcoString {
-    let Some(error) = errors.first() else {
-        return eco_format!("failed to parse BibLaTeX file ({path})");
-    };
-
-    let (span, msg) = match error {
-        BibLaTeXError::Parse(error) => (&error.span, error.kind.to_string()),
-        BibLaTeXError::Type(error) => (&error.span, error.kind.to_string()),
-    };
-    let line = src.get(..span.start).unwrap_or_default().lines().count();
-    eco_format!("failed to parse BibLaTeX file ({path}:{line}: {msg})")
+/// Parse a BibLaTeX file.
+///
+/// A malformed entry doesn't fail the whole file. Instead, it is skipped with
+/// a warning and the rest of the file is parsed again.
+fn parse_biblatex(
+    path: &str,
+    src: &str,
+    warnings: &mut EcoVec<EcoString>,
+) -> StrResult<hayagriva::Library> {
+    let mut src = src.to_string();
+    loop {
+        let errors = match hayagriva::io::from_biblatex_str(&src) {
+            Ok(library) => return Ok(library),
+            Err(errors) => errors,
+        };
+
+        let Some(error) = errors.first() else {
+            bail!("failed to parse BibLaTeX file ({path})");
+        };
+
+        let (start, msg) = match error {
+            BibLaTeXError::Parse(error) => (error.span.start, error.kind.to_string()),
+            BibLaTeXError::Type(error) => (error.span.start, error.kind.to_string()),
+        };
+        let line = src.get(..start).unwrap_or_default().lines().count();
+        let Some(range) = biblatex_entry_range(&src, start) else {
+            bail!("failed to parse BibLaTeX file ({path}:{line}: {msg})");
+        };
+
+        // Report the entry's key if it has a regular one.
+        let key = src[range.clone()]
+            .split_once('{')
+            .and_then(|(_, rest)| rest.split_once(','))
+            .map(|(key, _)| key.trim())
+            .filter(|key| !key.is_empty() && !key.contains(char::is_whitespace));
+        warnings.push(match key {
+            Some(key) => eco_format!(
+                "skipped malformed BibLaTeX entry `{key}` ({path}:{line}: {msg})"
+            ),
+            None => {
+                eco_format!("skipped malformed BibLaTeX entry ({path}:{line}: {msg})")
+            }
+        });
+
+        // Blank out the entry, keeping the line breaks so that later errors
+        // still point to the right lines.
+        let blank: String = src[range.clone()]
+            .chars()
+            .map(|c| if c == '\n' { c } else { ' ' })
+            .collect();
+        src.replace_range(range, &blank);
+    }
+}
+
+/// Find the byte range of the BibLaTeX entry that contains the given
+/// position. Entries start with an `@` at the beginning of a line.
+fn biblatex_entry_range(src: &str, pos: usize) -> Option<Range<usize>> {
+    let mut offset = 0;
+    let mut start = None;
+    let mut end = src.len();
+    for line in src.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with('@') {
+            let at = offset + line.len() - trimmed.len();
+            if at > pos {
+                end = at;
+                break;
+            }
+            start = Some(at);
+        }
+        offset += line.len();
+    }
+    start.map(|start| start..end)
 }
 
 /// A loaded CSL style.
@@ -514,6 +603,121 @@ impl CslStyle {
     }
 }
 
+/// The CSL locales that ship with the bibliography styles.
+static LOCALES: Lazy<Vec<citationberg::Locale>> = Lazy::new(hayagriva::archive::locales);
+
+/// Loaded CSL locale files.
+#[ty]
+#[derive(Debug, Default, Clone)]
+pub struct CslLocales {
+    locales: Arc<Vec<citationberg::Locale>>,
+    hash: u128,
+}
+
+impl CslLocales {
+    /// Parse the locales argument.
+    pub fn parse(engine: &mut Engine, args: &mut Args) -> SourceResult<Option<Self>> {
+        let Some(Spanned { v: paths, span }) =
+            args.named::<Spanned<BibliographyPaths>>("locales")?
+        else {
+            return Ok(None);
+        };
+
+        let data = paths
+            .0
+            .iter()
+            .map(|path| {
+                let id = span.resolve_path(path).at(span)?;
+                engine.world.file(id).at(span)
+            })
+            .collect::<SourceResult<Vec<Bytes>>>()?;
+
+        Ok(Some(Self::from_data(&data).at(span)?))
+    }
+
+    /// Load CSL locales from file contents.
+    ///
+    /// The built-in locales are added after the loaded ones, so that the
+    /// loaded ones take precedence, but terms missing from them are still
+    /// found.
+    #[comemo::memoize]
+    pub fn from_data(data: &[Bytes]) -> StrResult<Self> {
+        let mut locales = data
+            .iter()
+            .map(|bytes| {
+                let text = std::str::from_utf8(bytes).map_err(FileError::from)?;
+                citationberg::Locale::from_xml(text)
+                    .map_err(|err| eco_format!("failed to load CSL locale ({err})"))
+            })
+            .collect::<StrResult<Vec<_>>>()?;
+        locales.extend(LOCALES.iter().cloned());
+
+        Ok(Self {
+            locales: Arc::new(locales),
+            hash: crate::util::hash128(data),
+        })
+    }
+
+    /// Get the underlying locales, falling back to the built-in ones.
+    pub fn get(&self) -> &[citationberg::Locale] {
+        if self.locales.is_empty() {
+            &LOCALES
+        } else {
+            &self.locales
+        }
+    }
+}
+
+impl PartialEq for CslLocales {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Hash for CslLocales {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.hash.hash(state);
+    }
+}
+
+impl Reflect for CslLocales {
+    fn input() -> CastInfo {
+        Str::input() + Array::input()
+    }
+
+    fn output() -> CastInfo {
+        Array::output()
+    }
+
+    fn castable(value: &Value) -> bool {
+        matches!(value, Value::Dyn(dynamic) if dynamic.is::<Self>())
+    }
+}
+
+impl FromValue for CslLocales {
+    fn from_value(value: Value) -> StrResult<Self> {
+        if let Value::Dyn(dynamic) = &value {
+            if let Some(concrete) = dynamic.downcast::<Self>() {
+                return Ok(concrete.clone());
+            }
+        }
+
+        Err(<Self as Reflect>::error(&value))
+    }
+}
+
+impl IntoValue for CslLocales {
+    fn into_value(self) -> Value {
+        Value::dynamic(self)
+    }
+}
+
+impl Repr for CslLocales {
+    fn repr(&self) -> EcoString {
+        "..".into()
+    }
+}
+
 // This Reflect impl is technically a bit wrong because it doesn't say what
 // FromValue and IntoValue really do. Instead, it says what the `style` argument
 // on `bibliography` and `cite` expect (through manual parsing).
@@ -656,13 +860,13 @@ impl<'a> Generator<'a> {
 
     /// Drives hayagriva's citation driver.
     fn drive(&mut self) -> hayagriva::Rendered {
-        static LOCALES: Lazy<Vec<citationberg::Locale>> =
-            Lazy::new(hayagriva::archive::locales);
-
         let database = self.bibliography.bibliography();
         let bibliography_style = self.bibliography.style(StyleChain::default());
         let styles = Arena::new();
 
+        let locales = self.bibliography.locales(StyleChain::default());
+        let locales = locales.get();
+
         // Process all citation groups.
         let mut driver = BibliographyDriver::new();
         for elem in &self.groups {
@@ -738,7 +942,7 @@ impl<'a> Generator<'a> {
                 items,
                 style,
                 Some(locale(*first.lang(), *first.region())),
-                &LOCALES,
+                locales,
                 None,
             ));
         }
@@ -753,7 +957,7 @@ impl<'a> Generator<'a> {
                     vec![CitationItem::new(entry, None, None, true, None)],
                     bibliography_style.get(),
                     Some(locale.clone()),
-                    &LOCALES,
+                    locales,
                     None,
                 ));
             }
@@ -762,7 +966,7 @@ impl<'a> Generator<'a> {
         driver.finish(BibliographyRequest {
             style: bibliography_style.get(),
             locale: Some(locale),
-            locale_files: &LOCALES,
+            locale_files: locales,
         })
     }
 
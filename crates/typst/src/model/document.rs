@@ -1,12 +1,17 @@
+use std::num::NonZeroUsize;
+
 use ecow::EcoString;
+use serde::Serialize;
 
 use crate::diag::{bail, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Args, Array, Construct, Content, Datetime, Smart, StyleChain, Value,
+    cast, dict, elem, Args, Array, Construct, Content, Datetime, Dict, Selector, Smart,
+    StyleChain, Value,
 };
 use crate::introspection::{Introspector, ManualPageCounter};
-use crate::layout::{Frame, LayoutRoot, PageElem};
+use crate::layout::{Frame, LayoutRoot, PageElem, Position};
+use crate::syntax::Span;
 
 /// The root element of a document and its metadata.
 ///
@@ -23,7 +28,9 @@ use crate::layout::{Frame, LayoutRoot, PageElem};
 /// ```
 ///
 /// Note that metadata set with this function is not rendered within the
-/// document. Instead, it is embedded in the compiled PDF file.
+/// document. Instead, it is embedded in the compiled PDF, HTML, or EPUB file.
+/// Build tooling can also retrieve it with `typst query` and the `document`
+/// selector.
 #[elem(Construct, LayoutRoot)]
 pub struct DocumentElem {
     /// The document's title. This is often rendered as the title of the
@@ -117,7 +124,7 @@ impl LayoutRoot for DocumentElem {
 
 /// A list of authors.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
-pub struct Author(Vec<EcoString>);
+pub struct Author(pub Vec<EcoString>);
 
 cast! {
     Author,
@@ -128,7 +135,7 @@ cast! {
 
 /// A list of keywords.
 #[derive(Debug, Default, Clone, PartialEq, Hash)]
-pub struct Keywords(Vec<EcoString>);
+pub struct Keywords(pub Vec<EcoString>);
 
 cast! {
     Keywords,
@@ -154,6 +161,67 @@ pub struct Document {
     pub introspector: Introspector,
 }
 
+impl Document {
+    /// Find all elements in the document that match the selector, in document
+    /// order, together with the pages they ended up on.
+    ///
+    /// To query with a selector written in Typst syntax, first evaluate it
+    /// with [`eval_selector`](crate::eval::eval_selector).
+    pub fn query(&self, selector: &Selector) -> Vec<QueryMatch> {
+        self.introspector
+            .query(selector)
+            .into_iter()
+            .map(|elem| {
+                let elem = elem.into_inner();
+                let page = elem.location().map(|loc| self.introspector.page(loc));
+                QueryMatch { element: elem, page }
+            })
+            .collect()
+    }
+
+    /// Find the positions of everything in the document that stems from the
+    /// given span, in page order.
+    ///
+    /// This is the inverse of [`Frame::hit`] and lets editors scroll a
+    /// preview to the output of the code that is being edited.
+    pub fn position_of(&self, span: Span) -> Vec<Position> {
+        self.pages
+            .iter()
+            .enumerate()
+            .flat_map(|(i, frame)| {
+                let page = NonZeroUsize::new(i + 1).unwrap();
+                frame
+                    .positions_of(span)
+                    .into_iter()
+                    .map(move |point| Position { page, point })
+            })
+            .collect()
+    }
+
+    /// The document's metadata as set by the `document` element, with the
+    /// keys `title`, `author`, `keywords`, and `date`.
+    ///
+    /// This is what exporters embed into their output and is useful for
+    /// build tooling that needs to know about the document.
+    pub fn metadata(&self) -> Dict {
+        dict! {
+            "title" => self.title.clone(),
+            "author" => self.author.clone(),
+            "keywords" => self.keywords.clone(),
+            "date" => self.date,
+        }
+    }
+}
+
+/// An element found by a [document query](Document::query).
+#[derive(Debug, Clone, Hash, Serialize)]
+pub struct QueryMatch {
+    /// The element with all of its fields.
+    pub element: Content,
+    /// The one-based number of the page the element is on.
+    pub page: Option<NonZeroUsize>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
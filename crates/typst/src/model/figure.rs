@@ -201,6 +201,22 @@ pub struct FigureElem {
     #[default(true)]
     pub outlined: bool,
 
+    /// A text describing the figure for readers that can't see it, e.g. users
+    /// of screen readers.
+    ///
+    /// Images in the figure's body that don't have an [alternative
+    /// description]($image.alt) of their own use this one.
+    ///
+    /// ```example
+    /// #figure(
+    ///   image("molecular.jpg", width: 60%),
+    ///   alt: "Test tubes in a rack",
+    ///   caption: [Our lab's equipment],
+    /// )
+    /// ```
+    #[borrowed]
+    pub alt: Option<EcoString>,
+
     /// Convenience field to get access to the counter for this figure.
     ///
     /// The counter only depends on the `kind`:
@@ -220,6 +236,18 @@ impl FigureElem {
     type FigureCaption;
 }
 
+impl FigureElem {
+    /// The figure's body, in which images without an alternative description
+    /// of their own use the figure's.
+    pub fn described_body(&self, styles: StyleChain) -> Content {
+        let body = self.body().clone();
+        match self.alt(styles) {
+            Some(alt) => body.styled(ImageElem::set_alt(Some(alt.clone()))),
+            None => body,
+        }
+    }
+}
+
 impl Synthesize for FigureElem {
     fn synthesize(
         &mut self,
@@ -302,7 +330,7 @@ impl Synthesize for FigureElem {
 impl Show for FigureElem {
     #[tracing::instrument(name = "FigureElem::show", skip_all)]
     fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
-        let mut realized = self.body().clone();
+        let mut realized = self.described_body(styles);
 
         // Build the caption, if any.
         if let Some(caption) = self.caption(styles) {
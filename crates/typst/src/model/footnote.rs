@@ -1,16 +1,21 @@
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-use crate::diag::{bail, At, SourceResult, StrResult};
+use ecow::eco_vec;
+
+use crate::diag::{bail, warning, At, SourceResult, StrResult};
 use crate::engine::Engine;
+use crate::eval::Tracer;
 use crate::foundations::{
-    cast, elem, scope, Content, Finalize, Label, NativeElement, Show, Smart, StyleChain,
-    Synthesize,
+    cast, elem, scope, Cast, Content, Finalize, Label, NativeElement, Selector, Show,
+    Smart, StyleChain, Synthesize,
+};
+use crate::introspection::{
+    Count, Counter, CounterUpdate, Introspector, Locatable, Location,
 };
-use crate::introspection::{Count, Counter, CounterUpdate, Locatable, Location};
-use crate::layout::{Abs, Em, HElem, Length, Ratio};
-use crate::model::{Destination, Numbering, NumberingPattern, ParElem};
-use crate::text::{SuperElem, TextElem, TextSize};
+use crate::layout::{Abs, Em, HElem, Length, Ratio, VElem};
+use crate::model::{Destination, HeadingElem, Numbering, NumberingPattern, ParElem};
+use crate::text::{Lang, LocalName, Region, SuperElem, TextElem, TextSize};
 use crate::util::NonZeroExt;
 use crate::visualize::{LineElem, Stroke};
 
@@ -69,6 +74,23 @@ pub struct FootnoteElem {
     #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
     pub numbering: Numbering,
 
+    /// Where the note's content is placed.
+    ///
+    /// Endnotes are collected and listed wherever
+    /// [`footnote.endnotes`]($footnote.endnotes) is called. Typst warns about
+    /// endnotes that no listing follows, as they are not shown anywhere.
+    ///
+    /// ```example
+    /// #set footnote(placement: "end")
+    ///
+    /// 第一章 #footnote[作者自注。]
+    /// 的内容。#footnote[另见第三章。]
+    ///
+    /// #footnote.endnotes()
+    /// ```
+    #[default(FootnotePlacement::Page)]
+    pub placement: FootnotePlacement,
+
     /// The content to put into the footnote. Can also be the label of another
     /// footnote this one should point to.
     #[required]
@@ -79,6 +101,9 @@ pub struct FootnoteElem {
 impl FootnoteElem {
     #[elem]
     type FootnoteEntry;
+
+    #[elem]
+    type FootnoteEndnotes;
 }
 
 impl FootnoteElem {
@@ -123,6 +148,7 @@ impl FootnoteElem {
 impl Synthesize for FootnoteElem {
     fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
         self.push_numbering(self.numbering(styles).clone());
+        self.push_placement(self.placement(styles));
         Ok(())
     }
 }
@@ -149,6 +175,15 @@ impl Count for FootnoteElem {
     }
 }
 
+/// Where the content of a footnote is placed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FootnotePlacement {
+    /// At the bottom of the page the footnote is on.
+    Page,
+    /// In the next [endnote listing]($footnote.endnotes).
+    End,
+}
+
 /// The body of a footnote can be either some content or a label referencing
 /// another footnote.
 #[derive(Debug, Clone, PartialEq, Hash)]
@@ -307,6 +342,134 @@ impl Finalize for FootnoteEntry {
     }
 }
 
+/// A listing of endnotes.
+///
+/// Lists all footnotes with [`placement`]($footnote.placement) set to
+/// `{"end"}` since the previous endnote listing. Placing a listing at the end
+/// of each chapter gives per-chapter endnotes, while a single listing at the
+/// end of the document collects all of them. Each entry is a
+/// [`footnote.entry`]($footnote.entry) whose number links back to the note.
+///
+/// To restart the numbering in each chapter, reset the footnote
+/// [counter]($counter) after the listing.
+///
+/// ```example
+/// #set footnote(placement: "end")
+///
+/// = 第一章
+/// 正文。#footnote[第一章的注释。]
+/// #footnote.endnotes(title: [本章注释])
+/// #counter(footnote).update(0)
+///
+/// = 第二章
+/// 正文。#footnote[第二章的注释。]
+/// #footnote.endnotes(title: [本章注释])
+/// ```
+#[elem(name = "endnotes", title = "Endnotes", Locatable, Show, Finalize, LocalName)]
+pub struct FootnoteEndnotes {
+    /// The title of the listing.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the listing will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+}
+
+impl Show for FootnoteEndnotes {
+    #[tracing::instrument(name = "FootnoteEndnotes::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        // Collect the endnotes between the previous listing and this one.
+        let location = self.location().unwrap();
+        let selector =
+            Selector::Or(eco_vec![FootnoteElem::elem().select(), Self::elem().select()]);
+        let mut notes = vec![];
+        for elem in engine.introspector.query(&selector).iter() {
+            if elem.location() == Some(location) {
+                break;
+            } else if elem.is::<Self>() {
+                notes.clear();
+            } else if let Some(note) = elem.to::<FootnoteElem>() {
+                let end = note.placement(StyleChain::default()) == FootnotePlacement::End;
+                if end && !note.is_ref() {
+                    notes.push(note.clone());
+                }
+            }
+        }
+
+        let gap = FootnoteEntry::gap_in(styles);
+        for note in notes {
+            seq.push(VElem::new(gap.into()).with_weakness(3).pack());
+            seq.push(FootnoteEntry::new(note).pack().spanned(self.span()));
+        }
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+/// Warn about endnotes that no listing follows, since they would not appear
+/// anywhere.
+pub(crate) fn warn_unlisted_endnotes(introspector: &Introspector, tracer: &mut Tracer) {
+    let selector = Selector::Or(eco_vec![
+        FootnoteElem::elem().select(),
+        FootnoteEndnotes::elem().select()
+    ]);
+
+    let mut unlisted = vec![];
+    for elem in introspector.query(&selector).iter() {
+        if elem.is::<FootnoteEndnotes>() {
+            unlisted.clear();
+        } else if let Some(note) = elem.to::<FootnoteElem>() {
+            let end = note.placement(StyleChain::default()) == FootnotePlacement::End;
+            if end && !note.is_ref() {
+                unlisted.push(note.span());
+            }
+        }
+    }
+
+    for span in unlisted {
+        tracer.warn(
+            warning!(
+                span, "endnote is not listed";
+                hint: "add a `footnote.endnotes()` listing after it"
+            )
+            .with_code("unlisted-endnote"),
+        );
+    }
+}
+
+impl Finalize for FootnoteEndnotes {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized.styled(HeadingElem::set_numbering(None))
+    }
+}
+
+impl LocalName for FootnoteEndnotes {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::CHINESE if crate::util::option_eq(region, "TW") => "註釋",
+            Lang::CHINESE => "注释",
+            Lang::FRENCH => "Notes",
+            Lang::GERMAN => "Anmerkungen",
+            Lang::ITALIAN => "Note",
+            Lang::JAPANESE => "注",
+            Lang::RUSSIAN => "Примечания",
+            Lang::SPANISH => "Notas",
+            Lang::ENGLISH | _ => "Notes",
+        }
+    }
+}
+
 cast! {
     FootnoteElem,
     v: Content => v.to::<Self>().cloned().unwrap_or_else(|| Self::with_content(v.clone())),
@@ -84,7 +84,9 @@ pub struct HeadingElem {
     ///
     /// Note that this property, if set to `{true}`, ensures the heading is also
     /// shown as a bookmark in the exported PDF's outline (when exporting to
-    /// PDF). To change that behavior, use the `bookmarked` property.
+    /// PDF). To change that behavior, use the `bookmarked` property. Both the
+    /// outline entry and the bookmark start with the heading's number, if it
+    /// has one.
     ///
     /// ```example
     /// #outline()
@@ -124,6 +126,12 @@ pub struct HeadingElem {
     /// The heading's title.
     #[required]
     pub body: Content,
+
+    /// The heading's displayed number, for use in PDF bookmarks.
+    #[internal]
+    #[synthesized]
+    #[default(None)]
+    pub numbers: Option<Content>,
 }
 
 impl Synthesize for HeadingElem {
@@ -141,11 +149,24 @@ impl Synthesize for HeadingElem {
             }
         };
 
+        // Resolve the number for a bookmark, if there will be one.
+        let outlined = self.outlined(styles);
+        let bookmarked = self.bookmarked(styles);
+        let numbers = match (self.numbering(styles).as_ref(), self.location()) {
+            (Some(numbering), Some(location)) if bookmarked.unwrap_or(outlined) => Some(
+                Counter::of(Self::elem())
+                    .at(engine, location)?
+                    .display(engine, numbering)?,
+            ),
+            _ => None,
+        };
+
         self.push_level(self.level(styles));
         self.push_numbering(self.numbering(styles).clone());
         self.push_supplement(Smart::Custom(Some(Supplement::Content(supplement))));
-        self.push_outlined(self.outlined(styles));
-        self.push_bookmarked(self.bookmarked(styles));
+        self.push_outlined(outlined);
+        self.push_bookmarked(bookmarked);
+        self.push_numbers(numbers);
 
         Ok(())
     }
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::EcoString;
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, scope, Behave, Behaviour, Content, Finalize, Func, NativeElement, Show, Smart,
+    Str, StyleChain, Synthesize,
+};
+use crate::introspection::{Counter, CounterKey, Locatable, Location};
+use crate::model::{Destination, HeadingElem, NumberingPattern, ParbreakElem};
+use crate::text::{collation_key, Lang, LinebreakElem, LocalName, Region, TextElem};
+use crate::util::NonZeroExt;
+
+/// A back-of-book index.
+///
+/// Mark terms throughout the document with [`index.entry`]($index.entry).
+/// The index lists each term once, in sorted order, along with the pages on
+/// which it was marked. Each page number links to the marked place.
+///
+/// # Example
+/// ```example
+/// #index()
+///
+/// = Typesetting
+/// Typst #index.entry("Typst") is a
+/// markup-based #index.entry("markup")
+/// typesetting system.
+///
+/// #pagebreak()
+/// Typst #index.entry("Typst") compiles
+/// incrementally.
+/// ```
+///
+/// # Sorting Chinese terms
+/// Chinese terms are sorted by the built-in [pinyin]($pinyin) of their
+/// characters. As that is the most common reading of each character, terms
+/// with a polyphonic character may end up in the wrong place. Give such
+/// entries a `key` or set the index's `key` function to compute one with a
+/// dictionary:
+///
+/// ```example
+/// #let dict = ("长城": "chang2 cheng2")
+/// #index(key: term => pinyin(term, dict: dict, style: "numbers")
+///   .map(array.last)
+///   .join(" "))
+///
+/// 成都#index.entry("成都")和
+/// 长城#index.entry("长城")
+/// ```
+#[elem(scope, keywords = ["Back-of-book Index"], Show, Finalize, LocalName)]
+pub struct IndexElem {
+    /// The title of the index.
+    ///
+    /// - When set to `{auto}`, an appropriate title for the
+    ///   [text language]($text.lang) will be used. This is the default.
+    /// - When set to `{none}`, the index will not have a title.
+    /// - A custom title can be set by passing content.
+    #[default(Some(Smart::Auto))]
+    pub title: Option<Smart<Content>>,
+
+    /// A function that receives a term and returns the string used to sort
+    /// it. Entries with their own `key` don't use this function.
+    ///
+    /// When this is `{none}`, terms are sorted by themselves, with Chinese
+    /// characters replaced by their built-in pinyin.
+    pub key: Option<Func>,
+}
+
+#[scope]
+impl IndexElem {
+    #[elem]
+    type IndexEntry;
+}
+
+impl Show for IndexElem {
+    #[tracing::instrument(name = "IndexElem::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut seq = vec![ParbreakElem::new().pack()];
+        if let Some(title) = self.title(styles) {
+            let title = title.unwrap_or_else(|| {
+                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+            });
+
+            seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
+        }
+
+        // Collect the places where each term was marked, in order of their
+        // first appearance.
+        let mut terms: Vec<(Str, Option<Str>, Vec<Location>)> = vec![];
+        let mut indices = HashMap::new();
+        for elem in engine.introspector.query(&IndexEntry::elem().select()).iter() {
+            let entry = elem.to::<IndexEntry>().unwrap();
+            let location = elem.location().unwrap();
+            let term = entry.term().clone();
+            let i = *indices.entry(term.clone()).or_insert_with(|| {
+                terms.push((term, None, vec![]));
+                terms.len() - 1
+            });
+            let (_, key, locations) = &mut terms[i];
+            if key.is_none() {
+                *key = entry.key(StyleChain::default());
+            }
+            locations.push(location);
+        }
+
+        // Determine the sort keys.
+        let mut sorted = Vec::with_capacity(terms.len());
+        for (term, key, locations) in terms {
+            let key: EcoString = match (key, self.key(styles)) {
+                (Some(key), _) => key.into(),
+                (None, Some(func)) => {
+                    let key = func.call(engine, [term.clone()])?;
+                    key.cast::<Str>().at(self.span())?.into()
+                }
+                (None, None) => collation_key(&term),
+            };
+            sorted.push((key.to_lowercase(), key, term, locations));
+        }
+        sorted.sort_by(|a, b| (&a.0, &a.1, &a.2).cmp(&(&b.0, &b.1, &b.2)));
+
+        for (_, _, term, locations) in sorted {
+            seq.push(TextElem::packed(term));
+
+            // List each page only once, even if the term was marked more
+            // than once on it.
+            let mut last = None;
+            for location in locations {
+                let page = engine.introspector.page(location);
+                if last.replace(page) == Some(page) {
+                    continue;
+                }
+
+                let numbering =
+                    engine.introspector.page_numbering(location).cloned().unwrap_or_else(
+                        || NumberingPattern::from_str("1").unwrap().into(),
+                    );
+                let number = Counter::new(CounterKey::Page)
+                    .at(engine, location)?
+                    .display(engine, &numbering)?;
+
+                seq.push(TextElem::packed(", "));
+                seq.push(number.linked(Destination::Location(location)));
+            }
+
+            seq.push(LinebreakElem::new().pack());
+        }
+
+        seq.push(ParbreakElem::new().pack());
+
+        Ok(Content::sequence(seq))
+    }
+}
+
+impl Finalize for IndexElem {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        realized.styled(HeadingElem::set_numbering(None))
+    }
+}
+
+impl LocalName for IndexElem {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::CHINESE => "索引",
+            Lang::DANISH => "Indeks",
+            Lang::DUTCH => "Register",
+            Lang::FRENCH => "Index",
+            Lang::GERMAN => "Stichwortverzeichnis",
+            Lang::ITALIAN => "Indice analitico",
+            Lang::JAPANESE => "索引",
+            Lang::POLISH => "Indeks",
+            Lang::PORTUGUESE => "Índice remissivo",
+            Lang::RUSSIAN => "Предметный указатель",
+            Lang::SPANISH => "Índice alfabético",
+            Lang::ENGLISH | _ => "Index",
+        }
+    }
+}
+
+/// Marks a term for the [index]($index).
+///
+/// The entry itself is invisible. It records the page it is placed on, so
+/// put it right next to the text it belongs to.
+///
+/// ```example
+/// #index(title: none)
+///
+/// Rust #index.entry("Rust") and
+/// Typst #index.entry("Typst")
+/// ```
+#[elem(name = "entry", title = "Index Entry", Behave, Show, Synthesize, Locatable)]
+pub struct IndexEntry {
+    /// The term to list in the index. Entries with the same term are listed
+    /// together.
+    #[required]
+    pub term: Str,
+
+    /// The string to sort the term by instead of the term itself, like the
+    /// pinyin of a Chinese term. If multiple entries of the same term have a
+    /// key, the first one is used.
+    pub key: Option<Str>,
+}
+
+impl Synthesize for IndexEntry {
+    fn synthesize(&mut self, _: &mut Engine, styles: StyleChain) -> SourceResult<()> {
+        self.push_key(self.key(styles));
+        Ok(())
+    }
+}
+
+impl Show for IndexEntry {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+impl Behave for IndexEntry {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
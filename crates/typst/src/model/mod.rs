@@ -1,5 +1,6 @@
 //! Structuring elements that define the document model.
 
+mod abbr;
 mod bibliography;
 mod cite;
 mod document;
@@ -9,6 +10,7 @@ mod enum_;
 mod figure;
 mod footnote;
 mod heading;
+mod index;
 mod link;
 mod list;
 #[path = "numbering.rs"]
@@ -17,10 +19,13 @@ mod outline;
 mod par;
 mod quote;
 mod reference;
+mod solution;
 mod strong;
 mod table;
 mod terms;
+mod theorem;
 
+pub use self::abbr::*;
 pub use self::bibliography::*;
 pub use self::cite::*;
 pub use self::document::*;
@@ -29,6 +34,7 @@ pub use self::enum_::*;
 pub use self::figure::*;
 pub use self::footnote::*;
 pub use self::heading::*;
+pub use self::index::*;
 pub use self::link::*;
 pub use self::list::*;
 pub use self::numbering_::*;
@@ -36,9 +42,11 @@ pub use self::outline::*;
 pub use self::par::*;
 pub use self::quote::*;
 pub use self::reference::*;
+pub use self::solution::*;
 pub use self::strong::*;
 pub use self::table::*;
 pub use self::terms::*;
+pub use self::theorem::*;
 
 use crate::foundations::{category, Category, Scope};
 
@@ -57,8 +65,12 @@ pub fn define(global: &mut Scope) {
     global.define_elem::<RefElem>();
     global.define_elem::<LinkElem>();
     global.define_elem::<OutlineElem>();
+    global.define_elem::<IndexElem>();
+    global.define_elem::<AbbrElem>();
     global.define_elem::<HeadingElem>();
     global.define_elem::<FigureElem>();
+    global.define_elem::<TheoremElem>();
+    global.define_elem::<SolutionElem>();
     global.define_elem::<FootnoteElem>();
     global.define_elem::<QuoteElem>();
     global.define_elem::<CiteElem>();
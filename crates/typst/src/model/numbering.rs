@@ -25,6 +25,8 @@ use crate::text::Case;
 /// #numbering("1.1)", 1, 2, 3) \
 /// #numbering("1.a.i", 1, 2) \
 /// #numbering("I – 1", 12, 2) \
+/// #numbering("第一章", 12) \
+/// #numbering("（甲）", 3) \
 /// #numbering(
 ///   (..nums) => nums
 ///     .pos()
@@ -39,9 +41,14 @@ pub fn numbering(
     engine: &mut Engine,
     /// Defines how the numbering works.
     ///
-    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `い`, `イ`, `א`, `가`,
-    /// `ㄱ`, and `*`. They are replaced by the number in the sequence, in the
-    /// given case.
+    /// **Counting symbols** are `1`, `a`, `A`, `i`, `I`, `一`, `壹`, `甲`, `い`,
+    /// `イ`, `א`, `가`, `ㄱ`, and `*`. They are replaced by the number in the
+    /// sequence, in the given case.
+    ///
+    /// The `一` and `壹` characters count with Chinese numerals, in lower and
+    /// upper (financial) case respectively. The `甲` character counts with the
+    /// heavenly stems `甲`, `乙`, `丙`, `丁`, `戊`, `己`, `庚`, `辛`, `壬`, and
+    /// `癸`.
     ///
     /// The `*` character means that symbols should be used to count, in the
     /// order of `*`, `†`, `‡`, `§`, `¶`, and `‖`. If there are more than six
@@ -266,7 +273,7 @@ cast! {
             pat.push_str(prefix);
             let mut c = kind.to_char();
             if *case == Case::Upper {
-                c = c.to_ascii_uppercase();
+                c = if c == '一' { '壹' } else { c.to_ascii_uppercase() };
             }
             pat.push(c);
         }
@@ -292,6 +299,7 @@ pub enum NumberingKind {
     // character.
     #[allow(unused)]
     TraditionalChinese,
+    HeavenlyStem,
     HiraganaIroha,
     KatakanaIroha,
     KoreanJamo,
@@ -308,6 +316,7 @@ impl NumberingKind {
             '*' => NumberingKind::Symbol,
             'א' => NumberingKind::Hebrew,
             '一' | '壹' => NumberingKind::SimplifiedChinese,
+            '甲' => NumberingKind::HeavenlyStem,
             'い' => NumberingKind::HiraganaIroha,
             'イ' => NumberingKind::KatakanaIroha,
             'ㄱ' => NumberingKind::KoreanJamo,
@@ -326,6 +335,7 @@ impl NumberingKind {
             Self::Hebrew => 'א',
             Self::SimplifiedChinese => '一',
             Self::TraditionalChinese => '一',
+            Self::HeavenlyStem => '甲',
             Self::HiraganaIroha => 'い',
             Self::KatakanaIroha => 'イ',
             Self::KoreanJamo => 'ㄱ',
@@ -342,7 +352,7 @@ impl NumberingKind {
             Self::Letter => zeroless::<26>(
                 |x| match case {
                     Case::Lower => char::from(b'a' + x as u8),
-                    Case::Upper => char::from(b'A' + x as u8),
+                    Case::Upper | Case::Title => char::from(b'A' + x as u8),
                 },
                 n,
             ),
@@ -405,7 +415,7 @@ impl NumberingKind {
                         for c in name.chars() {
                             match case {
                                 Case::Lower => fmt.extend(c.to_lowercase()),
-                                Case::Upper => fmt.push(c),
+                                Case::Upper | Case::Title => fmt.push(c),
                             }
                         }
                     }
@@ -479,7 +489,7 @@ impl NumberingKind {
             l @ (Self::SimplifiedChinese | Self::TraditionalChinese) => {
                 let chinese_case = match case {
                     Case::Lower => ChineseCase::Lower,
-                    Case::Upper => ChineseCase::Upper,
+                    Case::Upper | Case::Title => ChineseCase::Upper,
                 };
 
                 match (n as u64).to_chinese(
@@ -495,6 +505,10 @@ impl NumberingKind {
                     Err(_) => '-'.into(),
                 }
             }
+            Self::HeavenlyStem => zeroless::<10>(
+                |x| ['甲', '乙', '丙', '丁', '戊', '己', '庚', '辛', '壬', '癸'][x],
+                n,
+            ),
             Self::KoreanJamo => zeroless::<14>(
                 |x| {
                     [
@@ -4,15 +4,19 @@ use std::str::FromStr;
 use crate::diag::{bail, At, SourceResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, scope, select_where, Content, Finalize, Func, LocatableSelector,
-    NativeElement, Show, Smart, StyleChain,
+    cast, elem, scope, select_where, Content, Finalize, FromValue, Func,
+    LocatableSelector, NativeElement, Selector, Show, Smart, StyleChain,
 };
 use crate::introspection::{Counter, CounterKey, Locatable};
 use crate::layout::{BoxElem, Fr, HElem, HideElem, Length, Rel, RepeatElem, Spacing};
-use crate::model::{Destination, HeadingElem, NumberingPattern, ParbreakElem, Refable};
+use crate::model::{
+    Destination, FigureElem, FigureKind, HeadingElem, NumberingPattern, ParbreakElem,
+    Refable, TableElem,
+};
 use crate::syntax::Span;
 use crate::text::{Lang, LinebreakElem, LocalName, Region, SpaceElem, TextElem};
 use crate::util::{option_eq, NonZeroExt};
+use crate::visualize::ImageElem;
 
 /// A table of contents, figures, or other elements.
 ///
@@ -62,7 +66,10 @@ pub struct OutlineElem {
     /// The title of the outline.
     ///
     /// - When set to `{auto}`, an appropriate title for the
-    ///   [text language]($text.lang) will be used. This is the default.
+    ///   [text language]($text.lang) will be used. This is the default. Lists
+    ///   of figures with images or tables, whose [`target`]($outline.target)
+    ///   is `{figure.where(kind: image)}` or `{figure.where(kind: table)}`,
+    ///   get their own title, like "插图清单" or "附表清单" in Chinese.
     /// - When set to `{none}`, the outline will not have a title.
     /// - A custom title can be set by passing content.
     ///
@@ -192,7 +199,7 @@ impl Show for OutlineElem {
         // Build the outline title.
         if let Some(title) = self.title(styles) {
             let title = title.unwrap_or_else(|| {
-                TextElem::packed(Self::local_name_in(styles)).spanned(self.span())
+                TextElem::packed(self.auto_title(styles)).spanned(self.span())
             });
 
             seq.push(HeadingElem::new(title).with_level(NonZeroUsize::ONE).pack());
@@ -245,6 +252,35 @@ impl Show for OutlineElem {
     }
 }
 
+impl OutlineElem {
+    /// The title to use when it is set to `{auto}`.
+    fn auto_title(&self, styles: StyleChain) -> &'static str {
+        let lang = TextElem::lang_in(styles);
+        let region = TextElem::region_in(styles);
+
+        // Find out whether this is a list of figures or tables.
+        if let Selector::Elem(elem, Some(fields)) = &self.target(styles).0 {
+            let kind = FigureElem::elem().field_id("kind").and_then(|id| {
+                let (_, value) = fields.iter().find(|(field, _)| *field == id)?;
+                FigureKind::from_value(value.clone()).ok()
+            });
+
+            match kind {
+                _ if *elem != FigureElem::elem() => {}
+                Some(FigureKind::Elem(kind)) if kind == ImageElem::elem() => {
+                    return figure_list_name(lang, region);
+                }
+                Some(FigureKind::Elem(kind)) if kind == TableElem::elem() => {
+                    return table_list_name(lang, region);
+                }
+                _ => {}
+            }
+        }
+
+        Self::local_name(lang, region)
+    }
+}
+
 impl Finalize for OutlineElem {
     fn finalize(&self, realized: Content, _: StyleChain) -> Content {
         realized
@@ -290,6 +326,40 @@ impl LocalName for OutlineElem {
     }
 }
 
+/// The title of a list of figures with images.
+fn figure_list_name(lang: Lang, region: Option<Region>) -> &'static str {
+    match lang {
+        Lang::CHINESE if option_eq(region, "TW") => "圖目錄",
+        Lang::CHINESE => "插图清单",
+        Lang::DUTCH => "Lijst van figuren",
+        Lang::FRENCH => "Table des figures",
+        Lang::GERMAN => "Abbildungsverzeichnis",
+        Lang::ITALIAN => "Elenco delle figure",
+        Lang::JAPANESE => "図目次",
+        Lang::PORTUGUESE => "Lista de figuras",
+        Lang::RUSSIAN => "Список иллюстраций",
+        Lang::SPANISH => "Índice de figuras",
+        Lang::ENGLISH | _ => "List of Figures",
+    }
+}
+
+/// The title of a list of figures with tables.
+fn table_list_name(lang: Lang, region: Option<Region>) -> &'static str {
+    match lang {
+        Lang::CHINESE if option_eq(region, "TW") => "表目錄",
+        Lang::CHINESE => "附表清单",
+        Lang::DUTCH => "Lijst van tabellen",
+        Lang::FRENCH => "Liste des tableaux",
+        Lang::GERMAN => "Tabellenverzeichnis",
+        Lang::ITALIAN => "Elenco delle tabelle",
+        Lang::JAPANESE => "表目次",
+        Lang::PORTUGUESE => "Lista de tabelas",
+        Lang::RUSSIAN => "Список таблиц",
+        Lang::SPANISH => "Índice de tablas",
+        Lang::ENGLISH | _ => "List of Tables",
+    }
+}
+
 /// Marks an element as being able to be outlined. This is used to implement the
 /// `#outline()` element.
 pub trait Outlinable: Refable {
@@ -41,6 +41,23 @@ pub struct ParElem {
     #[default(Em::new(0.65).into())]
     pub leading: Length,
 
+    /// The spacing between paragraphs.
+    ///
+    /// When this property is set to `{auto}`, its default value, paragraphs
+    /// are spaced like other blocks, i.e. according to the
+    /// [block's spacing]($block.spacing).
+    ///
+    /// ```example
+    /// #set par(spacing: 0.65em)
+    /// Dense paragraphs without
+    /// additional spacing.
+    ///
+    /// Only the leading separates
+    /// them from each other.
+    /// ```
+    #[ghost]
+    pub spacing: Smart<Length>,
+
     /// Whether to justify text in its line.
     ///
     /// Hyphenation will be enabled for justified paragraphs if the
@@ -54,6 +71,21 @@ pub struct ParElem {
     #[default(false)]
     pub justify: bool,
 
+    /// Whether to also justify the last line of a justified paragraph and
+    /// lines that end with a [line break]($linebreak).
+    ///
+    /// This has no effect unless [`justify`]($par.justify) is enabled.
+    ///
+    /// ```example
+    /// #set page(width: 180pt)
+    /// #set par(justify: true, justify-last-line: true)
+    /// 汉字的最后一行也会两端对齐，
+    /// 使其与上面的行同样宽。
+    /// ```
+    #[ghost]
+    #[default(false)]
+    pub justify_last_line: bool,
+
     /// How to determine line breaks.
     ///
     /// When this property is set to `{auto}`, its default value, optimized line
@@ -75,6 +107,16 @@ pub struct ParElem {
     /// challenging to break in a visually
     /// pleasing way.
     /// ```
+    ///
+    /// Balanced line breaks are meant for short blocks like headings and
+    /// captions, so that a title that barely overflows doesn't end with a
+    /// single character on its own line:
+    ///
+    /// ```example
+    /// #set page(width: 160pt)
+    /// #show heading: set par(linebreaks: "balanced")
+    /// = 关于高维数据可视化方法的若干研究
+    /// ```
     #[ghost]
     pub linebreaks: Smart<Linebreaks>,
 
@@ -154,6 +196,13 @@ pub enum Linebreaks {
     /// Typst will try to produce more evenly filled lines of text by
     /// considering the whole paragraph when calculating line breaks.
     Optimized,
+    /// Make all lines about equally long.
+    ///
+    /// Typst keeps the number of lines that simple line breaking would
+    /// produce, but breaks them as if the available width were as small as
+    /// possible. Paragraphs with more than ten lines are broken in the simple
+    /// style instead.
+    Balanced,
 }
 
 /// A paragraph break.
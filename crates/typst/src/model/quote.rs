@@ -1,8 +1,8 @@
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{
-    cast, elem, Content, Finalize, Label, NativeElement, Show, Smart, StyleChain,
-    Synthesize,
+    cast, elem, Content, Finalize, Fold, Label, NativeElement, Show, Smart, StyleChain,
+    Synthesize, Value,
 };
 use crate::layout::{Align, BlockElem, Em, HElem, PadElem, Spacing, VElem};
 use crate::model::{CitationForm, CiteElem};
@@ -64,7 +64,8 @@ pub struct QuoteElem {
     ///
     /// The double quotes used are inferred from the `quotes` property on
     /// [smartquote]($smartquote), which is affected by the `lang` property on
-    /// [text]($text).
+    /// [text]($text). Quotes nested in other quotes alternate between single
+    /// and double quotes.
     ///
     /// - `{true}`: Wrap this quote in double quotes.
     /// - `{false}`: Do not wrap this quote in double quotes.
@@ -126,6 +127,11 @@ pub struct QuoteElem {
     /// The quote.
     #[required]
     body: Content,
+
+    /// The nesting depth of quotes with quotation marks.
+    #[internal]
+    #[fold]
+    depth: Depth,
 }
 
 /// Attribution for a [quote](QuoteElem).
@@ -159,9 +165,13 @@ impl Show for QuoteElem {
         let block = self.block(styles);
 
         if self.quotes(styles) == Smart::Custom(true) || !block {
+            // Alternate between double and single quotes when nesting.
+            let double = self.depth(styles) % 2 == 0;
+            realized = realized.styled(Self::set_depth(Depth));
+
             // Add zero-width weak spacing to make the quotes "sticky".
             let hole = HElem::hole().pack();
-            let quote = SmartQuoteElem::new().with_double(true).pack();
+            let quote = SmartQuoteElem::new().with_double(double).pack();
             realized =
                 Content::sequence([quote.clone(), hole.clone(), realized, hole, quote]);
         }
@@ -212,3 +222,21 @@ impl Finalize for QuoteElem {
             .styled(BlockElem::set_below(VElem::block_around(below)))
     }
 }
+
+/// The nesting depth of quotes, counted by folding one level per quote.
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+struct Depth;
+
+cast! {
+    Depth,
+    self => Value::None,
+    _: Value => Self,
+}
+
+impl Fold for Depth {
+    type Output = usize;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        outer + 1
+    }
+}
@@ -0,0 +1,74 @@
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, NativeElement, Show, Smart, StyleChain};
+use crate::layout::{BlockElem, HideElem, Length};
+
+/// The solution of an exercise that can be shown or hidden.
+///
+/// In exam sheets and exercise books, the same document often needs to be
+/// built once with and once without its solutions. Instead of editing the
+/// document, toggle all solutions at once with a set rule, for example based
+/// on an [input]($category/foundations/sys) given on the command line:
+///
+/// ```example
+/// #set solution(
+///   shown: sys.inputs.at("answers", default: "false") == "true",
+///   space: auto,
+/// )
+///
+/// + What is $1 + 1$?
+///
+///   #solution[$1 + 1 = 2$]
+/// + Name a prime number.
+///
+///   #solution[Seven.]
+/// ```
+///
+/// Compiling with `--input answers=true` then shows the solutions.
+#[elem(Show)]
+pub struct SolutionElem {
+    /// The content of the solution.
+    #[required]
+    pub body: Content,
+
+    /// Whether to show the solution.
+    #[default(true)]
+    pub shown: bool,
+
+    /// How much space the solution takes up when it is hidden.
+    ///
+    /// - When set to `{none}`, hidden solutions take up no space. This is the
+    ///   default.
+    /// - When set to `{auto}`, hidden solutions take up as much space as when
+    ///   they are shown, so that the documents with and without solutions are
+    ///   paginated identically.
+    /// - When set to a length, solutions are placed in a block of that height,
+    ///   whether they are shown or not. This leaves a fixed amount of space to
+    ///   write the answer in.
+    ///
+    /// ```example
+    /// #set solution(shown: false)
+    /// Before
+    /// #solution(space: 1cm)[Answer]
+    /// After
+    /// ```
+    pub space: Option<Smart<Length>>,
+}
+
+impl Show for SolutionElem {
+    #[tracing::instrument(name = "SolutionElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let shown = self.shown(styles);
+        let body = self.body().clone();
+        Ok(match self.space(styles) {
+            Some(Smart::Custom(height)) => BlockElem::new()
+                .with_height(Smart::Custom(height.into()))
+                .with_breakable(false)
+                .with_body(shown.then_some(body))
+                .pack(),
+            _ if shown => body,
+            Some(Smart::Auto) => HideElem::new(body).pack(),
+            None => Content::empty(),
+        })
+    }
+}
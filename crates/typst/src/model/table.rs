@@ -1,16 +1,17 @@
 use crate::diag::{At, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
-    elem, Array, CastInfo, Content, FromValue, Func, IntoValue, NativeElement, Reflect,
-    Smart, StyleChain, Value,
+    elem, Array, CastInfo, Content, Fold, FromValue, Func, IntoValue, NativeElement,
+    Reflect, Resolve, Smart, StyleChain, Value,
 };
 use crate::layout::{
-    Abs, Align, AlignElem, Axes, Fragment, FrameItem, GridLayouter, Layout, Length,
-    Point, Regions, Rel, Sides, Size, TrackSizings,
+    Abs, Align, AlignElem, Axes, Fragment, Frame, FrameItem, GridLayouter, Layout,
+    Length, Point, Regions, Rel, RowPiece, Sides, Size, TrackSizings,
 };
 use crate::model::Figurable;
+use crate::syntax::Span;
 use crate::text::{Lang, LocalName, Region};
-use crate::visualize::{Geometry, Paint, Stroke};
+use crate::visualize::{FixedStroke, Geometry, Paint, Stroke};
 
 /// A table of items.
 ///
@@ -74,6 +75,30 @@ pub struct TableElem {
     #[borrowed]
     pub row_gutter: TrackSizings,
 
+    /// The number of rows at the top of the table that form its header. When
+    /// the table breaks across pages, these rows are repeated at the top of
+    /// each page. See the [grid documentation]($grid.header-rows) for more
+    /// information.
+    pub header_rows: usize,
+
+    /// The number of rows at the bottom of the table that form its footer.
+    /// When the table breaks across pages, these rows are repeated at the
+    /// bottom of each page. See the [grid documentation]($grid.footer-rows)
+    /// for more information.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #table(
+    ///   columns: 2,
+    ///   header-rows: 1,
+    ///   footer-rows: 1,
+    ///   [*Parameter*], [*Type*],
+    ///   ..range(8).map(i => (raw("arg-" + str(i)), [length])).flatten(),
+    ///   [*Parameter*], [*Type*],
+    /// )
+    /// ```
+    pub footer_rows: usize,
+
     /// How to fill the cells.
     ///
     /// This can be a color or a function that returns a color. The function is
@@ -117,15 +142,36 @@ pub struct TableElem {
 
     /// How to [stroke]($stroke) the cells.
     ///
-    /// Strokes can be disabled by setting this to `{none}`.
+    /// Strokes can be disabled by setting this to `{none}`. Like the
+    /// [rectangle's stroke]($rect.stroke), this can also be a dictionary with
+    /// a stroke for each side of the cells. To style individual cells, pass a
+    /// function that receives a cell's column and row index, starting at zero,
+    /// and returns a stroke or such a dictionary. Where two cells meet, the
+    /// top or left side of the later cell takes precedence over the bottom or
+    /// right side of the earlier one. Set rules with per-side dictionaries
+    /// combine side by side, so sides that are not mentioned keep their
+    /// stroke.
+    ///
+    /// This makes it possible to build a "three-line" table with rules only
+    /// above and below the header and at the very bottom:
     ///
-    /// _Note:_ Richer stroke customization for individual cells is not yet
-    /// implemented, but will be in the future. In the meantime, you can use the
-    /// third-party [tablex library](https://github.com/PgBiel/typst-tablex/).
-    #[resolve]
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x, y) => (
+    ///     top: if y == 0 { 1pt } else if y == 1 { 0.5pt },
+    ///     bottom: if y == 3 { 1pt },
+    ///     x: none,
+    ///   ),
+    ///   [*Name*], [*Age*], [*City*],
+    ///   [Alice], [31], [Berlin],
+    ///   [Bob], [27], [Tokyo],
+    ///   [Carol], [45], [Lima],
+    /// )
+    /// ```
     #[fold]
-    #[default(Some(Stroke::default()))]
-    pub stroke: Option<Stroke>,
+    #[default(Celled::Value(Sides::splat(Some(Some(Stroke::default())))))]
+    pub stroke: Celled<Sides<Option<Option<Stroke>>>>,
 
     /// How much to pad the cells' content.
     ///
@@ -191,11 +237,13 @@ impl Layout for TableElem {
             .collect::<SourceResult<_>>()?;
 
         let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(Stroke::unwrap_or_default);
+        let stroke = self.stroke(styles);
 
         // Prepare grid layout by unifying content and gutter tracks.
         let layouter =
-            GridLayouter::new(tracks, gutter, &cells, regions, styles, self.span());
+            GridLayouter::new(tracks, gutter, &cells, regions, styles, self.span())
+                .with_header(self.header_rows(styles))
+                .with_footer(self.footer_rows(styles));
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout(engine)?;
@@ -207,28 +255,28 @@ impl Layout for TableElem {
             }
 
             // Render table lines.
-            if let Some(stroke) = &stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(-half, offset),
-                        FrameItem::Shape(hline, self.span()),
-                    );
+            match &stroke {
+                Celled::Value(sides) if sides.is_uniform() => {
+                    let stroke = sides.left.clone().flatten();
+                    if let Some(stroke) = resolve_stroke(stroke, styles) {
+                        render_grid_lines(frame, &layout.cols, rows, stroke, self.span());
+                    }
                 }
+                _ => {
+                    // Resolve the strokes of all cells in this region.
+                    let mut cells = vec![];
+                    for row in rows {
+                        let mut line = vec![];
+                        for x in 0..layout.cols.len() {
+                            let sides = stroke.resolve(engine, x, row.y)?;
+                            line.push(
+                                sides.map(|side| side.map(|s| resolve_stroke(s, styles))),
+                            );
+                        }
+                        cells.push(line);
+                    }
 
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke.clone());
-                    frame.prepend(
-                        Point::new(offset, -half),
-                        FrameItem::Shape(vline, self.span()),
-                    );
+                    render_cell_lines(frame, &layout.cols, rows, &cells, self.span());
                 }
             }
 
@@ -253,6 +301,87 @@ impl Layout for TableElem {
     }
 }
 
+/// Resolve a stroke of a cell side to its final form.
+fn resolve_stroke(stroke: Option<Stroke>, styles: StyleChain) -> Option<FixedStroke> {
+    stroke.map(|stroke| stroke.resolve(styles).unwrap_or_default())
+}
+
+/// Render lines along all row and column boundaries of a table region.
+fn render_grid_lines(
+    frame: &mut Frame,
+    cols: &[Abs],
+    rows: &[RowPiece],
+    stroke: FixedStroke,
+    span: Span,
+) {
+    let thickness = stroke.thickness;
+    let half = thickness / 2.0;
+
+    // Render horizontal lines.
+    for offset in points(rows.iter().map(|piece| piece.height)) {
+        let target = Point::with_x(frame.width() + thickness);
+        let hline = Geometry::Line(target).stroked(stroke.clone());
+        frame.prepend(Point::new(-half, offset), FrameItem::Shape(hline, span));
+    }
+
+    // Render vertical lines.
+    for offset in points(cols.iter().copied()) {
+        let target = Point::with_y(frame.height() + thickness);
+        let vline = Geometry::Line(target).stroked(stroke.clone());
+        frame.prepend(Point::new(offset, -half), FrameItem::Shape(vline, span));
+    }
+}
+
+/// Render the sides of the individual cells of a table region.
+///
+/// Where two cells share a border, the top or left side of the later cell wins
+/// over the bottom or right side of the earlier cell. Sides that were not
+/// specified at all (`None`) defer to the neighbouring cell.
+fn render_cell_lines(
+    frame: &mut Frame,
+    cols: &[Abs],
+    rows: &[RowPiece],
+    cells: &[Vec<Sides<Option<Option<FixedStroke>>>>],
+    span: Span,
+) {
+    // Render horizontal lines, one segment per column.
+    let offsets: Vec<_> = points(rows.iter().map(|piece| piece.height)).collect();
+    for (i, &dy) in offsets.iter().enumerate() {
+        let mut dx = Abs::zero();
+        for (x, &col) in cols.iter().enumerate() {
+            let below = cells.get(i).and_then(|line| line[x].top.clone());
+            let above = i.checked_sub(1).and_then(|j| cells[j][x].bottom.clone());
+            if let Some(stroke) = below.or(above).flatten() {
+                let half = stroke.thickness / 2.0;
+                let start = if x == 0 { -half } else { Abs::zero() };
+                let end = if x + 1 == cols.len() { col + half } else { col };
+                let hline = Geometry::Line(Point::with_x(end - start)).stroked(stroke);
+                frame.prepend(Point::new(dx + start, dy), FrameItem::Shape(hline, span));
+            }
+            dx += col;
+        }
+    }
+
+    // Render vertical lines, one segment per row.
+    let offsets: Vec<_> = points(cols.iter().copied()).collect();
+    for (x, &dx) in offsets.iter().enumerate() {
+        let mut dy = Abs::zero();
+        for (i, row) in rows.iter().enumerate() {
+            let after = cells[i].get(x).and_then(|sides| sides.left.clone());
+            let before = x.checked_sub(1).and_then(|k| cells[i][k].right.clone());
+            if let Some(stroke) = after.or(before).flatten() {
+                let half = stroke.thickness / 2.0;
+                let start = if i == 0 { -half } else { Abs::zero() };
+                let end =
+                    if i + 1 == rows.len() { row.height + half } else { row.height };
+                let vline = Geometry::Line(Point::with_y(end - start)).stroked(stroke);
+                frame.prepend(Point::new(dx, dy + start), FrameItem::Shape(vline, span));
+            }
+            dy += row.height;
+        }
+    }
+}
+
 /// Turn an iterator of extents into an iterator of offsets before, in between,
 /// and after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
 fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
@@ -289,6 +418,24 @@ impl<T: Default + Clone + FromValue> Celled<T> {
     }
 }
 
+/// Folds bare per-side values side by side, so that a side that is not
+/// specified keeps its outer value. Functions and arrays replace outer values.
+impl<T: Fold<Output = T>> Fold for Celled<Sides<Option<T>>> {
+    type Output = Self;
+
+    fn fold(self, outer: Self::Output) -> Self::Output {
+        match (self, outer) {
+            (Self::Value(inner), Self::Value(outer)) => {
+                Self::Value(inner.zip(outer).map(|(inner, outer)| match (inner, outer) {
+                    (Some(inner), Some(outer)) => Some(inner.fold(outer)),
+                    (inner, outer) => inner.or(outer),
+                }))
+            }
+            (inner, _) => inner,
+        }
+    }
+}
+
 impl<T: Default> Default for Celled<T> {
     fn default() -> Self {
         Self::Value(T::default())
@@ -368,3 +515,34 @@ impl LocalName for TableElem {
 }
 
 impl Figurable for TableElem {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visualize::Color;
+
+    #[test]
+    fn test_celled_stroke_fold() {
+        let red = Stroke {
+            paint: Smart::Custom(Color::RED.into()),
+            ..Stroke::default()
+        };
+        let thick = Stroke {
+            thickness: Smart::Custom(Abs::pt(2.0).into()),
+            ..Stroke::default()
+        };
+
+        let outer = Celled::Value(Sides::splat(Some(Some(red.clone()))));
+        let inner = Celled::Value(Sides::new(Some(None), Some(Some(thick)), None, None));
+        let Celled::Value(folded) = inner.fold(outer) else { panic!("expected value") };
+
+        let both = Stroke {
+            thickness: Smart::Custom(Abs::pt(2.0).into()),
+            ..red.clone()
+        };
+        assert_eq!(folded.left, Some(None));
+        assert_eq!(folded.top, Some(Some(both)));
+        assert_eq!(folded.right, Some(Some(red.clone())));
+        assert_eq!(folded.bottom, Some(Some(red)));
+    }
+}
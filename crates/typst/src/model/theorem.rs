@@ -0,0 +1,508 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+
+use ecow::eco_vec;
+
+use crate::diag::{bail, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{
+    elem, select_where, Content, NativeElement, Selector, Show, Smart, Str, StyleChain,
+    Synthesize,
+};
+use crate::introspection::{Count, Counter, CounterKey, CounterUpdate, Locatable};
+use crate::layout::BlockElem;
+use crate::model::{HeadingElem, Numbering, NumberingPattern, Refable, Supplement};
+use crate::text::{Lang, LocalName, Region, SpaceElem, TextElem};
+use crate::util::option_eq;
+
+/// A numbered block, like a theorem, a definition or an exercise.
+///
+/// Theorem blocks start with their supplement, number and optional title in
+/// bold, followed by their body. Like figures, they can be labelled and
+/// [referenced]($ref).
+///
+/// The kinds `{"theorem"}`, `{"lemma"}`, `{"corollary"}`, `{"definition"}`,
+/// `{"example"}`, `{"exercise"}` and `{"proof"}` come with a supplement for
+/// the [text language]($text.lang). Other kinds need an explicit supplement.
+///
+/// # Example
+/// ```example
+/// #theorem(title: [Pythagoras])[
+///   $a^2 + b^2 = c^2$
+/// ] <pythagoras>
+///
+/// #theorem(kind: "definition")[
+///   A triangle is _right_ if one
+///   of its angles measures 90°.
+/// ]
+///
+/// #theorem(kind: "corollary", group: "theorem")[
+///   Follows from @pythagoras.
+/// ]
+/// ```
+///
+/// # Numbering per chapter
+/// To restart the numbers in each chapter, set `reset` to the level of the
+/// chapter headings. The number of a block then consists of the heading
+/// numbers up to that level, followed by the block's own number, so the
+/// numbering needs one more level:
+///
+/// ```example
+/// #set heading(numbering: "1.")
+/// #set theorem(reset: 1, numbering: "1.1")
+///
+/// = Triangles
+/// #theorem[Triangles have three sides.]
+/// #theorem[Their angles sum to 180°.]
+///
+/// = Squares
+/// #theorem[Squares have four sides.]
+/// ```
+#[elem(Locatable, Synthesize, Count, Show, Refable)]
+pub struct TheoremElem {
+    /// The content of the block.
+    #[required]
+    pub body: Content,
+
+    /// The block's title, shown in parentheses after its number.
+    pub title: Option<Content>,
+
+    /// The kind of the block, like `{"theorem"}` or `{"definition"}`.
+    ///
+    /// Blocks of different kinds are numbered separately unless they share a
+    /// [`group`]($theorem.group).
+    #[default("theorem".into())]
+    pub kind: Str,
+
+    /// The supplement to show in front of the number and in references.
+    ///
+    /// When set to `{auto}`, the supplement is chosen based on the kind and
+    /// the text language. If a function is specified, it is passed the body
+    /// and should return content.
+    #[borrowed]
+    pub supplement: Smart<Option<Supplement>>,
+
+    /// The counter group of the block. Blocks in the same group share their
+    /// numbers.
+    ///
+    /// When set to `{auto}`, each kind has its own group.
+    ///
+    /// ```example
+    /// #set theorem(group: "results")
+    /// #theorem[First.]
+    /// #theorem(kind: "lemma")[Second.]
+    /// ```
+    pub group: Smart<Str>,
+
+    /// How to number the block. Accepts a
+    /// [numbering pattern or function]($numbering).
+    #[default(Some(NumberingPattern::from_str("1").unwrap().into()))]
+    pub numbering: Option<Numbering>,
+
+    /// The level of the headings at which the numbers restart.
+    ///
+    /// When this is `{none}`, blocks are numbered throughout the document.
+    pub reset: Option<NonZeroUsize>,
+
+    /// The block's counter.
+    #[synthesized]
+    pub counter: Option<Counter>,
+}
+
+impl Synthesize for TheoremElem {
+    fn synthesize(
+        &mut self,
+        engine: &mut Engine,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        let kind = self.kind(styles);
+        let numbering = self.numbering(styles);
+        let reset = self.reset(styles);
+
+        // Resolve the supplement.
+        let supplement = match self.supplement(styles).as_ref() {
+            Smart::Auto => {
+                let name = local_name(
+                    &kind,
+                    TextElem::lang_in(styles),
+                    TextElem::region_in(styles),
+                );
+                if numbering.is_some() && name.is_none() {
+                    bail!(self.span(), "please specify the theorem's supplement")
+                }
+
+                Some(TextElem::packed(name.unwrap_or_default()))
+            }
+            Smart::Custom(None) => None,
+            Smart::Custom(Some(supplement)) => {
+                Some(supplement.resolve(engine, [self.body().clone()])?)
+            }
+        };
+
+        // Construct the counter. It counts through the blocks of the group and,
+        // if the numbers restart, the headings up to the reset level.
+        let group = self.group(styles).unwrap_or_else(|| kind.clone());
+        let mut selector = select_where!(Self, Group => group.clone());
+        if let Some(reset) = reset {
+            let mut selectors = eco_vec![selector];
+            for level in 1..=reset.get() {
+                let level = NonZeroUsize::new(level).unwrap();
+                selectors.push(select_where!(HeadingElem, Level => level));
+            }
+            selector = Selector::Or(selectors);
+        }
+
+        self.push_title(self.title(styles));
+        self.push_kind(kind);
+        self.push_supplement(Smart::Custom(supplement.map(Supplement::Content)));
+        self.push_group(Smart::Custom(group));
+        self.push_numbering(numbering);
+        self.push_reset(reset);
+        self.push_counter(Some(Counter::new(CounterKey::Selector(selector))));
+
+        Ok(())
+    }
+}
+
+impl Show for TheoremElem {
+    #[tracing::instrument(name = "TheoremElem::show", skip_all)]
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let mut head = Refable::supplement(self);
+        if let (Some(numbering), Some(counter)) = (self.numbering(styles), self.counter())
+        {
+            let location = self.location().unwrap();
+            let numbers = counter.at(engine, location)?.display(engine, &numbering)?;
+            if !head.is_empty() {
+                head += TextElem::packed('\u{a0}');
+            }
+            head += numbers;
+        }
+
+        if let Some(title) = self.title(styles) {
+            if !head.is_empty() {
+                head += SpaceElem::new().pack();
+            }
+            head += TextElem::packed('(') + title + TextElem::packed(')');
+        }
+
+        let mut realized = self.body().clone();
+        if !head.is_empty() {
+            realized = head.strong() + SpaceElem::new().pack() + realized;
+        }
+
+        Ok(BlockElem::new().with_body(Some(realized)).pack())
+    }
+}
+
+impl Count for TheoremElem {
+    fn update(&self) -> Option<CounterUpdate> {
+        // Step the level after the reset headings, so that the heading numbers
+        // come first.
+        let level = self.reset(StyleChain::default()).map_or(1, |reset| reset.get() + 1);
+        self.numbering(StyleChain::default())
+            .is_some()
+            .then(|| CounterUpdate::Step(NonZeroUsize::new(level).unwrap()))
+    }
+}
+
+impl Refable for TheoremElem {
+    fn supplement(&self) -> Content {
+        // After synthesis, this should always be custom content.
+        match self.supplement(StyleChain::default()).as_ref() {
+            Smart::Custom(Some(Supplement::Content(content))) => content.clone(),
+            _ => Content::empty(),
+        }
+    }
+
+    fn counter(&self) -> Counter {
+        self.counter().clone().unwrap_or_else(|| Counter::of(Self::elem()))
+    }
+
+    fn numbering(&self) -> Option<Numbering> {
+        self.numbering(StyleChain::default())
+    }
+}
+
+/// The supplement of a built-in kind in the given language and region.
+fn local_name(kind: &str, lang: Lang, region: Option<Region>) -> Option<&'static str> {
+    Some(match kind {
+        "theorem" => Theorem::local_name(lang, region),
+        "lemma" => Lemma::local_name(lang, region),
+        "corollary" => Corollary::local_name(lang, region),
+        "definition" => Definition::local_name(lang, region),
+        "example" => Example::local_name(lang, region),
+        "exercise" => Exercise::local_name(lang, region),
+        "proof" => Proof::local_name(lang, region),
+        _ => return None,
+    })
+}
+
+/// A theorem, as a kind of theorem block.
+struct Theorem;
+
+impl LocalName for Theorem {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Teoremë",
+            Lang::ARABIC => "مبرهنة",
+            Lang::BOKMÅL => "Teorem",
+            Lang::CHINESE => "定理",
+            Lang::CZECH => "Věta",
+            Lang::DANISH => "Sætning",
+            Lang::DUTCH => "Stelling",
+            Lang::ESTONIAN => "Teoreem",
+            Lang::FILIPINO => "Teorema",
+            Lang::FINNISH => "Lause",
+            Lang::FRENCH => "Théorème",
+            Lang::GERMAN => "Satz",
+            Lang::GREEK => "Θεώρημα",
+            Lang::HUNGARIAN => "Tétel",
+            Lang::ITALIAN => "Teorema",
+            Lang::NYNORSK => "Teorem",
+            Lang::POLISH => "Twierdzenie",
+            Lang::PORTUGUESE => "Teorema",
+            Lang::ROMANIAN => "Teoremă",
+            Lang::RUSSIAN => "Теорема",
+            Lang::SLOVENIAN => "Izrek",
+            Lang::SPANISH => "Teorema",
+            Lang::SWEDISH => "Sats",
+            Lang::TURKISH => "Teorem",
+            Lang::UKRAINIAN => "Теорема",
+            Lang::VIETNAMESE => "Định lý",
+            Lang::JAPANESE => "定理",
+            Lang::ENGLISH | _ => "Theorem",
+        }
+    }
+}
+
+/// A lemma, as a kind of theorem block.
+struct Lemma;
+
+impl LocalName for Lemma {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Lemë",
+            Lang::ARABIC => "تمهيدية",
+            Lang::BOKMÅL => "Lemma",
+            Lang::CHINESE => "引理",
+            Lang::CZECH => "Lemma",
+            Lang::DANISH => "Lemma",
+            Lang::DUTCH => "Lemma",
+            Lang::ESTONIAN => "Lemma",
+            Lang::FILIPINO => "Lema",
+            Lang::FINNISH => "Lemma",
+            Lang::FRENCH => "Lemme",
+            Lang::GERMAN => "Lemma",
+            Lang::GREEK => "Λήμμα",
+            Lang::HUNGARIAN => "Lemma",
+            Lang::ITALIAN => "Lemma",
+            Lang::NYNORSK => "Lemma",
+            Lang::POLISH => "Lemat",
+            Lang::PORTUGUESE => "Lema",
+            Lang::ROMANIAN => "Lemă",
+            Lang::RUSSIAN => "Лемма",
+            Lang::SLOVENIAN => "Lema",
+            Lang::SPANISH => "Lema",
+            Lang::SWEDISH => "Lemma",
+            Lang::TURKISH => "Lemma",
+            Lang::UKRAINIAN => "Лема",
+            Lang::VIETNAMESE => "Bổ đề",
+            Lang::JAPANESE => "補題",
+            Lang::ENGLISH | _ => "Lemma",
+        }
+    }
+}
+
+/// A corollary, as a kind of theorem block.
+struct Corollary;
+
+impl LocalName for Corollary {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Rrjedhim",
+            Lang::ARABIC => "نتيجة",
+            Lang::BOKMÅL => "Korollar",
+            Lang::CHINESE if option_eq(region, "TW") => "推論",
+            Lang::CHINESE => "推论",
+            Lang::CZECH => "Důsledek",
+            Lang::DANISH => "Korollar",
+            Lang::DUTCH => "Gevolg",
+            Lang::ESTONIAN => "Järeldus",
+            Lang::FILIPINO => "Korolaryo",
+            Lang::FINNISH => "Seuraus",
+            Lang::FRENCH => "Corollaire",
+            Lang::GERMAN => "Korollar",
+            Lang::GREEK => "Πόρισμα",
+            Lang::HUNGARIAN => "Következmény",
+            Lang::ITALIAN => "Corollario",
+            Lang::NYNORSK => "Korollar",
+            Lang::POLISH => "Wniosek",
+            Lang::PORTUGUESE => "Corolário",
+            Lang::ROMANIAN => "Corolar",
+            Lang::RUSSIAN => "Следствие",
+            Lang::SLOVENIAN => "Posledica",
+            Lang::SPANISH => "Corolario",
+            Lang::SWEDISH => "Korollarium",
+            Lang::TURKISH => "Sonuç",
+            Lang::UKRAINIAN => "Наслідок",
+            Lang::VIETNAMESE => "Hệ quả",
+            Lang::JAPANESE => "系",
+            Lang::ENGLISH | _ => "Corollary",
+        }
+    }
+}
+
+/// A definition, as a kind of theorem block.
+struct Definition;
+
+impl LocalName for Definition {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Përkufizim",
+            Lang::ARABIC => "تعريف",
+            Lang::BOKMÅL => "Definisjon",
+            Lang::CHINESE if option_eq(region, "TW") => "定義",
+            Lang::CHINESE => "定义",
+            Lang::CZECH => "Definice",
+            Lang::DANISH => "Definition",
+            Lang::DUTCH => "Definitie",
+            Lang::ESTONIAN => "Definitsioon",
+            Lang::FILIPINO => "Depinisyon",
+            Lang::FINNISH => "Määritelmä",
+            Lang::FRENCH => "Définition",
+            Lang::GERMAN => "Definition",
+            Lang::GREEK => "Ορισμός",
+            Lang::HUNGARIAN => "Definíció",
+            Lang::ITALIAN => "Definizione",
+            Lang::NYNORSK => "Definisjon",
+            Lang::POLISH => "Definicja",
+            Lang::PORTUGUESE => "Definição",
+            Lang::ROMANIAN => "Definiție",
+            Lang::RUSSIAN => "Определение",
+            Lang::SLOVENIAN => "Definicija",
+            Lang::SPANISH => "Definición",
+            Lang::SWEDISH => "Definition",
+            Lang::TURKISH => "Tanım",
+            Lang::UKRAINIAN => "Означення",
+            Lang::VIETNAMESE => "Định nghĩa",
+            Lang::JAPANESE => "定義",
+            Lang::ENGLISH | _ => "Definition",
+        }
+    }
+}
+
+/// An example, as a kind of theorem block.
+struct Example;
+
+impl LocalName for Example {
+    fn local_name(lang: Lang, _: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Shembull",
+            Lang::ARABIC => "مثال",
+            Lang::BOKMÅL => "Eksempel",
+            Lang::CHINESE => "例",
+            Lang::CZECH => "Příklad",
+            Lang::DANISH => "Eksempel",
+            Lang::DUTCH => "Voorbeeld",
+            Lang::ESTONIAN => "Näide",
+            Lang::FILIPINO => "Halimbawa",
+            Lang::FINNISH => "Esimerkki",
+            Lang::FRENCH => "Exemple",
+            Lang::GERMAN => "Beispiel",
+            Lang::GREEK => "Παράδειγμα",
+            Lang::HUNGARIAN => "Példa",
+            Lang::ITALIAN => "Esempio",
+            Lang::NYNORSK => "Døme",
+            Lang::POLISH => "Przykład",
+            Lang::PORTUGUESE => "Exemplo",
+            Lang::ROMANIAN => "Exemplu",
+            Lang::RUSSIAN => "Пример",
+            Lang::SLOVENIAN => "Primer",
+            Lang::SPANISH => "Ejemplo",
+            Lang::SWEDISH => "Exempel",
+            Lang::TURKISH => "Örnek",
+            Lang::UKRAINIAN => "Приклад",
+            Lang::VIETNAMESE => "Ví dụ",
+            Lang::JAPANESE => "例",
+            Lang::ENGLISH | _ => "Example",
+        }
+    }
+}
+
+/// An exercise, as a kind of theorem block.
+struct Exercise;
+
+impl LocalName for Exercise {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Ushtrim",
+            Lang::ARABIC => "تمرين",
+            Lang::BOKMÅL => "Oppgave",
+            Lang::CHINESE if option_eq(region, "TW") => "習題",
+            Lang::CHINESE => "习题",
+            Lang::CZECH => "Cvičení",
+            Lang::DANISH => "Opgave",
+            Lang::DUTCH => "Opgave",
+            Lang::ESTONIAN => "Harjutus",
+            Lang::FILIPINO => "Pagsasanay",
+            Lang::FINNISH => "Harjoitus",
+            Lang::FRENCH => "Exercice",
+            Lang::GERMAN => "Aufgabe",
+            Lang::GREEK => "Άσκηση",
+            Lang::HUNGARIAN => "Feladat",
+            Lang::ITALIAN => "Esercizio",
+            Lang::NYNORSK => "Oppgåve",
+            Lang::POLISH => "Ćwiczenie",
+            Lang::PORTUGUESE => "Exercício",
+            Lang::ROMANIAN => "Exercițiu",
+            Lang::RUSSIAN => "Упражнение",
+            Lang::SLOVENIAN => "Vaja",
+            Lang::SPANISH => "Ejercicio",
+            Lang::SWEDISH => "Övning",
+            Lang::TURKISH => "Alıştırma",
+            Lang::UKRAINIAN => "Вправа",
+            Lang::VIETNAMESE => "Bài tập",
+            Lang::JAPANESE => "演習",
+            Lang::ENGLISH | _ => "Exercise",
+        }
+    }
+}
+
+/// A proof, as a kind of theorem block.
+struct Proof;
+
+impl LocalName for Proof {
+    fn local_name(lang: Lang, region: Option<Region>) -> &'static str {
+        match lang {
+            Lang::ALBANIAN => "Vërtetim",
+            Lang::ARABIC => "برهان",
+            Lang::BOKMÅL => "Bevis",
+            Lang::CHINESE if option_eq(region, "TW") => "證明",
+            Lang::CHINESE => "证明",
+            Lang::CZECH => "Důkaz",
+            Lang::DANISH => "Bevis",
+            Lang::DUTCH => "Bewijs",
+            Lang::ESTONIAN => "Tõestus",
+            Lang::FILIPINO => "Patunay",
+            Lang::FINNISH => "Todistus",
+            Lang::FRENCH => "Démonstration",
+            Lang::GERMAN => "Beweis",
+            Lang::GREEK => "Απόδειξη",
+            Lang::HUNGARIAN => "Bizonyítás",
+            Lang::ITALIAN => "Dimostrazione",
+            Lang::NYNORSK => "Prov",
+            Lang::POLISH => "Dowód",
+            Lang::PORTUGUESE => "Demonstração",
+            Lang::ROMANIAN => "Demonstrație",
+            Lang::RUSSIAN => "Доказательство",
+            Lang::SLOVENIAN => "Dokaz",
+            Lang::SPANISH => "Demostración",
+            Lang::SWEDISH => "Bevis",
+            Lang::TURKISH => "İspat",
+            Lang::UKRAINIAN => "Доведення",
+            Lang::VIETNAMESE => "Chứng minh",
+            Lang::JAPANESE => "証明",
+            Lang::ENGLISH | _ => "Proof",
+        }
+    }
+}
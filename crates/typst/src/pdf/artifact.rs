@@ -0,0 +1,36 @@
+use smallvec::smallvec;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, Content, Show, StyleChain};
+use crate::introspection::{Meta, MetaElem};
+
+/// Marks content as decorative.
+///
+/// Decorative content like page headers and footers, background patterns, or
+/// separator lines is not part of the document's actual content. In PDF
+/// output, it is marked as an _artifact_ that assistive technology like
+/// screen readers skips. In all other export formats, this function has no
+/// effect.
+///
+/// ```typ
+/// #set page(header: pdf.artifact[
+///   _Lab report_ #h(1fr) 2024
+/// ])
+/// ```
+#[elem(Show)]
+pub struct ArtifactElem {
+    /// The decorative content.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for ArtifactElem {
+    #[tracing::instrument(name = "ArtifactElem::show", skip(self))]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(self
+            .body()
+            .clone()
+            .styled(MetaElem::set_data(smallvec![Meta::Artifact])))
+    }
+}
@@ -0,0 +1,84 @@
+use ecow::EcoString;
+
+use crate::diag::{At, SourceResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Behave, Behaviour, Bytes, Content, Show, StyleChain};
+use crate::introspection::Locatable;
+use crate::syntax::Spanned;
+use crate::World;
+
+/// A file that will be embedded into the output PDF.
+///
+/// This can be used to distribute additional files that are related to the
+/// PDF within it, for example the data a table was generated from or the
+/// document's own source code. PDF readers will display the files in a
+/// separate panel from which they can be opened and saved.
+///
+/// The embedded files do not produce any visible content and are ignored by
+/// all other export formats.
+///
+/// ```typ
+/// #pdf.embed(
+///   "data.csv",
+///   description: "Raw measurements",
+///   mime-type: "text/csv",
+/// )
+/// ```
+///
+/// Each embedded file is identified by its file name, so two files with the
+/// same name can't be embedded.
+///
+/// PDF/A-2b does not permit arbitrary embedded files, so exporting to that
+/// standard fails. PDF/A-3b, which is used by invoice formats like ZUGFeRD
+/// and Factur-X, allows them, but requires their MIME type.
+#[elem(Behave, Show, Locatable)]
+pub struct EmbedElem {
+    /// Path to a file to be embedded.
+    ///
+    /// For more details, see the [Paths section]($syntax/#paths).
+    #[required]
+    #[parse(
+        let Spanned { v: path, span } =
+            args.expect::<Spanned<EcoString>>("path to the file to be embedded")?;
+        let id = span.resolve_path(&path).at(span)?;
+        let data = engine.world.file(id).at(span)?;
+        path
+    )]
+    #[borrowed]
+    pub path: EcoString,
+
+    /// The raw file data.
+    #[internal]
+    #[required]
+    #[parse(data)]
+    pub data: Bytes,
+
+    /// A description of the embedded file, shown by PDF readers.
+    #[borrowed]
+    pub description: Option<EcoString>,
+
+    /// The MIME type of the embedded file, e.g. `{"text/csv"}`.
+    #[borrowed]
+    pub mime_type: Option<EcoString>,
+}
+
+impl EmbedElem {
+    /// The name under which the file is embedded: the last component of its
+    /// path.
+    pub fn file_name(&self) -> &str {
+        let path = self.path().as_str();
+        path.rsplit(['/', '\\']).next().unwrap_or(path)
+    }
+}
+
+impl Show for EmbedElem {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::empty())
+    }
+}
+
+impl Behave for EmbedElem {
+    fn behaviour(&self) -> Behaviour {
+        Behaviour::Invisible
+    }
+}
@@ -0,0 +1,163 @@
+use ecow::{eco_format, EcoString};
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{
+    cast, elem, Cast, Content, NativeElement, Repr, Show, Smart, StyleChain,
+};
+use crate::introspection::Meta;
+use crate::layout::{
+    BoxElem, Em, Fragment, Frame, FrameItem, Layout, Length, Point, Regions, Rel, Sizing,
+};
+use crate::syntax::Span;
+
+/// A fillable form field.
+///
+/// In PDF output, this becomes an interactive field of the document's form
+/// that can be filled in with a PDF reader. In all other export formats, it
+/// just reserves empty space.
+///
+/// The field is laid out like an inline [box]($box), so that it can be placed
+/// within a line of text. To give it a visible border, e.g. for printing,
+/// wrap it in a box with a stroke.
+///
+/// ```typ
+/// Name: #box(stroke: 0.5pt, form.field("name")) \
+/// Newsletter: #form.field("newsletter", kind: "checkbox", checked: true) \
+/// Signature: #form.field("signature", kind: "signature")
+/// ```
+#[elem(name = "field", title = "Form Field", Show)]
+pub struct FieldElem {
+    /// The field's name, under which its value is submitted. It must be
+    /// unique within the document.
+    #[required]
+    pub name: EcoString,
+
+    /// What kind of field to create.
+    #[default(FieldKind::Text)]
+    pub kind: FieldKind,
+
+    /// The width of the field.
+    ///
+    /// When set to `{auto}`, text fields are `{10em}` wide, checkboxes `{1em}`,
+    /// and signature fields `{15em}`.
+    pub width: Smart<Rel<Length>>,
+
+    /// The height of the field.
+    ///
+    /// When set to `{auto}`, text fields are `{1.4em}` high, checkboxes
+    /// `{1em}`, and signature fields `{3em}`.
+    pub height: Smart<Rel<Length>>,
+
+    /// The initial value of a text field.
+    #[borrowed]
+    pub value: Option<EcoString>,
+
+    /// Whether a checkbox is initially checked.
+    #[default(false)]
+    pub checked: bool,
+
+    /// Whether a text field accepts multiple lines of text.
+    #[default(false)]
+    pub multiline: bool,
+
+    /// A description of the field, shown by PDF readers when hovering over
+    /// the field and read out by screen readers.
+    #[borrowed]
+    pub tooltip: Option<EcoString>,
+}
+
+impl Show for FieldElem {
+    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let kind = self.kind(styles);
+        let (width, height) = match kind {
+            FieldKind::Text => (10.0, 1.4),
+            FieldKind::Checkbox => (1.0, 1.0),
+            FieldKind::Signature => (15.0, 3.0),
+        };
+
+        let field = FormField {
+            name: self.name().clone(),
+            kind,
+            value: self.value(styles).clone(),
+            checked: self.checked(styles),
+            multiline: self.multiline(styles),
+            tooltip: self.tooltip(styles).clone(),
+            span: self.span(),
+        };
+
+        let width = self.width(styles).unwrap_or_else(|| Em::new(width).into());
+        let height = self.height(styles).unwrap_or_else(|| Em::new(height).into());
+        Ok(BoxElem::new()
+            .with_width(Sizing::Rel(width))
+            .with_height(Smart::Custom(height))
+            .with_body(Some(FieldWidgetElem::new(field).pack().spanned(self.span())))
+            .pack()
+            .spanned(self.span()))
+    }
+}
+
+/// A kind of form field.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum FieldKind {
+    /// A field that text can be entered into.
+    Text,
+    /// A checkbox that can be checked and unchecked.
+    Checkbox,
+    /// A placeholder for a digital signature.
+    Signature,
+}
+
+/// A form field, as passed to the PDF exporter.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct FormField {
+    /// The field's name.
+    pub name: EcoString,
+    /// What kind of field it is.
+    pub kind: FieldKind,
+    /// The initial value of a text field.
+    pub value: Option<EcoString>,
+    /// Whether a checkbox is initially checked.
+    pub checked: bool,
+    /// Whether a text field accepts multiple lines of text.
+    pub multiline: bool,
+    /// A description of the field.
+    pub tooltip: Option<EcoString>,
+    /// The span of the field's element, for errors about the field.
+    pub span: Span,
+}
+
+cast! {
+    type FormField,
+}
+
+impl Repr for FormField {
+    fn repr(&self) -> EcoString {
+        eco_format!("{self:?}")
+    }
+}
+
+/// Attaches a form field to the area of the box that contains it.
+#[elem(Layout)]
+struct FieldWidgetElem {
+    /// The field to attach.
+    #[required]
+    field: FormField,
+}
+
+impl Layout for FieldWidgetElem {
+    #[tracing::instrument(name = "FieldWidgetElem::layout", skip_all)]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = regions.base();
+        let meta = Meta::Field(self.field().clone());
+        let mut frame = Frame::soft(size);
+        frame.push(Point::zero(), FrameItem::Meta(meta, size));
+        frame.meta(styles, true);
+        Ok(Fragment::frame(frame))
+    }
+}
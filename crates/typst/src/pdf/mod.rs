@@ -0,0 +1,26 @@
+//! PDF-specific functionality.
+
+mod artifact;
+mod embed;
+mod form;
+
+pub use self::artifact::*;
+pub use self::embed::*;
+pub use self::form::*;
+
+use crate::foundations::{Module, Scope};
+
+/// A module with PDF-specific definitions.
+pub fn module() -> Module {
+    let mut scope = Scope::deduplicating();
+    scope.define_elem::<EmbedElem>();
+    scope.define_elem::<ArtifactElem>();
+    Module::new("pdf", scope)
+}
+
+/// A module with definitions for fillable PDF forms.
+pub fn form() -> Module {
+    let mut scope = Scope::deduplicating();
+    scope.define_elem::<FieldElem>();
+    Module::new("form", scope)
+}
@@ -0,0 +1,170 @@
+//! Timing reports of compilations.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Write};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// How long a compilation spent where.
+///
+/// The profile consists of the self times of all distinct stacks of tracing
+/// spans that were entered during the compilation, e.g. the evaluation of an
+/// imported file, the shaping of a paragraph or the layout of a page. Spans
+/// with fields include them in their name, so that each imported file and
+/// page shows up separately.
+///
+/// Note that memoized work only shows up in the compilation that actually
+/// performed it. Work that happened on other threads is not recorded.
+#[derive(Debug, Default, Clone)]
+pub struct Profile {
+    stacks: Vec<(String, Duration)>,
+}
+
+impl Profile {
+    /// The total time spent in any span.
+    pub fn total(&self) -> Duration {
+        self.stacks.iter().map(|(_, time)| *time).sum()
+    }
+
+    /// The self time of each stack of spans, with the spans separated by
+    /// semicolons.
+    pub fn stacks(&self) -> impl Iterator<Item = (&str, Duration)> {
+        self.stacks.iter().map(|(stack, time)| (stack.as_str(), *time))
+    }
+
+    /// The profile in the collapsed stack format, with one stack and its self
+    /// time in microseconds per line.
+    ///
+    /// This is the input format of `inferno-flamegraph` and `flamegraph.pl`.
+    pub fn collapsed(&self) -> String {
+        let mut out = String::new();
+        for (stack, time) in &self.stacks {
+            writeln!(out, "{stack} {}", time.as_micros()).unwrap();
+        }
+        out
+    }
+}
+
+/// A tracing subscriber that records a [`Profile`].
+#[derive(Default)]
+pub(crate) struct Profiler(Mutex<State>);
+
+/// The mutable state of a profiler.
+#[derive(Default)]
+struct State {
+    /// The names of all spans created so far, indexed by their ID minus one.
+    names: Vec<String>,
+    /// The currently entered spans.
+    stack: Vec<usize>,
+    /// When a span was last entered or exited.
+    last: Option<Instant>,
+    /// The accumulated self time per stack.
+    times: HashMap<String, Duration>,
+}
+
+impl State {
+    /// Attribute the time since the last enter or exit to the current stack.
+    fn charge(&mut self) {
+        let now = Instant::now();
+        if let Some(last) = self.last.replace(now) {
+            if !self.stack.is_empty() {
+                let stack = self
+                    .stack
+                    .iter()
+                    .map(|&i| self.names[i].as_str())
+                    .collect::<Vec<_>>()
+                    .join(";");
+                *self.times.entry(stack).or_default() += now - last;
+            }
+        }
+    }
+}
+
+impl Profiler {
+    /// Finish profiling and return the recorded profile.
+    pub fn finish(&self) -> Profile {
+        let mut state = self.0.lock().unwrap();
+        let mut stacks: Vec<_> = state.times.drain().collect();
+        stacks.sort();
+        Profile { stacks }
+    }
+}
+
+impl Subscriber for Profiler {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.is_span()
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut name = span.metadata().name().to_string();
+        span.record(&mut Fields(&mut name));
+        let mut state = self.0.lock().unwrap();
+        state.names.push(name);
+        Id::from_u64(state.names.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        let mut state = self.0.lock().unwrap();
+        let name = &mut state.names[span.into_u64() as usize - 1];
+        values.record(&mut Fields(name));
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        let mut state = self.0.lock().unwrap();
+        state.charge();
+        state.stack.push(span.into_u64() as usize - 1);
+    }
+
+    fn exit(&self, span: &Id) {
+        let mut state = self.0.lock().unwrap();
+        state.charge();
+        let index = span.into_u64() as usize - 1;
+        if let Some(pos) = state.stack.iter().rposition(|&i| i == index) {
+            state.stack.remove(pos);
+        }
+    }
+}
+
+/// Appends the fields of a span to its name.
+struct Fields<'a>(&'a mut String);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        // Semicolons separate the spans of a stack in the collapsed format.
+        let value = format!("{value:?}").replace(';', ",");
+        write!(self.0, " {}={value}", field.name()).unwrap();
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        write!(self.0, " {}={}", field.name(), value.replace(';', ",")).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_nested_spans() {
+        let profiler = std::sync::Arc::new(Profiler::default());
+        tracing::subscriber::with_default(profiler.clone(), || {
+            let _outer = tracing::info_span!("layout").entered();
+            for number in 1..=2 {
+                let _inner = tracing::info_span!("page", number).entered();
+            }
+        });
+
+        let profile = profiler.finish();
+        let stacks: Vec<_> = profile.stacks().map(|(stack, _)| stack).collect();
+        assert_eq!(stacks, ["layout", "layout;page number=1", "layout;page number=2"]);
+        assert!(profile.collapsed().lines().all(|line| line.starts_with("layout")));
+    }
+}
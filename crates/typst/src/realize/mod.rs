@@ -10,11 +10,11 @@ use std::mem;
 use smallvec::smallvec;
 use typed_arena::Arena;
 
-use crate::diag::{bail, SourceResult};
+use crate::diag::{bail, error, SourceResult};
 use crate::engine::{Engine, Route};
 use crate::foundations::{
     Content, Finalize, Guard, NativeElement, Recipe, Selector, Show, StyleChain,
-    StyleVecBuilder, Styles, Synthesize,
+    StyleVecBuilder, Styles, Synthesize, Value,
 };
 use crate::introspection::{Locatable, Meta, MetaElem};
 use crate::layout::{
@@ -23,11 +23,12 @@ use crate::layout::{
 };
 use crate::math::{EquationElem, LayoutMath};
 use crate::model::{
-    CiteElem, CiteGroup, DocumentElem, EnumElem, EnumItem, ListElem, ListItem, ParElem,
-    ParbreakElem, TermItem, TermsElem,
+    CiteElem, CiteGroup, DocumentElem, EmphElem, EnumElem, EnumItem, FigureElem,
+    HeadingElem, LinkElem, ListElem, ListItem, ParElem, ParbreakElem, QuoteElem,
+    StrongElem, TableElem, TermItem, TermsElem,
 };
 use crate::syntax::Span;
-use crate::text::{LinebreakElem, SmartQuoteElem, SpaceElem, TextElem};
+use crate::text::{LinebreakElem, RawElem, SmartQuoteElem, SpaceElem, TextElem};
 use crate::util::hash128;
 use crate::visualize::{
     CircleElem, EllipseElem, ImageElem, LineElem, PathElem, PolygonElem, RectElem,
@@ -178,6 +179,145 @@ pub fn realize(
     Ok(realized)
 }
 
+/// Apply the show rules in the given style chain to content, but preserve its
+/// semantic structure instead of realizing it into flows and paragraphs.
+///
+/// Headings, lists, tables, figures, links, and the like are not shown
+/// through their built-in show rules. Instead, their content fields are
+/// realized recursively. User-defined show rules apply to them as usual. This
+/// is used by exporters that work on the structure of a document instead of
+/// its frames.
+pub fn realize_structure(
+    engine: &mut Engine,
+    content: &Content,
+    styles: StyleChain,
+) -> SourceResult<Content> {
+    if let Some(children) = content.to_sequence() {
+        let children = children
+            .map(|child| realize_structure(engine, child, styles))
+            .collect::<SourceResult<Vec<_>>>()?;
+        return Ok(Content::sequence(children));
+    }
+
+    if let Some((elem, local)) = content.to_styled() {
+        let body = realize_structure(engine, elem, styles.chain(local))?;
+        return Ok(body.styled_with_map(local.clone()));
+    }
+
+    let mut target = content.clone();
+    if target.needs_preparation() {
+        if target.can::<dyn Locatable>() || target.label().is_some() {
+            let location = engine.locator.locate(hash128(content));
+            target.set_location(location);
+        }
+
+        if let Some(elem) = target.with_mut::<dyn Synthesize>() {
+            elem.synthesize(engine, styles)?;
+        }
+
+        target.mark_prepared();
+    }
+
+    if let Some(realized) = show_structure(engine, &target, styles)? {
+        engine.route.increase();
+        if !engine.route.within(Route::MAX_SHOW_RULE_DEPTH) {
+            bail!(error!(
+                content.span(), "maximum show rule depth exceeded";
+                hint: "check whether the show rule matches its own output";
+                hint: "this is a current compiler limitation that will be resolved in the future",
+            )
+            .with_code("maximum-show-rule-depth"));
+        }
+        let v = realize_structure(engine, &realized, styles);
+        engine.route.decrease();
+        return v;
+    }
+
+    // Realize the content the element is made of. If a realized field does
+    // not fit the element anymore, e.g. because a show rule turned a caption
+    // into something else, the field is kept as it was.
+    let mut realized = target.clone();
+    for (name, value) in target.fields() {
+        let Some(id) = target.elem().field_id(&name) else { continue };
+        let value = match value {
+            Value::Content(body) => {
+                Value::Content(realize_structure(engine, &body, styles)?)
+            }
+            Value::Array(array)
+                if array.iter().any(|item| matches!(item, Value::Content(_))) =>
+            {
+                let items = array
+                    .into_iter()
+                    .map(|item| match item {
+                        Value::Content(body) => {
+                            realize_structure(engine, &body, styles).map(Value::Content)
+                        }
+                        item => Ok(item),
+                    })
+                    .collect::<SourceResult<_>>()?;
+                Value::Array(items)
+            }
+            _ => continue,
+        };
+        realized.try_set_field(id, value).ok();
+    }
+
+    Ok(realized)
+}
+
+/// Find the content an element is turned into by the show rules when
+/// preserving structure. Returns `None` if the element should stay as is.
+fn show_structure(
+    engine: &mut Engine,
+    target: &Content,
+    styles: StyleChain,
+) -> SourceResult<Option<Content>> {
+    // Find out how many recipes there are.
+    let mut n = styles.recipes().count();
+
+    // Find an applicable recipe.
+    for recipe in styles.recipes() {
+        let guard = Guard::Nth(n);
+        if recipe.applicable(target) && !target.is_guarded(guard) {
+            if let Some(content) = try_apply(engine, target, recipe, guard)? {
+                return Ok(Some(content));
+            }
+        }
+        n -= 1;
+    }
+
+    // Elements with semantics of their own are kept.
+    if is_structural(target) {
+        return Ok(None);
+    }
+
+    // Show everything else, e.g. references and outlines.
+    if let Some(showable) = target.with::<dyn Show>() {
+        if !target.is_guarded(Guard::Base(target.func())) {
+            return showable.show(engine, styles).map(Some);
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether an element is kept by [`realize_structure`] instead of being shown
+/// through its built-in show rule.
+fn is_structural(target: &Content) -> bool {
+    target.is::<HeadingElem>()
+        || target.is::<FigureElem>()
+        || target.is::<LinkElem>()
+        || target.is::<StrongElem>()
+        || target.is::<EmphElem>()
+        || target.is::<QuoteElem>()
+        || target.is::<RawElem>()
+        || target.is::<EquationElem>()
+        || target.is::<TableElem>()
+        || target.is::<ListElem>()
+        || target.is::<EnumElem>()
+        || target.is::<TermsElem>()
+}
+
 /// Try to apply a recipe to the target.
 fn try_apply(
     engine: &mut Engine,
@@ -241,15 +381,16 @@ fn try_apply(
             Ok(Some(Content::sequence(result)))
         }
 
+        // The recipe is known to be applicable, so these selectors transform
+        // the whole target.
+        Some(Selector::Location(_) | Selector::Or(_) | Selector::And(_)) => {
+            recipe.apply(engine, target.clone().guarded(guard)).map(Some)
+        }
+
         // Not supported here.
-        Some(
-            Selector::Or(_)
-            | Selector::And(_)
-            | Selector::Location(_)
-            | Selector::Can(_)
-            | Selector::Before { .. }
-            | Selector::After { .. },
-        ) => Ok(None),
+        Some(Selector::Can(_) | Selector::Before { .. } | Selector::After { .. }) => {
+            Ok(None)
+        }
 
         None => Ok(None),
     }
@@ -308,11 +449,12 @@ impl<'a, 'v, 't> Builder<'a, 'v, 't> {
         if let Some(realized) = realize(self.engine, content, styles)? {
             self.engine.route.increase();
             if !self.engine.route.within(Route::MAX_SHOW_RULE_DEPTH) {
-                bail!(
+                bail!(error!(
                     content.span(), "maximum show rule depth exceeded";
                     hint: "check whether the show rule matches its own output";
                     hint: "this is a current compiler limitation that will be resolved in the future",
-                );
+                )
+                .with_code("maximum-show-rule-depth"));
             }
             let stored = self.scratch.content.alloc(realized);
             let v = self.accept(stored, styles);
@@ -572,6 +714,13 @@ impl<'a> FlowBuilder<'a> {
 
             let (above, below) = if let Some(block) = content.to::<BlockElem>() {
                 (block.above(styles), block.below(styles))
+            } else if let Some(spacing) = content
+                .is::<ParElem>()
+                .then(|| ParElem::spacing_in(styles).as_custom())
+                .flatten()
+            {
+                let spacing = VElem::block_spacing(spacing.into());
+                (spacing.clone(), spacing)
             } else {
                 (BlockElem::above_in(styles), BlockElem::below_in(styles))
             };
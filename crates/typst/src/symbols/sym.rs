@@ -32,6 +32,7 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
         punct: '\u{2008}',
         thin: '\u{2009}',
         hair: '\u{200A}',
+        ideo: '\u{3000}',
     ],
 
     // Delimiters.
@@ -39,6 +40,8 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     brace: [l: '{', r: '}', t: '⏞', b: '⏟'],
     bracket: [l: '[', l.double: '⟦', r: ']', r.double: '⟧', t: '⎴', b: '⎵'],
     turtle: [l: '〔', r: '〕', t: '⏠', b: '⏡'],
+    corner: [l: '「', r: '」', l.double: '『', r.double: '』'],
+    lenticular: [l: '【', r: '】', l.stroked: '〖', r.stroked: '〗'],
     bar: [v: '|', v.double: '‖', v.triple: '⦀', v.broken: '¦', v.circle: '⦶', h: '―'],
     fence: [l: '⧘', l.double: '⧚', r: '⧙', r.double: '⧛', dotted: '⦙'],
     angle: [
@@ -78,7 +81,7 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
     backslash: ['\\', circle: '⦸', not: '⧷'],
     co: '℅',
     colon: [':', eq: '≔', double.eq: '⩴'],
-    comma: ',',
+    comma: [',', ideo: '、'],
     dagger: ['†', double: '‡'],
     dash: [
         en: '–',
@@ -99,6 +102,7 @@ pub(crate) const SYM: &[(&str, Symbol)] = symbols! {
         double: '¨',
         triple: '\u{20db}',
         quad: '\u{20dc}',
+        ideo: '。',
     ],
     excl: ['!', double: '‼', inv: '¡', quest: '⁉'],
     quest: ['?', double: '⁇', excl: '⁈', inv: '¿'],
@@ -1,3 +1,5 @@
+use unicode_segmentation::UnicodeSegmentation;
+
 use crate::foundations::{cast, func, Cast, Content, Str};
 use crate::text::TextElem;
 
@@ -33,6 +35,25 @@ pub fn upper(
     case(text, Case::Upper)
 }
 
+/// Converts a string or content to title case.
+///
+/// The first letter of each word is uppercased, while all other letters stay
+/// as they are. Text without case, like Chinese, is left untouched.
+///
+/// # Example
+/// ```example
+/// #title-case("the lord of the rings") \
+/// #title-case[*don't* panic] \
+/// #title-case[Already Titled]
+/// ```
+#[func(title = "Title Case")]
+pub fn title_case(
+    /// The text to convert to title case.
+    text: Caseable,
+) -> Caseable {
+    case(text, Case::Title)
+}
+
 /// Change the case of text.
 fn case(text: Caseable, case: Case) -> Caseable {
     match text {
@@ -66,14 +87,36 @@ pub enum Case {
     Lower,
     /// Everything is uppercased.
     Upper,
+    /// The first letter of each word is uppercased.
+    Title,
 }
 
 impl Case {
     /// Apply the case to a string.
     pub fn apply(self, text: &str) -> String {
+        self.apply_continuing(text, false)
+    }
+
+    /// Apply the case to a string that may directly continue a word of
+    /// preceding text. In that case, title case leaves the start of the string
+    /// as it is.
+    pub fn apply_continuing(self, text: &str, continuing: bool) -> String {
         match self {
             Self::Lower => text.to_lowercase(),
             Self::Upper => text.to_uppercase(),
+            Self::Title => text
+                .split_word_bounds()
+                .enumerate()
+                .map(|(i, word)| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 || !continuing => {
+                            first.to_uppercase().chain(chars).collect()
+                        }
+                        _ => word.to_string(),
+                    }
+                })
+                .collect(),
         }
     }
 }
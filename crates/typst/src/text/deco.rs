@@ -6,12 +6,15 @@ use ecow::{eco_format, EcoString};
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{cast, elem, ty, Content, Fold, Repr, Show, Smart, StyleChain};
-use crate::layout::{Abs, Em, Frame, FrameItem, Length, Point, Size};
+use crate::layout::{
+    Abs, Corners, Em, Frame, FrameItem, Length, Point, Rel, Sides, Size,
+};
 use crate::syntax::Span;
 use crate::text::{
-    BottomEdge, BottomEdgeMetric, TextElem, TextItem, TopEdge, TopEdgeMetric,
+    BottomEdge, BottomEdgeMetric, MetricOverrides, TextElem, TextItem, TopEdge,
+    TopEdgeMetric,
 };
-use crate::visualize::{Color, FixedStroke, Geometry, Paint, Stroke};
+use crate::visualize::{styled_rect, Color, FixedStroke, Geometry, Paint, Stroke};
 
 /// Underlines text.
 ///
@@ -285,6 +288,18 @@ pub struct HighlightElem {
     #[default(Color::from_u8(0xFF, 0xFF, 0x5F, 0xFF).into())]
     pub fill: Paint,
 
+    /// The highlight's border. See the
+    /// [rectangle's documentation]($rect.stroke) for more details.
+    ///
+    /// ```example
+    /// This is a #highlight(
+    ///   stroke: fuchsia
+    /// )[stroked highlight].
+    /// ```
+    #[resolve]
+    #[fold]
+    pub stroke: Sides<Option<Option<Stroke>>>,
+
     /// The top end of the background rectangle.
     ///
     /// ```example
@@ -318,6 +333,18 @@ pub struct HighlightElem {
     #[resolve]
     pub extent: Length,
 
+    /// How much to round the highlight's corners. See the
+    /// [rectangle's documentation]($rect.radius) for more details.
+    ///
+    /// ```example
+    /// Listen #highlight(
+    ///   radius: 5pt, extent: 2pt
+    /// )[carefully], it will be on the test.
+    /// ```
+    #[resolve]
+    #[fold]
+    pub radius: Corners<Option<Rel<Length>>>,
+
     /// The content that should be highlighted.
     #[required]
     pub body: Content,
@@ -329,8 +356,10 @@ impl Show for HighlightElem {
         Ok(self.body().clone().styled(TextElem::set_deco(Decoration {
             line: DecoLine::Highlight {
                 fill: self.fill(styles),
+                stroke: self.stroke(styles).map(|s| s.map(Stroke::unwrap_or_default)),
                 top_edge: self.top_edge(styles),
                 bottom_edge: self.bottom_edge(styles),
+                radius: self.radius(styles),
             },
             extent: self.extent(styles),
         })))
@@ -370,10 +399,30 @@ cast! {
 /// A kind of decorative line.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 enum DecoLine {
-    Underline { stroke: Stroke<Abs>, offset: Smart<Abs>, evade: bool, background: bool },
-    Strikethrough { stroke: Stroke<Abs>, offset: Smart<Abs>, background: bool },
-    Overline { stroke: Stroke<Abs>, offset: Smart<Abs>, evade: bool, background: bool },
-    Highlight { fill: Paint, top_edge: TopEdge, bottom_edge: BottomEdge },
+    Underline {
+        stroke: Stroke<Abs>,
+        offset: Smart<Abs>,
+        evade: bool,
+        background: bool,
+    },
+    Strikethrough {
+        stroke: Stroke<Abs>,
+        offset: Smart<Abs>,
+        background: bool,
+    },
+    Overline {
+        stroke: Stroke<Abs>,
+        offset: Smart<Abs>,
+        evade: bool,
+        background: bool,
+    },
+    Highlight {
+        fill: Paint,
+        stroke: Sides<Option<FixedStroke>>,
+        top_edge: TopEdge,
+        bottom_edge: BottomEdge,
+        radius: Corners<Rel<Abs>>,
+    },
 }
 
 /// Add line decorations to a single run of shaped text.
@@ -381,18 +430,25 @@ pub(crate) fn decorate(
     frame: &mut Frame,
     deco: &Decoration,
     text: &TextItem,
+    overrides: &MetricOverrides,
     width: Abs,
     shift: Abs,
     pos: Point,
 ) {
     let font_metrics = text.font.metrics();
 
-    if let DecoLine::Highlight { fill, top_edge, bottom_edge } = &deco.line {
-        let (top, bottom) = determine_edges(text, *top_edge, *bottom_edge);
-        let rect = Geometry::Rect(Size::new(width + 2.0 * deco.extent, top - bottom))
-            .filled(fill.clone());
+    if let DecoLine::Highlight { fill, stroke, top_edge, bottom_edge, radius } =
+        &deco.line
+    {
+        let (top, bottom) = determine_edges(text, overrides, *top_edge, *bottom_edge);
+        let size = Size::new(width + 2.0 * deco.extent, top - bottom);
+        let rects = styled_rect(size, *radius, Some(fill.clone()), stroke.clone());
         let origin = Point::new(pos.x - deco.extent, pos.y - top - shift);
-        frame.prepend(origin, FrameItem::Shape(rect, Span::detached()));
+        frame.prepend_multiple(
+            rects
+                .into_iter()
+                .map(|shape| (origin, FrameItem::Shape(shape, Span::detached()))),
+        );
         return;
     }
 
@@ -501,6 +557,7 @@ pub(crate) fn decorate(
 // Return the top/bottom edge of the text given the metric of the font.
 fn determine_edges(
     text: &TextItem,
+    overrides: &MetricOverrides,
     top_edge: TopEdge,
     bottom_edge: BottomEdge,
 ) -> (Abs, Abs) {
@@ -518,8 +575,8 @@ fn determine_edges(
             });
     }
 
-    let top = top_edge.resolve(text.size, &text.font, bbox);
-    let bottom = bottom_edge.resolve(text.size, &text.font, bbox);
+    let top = top_edge.resolve(text.size, &text.font, overrides, bbox);
+    let bottom = bottom_edge.resolve(text.size, &text.font, overrides, bbox);
     (top, bottom)
 }
 
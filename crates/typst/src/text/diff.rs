@@ -0,0 +1,276 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::diag::SourceResult;
+use crate::engine::Engine;
+use crate::foundations::{elem, scope, Content, NativeElement, Show, Str, StyleChain};
+use crate::text::{StrikeElem, TextElem, UnderlineElem};
+
+/// Marks the changes between two versions of a text.
+///
+/// Text that only occurs in the revised version is shown as an
+/// [insertion]($diff.insertion) and text that only occurs in the original
+/// version as a [deletion]($diff.deletion). The texts are compared word by word;
+/// Chinese and Japanese text is compared character by character.
+///
+/// # Example
+/// ```example
+/// #diff(
+///   "The fee is due within 30 days.",
+///   "The fee is due within 14 days of delivery.",
+/// )
+///
+/// #diff("甲方应于三十日内付款。", "甲方应于十四日内付款。")
+/// ```
+///
+/// # Styling
+/// Insertions are underlined and deletions are struck through. Show rules on
+/// the two elements change how they look, for example to hide the deletions
+/// or to color the changes:
+///
+/// ```example
+/// #show diff.insertion: set text(green)
+/// #show diff.deletion: none
+///
+/// #diff("Due in 30 days.", "Due in 14 days.")
+/// ```
+#[elem(scope, Show)]
+pub struct DiffElem {
+    /// The original version of the text.
+    #[required]
+    pub original: Str,
+
+    /// The revised version of the text.
+    #[required]
+    pub revised: Str,
+}
+
+#[scope]
+impl DiffElem {
+    #[elem]
+    type DiffInsertion;
+
+    #[elem]
+    type DiffDeletion;
+}
+
+impl Show for DiffElem {
+    #[tracing::instrument(name = "DiffElem::show", skip_all)]
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        let old: Vec<&str> = self.original().split_word_bounds().collect();
+        let new: Vec<&str> = self.revised().split_word_bounds().collect();
+
+        // Join consecutive words with the same kind of change.
+        let mut runs: Vec<(Change, String)> = vec![];
+        for (change, word) in diff(&old, &new) {
+            match runs.last_mut() {
+                Some((last, text)) if *last == change => text.push_str(word),
+                _ => runs.push((change, word.into())),
+            }
+        }
+
+        Ok(Content::sequence(runs.into_iter().map(|(change, text)| {
+            let text = TextElem::packed(text);
+            match change {
+                Change::Equal => text,
+                Change::Insert => DiffInsertion::new(text).pack(),
+                Change::Delete => DiffDeletion::new(text).pack(),
+            }
+        })))
+    }
+}
+
+/// Text that was inserted in the revised version of a [diff]($diff).
+#[elem(name = "insertion", title = "Diff Insertion", Show)]
+pub struct DiffInsertion {
+    /// The inserted text.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for DiffInsertion {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(UnderlineElem::new(self.body().clone()).pack())
+    }
+}
+
+/// Text that was deleted from the original version of a [diff]($diff).
+#[elem(name = "deletion", title = "Diff Deletion", Show)]
+pub struct DiffDeletion {
+    /// The deleted text.
+    #[required]
+    pub body: Content,
+}
+
+impl Show for DiffDeletion {
+    fn show(&self, _: &mut Engine, _: StyleChain) -> SourceResult<Content> {
+        Ok(StrikeElem::new(self.body().clone()).pack())
+    }
+}
+
+/// How a word changed between two versions.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Change {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// The largest number of insertions and deletions that a diff searches for.
+/// Texts that differ by more are shown as one deletion followed by one
+/// insertion, which keeps the time and memory of comparing large, unrelated
+/// texts in check.
+const MAX_EDITS: usize = 500;
+
+/// Compute the changes that turn `old` into `new` with as few insertions and
+/// deletions as possible. Deletions come before insertions at the same place.
+fn diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(Change, &'a str)> {
+    // Strip the common prefix and suffix, which are cheap to find.
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    let script = shortest_edit(a, b).unwrap_or_else(|| {
+        let deletions = a.iter().map(|&w| (Change::Delete, w));
+        let insertions = b.iter().map(|&w| (Change::Insert, w));
+        deletions.chain(insertions).collect()
+    });
+
+    let mut changes: Vec<_> = old[..prefix].iter().map(|&w| (Change::Equal, w)).collect();
+    let mut insertions = vec![];
+    for (change, word) in script {
+        match change {
+            Change::Insert => insertions.push((change, word)),
+            Change::Delete => changes.push((change, word)),
+            Change::Equal => {
+                changes.append(&mut insertions);
+                changes.push((change, word));
+            }
+        }
+    }
+    changes.append(&mut insertions);
+    changes.extend(old[old.len() - suffix..].iter().map(|&w| (Change::Equal, w)));
+    changes
+}
+
+/// Find the shortest edit script that turns `a` into `b` with Myers'
+/// algorithm, or `None` if it needs more than [`MAX_EDITS`] edits.
+fn shortest_edit<'a>(a: &[&'a str], b: &[&'a str]) -> Option<Vec<(Change, &'a str)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = (a.len() + b.len()).min(MAX_EDITS) as isize;
+    let index = |k: isize| (k + max + 1) as usize;
+
+    // For each diagonal `k`, the furthest `x` reached in `a` on it, before
+    // each step.
+    let mut furthest = vec![0; 2 * max as usize + 3];
+    let mut trace = vec![];
+
+    // Whether the path to diagonal `k` in step `d` comes from diagonal `k + 1`
+    // with an insertion instead of from `k - 1` with a deletion.
+    let down = |v: &[isize], d: isize, k: isize| {
+        k == -d || (k != d && v[index(k - 1)] < v[index(k + 1)])
+    };
+
+    for d in 0..=max {
+        trace.push(furthest.clone());
+        for k in (-d..=d).step_by(2) {
+            let mut x = if down(&furthest, d, k) {
+                furthest[index(k + 1)]
+            } else {
+                furthest[index(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            furthest[index(k)] = x;
+
+            if x >= n && y >= m {
+                return Some(backtrack(a, b, &trace, index, down));
+            }
+        }
+    }
+
+    None
+}
+
+/// Walk back through the steps of Myers' algorithm to find the edits.
+fn backtrack<'a>(
+    a: &[&'a str],
+    b: &[&'a str],
+    trace: &[Vec<isize>],
+    index: impl Fn(isize) -> usize,
+    down: impl Fn(&[isize], isize, isize) -> bool,
+) -> Vec<(Change, &'a str)> {
+    let (mut x, mut y) = (a.len() as isize, b.len() as isize);
+    let mut script = vec![];
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+        let k = x - y;
+        let prev_k = if down(v, d, k) { k + 1 } else { k - 1 };
+        let prev_x = v[index(prev_k)];
+        let prev_y = prev_x - prev_k;
+
+        while x > prev_x && y > prev_y {
+            script.push((Change::Equal, a[x as usize - 1]));
+            x -= 1;
+            y -= 1;
+        }
+
+        if d > 0 {
+            if x == prev_x {
+                script.push((Change::Insert, b[y as usize - 1]));
+                y -= 1;
+            } else {
+                script.push((Change::Delete, a[x as usize - 1]));
+                x -= 1;
+            }
+        }
+    }
+
+    script.reverse();
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(old: &str, new: &str) -> String {
+        let old: Vec<&str> = old.split_word_bounds().collect();
+        let new: Vec<&str> = new.split_word_bounds().collect();
+        diff(&old, &new)
+            .into_iter()
+            .map(|(change, word)| match change {
+                Change::Equal => word.into(),
+                Change::Insert => format!("+{word}"),
+                Change::Delete => format!("-{word}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(changes("a b c", "a b c"), "a b c");
+        assert_eq!(changes("a b c", "a c"), "a -b- c");
+        assert_eq!(changes("a c", "a b c"), "a +b+ c");
+        assert_eq!(changes("a b c", "a d c"), "a -b+d c");
+        assert_eq!(changes("", "a"), "+a");
+        assert_eq!(changes("a b", "b a"), "-a- b+ +a");
+    }
+
+    #[test]
+    fn test_diff_too_different() {
+        let old = vec!["a"; MAX_EDITS];
+        let new = vec!["b"; MAX_EDITS];
+        let changes = diff(&old, &new);
+        assert!(changes[..MAX_EDITS].iter().all(|&c| c == (Change::Delete, "a")));
+        assert!(changes[MAX_EDITS..].iter().all(|&c| c == (Change::Insert, "b")));
+    }
+}
@@ -45,6 +45,13 @@ impl FontBook {
         self.infos.push(info);
     }
 
+    /// Whether the book contains a font from the given family.
+    ///
+    /// The `family` should be all lowercase.
+    pub fn contains_family(&self, family: &str) -> bool {
+        self.families.contains_key(family)
+    }
+
     /// Get the font info for the given index.
     pub fn info(&self, index: usize) -> Option<&FontInfo> {
         self.infos.get(index)
@@ -10,6 +10,7 @@ use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
+use once_cell::sync::OnceCell;
 use ttf_parser::GlyphId;
 
 use self::book::find_name;
@@ -36,8 +37,9 @@ struct Repr {
     metrics: FontMetrics,
     /// The underlying ttf-parser face.
     ttf: ttf_parser::Face<'static>,
-    /// The underlying rustybuzz face.
-    rusty: rustybuzz::Face<'static>,
+    /// The underlying rustybuzz face. Parsed on first use because it reads
+    /// the large layout tables, which most fonts in the book never need.
+    rusty: OnceCell<rustybuzz::Face<'static>>,
 }
 
 impl Font {
@@ -53,9 +55,9 @@ impl Font {
             unsafe { std::slice::from_raw_parts(data.as_ptr(), data.len()) };
 
         let ttf = ttf_parser::Face::parse(slice, index).ok()?;
-        let rusty = rustybuzz::Face::from_slice(slice, index)?;
         let metrics = FontMetrics::from_ttf(&ttf);
         let info = FontInfo::from_ttf(&ttf)?;
+        let rusty = OnceCell::new();
 
         Some(Self(Arc::new(Repr { data, index, info, metrics, ttf, rusty })))
     }
@@ -120,7 +122,9 @@ impl Font {
     pub fn rusty(&self) -> &rustybuzz::Face<'_> {
         // We can't implement Deref because that would leak the
         // internal 'static lifetime.
-        &self.0.rusty
+        self.0
+            .rusty
+            .get_or_init(|| rustybuzz::Face::from_face(self.0.ttf.clone()))
     }
 }
 
@@ -218,8 +222,22 @@ impl FontMetrics {
             VerticalFontMetric::XHeight => self.x_height,
             VerticalFontMetric::Baseline => Em::zero(),
             VerticalFontMetric::Descender => self.descender,
+            VerticalFontMetric::IdeographicTop => {
+                self.ideographic_center() + Em::new(0.5)
+            }
+            VerticalFontMetric::IdeographicBottom => {
+                self.ideographic_center() - Em::new(0.5)
+            }
         }
     }
+
+    /// The vertical center of the ideographic em box.
+    ///
+    /// We don't read the `BASE` table, so the em box is assumed to be centered
+    /// between the ascender and the descender, like CSS does.
+    fn ideographic_center(&self) -> Em {
+        (self.ascender + self.descender) / 2.0
+    }
 }
 
 /// Metrics for a decorative line.
@@ -245,4 +263,9 @@ pub enum VerticalFontMetric {
     Baseline,
     /// The font's ascender, which typically exceeds the depth of all glyphs.
     Descender,
+    /// The top edge of the ideographic em box, which CJK characters are
+    /// designed to fill.
+    IdeographicTop,
+    /// The bottom edge of the ideographic em box.
+    IdeographicBottom,
 }
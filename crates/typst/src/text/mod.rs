@@ -2,12 +2,15 @@
 
 mod case;
 mod deco;
+mod diff;
 mod font;
 mod item;
 mod lang;
 mod linebreak;
 #[path = "lorem.rs"]
 mod lorem_;
+#[path = "pinyin.rs"]
+mod pinyin_;
 mod raw;
 mod shift;
 #[path = "smallcaps.rs"]
@@ -17,11 +20,13 @@ mod space;
 
 pub use self::case::*;
 pub use self::deco::*;
+pub use self::diff::*;
 pub use self::font::*;
 pub use self::item::*;
 pub use self::lang::*;
 pub use self::linebreak::*;
 pub use self::lorem_::*;
+pub use self::pinyin_::*;
 pub use self::raw::*;
 pub use self::shift::*;
 pub use self::smallcaps_::*;
@@ -34,16 +39,17 @@ use ecow::{eco_format, EcoString};
 use rustybuzz::{Feature, Tag};
 use ttf_parser::Rect;
 
-use crate::diag::{bail, SourceResult, StrResult};
+use crate::diag::{bail, warning, SourceResult, StrResult};
 use crate::engine::Engine;
 use crate::foundations::{
     cast, category, elem, Args, Array, Cast, Category, Construct, Content, Dict, Fold,
-    NativeElement, Never, PlainText, Repr, Resolve, Scope, Set, Smart, StyleChain, Value,
+    FromValue, NativeElement, Never, PlainText, Repr, Resolve, Scope, Set, Smart,
+    StyleChain, Value,
 };
-use crate::layout::{Abs, Axis, Dir, Length, Rel};
+use crate::layout::{Abs, Axis, Dir, Em, Length, Rel};
 use crate::model::ParElem;
 use crate::syntax::Spanned;
-use crate::visualize::{Color, Paint, RelativeTo};
+use crate::visualize::{Color, Paint, RelativeTo, Stroke};
 
 /// Text styling.
 ///
@@ -63,11 +69,14 @@ pub(super) fn define(global: &mut Scope) {
     global.define_elem::<OverlineElem>();
     global.define_elem::<StrikeElem>();
     global.define_elem::<HighlightElem>();
+    global.define_elem::<DiffElem>();
     global.define_elem::<RawElem>();
     global.define_func::<lower>();
     global.define_func::<upper>();
+    global.define_func::<title_case>();
     global.define_func::<smallcaps>();
     global.define_func::<lorem>();
+    global.define_func::<pinyin>();
 }
 
 /// Customizes the look and layout of text in a variety of ways.
@@ -117,6 +126,25 @@ pub struct TextElem {
     /// This is Latin. \
     /// هذا عربي.
     /// ```
+    #[parse({
+        let font_list: Option<Spanned<FontList>> = args.named("font")?;
+        if let Some(font_list) = &font_list {
+            let book = engine.world.book();
+            for family in &font_list.v {
+                if !book.contains_family(family.as_str()) {
+                    engine.tracer.warn(
+                        warning!(
+                            font_list.span,
+                            "unknown font family: {}",
+                            family.as_str(),
+                        )
+                        .with_code("unknown-font-family"),
+                    );
+                }
+            }
+        }
+        font_list.map(|font_list| font_list.v)
+    })]
     #[default(FontList(vec![FontFamily::new("Linux Libertine")]))]
     #[borrowed]
     #[ghost]
@@ -240,8 +268,26 @@ pub struct TextElem {
     #[ghost]
     pub fill: Paint,
 
+    /// How to stroke the text's glyphs, or `{none}` to only fill them.
+    ///
+    /// A stroke is drawn along the outline of each glyph, on top of the fill.
+    /// Combined with a light fill, this produces outlined text. Glyphs that
+    /// are stored as images, like color emoji, are not stroked.
+    ///
+    /// ```example
+    /// #text(size: 24pt, stroke: 0.5pt + red)[Stroked] \
+    /// #text(size: 24pt, fill: white, stroke: 0.5pt + black)[Outlined]
+    /// ```
+    #[resolve]
+    #[ghost]
+    pub stroke: Option<Stroke>,
+
     /// The amount of space that should be added between characters.
     ///
+    /// The space is added after each character except the last one of a
+    /// piece of text. Given in `em`, it scales with the font size, which is
+    /// the usual way to space out the characters of a Chinese heading.
+    ///
     /// ```example
     /// #set text(tracking: 1.5pt)
     /// Distant text.
@@ -279,6 +325,25 @@ pub struct TextElem {
     #[ghost]
     pub cjk_latin_spacing: Smart<Option<Never>>,
 
+    /// How much the gap after each CJK character may shrink to fit a line.
+    ///
+    /// Chinese and Japanese text has no spaces that could be shrunk. When a
+    /// line is slightly too long, Typst first compresses the blank halves of
+    /// CJK punctuation. Only if that isn't enough, the gaps between the
+    /// characters themselves shrink, up to this amount each. This setting
+    /// doesn't affect how far the gaps stretch in a justified paragraph.
+    ///
+    /// ```example
+    /// #set par(justify: true)
+    /// #set text(lang: "zh", cjk-shrink: 0.1em)
+    /// #rect(inset: 0pt, width: 8em)[
+    ///   汉字是汉族或华人的共同文字，是中国的官方文字。
+    /// ]
+    /// ```
+    #[resolve]
+    #[ghost]
+    pub cjk_shrink: Length,
+
     /// An amount to shift the text baseline by.
     ///
     /// ```example
@@ -343,6 +408,41 @@ pub struct TextElem {
     #[ghost]
     pub bottom_edge: BottomEdge,
 
+    /// Overrides for the vertical metrics of specific font families.
+    ///
+    /// Many CJK fonts ship ascenders and descenders that differ a lot from
+    /// those of Latin fonts, so that lines mixing both end up unevenly
+    /// spaced. This maps font family names to dictionaries with any of the
+    /// following keys, which then replace the font's own metrics when
+    /// resolving the [top]($text.top-edge) and
+    /// [bottom edge]($text.bottom-edge):
+    ///
+    /// - `ascender`, `cap-height`, and `x-height`: The heights of the
+    ///   respective metrics above the baseline.
+    /// - `descender`: The height of the descender above the baseline, which is
+    ///   typically negative.
+    /// - `line-gap`: Additional space below the family's text when its bottom
+    ///   edge is the `{"descender"}`, which is the only line-spacing metric.
+    ///   Typst ignores the line gap that fonts specify themselves.
+    ///
+    /// The `{"ideographic"}` edges are derived from the ascender and
+    /// descender, so they follow these overrides, too.
+    ///
+    /// ```example
+    /// #set rect(inset: 0pt)
+    /// #set text(font: ("Linux Libertine", "Noto Serif CJK SC"))
+    /// #set text(top-edge: "ascender", bottom-edge: "descender")
+    /// #rect(fill: aqua)[Typst 排版]
+    ///
+    /// #set text(metrics: (
+    ///   "Noto Serif CJK SC": (ascender: 0.8em, descender: -0.2em),
+    /// ))
+    /// #rect(fill: aqua)[Typst 排版]
+    /// ```
+    #[fold]
+    #[ghost]
+    pub metrics: MetricOverrides,
+
     /// An [ISO 639-1/2/3 language code.](https://en.wikipedia.org/wiki/ISO_639)
     ///
     /// Setting the correct language affects various parts of Typst:
@@ -796,12 +896,19 @@ impl TopEdge {
         matches!(self, Self::Metric(TopEdgeMetric::Bounds))
     }
 
-    /// Resolve the value of the text edge given a font's metrics.
-    pub fn resolve(self, font_size: Abs, font: &Font, bbox: Option<Rect>) -> Abs {
+    /// Resolve the value of the text edge given a font's metrics and possible
+    /// overrides for them.
+    pub fn resolve(
+        self,
+        font_size: Abs,
+        font: &Font,
+        overrides: &MetricOverrides,
+        bbox: Option<Rect>,
+    ) -> Abs {
         match self {
             TopEdge::Metric(metric) => {
                 if let Ok(metric) = metric.try_into() {
-                    font.metrics().vertical(metric).at(font_size)
+                    overrides.metrics(font, font_size).vertical(metric).at(font_size)
                 } else {
                     bbox.map(|bbox| (font.to_em(bbox.y_max)).at(font_size))
                         .unwrap_or_default()
@@ -833,6 +940,9 @@ pub enum TopEdgeMetric {
     XHeight,
     /// The baseline on which the letters rest.
     Baseline,
+    /// The top edge of the ideographic em box, which CJK characters are
+    /// designed to fill.
+    Ideographic,
     /// The top edge of the glyph's bounding box.
     Bounds,
 }
@@ -846,6 +956,7 @@ impl TryInto<VerticalFontMetric> for TopEdgeMetric {
             Self::CapHeight => Ok(VerticalFontMetric::CapHeight),
             Self::XHeight => Ok(VerticalFontMetric::XHeight),
             Self::Baseline => Ok(VerticalFontMetric::Baseline),
+            Self::Ideographic => Ok(VerticalFontMetric::IdeographicTop),
             _ => Err(()),
         }
     }
@@ -866,12 +977,26 @@ impl BottomEdge {
         matches!(self, Self::Metric(BottomEdgeMetric::Bounds))
     }
 
-    /// Resolve the value of the text edge given a font's metrics.
-    pub fn resolve(self, font_size: Abs, font: &Font, bbox: Option<Rect>) -> Abs {
+    /// Resolve the value of the text edge given a font's metrics and possible
+    /// overrides for them.
+    ///
+    /// An overridden line gap moves the descender further down. Other edges
+    /// don't contribute to line spacing, so they ignore it.
+    pub fn resolve(
+        self,
+        font_size: Abs,
+        font: &Font,
+        overrides: &MetricOverrides,
+        bbox: Option<Rect>,
+    ) -> Abs {
         match self {
+            BottomEdge::Metric(BottomEdgeMetric::Descender) => {
+                let metrics = overrides.metrics(font, font_size);
+                metrics.descender.at(font_size) - overrides.line_gap(font, font_size)
+            }
             BottomEdge::Metric(metric) => {
                 if let Ok(metric) = metric.try_into() {
-                    font.metrics().vertical(metric).at(font_size)
+                    overrides.metrics(font, font_size).vertical(metric).at(font_size)
                 } else {
                     bbox.map(|bbox| (font.to_em(bbox.y_min)).at(font_size))
                         .unwrap_or_default()
@@ -899,6 +1024,8 @@ pub enum BottomEdgeMetric {
     Baseline,
     /// The font's descender, which typically exceeds the depth of all glyphs.
     Descender,
+    /// The bottom edge of the ideographic em box.
+    Ideographic,
     /// The bottom edge of the glyph's bounding box.
     Bounds,
 }
@@ -910,6 +1037,7 @@ impl TryInto<VerticalFontMetric> for BottomEdgeMetric {
         match self {
             Self::Baseline => Ok(VerticalFontMetric::Baseline),
             Self::Descender => Ok(VerticalFontMetric::Descender),
+            Self::Ideographic => Ok(VerticalFontMetric::IdeographicBottom),
             _ => Err(()),
         }
     }
@@ -1048,6 +1176,130 @@ impl Fold for FontFeatures {
     }
 }
 
+/// Overrides for the vertical metrics of font families.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct MetricOverrides(pub Vec<(EcoString, MetricOverride)>);
+
+impl MetricOverrides {
+    /// The overrides for the given font's family, if any.
+    pub fn get(&self, font: &Font) -> Option<&MetricOverride> {
+        let family = || font.info().family.chars().flat_map(char::to_lowercase);
+        self.0
+            .iter()
+            .find(|(name, _)| name.chars().eq(family()))
+            .map(|(_, metrics)| metrics)
+    }
+
+    /// The given font's metrics with the overrides applied.
+    pub fn metrics(&self, font: &Font, font_size: Abs) -> FontMetrics {
+        let mut metrics = *font.metrics();
+        if let Some(overrides) = self.get(font) {
+            let mut apply = |metric: &mut Em, length: Option<Length>| {
+                if let Some(length) = length {
+                    *metric = Em::from_length(length.at(font_size), font_size);
+                }
+            };
+            apply(&mut metrics.ascender, overrides.ascender);
+            apply(&mut metrics.cap_height, overrides.cap_height);
+            apply(&mut metrics.x_height, overrides.x_height);
+            apply(&mut metrics.descender, overrides.descender);
+        }
+        metrics
+    }
+
+    /// The overridden line gap of the given font, or zero.
+    pub fn line_gap(&self, font: &Font, font_size: Abs) -> Abs {
+        self.get(font)
+            .and_then(|overrides| overrides.line_gap)
+            .map(|gap| gap.at(font_size))
+            .unwrap_or_default()
+    }
+}
+
+cast! {
+    MetricOverrides,
+    self => self.0
+        .into_iter()
+        .map(|(family, metrics)| (family.into(), metrics.into_value()))
+        .collect::<Dict>()
+        .into_value(),
+    values: Dict => Self(values
+        .into_iter()
+        .map(|(k, v)| Ok((k.to_lowercase().into(), v.cast::<MetricOverride>()?)))
+        .collect::<StrResult<_>>()?),
+}
+
+impl Fold for MetricOverrides {
+    type Output = Self;
+
+    fn fold(mut self, outer: Self::Output) -> Self::Output {
+        for (family, outer) in outer.0 {
+            if let Some((_, inner)) = self.0.iter_mut().find(|(f, _)| *f == family) {
+                *inner = inner.clone().or(outer);
+            } else {
+                self.0.push((family, outer));
+            }
+        }
+        self
+    }
+}
+
+/// Overrides for the vertical metrics of a font family.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Hash)]
+pub struct MetricOverride {
+    /// The height of the ascender.
+    pub ascender: Option<Length>,
+    /// The height of uppercase letters.
+    pub cap_height: Option<Length>,
+    /// The height of non-ascending lowercase letters.
+    pub x_height: Option<Length>,
+    /// The height of the descender.
+    pub descender: Option<Length>,
+    /// Additional space below the bottom edge.
+    pub line_gap: Option<Length>,
+}
+
+impl MetricOverride {
+    /// Fill in the metrics that aren't overridden here from `other`.
+    fn or(self, other: Self) -> Self {
+        Self {
+            ascender: self.ascender.or(other.ascender),
+            cap_height: self.cap_height.or(other.cap_height),
+            x_height: self.x_height.or(other.x_height),
+            descender: self.descender.or(other.descender),
+            line_gap: self.line_gap.or(other.line_gap),
+        }
+    }
+}
+
+cast! {
+    MetricOverride,
+    self => {
+        let mut dict = Dict::new();
+        let mut insert = |key: &str, value: Option<Length>| {
+            if let Some(value) = value {
+                dict.insert(key.into(), value.into_value());
+            }
+        };
+        insert("ascender", self.ascender);
+        insert("cap-height", self.cap_height);
+        insert("x-height", self.x_height);
+        insert("descender", self.descender);
+        insert("line-gap", self.line_gap);
+        dict.into_value()
+    },
+    mut dict: Dict => {
+        let mut take = |key| dict.take(key).ok().map(Length::from_value).transpose();
+        let ascender = take("ascender")?;
+        let cap_height = take("cap-height")?;
+        let x_height = take("x-height")?;
+        let descender = take("descender")?;
+        let line_gap = take("line-gap")?;
+        dict.finish(&["ascender", "cap-height", "x-height", "descender", "line-gap"])?;
+        Self { ascender, cap_height, x_height, descender, line_gap }
+    },
+}
+
 /// Collect the OpenType features to apply.
 pub(crate) fn features(styles: StyleChain) -> Vec<Feature> {
     let mut tags = vec![];
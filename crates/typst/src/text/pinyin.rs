@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+
+use ecow::{eco_format, EcoString};
+use once_cell::sync::Lazy;
+
+use crate::diag::{bail, StrResult};
+use crate::foundations::{array, func, Array, Cast, Dict, IntoValue, Str, Value};
+
+/// Looks up the pinyin readings of Chinese text.
+///
+/// Typst ships the most common reading of each character in the CJK Unified
+/// Ideographs block. Characters with several readings, like the `重` in
+/// `重庆`, thus need a dictionary that maps them or the words they appear in
+/// to the right pinyin, for example loaded from a file with [`yaml`]($yaml)
+/// or [`json`]($json). Readings may be written with tone numbers
+/// (`{"chong2 qing4"}`) or tone marks (`{"chóng qìng"}`). Words take
+/// precedence over single characters, so listing a word fixes the reading of
+/// a polyphonic character within it.
+///
+/// The result is an array of pairs of a piece of the text and its reading,
+/// which can be used to annotate each character. Pieces without a reading,
+/// like punctuation, have `{none}` as their reading.
+///
+/// # Example
+/// ```example
+/// #let dict = ("重庆": "chong2 qing4")
+/// #for (base, reading) in pinyin("重庆很重要", dict: dict) {
+///   box(align(center, stack(
+///     spacing: 2pt,
+///     text(0.6em, if reading == none { [] } else { reading }),
+///     base,
+///   )))
+/// }
+/// ```
+#[func]
+pub fn pinyin(
+    /// The text to look up.
+    text: Str,
+    /// Readings of characters and words that take precedence over the
+    /// built-in ones. Readings of words consist of one syllable per
+    /// character, separated by spaces.
+    #[named]
+    #[default]
+    dict: Dict,
+    /// How to write the readings.
+    #[named]
+    #[default(PinyinStyle::Marks)]
+    style: PinyinStyle,
+) -> StrResult<Array> {
+    let longest = dict.iter().map(|(key, _)| key.chars().count()).max().unwrap_or(0);
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let offset = |i: usize| chars.get(i).map_or(text.len(), |&(offset, _)| offset);
+
+    let mut pairs = Array::new();
+    let mut unknown = None;
+    let mut i = 0;
+    while i < chars.len() {
+        // Find the longest word at this position that has a reading, falling
+        // back to the built-in reading of the character.
+        let found = (1..=longest.min(chars.len() - i))
+            .rev()
+            .find_map(|len| {
+                let word = &text[offset(i)..offset(i + len)];
+                dict.get(word).ok().map(|reading| (word, len, reading.clone()))
+            })
+            .or_else(|| {
+                let reading = Str::from(*READINGS.get(&chars[i].1)?);
+                Some((&text[offset(i)..offset(i + 1)], 1, reading.into_value()))
+            });
+
+        let Some((word, len, reading)) = found else {
+            unknown.get_or_insert(i);
+            i += 1;
+            continue;
+        };
+
+        if let Some(start) = unknown.take() {
+            let base = &text[offset(start)..offset(i)];
+            pairs.push(array![base, Value::None].into_value());
+        }
+
+        let reading = reading.cast::<Str>()?;
+        let syllables = reading
+            .split_whitespace()
+            .map(|syllable| Syllable::parse(syllable)?.write(style))
+            .collect::<StrResult<Vec<_>>>()?;
+
+        // Distribute the syllables across the characters of the word if
+        // possible.
+        if syllables.len() == len {
+            for (k, syllable) in syllables.into_iter().enumerate() {
+                let base = &text[offset(i + k)..offset(i + k + 1)];
+                pairs.push(array![base, syllable].into_value());
+            }
+        } else {
+            pairs.push(array![word, syllables.join(" ")].into_value());
+        }
+
+        i += len;
+    }
+
+    if let Some(start) = unknown {
+        pairs.push(array![&text[offset(start)..], Value::None].into_value());
+    }
+
+    Ok(pairs)
+}
+
+/// The built-in reading of each character.
+static READINGS: Lazy<HashMap<char, &str>> = Lazy::new(|| {
+    include_str!("../../assets/pinyin.txt")
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once(' '))
+        .flat_map(|(reading, chars)| chars.chars().map(move |c| (c, reading)))
+        .collect()
+});
+
+/// A string that sorts Chinese text by its built-in pinyin.
+///
+/// Each character with a reading is replaced by the reading with a tone
+/// number, so that `字体` becomes `zi4 ti3`. Other characters are kept.
+pub(crate) fn collation_key(text: &str) -> EcoString {
+    let mut key = EcoString::new();
+    let mut last_was_syllable = false;
+    for c in text.chars() {
+        let syllable = READINGS.get(&c).and_then(|reading| Syllable::parse(reading).ok());
+        if let Some(syllable) = &syllable {
+            if last_was_syllable {
+                key.push(' ');
+            }
+            // Write `ü` as `v` so that it sorts right after `u`.
+            key.push_str(&syllable.letters.replace('ü', "v"));
+            key.push_str(&syllable.tone.to_string());
+        } else {
+            key.push(c);
+        }
+        last_was_syllable = syllable.is_some();
+    }
+    key
+}
+
+/// How to write pinyin readings.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum PinyinStyle {
+    /// With tone marks, like `zhōng`.
+    Marks,
+    /// With tone numbers, like `zhong1`. The neutral tone is written as `5`.
+    Numbers,
+    /// In zhuyin (bopomofo), like `ㄓㄨㄥ`.
+    Zhuyin,
+}
+
+/// A pinyin syllable.
+struct Syllable {
+    /// The letters without tone marks, with `ü` instead of `v` or `u:`.
+    letters: EcoString,
+    /// The tone from 1 to 4, or 5 for the neutral tone.
+    tone: u8,
+}
+
+impl Syllable {
+    /// Parse a syllable with a tone number or a tone mark.
+    fn parse(text: &str) -> StrResult<Self> {
+        // A zero is sometimes used for the neutral tone, too.
+        let (body, mut tone) = match text.char_indices().last() {
+            Some((i, '0')) => (&text[..i], 5),
+            Some((i, c @ '1'..='5')) => (&text[..i], c as u8 - b'0'),
+            _ => (text, 5),
+        };
+
+        let mut letters = EcoString::new();
+        for c in body.replace("u:", "ü").replace("U:", "Ü").chars() {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            let plain = match lower {
+                'v' => 'ü',
+                'a'..='z' | 'ü' | 'ê' => lower,
+                _ => match VOWELS.iter().find_map(|(vowel, marks)| {
+                    marks.iter().position(|&m| m == lower).map(|t| (*vowel, t))
+                }) {
+                    Some((vowel, t)) => {
+                        tone = t as u8 + 1;
+                        vowel
+                    }
+                    None => bail!("invalid pinyin syllable `{text}`"),
+                },
+            };
+
+            if c.is_uppercase() {
+                letters.push(uppercase(plain));
+            } else {
+                letters.push(plain);
+            }
+        }
+
+        if letters.is_empty() {
+            bail!("invalid pinyin syllable `{text}`");
+        }
+
+        Ok(Self { letters, tone })
+    }
+
+    /// Write the syllable in the given style.
+    fn write(&self, style: PinyinStyle) -> StrResult<EcoString> {
+        match style {
+            PinyinStyle::Marks => Ok(self.marked()),
+            PinyinStyle::Numbers => Ok(eco_format!("{}{}", self.letters, self.tone)),
+            PinyinStyle::Zhuyin => self.zhuyin().ok_or_else(|| {
+                eco_format!("cannot write `{}{}` in zhuyin", self.letters, self.tone)
+            }),
+        }
+    }
+
+    /// The syllable with a tone mark.
+    ///
+    /// The mark goes on `a` or `e` if there is one, on the `o` of `ou`, and
+    /// on the last vowel otherwise.
+    fn marked(&self) -> EcoString {
+        let lower: Vec<char> = self.letters.to_lowercase().chars().collect();
+        let is_vowel = |c: &char| VOWELS.iter().any(|(vowel, _)| vowel == c);
+        let index = lower
+            .iter()
+            .position(|&c| c == 'a' || c == 'e')
+            .or_else(|| lower.windows(2).position(|pair| pair == ['o', 'u']))
+            .or_else(|| lower.iter().rposition(is_vowel));
+
+        let mark = index.filter(|_| (1..=4).contains(&self.tone)).and_then(|index| {
+            let (_, marks) = VOWELS.iter().find(|(vowel, _)| *vowel == lower[index])?;
+            Some((index, marks[self.tone as usize - 1]))
+        });
+
+        let Some((index, mark)) = mark else { return self.letters.clone() };
+        self.letters
+            .chars()
+            .enumerate()
+            .map(|(i, c)| match (i == index, c.is_uppercase()) {
+                (true, true) => uppercase(mark),
+                (true, false) => mark,
+                (false, _) => c,
+            })
+            .collect()
+    }
+
+    /// The syllable in zhuyin, with the tone mark after the syllable or, for
+    /// the neutral tone, before it.
+    fn zhuyin(&self) -> Option<EcoString> {
+        let letters = self.letters.to_lowercase();
+
+        // Syllables starting with `y` and `w` have no initial and spell their
+        // medial differently.
+        let (initial, rest) = if let Some(rest) = letters.strip_prefix("yu") {
+            ("", eco_format!("ü{rest}"))
+        } else if let Some(rest) = letters.strip_prefix("yi") {
+            ("", eco_format!("i{rest}"))
+        } else if let Some(rest) = letters.strip_prefix('y') {
+            ("", eco_format!("i{rest}"))
+        } else if let Some(rest) = letters.strip_prefix("wu") {
+            ("", eco_format!("u{rest}"))
+        } else if let Some(rest) = letters.strip_prefix('w') {
+            ("", eco_format!("u{rest}"))
+        } else {
+            let (initial, symbol) = INITIALS
+                .iter()
+                .find(|(initial, _)| letters.starts_with(initial))
+                .copied()
+                .unwrap_or(("", ""));
+            let mut rest = EcoString::from(&letters[initial.len()..]);
+
+            // After `j`, `q`, and `x`, `u` stands for `ü`.
+            if matches!(initial, "j" | "q" | "x") && rest.starts_with('u') {
+                rest = eco_format!("ü{}", &rest[1..]);
+            }
+
+            // Abbreviated finals after an initial.
+            let full = match rest.as_str() {
+                "iu" => Some("iou"),
+                "ui" => Some("uei"),
+                "un" => Some("uen"),
+                "i" if matches!(initial, "zh" | "ch" | "sh" | "r" | "z" | "c" | "s") => {
+                    Some("")
+                }
+                _ => None,
+            };
+            if let Some(full) = full {
+                rest = full.into();
+            }
+
+            (symbol, rest)
+        };
+
+        let final_ = if rest.is_empty() && !initial.is_empty() {
+            ""
+        } else {
+            FINALS.iter().find(|(spelling, _)| *spelling == rest.as_str())?.1
+        };
+
+        Some(match self.tone {
+            5 => eco_format!("˙{initial}{final_}"),
+            tone => {
+                let mark = ["", "ˊ", "ˇ", "ˋ"][tone as usize - 1];
+                eco_format!("{initial}{final_}{mark}")
+            }
+        })
+    }
+}
+
+/// The uppercase form of a letter.
+fn uppercase(c: char) -> char {
+    c.to_uppercase().next().unwrap_or(c)
+}
+
+/// The vowels that can carry a tone mark, along with their marked forms for
+/// the first to fourth tone.
+const VOWELS: [(char, [char; 4]); 6] = [
+    ('a', ['ā', 'á', 'ǎ', 'à']),
+    ('e', ['ē', 'é', 'ě', 'è']),
+    ('i', ['ī', 'í', 'ǐ', 'ì']),
+    ('o', ['ō', 'ó', 'ǒ', 'ò']),
+    ('u', ['ū', 'ú', 'ǔ', 'ù']),
+    ('ü', ['ǖ', 'ǘ', 'ǚ', 'ǜ']),
+];
+
+/// The initials and their zhuyin symbols, with longer spellings first.
+const INITIALS: [(&str, &str); 21] = [
+    ("zh", "ㄓ"),
+    ("ch", "ㄔ"),
+    ("sh", "ㄕ"),
+    ("b", "ㄅ"),
+    ("p", "ㄆ"),
+    ("m", "ㄇ"),
+    ("f", "ㄈ"),
+    ("d", "ㄉ"),
+    ("t", "ㄊ"),
+    ("n", "ㄋ"),
+    ("l", "ㄌ"),
+    ("g", "ㄍ"),
+    ("k", "ㄎ"),
+    ("h", "ㄏ"),
+    ("j", "ㄐ"),
+    ("q", "ㄑ"),
+    ("x", "ㄒ"),
+    ("r", "ㄖ"),
+    ("z", "ㄗ"),
+    ("c", "ㄘ"),
+    ("s", "ㄙ"),
+];
+
+/// The finals in their full spelling and their zhuyin symbols.
+const FINALS: [(&str, &str); 37] = [
+    ("a", "ㄚ"),
+    ("o", "ㄛ"),
+    ("e", "ㄜ"),
+    ("ê", "ㄝ"),
+    ("ai", "ㄞ"),
+    ("ei", "ㄟ"),
+    ("ao", "ㄠ"),
+    ("ou", "ㄡ"),
+    ("an", "ㄢ"),
+    ("en", "ㄣ"),
+    ("ang", "ㄤ"),
+    ("eng", "ㄥ"),
+    ("ong", "ㄨㄥ"),
+    ("er", "ㄦ"),
+    ("i", "ㄧ"),
+    ("ia", "ㄧㄚ"),
+    ("ie", "ㄧㄝ"),
+    ("iao", "ㄧㄠ"),
+    ("iou", "ㄧㄡ"),
+    ("ian", "ㄧㄢ"),
+    ("in", "ㄧㄣ"),
+    ("iang", "ㄧㄤ"),
+    ("ing", "ㄧㄥ"),
+    ("iong", "ㄩㄥ"),
+    ("u", "ㄨ"),
+    ("ua", "ㄨㄚ"),
+    ("uo", "ㄨㄛ"),
+    ("uai", "ㄨㄞ"),
+    ("uei", "ㄨㄟ"),
+    ("uan", "ㄨㄢ"),
+    ("uen", "ㄨㄣ"),
+    ("uang", "ㄨㄤ"),
+    ("ueng", "ㄨㄥ"),
+    ("ü", "ㄩ"),
+    ("üe", "ㄩㄝ"),
+    ("üan", "ㄩㄢ"),
+    ("ün", "ㄩㄣ"),
+];
@@ -1,4 +1,5 @@
 use std::hash::Hash;
+use std::num::NonZeroUsize;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -15,8 +16,8 @@ use crate::foundations::{
     cast, elem, scope, Args, Array, Bytes, Content, Finalize, Fold, NativeElement,
     PlainText, Show, Smart, StyleChain, Styles, Synthesize, Value,
 };
-use crate::layout::{BlockElem, Em, HAlign};
-use crate::model::Figurable;
+use crate::layout::{Align, BlockElem, BoxElem, Em, HAlign, HElem, Rel, Sizing};
+use crate::model::{Figurable, Numbering};
 use crate::syntax::{split_newlines, LinkedNode, Spanned};
 use crate::text::{
     FontFamily, FontList, Hyphenate, Lang, LinebreakElem, LocalName, Region,
@@ -256,6 +257,41 @@ pub struct RawElem {
     #[default(2)]
     pub tab_size: usize,
 
+    /// How to number the lines of a raw block, or `{none}` to leave them
+    /// unnumbered. Accepts a [numbering pattern or function]($numbering).
+    ///
+    /// The numbers hang into the margin next to the block, so that the code
+    /// itself stays aligned with the surrounding text. Inline raw text is
+    /// never numbered.
+    ///
+    /// ````example
+    /// #set raw(numbering: n => text(gray, str(n)))
+    /// ```rust
+    /// fn main() {
+    ///     println!("Hello");
+    /// }
+    /// ```
+    /// ````
+    #[borrowed]
+    pub numbering: Option<Numbering>,
+
+    /// The number of the first line, e.g. for an excerpt from a larger file.
+    ///
+    /// ````example
+    /// #set raw(numbering: "1", number-start: 41)
+    /// ```py
+    /// def answer():
+    ///     return 42
+    /// ```
+    /// ````
+    #[default(1)]
+    pub number_start: usize,
+
+    /// Only lines whose number is a multiple of this get a number, e.g. `{5}`
+    /// to number every fifth line.
+    #[default(NonZeroUsize::ONE)]
+    pub number_step: NonZeroUsize,
+
     /// The stylized lines of raw text.
     ///
     /// Made accessible for the [`raw.line` element]($raw.line).
@@ -393,13 +429,22 @@ impl Synthesize for RawElem {
 
 impl Show for RawElem {
     #[tracing::instrument(name = "RawElem::show", skip_all)]
-    fn show(&self, _: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+    fn show(&self, engine: &mut Engine, styles: StyleChain) -> SourceResult<Content> {
+        let numbering = self.numbering(styles).as_ref().filter(|_| self.block(styles));
+        let start = self.number_start(styles);
+        let step = self.number_step(styles).get();
+
         let mut lines = EcoVec::with_capacity((2 * self.lines().len()).saturating_sub(1));
         for (i, line) in self.lines().iter().enumerate() {
             if i != 0 {
                 lines.push(LinebreakElem::new().pack());
             }
 
+            let number = start + i;
+            if let Some(numbering) = numbering.filter(|_| number % step == 0) {
+                lines.push(line_number(engine, numbering, number)?);
+            }
+
             lines.push(line.clone().pack());
         }
 
@@ -469,6 +514,21 @@ impl PlainText for RawElem {
     }
 }
 
+/// Create the number for a line of a raw block, hanging into the margin before
+/// the line.
+fn line_number(
+    engine: &mut Engine,
+    numbering: &Numbering,
+    number: usize,
+) -> SourceResult<Content> {
+    let body = numbering.apply(engine, &[number])?.display()
+        + HElem::new(Em::new(1.0).into()).pack();
+    Ok(BoxElem::new()
+        .with_width(Sizing::Rel(Rel::zero()))
+        .with_body(Some(body.aligned(Align::END)))
+        .pack())
+}
+
 /// A highlighted line of raw text.
 ///
 /// This is a helper element that is synthesized by [`raw`]($raw) elements.
@@ -1,10 +1,12 @@
 use ecow::EcoString;
+use ttf_parser::gsub::SubstitutionSubtable;
+use ttf_parser::Tag;
 
 use crate::diag::SourceResult;
 use crate::engine::Engine;
 use crate::foundations::{elem, Content, Show, StyleChain};
 use crate::layout::{Em, Length};
-use crate::text::{variant, SpaceElem, TextElem, TextSize};
+use crate::text::{variant, FontFeatures, SpaceElem, TextElem, TextSize};
 use crate::World;
 
 /// Renders text in subscript.
@@ -20,8 +22,9 @@ pub struct SubElem {
     /// Whether to prefer the dedicated subscript characters of the font.
     ///
     /// If this is enabled, Typst first tries to transform the text to subscript
-    /// codepoints. If that fails, it falls back to rendering lowered and shrunk
-    /// normal letters.
+    /// codepoints. If the text has no such codepoints, it tries the font's
+    /// OpenType subscript glyphs (the `subs` feature) instead. If that fails,
+    /// too, it falls back to rendering lowered and shrunk normal letters.
     ///
     /// ```example
     /// N#sub(typographic: true)[1]
@@ -31,14 +34,14 @@ pub struct SubElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has subscript codepoints or glyphs
+    /// for the given `body`.
     #[default(Em::new(0.2).into())]
     pub baseline: Length,
 
     /// The font size for synthetic subscripts. Does not apply if
-    /// `typographic` is true and the font has subscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has subscript codepoints or glyphs
+    /// for the given `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
 
@@ -53,11 +56,7 @@ impl Show for SubElem {
         let body = self.body().clone();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, true) {
-                if is_shapable(engine, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
+            transformed = typographic(engine, &body, styles, true);
         };
 
         Ok(transformed.unwrap_or_else(|| {
@@ -80,8 +79,9 @@ pub struct SuperElem {
     /// Whether to prefer the dedicated superscript characters of the font.
     ///
     /// If this is enabled, Typst first tries to transform the text to
-    /// superscript codepoints. If that fails, it falls back to rendering
-    /// raised and shrunk normal letters.
+    /// superscript codepoints. If the text has no such codepoints, it tries the
+    /// font's OpenType superscript glyphs (the `sups` feature) instead. If that
+    /// fails, too, it falls back to rendering raised and shrunk normal letters.
     ///
     /// ```example
     /// N#super(typographic: true)[1]
@@ -91,14 +91,14 @@ pub struct SuperElem {
     pub typographic: bool,
 
     /// The baseline shift for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has superscript codepoints or glyphs
+    /// for the given `body`.
     #[default(Em::new(-0.5).into())]
     pub baseline: Length,
 
     /// The font size for synthetic superscripts. Does not apply if
-    /// `typographic` is true and the font has superscript codepoints for the
-    /// given `body`.
+    /// `typographic` is true and the font has superscript codepoints or glyphs
+    /// for the given `body`.
     #[default(TextSize(Em::new(0.6).into()))]
     pub size: TextSize,
 
@@ -113,11 +113,7 @@ impl Show for SuperElem {
         let body = self.body().clone();
         let mut transformed = None;
         if self.typographic(styles) {
-            if let Some(text) = search_text(&body, false) {
-                if is_shapable(engine, &text, styles) {
-                    transformed = Some(TextElem::packed(text));
-                }
-            }
+            transformed = typographic(engine, &body, styles, false);
         };
 
         Ok(transformed.unwrap_or_else(|| {
@@ -127,17 +123,40 @@ impl Show for SuperElem {
     }
 }
 
-/// Find and transform the text contained in `content` to the given script kind
-/// if and only if it only consists of `Text`, `Space`, and `Empty` leafs.
-fn search_text(content: &Content, sub: bool) -> Option<EcoString> {
+/// Try to display the body with dedicated sub- or superscript codepoints or
+/// glyphs.
+fn typographic(
+    engine: &Engine,
+    body: &Content,
+    styles: StyleChain,
+    sub: bool,
+) -> Option<Content> {
+    if let Some(text) = search_text(body, &|text| convert_script(text, sub)) {
+        return is_shapable(engine, &text, styles).then(|| TextElem::packed(text));
+    }
+
+    let feature = if sub { b"subs" } else { b"sups" };
+    let text = search_text(body, &|text| Some(text.into()))?;
+    has_substitutes(engine, &text, styles, feature).then(|| {
+        let features = FontFeatures(vec![(Tag::from_bytes(feature), 1)]);
+        body.clone().styled(TextElem::set_features(features))
+    })
+}
+
+/// Find and transform the text contained in `content` with `convert` if and
+/// only if it only consists of `Text`, `Space`, and `Empty` leafs.
+fn search_text(
+    content: &Content,
+    convert: &dyn Fn(&str) -> Option<EcoString>,
+) -> Option<EcoString> {
     if content.is::<SpaceElem>() {
         Some(' '.into())
     } else if let Some(elem) = content.to::<TextElem>() {
-        convert_script(elem.text(), sub)
+        convert(elem.text())
     } else if let Some(children) = content.to_sequence() {
         let mut full = EcoString::new();
         for item in children {
-            match search_text(item, sub) {
+            match search_text(item, convert) {
                 Some(text) => full.push_str(&text),
                 None => return None,
             }
@@ -165,6 +184,45 @@ fn is_shapable(engine: &Engine, text: &str, styles: StyleChain) -> bool {
     false
 }
 
+/// Whether the first available font substitutes every character of the text
+/// through the given OpenType feature.
+fn has_substitutes(
+    engine: &Engine,
+    text: &str,
+    styles: StyleChain,
+    feature: &[u8; 4],
+) -> bool {
+    let world = engine.world;
+    for family in TextElem::font_in(styles) {
+        if let Some(font) = world
+            .book()
+            .select(family.as_str(), variant(styles))
+            .and_then(|id| world.font(id))
+        {
+            let ttf = font.ttf();
+            let Some(gsub) = ttf.tables().gsub else { return false };
+            let Some(feature) = gsub.features.find(Tag::from_bytes(feature)) else {
+                return false;
+            };
+
+            return text.chars().filter(|c| !c.is_whitespace()).all(|c| {
+                ttf.glyph_index(c).is_some_and(|id| {
+                    feature
+                        .lookup_indices
+                        .into_iter()
+                        .filter_map(|index| gsub.lookups.get(index))
+                        .flat_map(|lookup| {
+                            lookup.subtables.into_iter::<SubstitutionSubtable>()
+                        })
+                        .any(|subtable| subtable.coverage().contains(id))
+                })
+            });
+        }
+    }
+
+    false
+}
+
 /// Convert a string to sub- or superscript codepoints if all characters
 /// can be mapped to such a codepoint.
 fn convert_script(text: &str, sub: bool) -> Option<EcoString> {
@@ -187,8 +187,8 @@ impl<'s> SmartQuotes<'s> {
     /// Currently, the supported languages are: English, Czech, Danish, German,
     /// Swiss / Liechtensteinian German, Estonian, Icelandic, Lithuanian,
     /// Latvian, Slovak, Slovenian, Spanish, Bosnian, Finnish, Swedish, French,
-    /// Hungarian, Polish, Romanian, Japanese, Traditional Chinese, Russian, and
-    /// Norwegian.
+    /// Hungarian, Polish, Romanian, Japanese, Traditional Chinese (Taiwan, Hong
+    /// Kong, and Macau), Russian, and Norwegian.
     ///
     /// For unknown languages, the English quotes are used as fallback.
     pub fn new(
@@ -218,6 +218,10 @@ impl<'s> SmartQuotes<'s> {
             "no" | "nb" | "nn" if alternative => low_high,
             "ru" | "no" | "nb" | "nn" | "ua" => ("’", "’", "«", "»"),
             "gr" => ("‘", "’", "«", "»"),
+            "ja" => ("『", "』", "「", "」"),
+            "zh" if matches!(region, Some("TW" | "HK" | "MO")) => {
+                ("『", "』", "「", "」")
+            }
             _ if lang.dir() == Dir::RTL => ("’", "‘", "”", "“"),
             _ => default,
         };
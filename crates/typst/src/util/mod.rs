@@ -210,3 +210,43 @@ pub trait Numeric:
 pub fn round_2(value: f64) -> f64 {
     (value * 100.0).round() / 100.0
 }
+
+/// Find the candidate that is closest to `name` in terms of edit distance,
+/// if any is close enough to probably be what was meant.
+///
+/// Exact matches are never returned, since they would not be a typo.
+pub fn closest<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    // Allow roughly one typo per three characters, so that very short names
+    // don't match almost anything.
+    let max = name.chars().count() / 3;
+    if max == 0 {
+        return None;
+    }
+
+    candidates
+        .into_iter()
+        .filter(|&candidate| candidate != name)
+        .map(|candidate| (levenshtein(name, candidate), candidate))
+        .filter(|&(distance, _)| distance <= max)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between two strings, counted in characters.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &y) in b.iter().enumerate() {
+            let substitute = diagonal + usize::from(x != y);
+            diagonal = row[j + 1];
+            row[j + 1] = substitute.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
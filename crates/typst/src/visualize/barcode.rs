@@ -0,0 +1,304 @@
+use qrcodegen::{QrCode, QrCodeEcc};
+
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::{elem, Cast, NativeElement, Smart, Str, StyleChain};
+use crate::layout::{
+    Abs, Axes, Fragment, Frame, FrameItem, Layout, Length, Point, Regions, Rel, Size,
+};
+use crate::visualize::{Color, Geometry, Paint};
+
+/// A QR code or barcode.
+///
+/// The code is drawn as vector shapes, so it stays sharp at any size. It does
+/// not include a quiet zone, so leave some space around it when placing it
+/// next to other content.
+///
+/// # Example
+/// ```example
+/// #set align(center)
+/// #barcode("https://typst-doc-cn.github.io/docs/")
+///
+/// #barcode("4006381333931", format: "ean13")
+/// #barcode("TICKET-0042", format: "code128", width: 4cm)
+/// ```
+#[elem(Layout)]
+pub struct BarcodeElem {
+    /// The data to encode.
+    ///
+    /// - QR codes can encode any text.
+    /// - Code 128 barcodes can encode printable ASCII characters.
+    /// - EAN-13 barcodes encode 12 digits followed by a check digit. If only
+    ///   12 digits are given, the check digit is computed automatically.
+    #[required]
+    pub data: Str,
+
+    /// The kind of code to generate.
+    #[default(BarcodeFormat::Qr)]
+    pub format: BarcodeFormat,
+
+    /// The width of the code.
+    #[resolve]
+    #[default(Abs::cm(3.0).into())]
+    pub width: Rel<Length>,
+
+    /// The height of the code.
+    ///
+    /// When set to `{auto}`, QR codes are square and barcodes are a third as
+    /// high as they are wide.
+    #[resolve]
+    pub height: Smart<Rel<Length>>,
+
+    /// The color of the dark modules and bars.
+    #[default(Color::BLACK.into())]
+    pub fill: Paint,
+
+    /// How much of a QR code can be damaged while still being readable.
+    /// Higher levels make the code larger. Barcodes ignore this.
+    #[default(QrCorrection::Medium)]
+    pub correction: QrCorrection,
+}
+
+impl Layout for BarcodeElem {
+    #[tracing::instrument(name = "BarcodeElem::layout", skip_all)]
+    fn layout(
+        &self,
+        _: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let format = self.format(styles);
+        let modules = match format {
+            BarcodeFormat::Qr => encode_qr(self.data(), self.correction(styles)),
+            BarcodeFormat::Code128 => encode_code128(self.data()).map(|row| vec![row]),
+            BarcodeFormat::Ean13 => encode_ean13(self.data()).map(|row| vec![row]),
+        }
+        .at(self.span())?;
+
+        let width = self.width(styles).relative_to(regions.base().x);
+        let height = match self.height(styles) {
+            Smart::Custom(height) => height.relative_to(regions.base().y),
+            Smart::Auto if format == BarcodeFormat::Qr => width,
+            Smart::Auto => width / 3.0,
+        };
+
+        let size = Size::new(width, height);
+        if !size.is_finite() {
+            bail!(self.span(), "cannot create barcode with infinite size");
+        }
+
+        // Draw each run of dark modules in a row as one rectangle.
+        let fill = self.fill(styles);
+        let module =
+            Axes::new(width / modules[0].len() as f64, height / modules.len() as f64);
+        let mut frame = Frame::soft(size);
+        for (y, row) in modules.iter().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                let run = row[x..].iter().take_while(|&&dark| dark == row[x]).count();
+                if row[x] {
+                    let pos = Point::new(module.x * x as f64, module.y * y as f64);
+                    let size = Size::new(module.x * run as f64, module.y);
+                    let shape = Geometry::Rect(size).filled(fill.clone());
+                    frame.push(pos, FrameItem::Shape(shape, self.span()));
+                }
+                x += run;
+            }
+        }
+
+        Ok(Fragment::frame(frame))
+    }
+}
+
+/// A kind of QR code or barcode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum BarcodeFormat {
+    /// A two-dimensional QR code.
+    Qr,
+    /// A Code 128 barcode, as used for shipping labels and tickets.
+    #[string("code128")]
+    Code128,
+    /// An EAN-13 barcode, as used for retail products and books.
+    #[string("ean13")]
+    Ean13,
+}
+
+/// The error correction level of a QR code.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum QrCorrection {
+    /// Recovers about 7% of the data.
+    Low,
+    /// Recovers about 15% of the data.
+    Medium,
+    /// Recovers about 25% of the data.
+    Quartile,
+    /// Recovers about 30% of the data.
+    High,
+}
+
+/// Encode text as the rows of dark and light modules of a QR code.
+fn encode_qr(data: &str, correction: QrCorrection) -> StrResult<Vec<Vec<bool>>> {
+    let ecc = match correction {
+        QrCorrection::Low => QrCodeEcc::Low,
+        QrCorrection::Medium => QrCodeEcc::Medium,
+        QrCorrection::Quartile => QrCodeEcc::Quartile,
+        QrCorrection::High => QrCodeEcc::High,
+    };
+
+    let Ok(qr) = QrCode::encode_text(data, ecc) else {
+        bail!("data is too long for a QR code");
+    };
+
+    Ok((0..qr.size())
+        .map(|y| (0..qr.size()).map(|x| qr.get_module(x, y)).collect())
+        .collect())
+}
+
+/// Encode printable ASCII text as the modules of a Code 128 barcode, using
+/// code set B.
+fn encode_code128(data: &str) -> StrResult<Vec<bool>> {
+    if data.is_empty() {
+        bail!("barcode data must not be empty");
+    }
+
+    let mut values = vec![CODE128_START_B];
+    for c in data.chars() {
+        if !matches!(c, ' '..='~') {
+            bail!("Code 128 cannot encode {c:?}");
+        }
+        values.push(c as usize - ' ' as usize);
+    }
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, &value)| i.max(1) * value)
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+    values.push(CODE128_STOP);
+
+    let mut modules = vec![];
+    for value in values {
+        for (i, width) in CODE128_PATTERNS[value].bytes().enumerate() {
+            let dark = i % 2 == 0;
+            modules.extend(std::iter::repeat(dark).take((width - b'0') as usize));
+        }
+    }
+
+    Ok(modules)
+}
+
+/// Encode 12 or 13 digits as the modules of an EAN-13 barcode.
+fn encode_ean13(data: &str) -> StrResult<Vec<bool>> {
+    let mut digits = data
+        .chars()
+        .map(|c| c.to_digit(10).map(|d| d as usize))
+        .collect::<Option<Vec<_>>>()
+        .filter(|digits| matches!(digits.len(), 12 | 13))
+        .ok_or("EAN-13 data must consist of 12 or 13 digits")?;
+
+    let sum: usize = digits[..12]
+        .iter()
+        .enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { d } else { 3 * d })
+        .sum();
+    let check = (10 - sum % 10) % 10;
+    match digits.get(12) {
+        Some(&given) if given != check => bail!("EAN-13 check digit should be {check}"),
+        Some(_) => {}
+        None => digits.push(check),
+    }
+
+    let mut modules = vec![];
+    let mut push = |bits: u8, len: usize| {
+        modules.extend((0..len).rev().map(|i| bits >> i & 1 == 1));
+    };
+
+    // The first digit is encoded in the parity of the next six ones.
+    let parity = EAN13_PARITY[digits[0]];
+    push(0b101, 3);
+    for (i, &d) in digits[1..7].iter().enumerate() {
+        let odd = parity >> (5 - i) & 1 == 0;
+        let right = !EAN13_LEFT[d] & 0x7F;
+        push(if odd { EAN13_LEFT[d] } else { right.reverse_bits() >> 1 }, 7);
+    }
+    push(0b01010, 5);
+    for &d in &digits[7..] {
+        push(!EAN13_LEFT[d] & 0x7F, 7);
+    }
+    push(0b101, 3);
+
+    Ok(modules)
+}
+
+/// The value of the start symbol for Code 128 code set B.
+const CODE128_START_B: usize = 104;
+
+/// The value of the Code 128 stop symbol.
+const CODE128_STOP: usize = 106;
+
+/// The widths of the alternating bars and spaces of each Code 128 symbol.
+const CODE128_PATTERNS: [&str; 107] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312",
+    "132212", "221213", "221312", "231212", "112232", "122132", "122231", "113222",
+    "123122", "123221", "223211", "221132", "221231", "213212", "223112", "312131",
+    "311222", "321122", "321221", "312212", "322112", "322211", "212123", "212321",
+    "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121",
+    "313121", "211331", "231131", "213113", "213311", "213131", "311123", "311321",
+    "331121", "312113", "312311", "332111", "314111", "221411", "431111", "111224",
+    "111422", "121124", "121421", "141122", "141221", "112214", "112412", "122114",
+    "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112",
+    "421211", "212141", "214121", "412121", "111143", "111341", "131141", "114113",
+    "114311", "411113", "411311", "113141", "114131", "311141", "411131", "211412",
+    "211214", "211232", "2331112",
+];
+
+/// The odd-parity patterns of the digits on the left half of an EAN-13
+/// barcode. The right half uses their complements and the even-parity
+/// patterns are the reversed complements.
+const EAN13_LEFT: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111,
+    0b0111011, 0b0110111, 0b0001011,
+];
+
+/// Which of the six digits after the first one have even parity (a set bit),
+/// depending on the first digit.
+const EAN13_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101,
+    0b010110, 0b011010,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(modules: &[bool]) -> String {
+        modules.iter().map(|&dark| if dark { '1' } else { '0' }).collect()
+    }
+
+    #[test]
+    fn test_code128() {
+        // Start B, `A`, the check symbol `B`, and stop.
+        let expected = "11010010000\
+                        10100011000\
+                        10001011000\
+                        1100011101011";
+        assert_eq!(bits(&encode_code128("A").unwrap()), expected);
+        assert_eq!(encode_code128("PJJ123C").unwrap().len(), 11 * 9 + 13);
+    }
+
+    #[test]
+    fn test_ean13() {
+        let expected = "101\
+                        000110101001110101111011110100010010110011\
+                        01010\
+                        100001010000101000010111010010000101100110\
+                        101";
+        assert_eq!(bits(&encode_ean13("4006381333931").unwrap()), expected);
+        assert_eq!(bits(&encode_ean13("400638133393").unwrap()), expected);
+        assert!(encode_ean13("4006381333932").is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use std::f64::consts::{FRAC_PI_2, TAU};
+
+use crate::diag::{bail, SourceResult, StrResult};
+use crate::engine::Engine;
+use crate::foundations::repr::format_float;
+use crate::foundations::{
+    cast, elem, Array, Cast, Content, Dict, IntoValue, NativeElement, Resolve, Str,
+    StyleChain,
+};
+use crate::layout::{
+    Abs, Axes, Em, Fragment, Frame, FrameItem, Layout, Length, Point, Regions, Rel, Size,
+};
+use crate::syntax::Span;
+use crate::text::TextElem;
+use crate::util::Scalar;
+use crate::visualize::{Color, FixedStroke, Geometry, Paint, Path, Shape};
+
+/// A bar, line or pie chart.
+///
+/// The chart is drawn from the given data, with axes, ticks and an optional
+/// legend. Its labels use the current text style.
+///
+/// # Example
+/// ```example
+/// #let sales = (
+///   "一月": (120, 90),
+///   "二月": (150, 110),
+///   "三月": (90, 130),
+/// )
+///
+/// #chart(sales, legend: ([华东], [华南]))
+/// #chart(sales, kind: "line", legend: ([华东], [华南]))
+/// #chart(
+///   ("Rent": 1200, "Food": 600, "Other": 300),
+///   kind: "pie",
+///   height: 3cm,
+/// )
+/// ```
+#[elem(Layout)]
+pub struct ChartElem {
+    /// The data to show.
+    ///
+    /// Either a dictionary that maps each category to its value, or an array
+    /// of `(category, value)` pairs. To show more than one series, give each
+    /// category an array of values, one for each series. Pie charts only show
+    /// the first series.
+    #[required]
+    pub data: ChartData,
+
+    /// The kind of chart to draw.
+    #[default(ChartKind::Bar)]
+    pub kind: ChartKind,
+
+    /// The width of the chart.
+    #[resolve]
+    #[default(Abs::cm(8.0).into())]
+    pub width: Rel<Length>,
+
+    /// The height of the chart.
+    #[resolve]
+    #[default(Abs::cm(5.0).into())]
+    pub height: Rel<Length>,
+
+    /// The names of the series, shown in a legend above bar and line charts.
+    /// Pie charts always have a legend with the categories instead.
+    pub legend: Vec<Content>,
+
+    /// The colors of the series or, in pie charts, of the categories. If there
+    /// are more series than colors, the colors repeat.
+    #[default(DEFAULT_COLORS.iter().map(|&color| color.into()).collect())]
+    pub colors: Vec<Paint>,
+}
+
+impl Layout for ChartElem {
+    #[tracing::instrument(name = "ChartElem::layout", skip_all)]
+    fn layout(
+        &self,
+        engine: &mut Engine,
+        styles: StyleChain,
+        regions: Regions,
+    ) -> SourceResult<Fragment> {
+        let size = Size::new(
+            self.width(styles).relative_to(regions.base().x),
+            self.height(styles).relative_to(regions.base().y),
+        );
+        if !size.is_finite() {
+            bail!(self.span(), "cannot create chart with infinite size");
+        }
+
+        let data = &self.data().0;
+        if data.is_empty() {
+            bail!(self.span(), "chart data must not be empty");
+        }
+
+        let colors = self.colors(styles);
+        if colors.is_empty() {
+            bail!(self.span(), "chart needs at least one color");
+        }
+
+        let mut layouter = ChartLayouter {
+            engine,
+            styles,
+            span: self.span(),
+            frame: Frame::soft(size),
+            gap: Em::new(0.4).resolve(styles),
+            colors,
+        };
+
+        match self.kind(styles) {
+            ChartKind::Bar | ChartKind::Line => {
+                layouter.layout_xy(data, self.kind(styles), &self.legend(styles))?
+            }
+            ChartKind::Pie => layouter.layout_pie(data)?,
+        }
+
+        Ok(Fragment::frame(layouter.frame))
+    }
+}
+
+/// A kind of chart.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum ChartKind {
+    /// Vertical bars, grouped by category.
+    Bar,
+    /// Lines connecting the values of each series.
+    Line,
+    /// Slices of a circle, sized by their share of the total.
+    Pie,
+}
+
+/// The categories of a chart with the values of each series.
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct ChartData(Vec<(Str, Vec<Scalar>)>);
+
+cast! {
+    ChartData,
+    self => self.0
+        .into_iter()
+        .map(|(label, values)| {
+            let values: Array =
+                values.into_iter().map(|v| v.get().into_value()).collect();
+            (label, values.into_value())
+        })
+        .collect::<Dict>()
+        .into_value(),
+    v: Dict => Self(
+        v.into_iter()
+            .map(|(label, values)| Ok((label, values.cast::<ChartValues>()?.0)))
+            .collect::<StrResult<_>>()?,
+    ),
+    v: Array => Self(
+        v.into_iter()
+            .map(|pair| {
+                let mut iter = pair.cast::<Array>()?.into_iter();
+                match (iter.next(), iter.next(), iter.next()) {
+                    (Some(a), Some(b), None) => {
+                        Ok((a.cast()?, b.cast::<ChartValues>()?.0))
+                    }
+                    _ => bail!("array must contain exactly two entries"),
+                }
+            })
+            .collect::<StrResult<_>>()?,
+    ),
+}
+
+/// The values of one category.
+struct ChartValues(Vec<Scalar>);
+
+cast! {
+    ChartValues,
+    v: f64 => Self(vec![Scalar::new(v)]),
+    v: Vec<f64> => Self(v.into_iter().map(Scalar::new).collect()),
+}
+
+/// The default colors of the series.
+const DEFAULT_COLORS: [Color; 6] =
+    [Color::BLUE, Color::RED, Color::GREEN, Color::ORANGE, Color::PURPLE, Color::TEAL];
+
+/// Draws the parts of a chart into a frame.
+struct ChartLayouter<'a, 'b> {
+    engine: &'a mut Engine<'b>,
+    styles: StyleChain<'a>,
+    span: Span,
+    frame: Frame,
+    gap: Abs,
+    colors: Vec<Paint>,
+}
+
+impl ChartLayouter<'_, '_> {
+    /// Draw a bar or line chart.
+    fn layout_xy(
+        &mut self,
+        data: &[(Str, Vec<Scalar>)],
+        kind: ChartKind,
+        legend: &[Content],
+    ) -> SourceResult<()> {
+        let size = self.frame.size();
+        let gap = self.gap;
+
+        // Place the legend in a row at the top.
+        let mut top = Abs::zero();
+        if !legend.is_empty() {
+            let mut x = Abs::zero();
+            for (i, name) in legend.iter().enumerate() {
+                let label = self.label(name.clone())?;
+                let swatch = label.height() * 0.7;
+                let pos = Point::new(x, (label.height() - swatch) / 2.0);
+                let rect = Geometry::Rect(Size::splat(swatch));
+                self.push_shape(pos, rect.filled(self.color(i)));
+                x += swatch + gap / 2.0;
+                top.set_max(label.height() + gap);
+                let width = label.width();
+                self.frame.push_frame(Point::with_x(x), label);
+                x += width + gap * 2.0;
+            }
+        }
+
+        // Find the range of the value axis, which always includes zero.
+        let values = data.iter().flat_map(|(_, values)| values).map(|v| v.get());
+        let (lo, hi) = values.fold((0.0, 0.0), |(lo, hi), v| (v.min(lo), v.max(hi)));
+        let (step, ticks) = nice_ticks(lo, hi);
+        let (min, max) = (ticks[0], ticks[ticks.len() - 1]);
+        let precision = (-step.log10().floor()).max(0.0) as u8;
+
+        let mut tick_labels = vec![];
+        for &tick in &ticks {
+            let text = format_float(tick, Some(precision), "");
+            tick_labels.push(self.label(TextElem::packed(text))?);
+        }
+
+        let mut category_labels = vec![];
+        for (label, _) in data {
+            category_labels.push(self.label(TextElem::packed(label.clone()))?);
+        }
+
+        // Leave room for the tick labels on the left and for half of the top
+        // one above the plot.
+        let largest = |frames: &[Frame], f: fn(&Frame) -> Abs| {
+            frames.iter().map(f).fold(Abs::zero(), Abs::max)
+        };
+        let left = largest(&tick_labels, Frame::width) + gap;
+        top.set_max(largest(&tick_labels, Frame::height) / 2.0);
+        let bottom = largest(&category_labels, Frame::height) + gap;
+        let plot = Size::new(size.x - left, size.y - top - bottom);
+        if plot.x <= Abs::zero() || plot.y <= Abs::zero() {
+            bail!(self.span, "chart is too small for its labels");
+        }
+
+        let y_of = |v: f64| top + plot.y * ((max - v) / (max - min));
+        let slot = plot.x / data.len() as f64;
+
+        // Draw the grid with the tick labels.
+        let grid = FixedStroke { paint: Color::SILVER.into(), ..Self::thin_stroke() };
+        for (&tick, label) in ticks.iter().zip(tick_labels) {
+            let y = y_of(tick);
+            let line = Geometry::Line(Point::with_x(plot.x)).stroked(grid.clone());
+            self.push_shape(Point::new(left, y), line);
+            let pos = Point::new(left - gap - label.width(), y - label.height() / 2.0);
+            self.frame.push_frame(pos, label);
+        }
+
+        for (i, label) in category_labels.into_iter().enumerate() {
+            let x = left + slot * (i as f64 + 0.5) - label.width() / 2.0;
+            self.frame.push_frame(Point::new(x, top + plot.y + gap), label);
+        }
+
+        // Draw the data.
+        let series = data.iter().map(|(_, values)| values.len()).max().unwrap_or(0);
+        match kind {
+            ChartKind::Bar => {
+                let width = slot * 0.8 / series.max(1) as f64;
+                for (i, (_, values)) in data.iter().enumerate() {
+                    for (j, v) in values.iter().enumerate() {
+                        let (y0, y1) = (y_of(0.0), y_of(v.get()));
+                        let x = left + slot * (i as f64 + 0.1) + width * j as f64;
+                        let pos = Point::new(x, y0.min(y1));
+                        let rect = Geometry::Rect(Size::new(width, (y1 - y0).abs()));
+                        self.push_shape(pos, rect.filled(self.color(j)));
+                    }
+                }
+            }
+            _ => {
+                for j in 0..series {
+                    let mut path = Path::new();
+                    for (i, (_, values)) in data.iter().enumerate() {
+                        let Some(v) = values.get(j) else { continue };
+                        let x = left + slot * (i as f64 + 0.5);
+                        let point = Point::new(x, y_of(v.get()));
+                        if path.0.is_empty() {
+                            path.move_to(point);
+                        } else {
+                            path.line_to(point);
+                        }
+                    }
+                    let stroke = FixedStroke {
+                        paint: self.color(j),
+                        thickness: Abs::pt(1.5),
+                        ..FixedStroke::default()
+                    };
+                    self.push_shape(Point::zero(), Geometry::Path(path).stroked(stroke));
+                }
+            }
+        }
+
+        // Draw the axes on top.
+        let axis = Self::thin_stroke();
+        let y_axis = Geometry::Line(Point::with_y(plot.y)).stroked(axis.clone());
+        self.push_shape(Point::new(left, top), y_axis);
+        let x_axis = Geometry::Line(Point::with_x(plot.x)).stroked(axis);
+        self.push_shape(Point::new(left, y_of(0.0)), x_axis);
+
+        Ok(())
+    }
+
+    /// Draw a pie chart with a legend on its right.
+    fn layout_pie(&mut self, data: &[(Str, Vec<Scalar>)]) -> SourceResult<()> {
+        let size = self.frame.size();
+        let gap = self.gap;
+
+        let values: Vec<f64> = data
+            .iter()
+            .map(|(_, values)| values.first().map_or(0.0, |v| v.get()))
+            .collect();
+        if values.iter().any(|&v| v < 0.0) {
+            bail!(self.span, "pie chart values must not be negative");
+        }
+
+        let total: f64 = values.iter().sum();
+        if total <= 0.0 {
+            bail!(self.span, "pie chart values must not all be zero");
+        }
+
+        let mut labels = vec![];
+        for (label, _) in data {
+            labels.push(self.label(TextElem::packed(label.clone()))?);
+        }
+
+        let line = labels.iter().map(Frame::height).fold(Abs::zero(), Abs::max);
+        let swatch = line * 0.7;
+        let legend = swatch
+            + gap / 2.0
+            + labels.iter().map(Frame::width).fold(Abs::zero(), Abs::max);
+        let radius = (size.x - legend - gap * 2.0).min(size.y) / 2.0;
+        if radius <= Abs::zero() {
+            bail!(self.span, "chart is too small for its labels");
+        }
+
+        // Draw the slices clockwise, starting at the top.
+        let center = Point::new(radius, size.y / 2.0);
+        let border = FixedStroke { paint: Color::WHITE.into(), ..Self::thin_stroke() };
+        let mut angle = -FRAC_PI_2;
+        for (i, &value) in values.iter().enumerate() {
+            let sweep = value / total * TAU;
+            if sweep > 0.0 {
+                let shape = Shape {
+                    geometry: Geometry::Path(slice(center, radius, angle, sweep)),
+                    fill: Some(self.color(i)),
+                    stroke: Some(border.clone()),
+                };
+                self.push_shape(Point::zero(), shape);
+            }
+            angle += sweep;
+        }
+
+        // Stack the legend entries, centered vertically.
+        let x = radius * 2.0 + gap * 2.0;
+        let mut y = (size.y - line * labels.len() as f64) / 2.0;
+        for (i, label) in labels.into_iter().enumerate() {
+            let pos = Point::new(x, y + (line - swatch) / 2.0);
+            let rect = Geometry::Rect(Size::splat(swatch));
+            self.push_shape(pos, rect.filled(self.color(i)));
+            self.frame.push_frame(Point::new(x + swatch + gap / 2.0, y), label);
+            y += line;
+        }
+
+        Ok(())
+    }
+
+    /// Lay out a label with the chart's styles.
+    fn label(&mut self, body: Content) -> SourceResult<Frame> {
+        let pod = Regions::one(Size::splat(Abs::inf()), Axes::splat(false));
+        Ok(body.layout(self.engine, self.styles, pod)?.into_frame())
+    }
+
+    /// The color of the series or category with the given index.
+    fn color(&self, i: usize) -> Paint {
+        self.colors[i % self.colors.len()].clone()
+    }
+
+    /// Add a shape to the frame.
+    fn push_shape(&mut self, pos: Point, shape: Shape) {
+        self.frame.push(pos, FrameItem::Shape(shape, self.span));
+    }
+
+    /// The stroke of the axes and grid lines.
+    fn thin_stroke() -> FixedStroke {
+        FixedStroke { thickness: Abs::pt(0.5), ..FixedStroke::default() }
+    }
+}
+
+/// Choose about five evenly spaced, round tick values that cover the range
+/// from `lo` to `hi`. Returns the distance between the ticks and the ticks.
+fn nice_ticks(lo: f64, hi: f64) -> (f64, Vec<f64>) {
+    let range = if hi > lo { hi - lo } else { 1.0 };
+    let raw = range / 5.0;
+    let magnitude = 10f64.powf(raw.log10().floor());
+    let factor = match raw / magnitude {
+        f if f <= 1.0 => 1.0,
+        f if f <= 2.0 => 2.0,
+        f if f <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+
+    let step = factor * magnitude;
+    let first = (lo / step).floor() as i64;
+    let last = ((hi / step).ceil() as i64).max(first + 1);
+    (step, (first..=last).map(|k| k as f64 * step).collect())
+}
+
+/// A path for a slice of a circle, approximating the arc with one cubic
+/// bezier curve per quarter circle.
+fn slice(center: Point, radius: Abs, start: f64, sweep: f64) -> Path {
+    let at = |angle: f64| center + Point::new(radius * angle.cos(), radius * angle.sin());
+
+    let mut path = Path::new();
+    path.move_to(center);
+    path.line_to(at(start));
+
+    let segments = (sweep / FRAC_PI_2).ceil().max(1.0) as usize;
+    let theta = sweep / segments as f64;
+    let k = 4.0 / 3.0 * (theta / 4.0).tan();
+    for i in 0..segments {
+        let a0 = start + theta * i as f64;
+        let a1 = a0 + theta;
+        let tangent =
+            |angle: f64| Point::new(-radius * angle.sin(), radius * angle.cos()) * k;
+        path.cubic_to(at(a0) + tangent(a0), at(a1) - tangent(a1), at(a1));
+    }
+
+    path.close_path();
+    path
+}
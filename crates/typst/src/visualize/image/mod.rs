@@ -20,8 +20,8 @@ use crate::foundations::{
     StyleChain,
 };
 use crate::layout::{
-    Abs, Axes, FixedAlign, Fragment, Frame, FrameItem, Layout, Length, Point, Regions,
-    Rel, Size,
+    Abs, Axes, FixedAlign, Fragment, Frame, FrameItem, Layout, Length, Point, Ratio,
+    Regions, Rel, Size,
 };
 use crate::loading::Readable;
 use crate::model::Figurable;
@@ -86,6 +86,18 @@ pub struct ImageElem {
     /// How the image should adjust itself to a given area.
     #[default(ImageFit::Cover)]
     pub fit: ImageFit,
+
+    /// How opaque the image is. At `{0%}`, the image is invisible.
+    ///
+    /// This is useful for stamps and logos in the
+    /// [background]($page.background) of a page:
+    ///
+    /// ```example
+    /// #set page(background: image("tiger.jpg", width: 60%, opacity: 25%))
+    /// #lorem(20)
+    /// ```
+    #[default(Ratio::one())]
+    pub opacity: Ratio,
 }
 
 #[scope]
@@ -121,6 +133,9 @@ impl ImageElem {
         /// How the image should adjust itself to a given area.
         #[named]
         fit: Option<ImageFit>,
+        /// How opaque the image is.
+        #[named]
+        opacity: Option<Ratio>,
     ) -> StrResult<Content> {
         let mut elem = ImageElem::new(EcoString::new(), data);
         if let Some(format) = format {
@@ -138,6 +153,9 @@ impl ImageElem {
         if let Some(fit) = fit {
             elem.push_fit(fit);
         }
+        if let Some(opacity) = opacity {
+            elem.push_opacity(opacity);
+        }
         Ok(elem.pack())
     }
 }
@@ -229,7 +247,9 @@ impl Layout for ImageElem {
         // the frame to the target size, center aligning the image in the
         // process.
         let mut frame = Frame::soft(fitted);
-        frame.push(Point::zero(), FrameItem::Image(image, fitted, self.span()));
+        let opacity = self.opacity(styles).get().clamp(0.0, 1.0);
+        let item = FrameItem::Image(image, fitted, Ratio::new(opacity), self.span());
+        frame.push(Point::zero(), item);
         frame.resize(target, Axes::splat(FixedAlign::Center));
 
         // Create a clipping group if only part of the image should be visible.
@@ -1,5 +1,7 @@
 //! Drawing and visualization.
 
+mod barcode;
+mod chart;
 mod color;
 mod gradient;
 mod image;
@@ -11,6 +13,8 @@ mod polygon;
 mod shape;
 mod stroke;
 
+pub use self::barcode::*;
+pub use self::chart::*;
 pub use self::color::*;
 pub use self::gradient::*;
 pub use self::image::*;
@@ -40,6 +44,8 @@ pub(super) fn define(global: &mut Scope) {
     global.define_type::<Pattern>();
     global.define_type::<Stroke>();
     global.define_elem::<ImageElem>();
+    global.define_elem::<BarcodeElem>();
+    global.define_elem::<ChartElem>();
     global.define_elem::<LineElem>();
     global.define_elem::<RectElem>();
     global.define_elem::<SquareElem>();
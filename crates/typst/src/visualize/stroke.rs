@@ -352,7 +352,7 @@ impl Resolve for Stroke {
     }
 }
 
-impl Fold for Stroke<Abs> {
+impl<T: Numeric> Fold for Stroke<T> {
     type Output = Self;
 
     fn fold(self, outer: Self::Output) -> Self::Output {
@@ -1,18 +1,35 @@
 use comemo::{Prehashed, Track, Tracked};
-use iai::{black_box, main, Iai};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use typst::diag::FileResult;
 use typst::eval::Tracer;
 use typst::foundations::{Bytes, Datetime};
+use typst::model::Document;
 use typst::syntax::{FileId, Source};
 use typst::text::{Font, FontBook};
 use typst::visualize::Color;
 use typst::{Library, World};
+use typst_pdf::PdfOptions;
 use unscanny::Scanner;
 
 const TEXT: &str = include_str!("../typ/compiler/bench.typ");
-const FONT: &[u8] = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
-
-main!(
+const FONTS: &[&[u8]] = &[
+    include_bytes!("../../assets/fonts/LinLibertine_R.ttf"),
+    include_bytes!("../../assets/fonts/LinLibertine_RB.ttf"),
+    include_bytes!("../../assets/fonts/LinLibertine_RI.ttf"),
+    include_bytes!("../../assets/fonts/NewCMMath-Regular.otf"),
+    include_bytes!("../../assets/fonts/DejaVuSansMono.ttf"),
+    include_bytes!("../../assets/fonts/NotoSerifCJKsc-Regular.otf"),
+];
+
+// Representative workloads, each of which is benchmarked in every phase.
+const WORKLOADS: &[(&str, &str)] = &[
+    ("article", include_str!("../typ/benches/article.typ")),
+    ("book-zh", include_str!("../typ/benches/book-zh.typ")),
+    ("tables", include_str!("../typ/benches/tables.typ")),
+];
+
+criterion_group!(
+    benches,
     bench_decode,
     bench_scan,
     bench_parse,
@@ -20,81 +37,158 @@ main!(
     bench_eval,
     bench_compile,
     bench_render,
+    bench_workloads,
 );
+criterion_main!(benches);
+
+fn bench_decode(c: &mut Criterion) {
+    c.bench_function("decode", |b| {
+        b.iter(|| {
+            // We don't use chars().count() because that has a special
+            // superfast implementation.
+            let mut count = 0;
+            let chars = black_box(TEXT).chars();
+            for _ in chars {
+                count += 1;
+            }
+            count
+        })
+    });
+}
 
-fn bench_decode(iai: &mut Iai) {
-    iai.run(|| {
-        // We don't use chars().count() because that has a special
-        // superfast implementation.
-        let mut count = 0;
-        let chars = black_box(TEXT).chars();
-        for _ in chars {
-            count += 1;
-        }
-        count
-    })
+fn bench_scan(c: &mut Criterion) {
+    c.bench_function("scan", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            let mut scanner = Scanner::new(black_box(TEXT));
+            while scanner.eat().is_some() {
+                count += 1;
+            }
+            count
+        })
+    });
 }
 
-fn bench_scan(iai: &mut Iai) {
-    iai.run(|| {
-        let mut count = 0;
-        let mut scanner = Scanner::new(black_box(TEXT));
-        while scanner.eat().is_some() {
-            count += 1;
-        }
-        count
-    })
+fn bench_parse(c: &mut Criterion) {
+    c.bench_function("parse", |b| b.iter(|| typst::syntax::parse(black_box(TEXT))));
+}
+
+fn bench_edit(c: &mut Criterion) {
+    let source = Source::detached(TEXT);
+    c.bench_function("edit", |b| {
+        b.iter_batched_ref(
+            || source.clone(),
+            |source| source.edit(1168..1171, "_Uhr_"),
+            BatchSize::SmallInput,
+        )
+    });
 }
 
-fn bench_parse(iai: &mut Iai) {
-    iai.run(|| typst::syntax::parse(TEXT));
+fn bench_eval(c: &mut Criterion) {
+    let world = BenchWorld::new(TEXT);
+    c.bench_function("eval", |b| {
+        b.iter_batched(typst::memory::flush, |()| eval(&world), BatchSize::PerIteration)
+    });
 }
 
-fn bench_edit(iai: &mut Iai) {
-    let mut source = Source::detached(TEXT);
-    iai.run(|| black_box(source.edit(1168..1171, "_Uhr_")));
+fn bench_compile(c: &mut Criterion) {
+    let world = BenchWorld::new(TEXT);
+    c.bench_function("compile", |b| {
+        b.iter_batched(
+            typst::memory::flush,
+            |()| compile(&world),
+            BatchSize::PerIteration,
+        )
+    });
 }
 
-fn bench_eval(iai: &mut Iai) {
-    let world = BenchWorld::new();
-    let route = typst::engine::Route::default();
-    let mut tracer = typst::eval::Tracer::new();
-    iai.run(|| {
-        typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
-            .unwrap()
+fn bench_render(c: &mut Criterion) {
+    let world = BenchWorld::new(TEXT);
+    let document = compile(&world);
+    c.bench_function("render", |b| {
+        b.iter(|| typst_render::render(&document.pages[0], 1.0, Color::WHITE))
     });
 }
 
-fn bench_compile(iai: &mut Iai) {
-    let world = BenchWorld::new();
+/// Time all phases of each workload.
+///
+/// Every phase is timed on its own, so that a regression can be traced back
+/// to it. Since compilation is memoized, the caches are flushed before each
+/// iteration. The layout phase then evaluates the document up front, so that
+/// the compilation only reuses the memoized module and the measurement
+/// covers layout alone.
+fn bench_workloads(c: &mut Criterion) {
+    for &(name, text) in WORKLOADS {
+        let world = BenchWorld::new(text);
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+
+        group.bench_function("parse", |b| {
+            b.iter(|| typst::syntax::parse(black_box(text)))
+        });
+
+        group.bench_function("eval", |b| {
+            b.iter_batched(
+                typst::memory::flush,
+                |()| eval(&world),
+                BatchSize::PerIteration,
+            )
+        });
+
+        group.bench_function("layout", |b| {
+            b.iter_batched(
+                || {
+                    typst::memory::flush();
+                    eval(&world);
+                },
+                |()| compile(&world),
+                BatchSize::PerIteration,
+            )
+        });
+
+        let document = compile(&world);
+        group.bench_function("pdf", |b| {
+            b.iter(|| typst_pdf::pdf(&document, &PdfOptions::default()).unwrap())
+        });
+
+        group.finish();
+    }
+}
+
+/// Evaluate the world's main source file.
+fn eval(world: &BenchWorld) -> typst::foundations::Module {
+    let route = typst::engine::Route::default();
     let mut tracer = Tracer::new();
-    iai.run(|| typst::compile(&world, &mut tracer));
+    typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
+        .unwrap()
 }
 
-fn bench_render(iai: &mut Iai) {
-    let world = BenchWorld::new();
+/// Compile the world's main source file into a document.
+fn compile(world: &BenchWorld) -> Document {
     let mut tracer = Tracer::new();
-    let document = typst::compile(&world, &mut tracer).unwrap();
-    iai.run(|| typst_render::render(&document.pages[0], 1.0, Color::WHITE))
+    typst::compile(world, &mut tracer).unwrap()
 }
 
 struct BenchWorld {
     library: Prehashed<Library>,
     book: Prehashed<FontBook>,
-    font: Font,
+    fonts: Vec<Font>,
     source: Source,
 }
 
 impl BenchWorld {
-    fn new() -> Self {
-        let font = Font::new(FONT.into(), 0).unwrap();
-        let book = FontBook::from_fonts([&font]);
+    fn new(text: &str) -> Self {
+        let fonts: Vec<_> = FONTS
+            .iter()
+            .flat_map(|&data| Font::iter(Bytes::from_static(data)))
+            .collect();
+        let book = FontBook::from_fonts(&fonts);
 
         Self {
             library: Prehashed::new(Library::build()),
             book: Prehashed::new(book),
-            font,
-            source: Source::detached(TEXT),
+            fonts,
+            source: Source::detached(text),
         }
     }
 
@@ -124,8 +218,8 @@ impl World for BenchWorld {
         unimplemented!()
     }
 
-    fn font(&self, _: usize) -> Option<Font> {
-        Some(self.font.clone())
+    fn font(&self, index: usize) -> Option<Font> {
+        self.fonts.get(index).cloned()
     }
 
     fn today(&self, _: Option<i64>) -> Option<Datetime> {
@@ -0,0 +1,69 @@
+//! Tests for the compiler's public API.
+
+use typst::eval::Tracer;
+use typst::foundations::dict;
+use typst::text::Lang;
+use typst::{compile, CancelToken, Compiler, Library, Phase, World};
+use typst_tests::TestWorld;
+
+#[test]
+fn test_compiler_cancel_between_layout_iterations() {
+    // The query needs a second layout pass to converge.
+    let world = TestWorld::new("#locate(loc => counter(page).final(loc).first())");
+
+    let token = CancelToken::new();
+    let cancel = token.clone();
+    let compiler = Compiler::new().with_progress(move |phase| {
+        if phase == Phase::Layout(0) {
+            cancel.cancel();
+        }
+    });
+
+    let mut tracer = Tracer::new();
+    let errors = compiler.compile_cancellable(&world, &mut tracer, &token).unwrap_err();
+    assert_eq!(errors[0].message, "compilation was cancelled");
+    assert_eq!(tracer.passes(), 1);
+
+    // A fresh compilation is not affected by the old token.
+    let mut tracer = Tracer::new();
+    assert!(compiler.compile(&world, &mut tracer).is_ok());
+    assert_eq!(tracer.passes(), 2);
+}
+
+#[test]
+fn test_compiler_pass_limit() {
+    let world = TestWorld::new("#locate(loc => counter(page).final(loc).first())");
+    let compiler = Compiler::new().with_max_passes(1).with_lang(Lang::CHINESE, None);
+
+    let mut tracer = Tracer::new();
+    assert!(compiler.compile(&world, &mut tracer).is_ok());
+    assert_eq!(tracer.passes(), 1);
+
+    let warnings = tracer.warnings();
+    assert_eq!(warnings[0].code, Some("layout-did-not-converge"));
+    assert_eq!(warnings[0].message, "布局在 1 次尝试内未能收敛");
+}
+
+#[test]
+fn test_library_inputs() {
+    let world = TestWorld::new("#assert.eq(sys.inputs, (edition: \"teacher\"))");
+    let inputs = dict! { "edition" => "teacher" };
+    let world = world.with_library(Library::builder().with_inputs(inputs).build());
+    assert!(compile(&world, &mut Tracer::new()).is_ok());
+
+    // Without inputs, the dictionary is empty.
+    let world = TestWorld::new("#assert.eq(sys.inputs, (:))");
+    assert!(compile(&world, &mut Tracer::new()).is_ok());
+}
+
+#[test]
+fn test_unknown_variable_suggestion() {
+    let world = TestWorld::new("#let alpha = 1\n#alpho");
+    let errors = compile(&world, &mut Tracer::new()).unwrap_err();
+    assert_eq!(errors[0].code, Some("unknown-variable"));
+    assert_eq!(errors[0].suggestions.len(), 1);
+
+    let suggestion = &errors[0].suggestions[0];
+    assert_eq!(suggestion.replacement, "alpha");
+    assert_eq!(world.main().range(suggestion.span), Some(16..21));
+}
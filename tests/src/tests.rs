@@ -28,6 +28,8 @@ use typst::syntax::{FileId, PackageVersion, Source, SyntaxNode, VirtualPath};
 use typst::text::{Font, FontBook, TextElem, TextSize};
 use typst::visualize::Color;
 use typst::{Library, World, WorldExt};
+use typst_pdf::PdfOptions;
+use typst_svg::SvgOptions;
 use unscanny::Scanner;
 use walkdir::WalkDir;
 
@@ -37,6 +39,7 @@ const REF_DIR: &str = "ref";
 const PNG_DIR: &str = "png";
 const PDF_DIR: &str = "pdf";
 const SVG_DIR: &str = "svg";
+const DIFF_DIR: &str = "diff";
 const FONT_DIR: &str = "../assets/fonts";
 const ASSET_DIR: &str = "../assets";
 
@@ -52,6 +55,12 @@ struct Args {
     exact: bool,
     #[arg(long, default_value_t = env::var_os("UPDATE_EXPECT").is_some())]
     update: bool,
+    /// by how much a color channel may deviate from the reference image
+    #[arg(long, default_value_t = 2)]
+    tolerance: u8,
+    /// how many pixels may deviate from the reference image
+    #[arg(long, default_value_t = 0)]
+    max_diff: usize,
     #[arg(long)]
     pdf: bool,
     #[command(flatten)]
@@ -427,11 +436,16 @@ fn test(
     let document = Document { pages: frames, ..Default::default() };
     if compare_ever {
         if let Some(pdf_path) = pdf_path {
+            let ident = format!("typst-test: {}", name.display());
             let pdf_data = typst_pdf::pdf(
                 &document,
-                Some(&format!("typst-test: {}", name.display())),
-                world.today(Some(0)),
-            );
+                &PdfOptions {
+                    ident: Some(&ident),
+                    timestamp: world.today(Some(0)),
+                    ..PdfOptions::default()
+                },
+            )
+            .unwrap();
             fs::create_dir_all(pdf_path.parent().unwrap()).unwrap();
             fs::write(pdf_path, pdf_data).unwrap();
         }
@@ -446,26 +460,40 @@ fn test(
         fs::create_dir_all(png_path.parent().unwrap()).unwrap();
         canvas.save_png(png_path).unwrap();
 
-        let svg = typst_svg::svg_merged(&document.pages, Abs::pt(5.0));
+        let svg =
+            typst_svg::svg_merged(&document.pages, Abs::pt(5.0), &SvgOptions::default());
         fs::create_dir_all(svg_path.parent().unwrap()).unwrap();
         std::fs::write(svg_path, svg.as_bytes()).unwrap();
 
         if let Ok(ref_pixmap) = sk::Pixmap::load_png(ref_path) {
-            if canvas.width() != ref_pixmap.width()
+            let diff_path = Path::new(DIFF_DIR).join(name).with_extension("png");
+            let mismatch = if canvas.width() != ref_pixmap.width()
                 || canvas.height() != ref_pixmap.height()
-                || canvas
-                    .data()
-                    .iter()
-                    .zip(ref_pixmap.data())
-                    .any(|(&a, &b)| a.abs_diff(b) > 2)
             {
+                Some("  Does not match reference image in size.".into())
+            } else {
+                let (diff, count) = diff(&canvas, &ref_pixmap, args.tolerance);
+                (count > args.max_diff).then(|| {
+                    fs::create_dir_all(diff_path.parent().unwrap()).unwrap();
+                    diff.save_png(&diff_path).unwrap();
+                    eco_format!(
+                        "  Does not match reference image ({count} pixels differ, \
+                         see {}).",
+                        diff_path.display()
+                    )
+                })
+            };
+
+            if let Some(message) = mismatch {
                 if args.update {
                     update_image(png_path, ref_path);
                     updated = true;
                 } else {
-                    writeln!(output, "  Does not match reference image.").unwrap();
+                    writeln!(output, "{message}").unwrap();
                     ok = false;
                 }
+            } else {
+                fs::remove_file(&diff_path).ok();
             }
         } else if !document.pages.is_empty() {
             if args.update {
@@ -509,6 +537,38 @@ fn get_flag_metadata(line: &str, key: &str) -> Option<bool> {
     get_metadata(line, key).map(|value| value == "true")
 }
 
+/// Compare a rendered page with its reference image of the same size.
+///
+/// Returns an image that shows the reference faded out with the differing
+/// pixels in red, and the number of differing pixels. A pixel differs if any
+/// of its channels deviates by more than `tolerance`.
+fn diff(
+    canvas: &sk::Pixmap,
+    reference: &sk::Pixmap,
+    tolerance: u8,
+) -> (sk::Pixmap, usize) {
+    let mut diff = sk::Pixmap::new(canvas.width(), canvas.height()).unwrap();
+    let mut count = 0;
+    for ((out, a), b) in diff
+        .data_mut()
+        .chunks_exact_mut(4)
+        .zip(canvas.data().chunks_exact(4))
+        .zip(reference.data().chunks_exact(4))
+    {
+        if a.iter().zip(b).any(|(&a, &b)| a.abs_diff(b) > tolerance) {
+            count += 1;
+            out.copy_from_slice(&[255, 0, 0, 255]);
+        } else {
+            // Blend the reference onto white with a quarter of its strength.
+            for (o, &c) in out.iter_mut().zip(b).take(3) {
+                *o = 191 + c / 4;
+            }
+            out[3] = 255;
+        }
+    }
+    (diff, count)
+}
+
 fn update_image(png_path: &Path, ref_path: &Path) {
     oxipng::optimize(
         &InFile::Path(png_path.to_owned()),
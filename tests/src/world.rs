@@ -0,0 +1,86 @@
+//! A small world for the unit tests of the compiler and the exporters.
+//!
+//! Unlike the world of the test runner, it has only a single font and keeps
+//! the default styles, so that tests can check the output for plain markup.
+
+use std::path::Path;
+
+use comemo::Prehashed;
+use typst::diag::{FileError, FileResult};
+use typst::eval::Tracer;
+use typst::foundations::{Bytes, Datetime};
+use typst::model::Document;
+use typst::syntax::{FileId, Source};
+use typst::text::{Font, FontBook};
+use typst::{Library, World};
+
+const FONT: &[u8] = include_bytes!("../../assets/fonts/LinLibertine_R.ttf");
+
+/// A world with a single font that reads files from the assets.
+pub struct TestWorld {
+    library: Prehashed<Library>,
+    book: Prehashed<FontBook>,
+    font: Font,
+    source: Source,
+}
+
+impl TestWorld {
+    /// Create a world whose main file consists of the given markup.
+    pub fn new(text: &str) -> Self {
+        let font = Font::new(FONT.into(), 0).unwrap();
+        let book = FontBook::from_fonts([&font]);
+        Self {
+            library: Prehashed::new(Library::build()),
+            book: Prehashed::new(book),
+            font,
+            source: Source::detached(text),
+        }
+    }
+
+    /// Use a different standard library.
+    pub fn with_library(mut self, library: Library) -> Self {
+        self.library = Prehashed::new(library);
+        self
+    }
+}
+
+impl World for TestWorld {
+    fn library(&self) -> &Prehashed<Library> {
+        &self.library
+    }
+
+    fn book(&self) -> &Prehashed<FontBook> {
+        &self.book
+    }
+
+    fn main(&self) -> Source {
+        self.source.clone()
+    }
+
+    fn source(&self, id: FileId) -> FileResult<Source> {
+        Err(FileError::NotFound(id.vpath().as_rootless_path().into()))
+    }
+
+    fn file(&self, id: FileId) -> FileResult<Bytes> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets");
+        let path = id.vpath().resolve(&root).ok_or(FileError::AccessDenied)?;
+        std::fs::read(&path)
+            .map(Bytes::from)
+            .map_err(|err| FileError::from_io(err, &path))
+    }
+
+    fn font(&self, _: usize) -> Option<Font> {
+        Some(self.font.clone())
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        None
+    }
+}
+
+/// Compile a document from markup.
+pub fn compile(text: &str) -> Document {
+    let world = TestWorld::new(text);
+    let mut tracer = Tracer::new();
+    typst::compile(&world, &mut tracer).expect("document should compile")
+}
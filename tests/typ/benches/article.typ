@@ -0,0 +1,31 @@
+// A Latin-script article with headings, lists, footnotes, and math.
+
+#set page(paper: "a4", numbering: "1")
+#set par(justify: true)
+#set heading(numbering: "1.1")
+#set text(font: "Linux Libertine", hyphenate: true)
+
+#align(center, text(17pt)[*On the Layout of Long Documents*])
+
+#outline()
+
+#for chapter in range(1, 9) [
+  = Chapter #chapter
+  #lorem(120)
+
+  == Motivation
+  #lorem(80)#footnote(lorem(12)) #lorem(60)
+
+  - #lorem(14)
+  - #lorem(10)
+    - #lorem(8)
+  + #lorem(12)
+  + #lorem(16)
+
+  == Results
+  The area of a circle with radius $r$ is $A = pi r^2$. More generally,
+  $ sum_(k=1)^n k^2 = (n (n + 1) (2n + 1)) / 6 $
+  #lorem(100)
+
+  #quote(block: true, lorem(30))
+]
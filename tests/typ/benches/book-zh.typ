@@ -0,0 +1,26 @@
+// A 300-page Chinese book with CJK-Latin spacing and justification.
+
+#set page(paper: "a5", numbering: "1", margin: 2cm)
+#set text(font: ("Linux Libertine", "Noto Serif CJK SC"), lang: "zh", region: "cn")
+#set par(justify: true, first-line-indent: 2em, leading: 0.9em)
+#set heading(numbering: "1.1")
+#show heading.where(level: 1): it => pagebreak(weak: true) + it
+
+#let para = [
+  排版是将文字、图片等元素按照一定的规则排列在页面上的过程。Typst 是一个新的
+  基于标记的排版系统，它的设计目标是既强大又易于学习。在中文排版中，标点挤压、
+  中西文间距和避头尾规则都需要仔细处理，这样才能得到美观、易读的版面。
+  例如“引号”和（括号）在行首或行尾时的处理方式就与西文不同。版心的宽度最好是
+  字号的整数倍，这样每一行的字数相同，两端对齐时也不必拉伸字距。
+]
+
+#for chapter in range(1, 31) [
+  = 第 #chapter 章的标题
+  #for section in range(1, 5) [
+    == 第 #section 节
+    #for _ in range(6) [
+      #para
+
+    ]
+  ]
+]
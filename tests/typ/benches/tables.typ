@@ -0,0 +1,24 @@
+// A reference manual that consists mostly of large tables.
+
+#set page(paper: "a4", flipped: true, numbering: "1")
+#set text(font: "Linux Libertine", size: 9pt)
+
+#let row(i) = (
+  [#i],
+  [`item-` #str(i)],
+  lorem(calc.rem(i, 7) + 3),
+  [#calc.round(calc.sin(i) * 100, digits: 2)],
+  [#calc.rem(i * 37, 101)],
+  if calc.even(i) [yes] else [no],
+)
+
+#for part in range(1, 11) [
+  = Part #part
+  #table(
+    columns: (auto, auto, 1fr, auto, auto, auto),
+    fill: (_, y) => if y == 0 { luma(220) } else if calc.odd(y) { luma(245) },
+    align: (x, _) => if x in (0, 3, 4) { right } else { left },
+    [*No.*], [*Key*], [*Description*], [*Value*], [*Code*], [*Flag*],
+    ..range(1, 81).map(i => row(part * 100 + i)).flatten(),
+  )
+]
@@ -102,3 +102,26 @@
 #test(duration(minutes: 20) < duration(minutes: 10), false)
 #test(duration(minutes: 20) <= duration(minutes: 10), false)
 #test(duration(minutes: 20) == duration(minutes: 10), false)
+
+---
+// Test displaying durations.
+#let d = duration(hours: 26, minutes: 5, seconds: 9)
+#test(d.display(), "26:05:09")
+#test(d.display("[days]d [hours]h [minutes]m"), "1d 2h 05m")
+#test(d.display("[minutes] min"), "1565 min")
+#test(duration(seconds: 5).display("[[[seconds]]"), "[5]")
+#test((-duration(minutes: 90)).display("[hours]:[minutes]"), "-1:30")
+
+---
+// Test computing schedules with durations.
+#let start = datetime(hour: 9, minute: 0, second: 0)
+#let slot = duration(minutes: 45)
+#test((start + slot * 2).display("[hour]:[minute]"), "10:30")
+
+---
+// Error: 2-38 unknown duration component `hour`
+#duration(hours: 1).display("[hour]")
+
+---
+// Error: 2-38 unclosed component in duration format
+#duration(hours: 1).display("[hours")
@@ -39,3 +39,29 @@ Can not be used as @intro
 
 // Error: 1:20-1:26 cannot reference heading without numbering
 Can not be used as @intro
+
+---
+#{
+  let zhangsan = 1
+  // Error: 3-11 unknown variable: zhangsna
+  // Hint: 3-11 did you mean `zhangsan`?
+  zhangsna
+}
+
+---
+// Error: 2-7 unknown variable: rectt
+// Hint: 2-7 did you mean `rect`?
+#rectt(width: 1cm)
+
+---
+// Error: 7-17 unexpected argument: heigt
+// Hint: 7-17 did you mean `height`?
+#rect(heigt: 1cm)
+
+---
+#{
+  let greet(name, birthday: false) = name
+  // Error: 18-32 unexpected argument: brithday
+  // Hint: 18-32 did you mean `birthday`?
+  greet("Typst", brithday: true)
+}
@@ -0,0 +1,8 @@
+// Test that plugins which never finish are aborted.
+// Ref: false
+
+---
+#let p = plugin("/files/plugin-loop.wasm")
+
+// Error: 2-10 plugin function `spin` took too long and was aborted
+#p.spin()
@@ -42,6 +42,13 @@
 #let rec(n) = rec(n) + 1
 #rec(1)
 
+---
+// Repeated calls through the same call site are collapsed in the trace.
+// Error: 30-38 panicked with: "done"
+// Hint: 30-38 error occurred in this call of function `f` (repeated 3 times)
+#let f(n) = if n == 0 { panic("done") } else { f(n - 1) }
+#f(3)
+
 ---
 // Test cyclic imports during layout.
 // Error: 14-37 maximum layout depth exceeded
@@ -0,0 +1,40 @@
+// Test show rules with compound and location selectors.
+// Ref: false
+
+---
+// Test an or selector.
+#let count = counter("or")
+#show heading.where(level: 1).or(<marked>): it => count.step()
+= One
+== Two
+== Three <marked>
+#locate(loc => test(count.final(loc), (2,)))
+
+---
+// Test an and selector.
+#let count = counter("and")
+#show selector(heading).and(<special>): it => count.step()
+= One
+= Two <special>
+=== Three <special>
+#locate(loc => test(count.final(loc), (2,)))
+
+---
+// Test a location selector.
+#let count = counter("loc")
+#show: doc => locate(loc => {
+  let second = query(heading, loc).at(1, default: none)
+  if second != none {
+    show second.location(): it => count.step()
+    doc
+  } else {
+    doc
+  }
+})
+= First
+= Second
+#locate(loc => test(count.final(loc), (1,)))
+
+---
+// Error: 7-39 regex selectors cannot be combined in show rules
+#show selector(heading).or(regex("a")): none
@@ -39,5 +39,5 @@ the ```rs &mut T``` reference.
 === Green
 
 ---
-// Error: 7-35 this selector cannot be used with show
-#show selector(heading).or(figure): none
+// Error: 7-38 this selector cannot be used with show
+#show selector(heading).before(<end>): none
@@ -222,6 +222,36 @@
 // Error: 12-15 unknown variable: arg
 #"abc".rev(arg)
 
+---
+// Test the `normalize` method.
+#test("e\u{0301}".normalize(), "é")
+#test("é".normalize(form: "nfd"), "e\u{0301}")
+#test("ﬁ".normalize(), "ﬁ")
+#test("ﬁ".normalize(form: "nfkc"), "fi")
+#test("Ａ１".normalize(form: "nfkd"), "A1")
+
+---
+// Error: 24-28 expected "nfc", "nfd", "nfkc", or "nfkd"
+#"abc".normalize(form: "nf")
+
+---
+// Test the `to-fullwidth` and `to-halfwidth` methods.
+#test("Typst 2023!".to-fullwidth(), "Ｔｙｐｓｔ　２０２３！")
+#test("Ｔｙｐｓｔ　２０２３！".to-halfwidth(), "Typst 2023!")
+#test("排版, ok".to-fullwidth(), "排版，　ｏｋ")
+#test("（排版）".to-halfwidth(), "(排版)")
+#test("~".to-fullwidth().to-halfwidth(), "~")
+
+---
+// Test the `display-width` method.
+#test("".display-width(), 0)
+#test("Typst".display-width(), 5)
+#test("排版".display-width(), 4)
+#test("Ｔｙｐｓｔ".display-width(), 10)
+#test("e\u{0301}".display-width(), 1)
+#test("“".display-width(), 1)
+#test("“".display-width(cjk: true), 2)
+
 ---
 // Error: 2-2:1 unclosed string
 #"hello\"
@@ -0,0 +1,30 @@
+// Test looking up set rule properties from the active styles.
+// Ref: false
+
+---
+// Unset properties fall back to the default.
+#style(styles => {
+  test(styles.get(text, "fill"), none)
+  test(styles.get(text, "fill", default: black), black)
+})
+
+---
+// The innermost set rule wins.
+#set text(fill: red)
+#[
+  #set text(fill: blue, size: 12pt)
+  #style(styles => {
+    test(styles.get(text, "fill"), blue)
+    test(styles.get(text, "size"), 12pt)
+  })
+]
+#style(styles => test(styles.get(text, "fill"), red))
+
+---
+// Set rules inside of show rules are visible as well.
+#show strong: set text(style: "italic")
+*#style(styles => test(styles.get(text, "style"), "italic"))*
+
+---
+// Error: 18-44 element `text` does not have field `colour`
+#style(styles => styles.get(text, "colour"))
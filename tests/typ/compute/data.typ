@@ -14,6 +14,15 @@
 // Error: 18-34 file is not valid utf-8
 #let data = read("/files/bad.txt")
 
+---
+// Test reading GBK-encoded text.
+#let data = read("/files/gbk.txt", encoding: "gbk")
+#test(data, "你好，世界！\n")
+
+---
+// Error: 18-34 file is not valid gbk
+#let data = read("/files/bad.txt", encoding: "gbk")
+
 ---
 // Test reading CSV data.
 // Ref: true
@@ -26,6 +35,16 @@
 // Error: 6-16 file not found (searched at typ/compute/nope.csv)
 #csv("nope.csv")
 
+---
+// Test loading CSV data that was read as GBK.
+#let data = csv(read("/files/gbk.csv", encoding: "gbk"))
+#test(data, (("城市", "人口"), ("北京", "2189"), ("上海", "2487")))
+
+---
+// Data that isn't UTF-8 is not guessed to be GBK.
+// Error: 6-22 file is not valid utf-8
+#csv("/files/gbk.csv")
+
 ---
 // Error: 6-22 failed to parse CSV (found 3 instead of 2 fields in line 3)
 #csv("/files/bad.csv")
@@ -0,0 +1,56 @@
+// Test the `hash` and `encoding` modules.
+// Ref: false
+
+---
+// Test hashes.
+#test(hash.sha256("Typst").len(), 32)
+#test(
+  encoding.hex(hash.sha256("Typst")),
+  "38c14037e93b9c2bad92513749a7e5c37340291749afe2c605b3d196ad2e0f2a",
+)
+#test(
+  encoding.hex(hash.sha256(bytes(()))),
+  "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+)
+#test(encoding.hex(hash.md5(bytes("Typst"))), "38fda039e10e075c9ca63079ac87725d")
+
+---
+// Test Base64.
+#test(encoding.base64("Typst"), "VHlwc3Q=")
+#test(encoding.base64("排版"), "5o6S54mI")
+#test(encoding.base64(bytes((0, 255))), "AP8=")
+#test(str(encoding.base64-decode("5o6S54mI")), "排版")
+#test(encoding.base64-decode(""), bytes(()))
+
+---
+// Test hexadecimal.
+#test(encoding.hex(bytes((0, 15, 16, 255))), "000f10ff")
+#test(encoding.hex-decode("000F10ff"), bytes((0, 15, 16, 255)))
+#test(str(encoding.hex-decode(encoding.hex("排版"))), "排版")
+
+---
+// Test URL encoding.
+#test(encoding.url("排版 & typst"), "%E6%8E%92%E7%89%88%20%26%20typst")
+#test(encoding.url("a-b_c.d~e"), "a-b_c.d~e")
+#test(encoding.url-decode("%E6%8E%92%E7%89%88%20%26%20typst"), "排版 & typst")
+#test(encoding.url-decode("a+b"), "a+b")
+
+---
+// Error: 2-39 text is not valid base64
+#encoding.base64-decode("not base64!")
+
+---
+// Error: 2-28 hexadecimal text must have an even number of digits
+#encoding.hex-decode("abc")
+
+---
+// Error: 2-29 text is not valid hexadecimal
+#encoding.hex-decode("+f0g")
+
+---
+// Error: 2-27 invalid percent-encoding
+#encoding.url-decode("%E")
+
+---
+// Error: 2-28 decoded text is not valid utf-8
+#encoding.url-decode("%FF")
@@ -0,0 +1,44 @@
+// Test the `format-number` function.
+// Ref: false
+
+---
+// Test decimal numbers.
+#test(format-number(1234), "1234")
+#test(format-number(-1234), "−1234")
+#test(format-number(2.5), "2.5")
+#test(format-number(5, decimals: 2), "5.00")
+#test(format-number(2.6, decimals: 0), "3")
+#test(format-number(3.14159, decimals: 3), "3.142")
+#test(format-number(-0.001, decimals: 2), "0.00")
+
+---
+// Test digit grouping.
+#test(format-number(123, grouping: ","), "123")
+#test(format-number(1234, grouping: ","), "1,234")
+#test(format-number(1234567.891, decimals: 2, grouping: ","), "1,234,567.89")
+#test(format-number(-1234.5, grouping: " "), "−1 234.5")
+
+---
+// Test percentages and scientific notation.
+#test(format-number(1, style: "percent"), "100%")
+#test(format-number(0.256, decimals: 1, style: "percent"), "25.6%")
+#test(format-number(1234, style: "scientific"), "1.234e3")
+#test(format-number(1234, decimals: 1, style: "scientific"), "1.2e3")
+#test(format-number(0.0015, style: "scientific"), "1.5e−3")
+#test(format-number(calc.inf), "inf")
+
+---
+// Test Chinese numerals.
+#test(format-number(0, style: "chinese"), "零")
+#test(format-number(123, style: "chinese"), "一百二十三")
+#test(format-number(-3.14, style: "chinese"), "负三点一四")
+#test(format-number(1234, style: "chinese-financial"), "壹仟贰佰叁拾肆")
+#test(format-number(1234.5, decimals: 2, style: "chinese-financial"), "壹仟贰佰叁拾肆点伍零")
+
+---
+// Error: 2-43 cannot write inf in Chinese numerals
+#format-number(calc.inf, style: "chinese")
+
+---
+// Error: 26-33 expected "decimal", "percent", "scientific", "chinese", or "chinese-financial"
+#format-number(1, style: "roman")
@@ -9,6 +9,31 @@
 ---
 #test(repr(ltr), "ltr")
 #test(repr((1, 2, false, )), "(1, 2, false)")
+#test(repr(<intro>), "<intro>")
+#test(repr(label("with space")), "label(\"with space\")")
+#test(repr(calc.inf), "calc.inf")
+#test(repr(-calc.inf), "−calc.inf")
+
+---
+// Test that representations evaluate back to equal values.
+#let roundtrip(value) = test(eval(repr(value)), value)
+#roundtrip(none)
+#roundtrip(-12)
+#roundtrip(3.25)
+#roundtrip(-calc.inf)
+#roundtrip("a\n\"b\"\u{1F680}")
+#roundtrip((1, (2,), (a: 3, "b c": 4)))
+#roundtrip(2pt + 1em)
+#roundtrip(50% + 1cm)
+#roundtrip(45deg)
+#roundtrip(2fr)
+#roundtrip(top + left)
+#roundtrip(rgb("#f7a205"))
+#roundtrip(<intro>)
+#roundtrip(label("with space"))
+#roundtrip(version(1, 2))
+#roundtrip(datetime(year: 2023, month: 1, day: 2))
+#roundtrip(duration(days: 3, seconds: 5))
 
 ---
 // Test panic.
@@ -0,0 +1,54 @@
+// Test the `random` module.
+// Ref: false
+
+---
+// The same seed always yields the same result.
+#test(random.integer(1, 7, seed: 1), 4)
+#test(random.integer(1, 7, seed: 3), 1)
+#test(random.uniform(seed: 1), 0.5665615751722809)
+#test(random.shuffle(range(1, 6), seed: 1), (2, 1, 4, 5, 3))
+#test(random.shuffle(range(1, 6), seed: 2), (1, 4, 2, 5, 3))
+#test(random.choice(("A", "B", "C", "D"), seed: 3), "A")
+#test(random.integer(0, 100, seed: "exam"), random.integer(0, 100, seed: "exam"))
+#test(random.uniform(seed: (1, "a")), random.uniform(seed: (1, "a")))
+
+---
+// Without a seed, the result depends on the call site.
+#let roll() = random.integer(0, 1000000)
+#test(roll(), roll())
+#test(range(3).map(_ => random.uniform()).dedup().len(), 1)
+#test(random.integer(0, 1000000) == random.integer(0, 1000000), false)
+
+---
+// Test ranges.
+#for seed in range(20) {
+  let x = random.uniform(-2, 3, seed: seed)
+  assert(-2 <= x and x < 3)
+  let n = random.integer(-5, 5, seed: (seed, "n"))
+  assert(-5 <= n and n < 5)
+}
+#test(random.integer(7, 8, seed: 5), 7)
+#test(random.integer(-9223372036854775807, 9223372036854775807) < 9223372036854775807, true)
+
+---
+// Shuffling keeps the items.
+#let items = ("a", "b", "c", "d", "e", "f")
+#test(random.shuffle(items, seed: "q1").sorted(), items)
+#test(random.shuffle((), seed: 1), ())
+#assert(items.contains(random.choice(items, seed: "q1")))
+
+---
+// Error: 2-22 range must not be empty
+#random.integer(3, 3)
+
+---
+// Error: 2-22 `low` must be smaller than `high`
+#random.uniform(2, 1)
+
+---
+// Error: 2-19 array is empty
+#random.choice(())
+
+---
+// Error: 23-26 expected integer, string, array, or none, found float
+#random.uniform(seed: 1.5)
@@ -0,0 +1,10 @@
+// Test the inputs passed to the compiler.
+// Ref: false
+
+---
+#test(type(sys.inputs), dictionary)
+#test(sys.inputs.at("edition", default: "student"), "student")
+
+---
+// Test the export target, which is PDF unless configured otherwise.
+#test(sys.target, "pdf")
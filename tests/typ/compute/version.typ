@@ -45,3 +45,22 @@
 ---
 // Test the type of `sys.version`
 #test(type(sys.version), version)
+
+---
+// Test that missing components are zero.
+#test(version(1).minor, 0)
+#test(version().patch, 0)
+
+---
+// Test parsing versions from strings.
+#test(version("0.12.0"), version(0, 12))
+#test(version("1.2", 3), version(1, 2, 3))
+#test(sys.version >= version("0.1.0"), true)
+
+---
+// Error: 10-18 `0.12.x` is not a valid version
+#version("0.12.x")
+
+---
+// Error: 10-12 version must not be empty
+#version("")
@@ -0,0 +1,21 @@
+// Test aligning boxes with the surrounding text.
+// Ref: false
+
+---
+// Test that alignments position the box relative to the text edges.
+#set text(top-edge: "cap-height", bottom-edge: "baseline")
+#let icon = box.with(width: 8pt, height: 8pt, fill: eastern)
+#style(styles => {
+  let line(align) = measure([A#icon(baseline: align)#icon()], styles).height
+  test(line(bottom), 8pt)
+  test(line(horizon) > line(bottom), true)
+  test(line(top) > line(horizon), true)
+})
+
+---
+// Test that the shift still works.
+#box(baseline: 40%, rect(width: 10pt, height: 10pt))
+
+---
+// Error: 16-20 expected `top`, `horizon`, or `bottom`, found left
+#box(baseline: left)
@@ -0,0 +1,29 @@
+// Test overflow handling of boxes.
+// Ref: false
+
+---
+// Truncated text stays on a single line.
+#style(styles => {
+  let line = measure(box[很], styles).height
+  let long = box(width: 40pt, overflow: "ellipsis", ellipsis: "。。。")[
+    很长很长很长很长很长的文字
+  ]
+  test(measure(long, styles).height, line)
+  test(measure(long, styles).width, 40pt)
+})
+
+---
+// Shrunk text is smaller than the original.
+#style(styles => {
+  let word = [Supercalifragilistic]
+  let normal = measure(box(width: 30pt, word), styles).height
+  let shrunk = measure(box(width: 30pt, overflow: "shrink", word), styles).height
+  test(shrunk < normal, true)
+})
+
+// Content that fits is left alone.
+#box(width: 3cm, overflow: "ellipsis")[Short]
+
+---
+// Error: 16-22 expected "ellipsis", "shrink", or none
+#box(overflow: "hide")[Text]
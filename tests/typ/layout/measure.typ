@@ -0,0 +1,33 @@
+// Test measuring content.
+// Ref: false
+
+---
+// Test that measuring respects the given styles.
+#style(styles => {
+  let small = measure([Hello], styles)
+  let big = measure(text(20pt)[Hello], styles)
+  test(small.width < big.width, true)
+  test(small.height < big.height, true)
+})
+
+---
+// Test that text wraps in a limited width.
+#style(styles => {
+  let body = lorem(20)
+  let wide = measure(body, styles)
+  let narrow = measure(body, styles, width: 3cm)
+  test(narrow.width <= 3cm, true)
+  test(narrow.height > wide.height, true)
+})
+
+---
+// Test that the width can be relative to the font size.
+#style(styles => {
+  let a = measure(lorem(20), styles, width: 10em)
+  let b = measure(text(20pt, lorem(20)), styles, width: 10em)
+  test(a.height < b.height, true)
+})
+
+---
+// Error: 45-46 expected length or auto, found integer
+#style(styles => measure([], styles, width: 1))
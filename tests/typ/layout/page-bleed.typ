@@ -0,0 +1,14 @@
+// Test bleed and printer's marks.
+// Ref: false
+
+---
+// Error: 18-23 expected length, found string
+#set page(bleed: "3mm")
+
+---
+// Error: 18-22 bleed must not be negative
+#set page(bleed: -3mm)
+
+---
+// Error: 18-27 bleed must not be negative
+#set page(bleed: 3mm - 1em)
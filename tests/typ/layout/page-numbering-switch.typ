@@ -0,0 +1,23 @@
+// Test switching the page numbering between the front matter and the main
+// text.
+// Ref: false
+
+---
+#set page(height: 80pt, numbering: "I")
+= 摘要
+#pagebreak()
+= Abstract
+
+#set page(numbering: "1")
+#counter(page).update(1)
+= 绪论
+#pagebreak()
+= 方法
+
+#locate(loc => {
+  let pages = query(heading.where(outlined: true), loc).map(it => {
+    let loc = it.location()
+    numbering(loc.page-numbering(), ..counter(page).at(loc))
+  })
+  test(pages, ("I", "II", "1", "2"))
+})
@@ -0,0 +1,48 @@
+// Test balanced line breaks.
+// Ref: false
+
+---
+// Balancing keeps the number of lines.
+#let title = [关于高维数据可视化方法的若干研究]
+#style(styles => {
+  let height(mode) = measure(block(width: 90pt, {
+    set par(linebreaks: mode)
+    title
+  }), styles).height
+  test(height("balanced"), height("simple"))
+})
+
+---
+// Balanced lines are about equally long, unlike simple ones, which break as
+// nine plus seven characters. Each character is followed by a marker so that
+// the end of every line can be queried.
+#let title = "关于高维数据可视化方法的若干研究"
+#for mode in ("balanced", "simple") {
+  block(width: 100pt, {
+    set par(linebreaks: mode)
+    for c in title.clusters() [#c#metadata(mode)]
+  })
+}
+
+#let widths(mode, loc) = {
+  let ends = (:)
+  for m in query(metadata, loc).filter(m => m.value == mode) {
+    let pos = m.location().position()
+    let key = repr(pos.y)
+    ends.insert(key, calc.max(ends.at(key, default: 0pt), pos.x))
+  }
+  ends.values()
+}
+
+#locate(loc => {
+  let balanced = widths("balanced", loc)
+  let simple = widths("simple", loc)
+  test(balanced.len(), 2)
+  test(simple.len(), 2)
+  test(calc.abs(balanced.first() - balanced.last()) < 11pt, true)
+  test(calc.abs(simple.first() - simple.last()) > 11pt, true)
+})
+
+---
+// Error: 22-31 expected "simple", "optimized", "balanced", or auto
+#set par(linebreaks: "balance")
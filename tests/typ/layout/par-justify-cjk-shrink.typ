@@ -0,0 +1,27 @@
+// Test compressing the gaps between CJK characters.
+// Ref: false
+
+---
+#set par(justify: true)
+#set text(lang: "zh", font: "Noto Serif CJK SC")
+#style(styles => {
+  let body = [汉字是汉族或华人的]
+  let natural = measure(body, styles, width: 8.5em)
+  let compressed = measure(text(cjk-shrink: 0.1em, body), styles, width: 8.5em)
+  test(compressed.height < natural.height, true)
+})
+
+---
+// The last character keeps its gap, so that it ends at the line's edge. The
+// characters are 10pt wide and the block starts at 10pt.
+#set par(justify: true)
+#set text(lang: "zh", font: "Noto Serif CJK SC", cjk-shrink: 0.1em)
+#block(width: 8.5em)[汉字是汉族或华人#metadata(none)<before-last>的]
+#locate(loc => {
+  let x = query(<before-last>, loc).first().location().position().x
+  test(calc.abs(x + 10pt - 95pt) < 0.01pt, true)
+})
+
+---
+// Error: 23-26 expected length, found ratio
+#set text(cjk-shrink: 10%)
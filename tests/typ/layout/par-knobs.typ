@@ -0,0 +1,45 @@
+// Test paragraph spacing and last line justification.
+// Ref: false
+
+---
+// Test that paragraph spacing overrides the block spacing.
+#style(styles => {
+  let normal = measure(block[A #parbreak() B], styles)
+  let tight = measure(block[#set par(spacing: 0pt); A #parbreak() B], styles)
+  let wide = measure(block[#set par(spacing: 2em); A #parbreak() B], styles)
+  test(tight.height < normal.height, true)
+  test(wide.height > normal.height, true)
+})
+
+---
+// Test that auto falls back to the block spacing.
+#style(styles => {
+  let block-spacing = measure(block[#set block(spacing: 5pt); A #parbreak() B], styles)
+  let auto-spacing = measure(block[#set block(spacing: 5pt); #set par(spacing: auto); A #parbreak() B], styles)
+  test(block-spacing.height, auto-spacing.height)
+})
+
+---
+// Test justification of the last line and of lines before forced breaks. The
+// ends of justified lines reach the right margin at 110pt.
+#let end(label, loc) = query(label, loc).first().location().position().x
+#set par(justify: true, justify-last-line: true)
+A B#metadata(none) <forced> \
+C D#metadata(none) <last>
+
+#locate(loc => {
+  test(end(<forced>, loc) > 109pt, true)
+  test(end(<last>, loc) > 109pt, true)
+})
+
+---
+// Without it, these lines keep their natural width.
+#let end(label, loc) = query(label, loc).first().location().position().x
+#set par(justify: true)
+A B#metadata(none) <forced> \
+C D#metadata(none) <last>
+
+#locate(loc => {
+  test(end(<forced>, loc) < 60pt, true)
+  test(end(<last>, loc) < 60pt, true)
+})
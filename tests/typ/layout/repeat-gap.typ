@@ -0,0 +1,35 @@
+// Test the gap and justification of repeated content.
+// Ref: false
+
+---
+// Test leaders with a fixed gap in a price list.
+#set page(width: 120pt)
+Tea #box(width: 1fr, repeat(gap: 2pt)[.]) 2.50 € \
+Coffee #box(width: 1fr, repeat(gap: 2pt)[.]) 3.00 €
+
+---
+// Test where the first instance is placed with and without justification.
+// Four instances of 4pt with a 3pt gap leave 5pt of the 30pt box.
+#let piece = [#box(width: 4pt, height: 1pt)#metadata(none) <piece>]
+#let first(loc) = query(<piece>, loc).first().location().position().x
+#box(width: 30pt, align(right, repeat(gap: 3pt, justify: false, piece)))
+#locate(loc => test(first(loc), 10pt + 5pt + 4pt))
+
+---
+// Justification spreads the instances over the whole box regardless of the
+// alignment.
+#let piece = [#box(width: 4pt, height: 1pt)#metadata(none) <piece>]
+#let first(loc) = query(<piece>, loc).first().location().position().x
+#box(width: 30pt, align(right, repeat(gap: 3pt, piece)))
+#locate(loc => test(first(loc), 10pt + 4pt))
+
+---
+// A single instance is aligned even if justification is enabled.
+#let piece = [#box(width: 20pt, height: 1pt)#metadata(none) <piece>]
+#let first(loc) = query(<piece>, loc).first().location().position().x
+#box(width: 30pt, align(right, repeat(gap: 3pt, piece)))
+#locate(loc => test(first(loc), 10pt + 10pt + 20pt))
+
+---
+// Error: 14-18 expected length, found boolean
+#repeat(gap: true)[.]
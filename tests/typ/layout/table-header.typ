@@ -0,0 +1,87 @@
+// Test repeated header rows and per-side cell strokes.
+// Ref: false
+
+---
+// Test that the header is repeated on every page. Each page fits the header
+// and four rows, so that nine rows need three pages.
+#set page(height: 100pt, margin: 0pt)
+#table(
+  rows: 20pt,
+  header-rows: 1,
+  [*Name*],
+  ..range(9).map(str),
+)
+#locate(loc => test(counter(page).final(loc), (3,)))
+
+---
+// Test a header with multiple rows and gutter in a grid.
+#set page(height: 80pt)
+#grid(
+  columns: 2,
+  gutter: 3pt,
+  header-rows: 2,
+  [A], [B],
+  [C], [D],
+  ..range(20).map(str),
+)
+
+---
+// Test that a header larger than the grid is harmless.
+#table(columns: 2, header-rows: 5, [A], [B])
+
+---
+// Test a three-line table.
+#table(
+  columns: 3,
+  stroke: (x, y) => (
+    top: if y == 0 { 1pt } else if y == 1 { 0.5pt },
+    bottom: if y == 2 { 1pt },
+    x: none,
+  ),
+  [A], [B], [C],
+  [1], [2], [3],
+  [4], [5], [6],
+)
+
+---
+// Test per-side strokes for all cells.
+#table(
+  columns: 2,
+  stroke: (left: none, rest: red),
+  [A], [B], [C], [D],
+)
+
+---
+// Error: 21-23 number must be at least zero
+#table(header-rows: -1)
+
+---
+// Test that the footer is repeated on every page. Each page fits the header,
+// the two footer rows, and two rows, so that five rows need three pages.
+#set page(height: 100pt, margin: 0pt)
+#table(
+  rows: 20pt,
+  header-rows: 1,
+  footer-rows: 2,
+  [*Name*],
+  ..range(5).map(str),
+  [*Total*], [*Sum*],
+)
+#locate(loc => test(counter(page).final(loc), (3,)))
+
+---
+// Test that the header is not left alone at the bottom of a page. Only the
+// header would fit below the spacing, so it moves to the second page.
+#set page(height: 100pt, margin: 0pt)
+#v(70pt)
+#table(
+  rows: 20pt,
+  header-rows: 1,
+  [*Name* #metadata(none) <header>],
+  ..range(3).map(str),
+)
+#locate(loc => test(query(<header>, loc).first().location().page(), 2))
+
+---
+// Test that header and footer never overlap.
+#grid(columns: 2, header-rows: 1, footer-rows: 2, [A], [B], [C], [D])
@@ -0,0 +1,32 @@
+// Test the spacing and separator of term lists.
+// Ref: false
+
+---
+// Test that wide term lists are spaced further apart than tight ones.
+#style(styles => {
+  let tight = measure(terms(([A], [B]), ([C], [D])), styles)
+  let wide = measure(terms(tight: false, ([A], [B]), ([C], [D])), styles)
+  test(tight.height < wide.height, true)
+})
+
+---
+// Test the spacing of wide term lists.
+#style(styles => {
+  let small = measure(terms(tight: false, spacing: 1em, ([A], [B]), ([C], [D])), styles)
+  let big = measure(terms(tight: false, spacing: 3em, ([A], [B]), ([C], [D])), styles)
+  test(small.height < big.height, true)
+})
+
+---
+// Test the separator and indent.
+#style(styles => {
+  let plain = measure(terms(([A], [B])), styles)
+  let separated = measure(terms(separator: h(2cm), ([A], [B])), styles)
+  let indented = measure(terms(indent: 2cm, ([A], [B])), styles)
+  test(plain.width < separated.width, true)
+  test(plain.width < indented.width, true)
+})
+
+---
+// Error: 8-14 array must contain exactly two entries
+#terms(([A],))
@@ -0,0 +1,35 @@
+// Test transformations that impact the layout.
+// Ref: false
+
+---
+// Test that a reflowed rotation swaps the dimensions.
+#style(styles => {
+  let body = rect(width: 40pt, height: 10pt)
+  let size = measure(rotate(90deg, reflow: true, body), styles)
+  test(calc.round(size.width.pt()), 10)
+  test(calc.round(size.height.pt()), 40)
+  let size = measure(rotate(90deg, body), styles)
+  test(size.width, 40pt)
+})
+
+---
+// Test that a reflowed scale resizes the bounding box.
+#style(styles => {
+  let size = measure(scale(x: 50%, y: 200%, reflow: true, rect(width: 40pt, height: 10pt)), styles)
+  test(size.width, 20pt)
+  test(size.height, 20pt)
+})
+
+---
+// Test skewing with and without reflow.
+#style(styles => {
+  let body = rect(width: 10pt, height: 10pt)
+  let size = measure(skew(ax: 45deg, reflow: true, body), styles)
+  test(calc.round(size.width.pt()), 20)
+  test(size.height, 10pt)
+  test(measure(skew(ay: 45deg, body), styles).height, 10pt)
+})
+
+---
+// Test vertical spine text.
+#box(rotate(-90deg, reflow: true)[Spine Title]) Text
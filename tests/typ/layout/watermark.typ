@@ -0,0 +1,28 @@
+// Test watermarks.
+// Ref: false
+
+---
+// The watermark fills the area no matter how it is arranged.
+#style(styles => {
+  let area = (width: 120pt, height: 80pt)
+  let tiled = watermark(text(fill: luma(200))[机密])
+  let centered = watermark(tiled: false, angle: 0deg)[DRAFT]
+  let stamp = watermark(image("/files/tiger.jpg", width: 20pt, opacity: 30%))
+  test(measure(tiled, styles, ..area), area)
+  test(measure(centered, styles, ..area), area)
+  test(measure(stamp, styles, ..area), area)
+  test(stamp.body.opacity, 30%)
+  test(centered.tiled, false)
+})
+
+---
+#style(styles => {
+  let size = measure(watermark[A], styles, width: 50pt, height: 30pt)
+  test(size, (width: 50pt, height: 30pt))
+})
+
+---
+#style(styles => {
+  // Error: 11-23 watermark with no size restrictions
+  measure(watermark[A], styles)
+})
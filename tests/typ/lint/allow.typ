@@ -0,0 +1,30 @@
+// Test suppressing warnings in source code.
+// Ref: false
+
+---
+// @allow(empty-strong)
+**
+
+---
+**not bold** // @allow(empty-emph, empty-strong)
+
+---
+// Only the listed codes are suppressed.
+// @allow(empty-emph)
+// Warning: 1-3 no text within stars
+// Hint: 1-3 using multiple consecutive stars (e.g. **) has no additional effect
+**
+
+---
+// Warning: 17-30 unknown font family: nonexistent
+#set text(font: "Nonexistent")
+
+---
+// @allow(unknown-font-family)
+#set text(font: ("Nonexistent", "PT Sans"))
+
+---
+// Comment markers in strings don't suppress anything.
+// Warning: 1-3 no text within stars
+// Hint: 1-3 using multiple consecutive stars (e.g. **) has no additional effect
+** #"// @allow(empty-strong)"
@@ -0,0 +1,44 @@
+// Test the LaTeX math converter.
+// Ref: false
+
+---
+#test(math.latex(`\frac{a}{b}`.text), $frac(a, b)$)
+#test(math.latex(`x^2 + \alpha_i`.text), $x^(2) + alpha_(i)$)
+#test(math.latex(`\sqrt[3]{x} \leq \infty`.text), $root(3, x) lt.eq infinity$)
+#test(math.latex(`\mathbb{R} \to \mathbf{v}`.text), $bb(R) arrow.r bold(v)$)
+#test(math.latex(`面积 = \pi r^2`.text), $"面积" = pi r^(2)$)
+#test(math.latex(`\text{若 } x \neq 0`.text), $"若 " x eq.not 0$)
+#test(math.latex(`{a+b}^2 + {x}_1`.text), $(a + b)^(2) + x_(1)$)
+
+---
+// Negations.
+#test(math.latex(`x \not\in A`.text), $x in.not A$)
+#test(math.latex(`A \not\subset B`.text), $A subset.not B$)
+#test(math.latex(`a \not= b \not\leq c`.text), $a eq.not b lt.eq.not c$)
+#test(math.latex(`\not x`.text), $cancel(x)$)
+
+---
+#test(
+  math.latex(`\begin{pmatrix} 1 & 2 \\ 3 & 4 \end{pmatrix}`.text),
+  $mat(delim: "(", 1, 2; 3, 4)$,
+)
+#test(
+  math.latex(`\begin{cases} 1 & x > 0 \\ 0 & \text{否则} \end{cases}`.text),
+  $cases(1 & x > 0, 0 & "否则")$,
+)
+
+---
+#test(math.latex("x").block, false)
+#test(math.latex("x", block: true).block, true)
+
+---
+// Error: 13-46 unsupported LaTeX environment `tabular`
+#math.latex("\\begin{tabular}x\\end{tabular}")
+
+---
+// Error: 13-20 expected symbol after `\not`
+#math.latex("\\not")
+
+---
+// Error: 13-26 expected `}`
+#math.latex("\\frac{a}{b")
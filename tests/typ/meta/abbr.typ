@@ -0,0 +1,31 @@
+// Test abbreviations.
+// Ref: false
+
+---
+#abbr.list(title: none)
+
+#abbr("PDF", [Portable Document Format]) and #abbr("CJK")
+and #abbr("PDF") and #abbr("CJK", [Chinese, Japanese, and Korean])
+
+#locate(loc => {
+  let abbrs = query(abbr, loc)
+  test(abbrs.len(), 4)
+  test(abbrs.map(it => it.short), ("PDF", "CJK", "PDF", "CJK"))
+  test(abbrs.at(3).long, [Chinese, Japanese, and Korean])
+  test(abbrs.at(1).has("long"), false)
+})
+
+---
+// Only the first use is written out in full, even if the long form is given
+// later.
+#let log = state("log", "")
+#[
+  #show text: it => log.update(s => s + it.text + "|") + it
+  #abbr("PDF")#abbr("PDF", "Portable Document Format")#abbr("PDF")
+]
+
+#locate(loc => test(log.final(loc), "Portable Document Format| (|PDF|)|PDF|PDF|"))
+
+---
+// Error: 2-13 abbreviation `XYZ` has no long form
+#abbr("XYZ")
@@ -0,0 +1,26 @@
+// Test alternative descriptions and decorative content.
+// Ref: false
+
+---
+#figure(
+  image("/files/tiger.jpg", width: 2cm),
+  alt: "A tiger resting in the grass",
+  caption: [A tiger],
+) <tiger>
+
+#locate(loc => {
+  let figure = query(<tiger>, loc).first()
+  test(figure.alt, "A tiger resting in the grass")
+  test(figure.body.has("alt"), false)
+})
+
+---
+// Test that marking content as decorative doesn't change its layout.
+#style(styles => {
+  let plain = measure(block(width: 3cm)[Decoration], styles)
+  let artifact = measure(pdf.artifact(block(width: 3cm)[Decoration]), styles)
+  test(plain, artifact)
+})
+
+#set page(header: pdf.artifact[_Header_ #h(1fr) 2024])
+Content.
@@ -0,0 +1,10 @@
+// Test BibLaTeX files with string macros, cross references, Chinese names,
+// and malformed entries.
+// Ref: false
+
+---
+#set text(lang: "zh")
+@zhang2020 @li2021
+
+// Warning: 15-36 skipped malformed BibLaTeX entry `broken` (/files/works-zh.bib:21: wrong number of digits)
+#bibliography("/files/works-zh.bib")
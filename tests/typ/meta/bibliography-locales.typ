@@ -0,0 +1,22 @@
+// Test custom CSL locale files.
+// Ref: false
+
+---
+// The loaded locale takes precedence over the built-in one, which has `和`.
+#let log = state("log", "")
+#set text(lang: "zh", region: "CN")
+见 @arrgh 与 @netwok。
+#[
+  #show text: it => log.update(s => s + it.text) + it
+  #bibliography("/files/works.bib", locales: "/files/locales-zh-CN.xml")
+]
+
+#locate(loc => {
+  let text = log.final(loc)
+  test(text.contains("及"), true)
+  test(text.contains("和"), false)
+})
+
+---
+// Error: 44-64 file not found (searched at files/missing.xml)
+#bibliography("/files/works.bib", locales: "/files/missing.xml")
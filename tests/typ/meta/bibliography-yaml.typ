@@ -0,0 +1,15 @@
+// Test Hayagriva YAML bibliographies.
+// Ref: false
+
+---
+#set text(lang: "zh")
+@shu2021 @chen2019
+#bibliography("/files/works-zh.yml")
+
+---
+// Error: 15-32 failed to parse YAML file (/files/bad.yaml: did not find expected ',' or ']' at line 2 column 1, while parsing a flow sequence at line 1 column 18)
+#bibliography("/files/bad.yaml")
+
+---
+// Error: 15-40 failed to parse YAML file (/files/bad-entries.yaml: invalid type: sequence, expected a map at line 2 column 1)
+#bibliography("/files/bad-entries.yaml")
@@ -0,0 +1,38 @@
+// Test endnotes.
+// Ref: false
+
+---
+#let log = state("log", ())
+#show footnote.entry: it => log.update(l => l + ((it.note.placement, it.note.body),)) + it
+#set footnote(placement: "end")
+#set text(lang: "zh")
+
+= 第一章
+正文#footnote[第一条注释。] <first>
+#footnote.endnotes()
+
+= 第二章
+正文#footnote[第二条注释。]
+#footnote(placement: "page")[页下注。]
+#footnote.endnotes(title: none)
+
+#locate(loc => {
+  test(query(<first>, loc).first().placement, "end")
+  let entries = log.final(loc)
+  test(
+    entries.filter(e => e.first() == "end").map(array.last),
+    ([第一条注释。], [第二条注释。]),
+  )
+  test(entries.filter(e => e.first() == "page").len(), 1)
+})
+
+---
+// Warning: 6-22 endnote is not listed
+// Hint: 6-22 add a `footnote.endnotes()` listing after it
+#set footnote(placement: "end")
+#footnote.endnotes()
+Text#footnote[Orphan]
+
+---
+// Error: 26-34 expected "page" or "end"
+#set footnote(placement: "margin")
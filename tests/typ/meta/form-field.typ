@@ -0,0 +1,32 @@
+// Test fillable form fields.
+// Ref: false
+
+---
+// Test the default sizes of the different kinds of fields.
+#set text(10pt)
+#style(styles => {
+  let text = measure(form.field("name"), styles)
+  test(text.width, 100pt)
+  test(text.height, 14pt)
+  let checkbox = measure(form.field("newsletter", kind: "checkbox"), styles)
+  test(checkbox.width, 10pt)
+  test(checkbox.height, 10pt)
+  let signature = measure(form.field("signature", kind: "signature"), styles)
+  test(signature.width, 150pt)
+  test(signature.height, 30pt)
+})
+
+---
+// Test that fields can be sized and placed within a line.
+#style(styles => {
+  let field = form.field("city", width: 3cm, height: 1cm, value: "Berlin")
+  test(measure(field, styles).width, 3cm)
+  test(measure(field, styles).height, 1cm)
+})
+
+City: #form.field("city", tooltip: "The city you live in") \
+Agree: #form.field("agree", kind: "checkbox", checked: true)
+
+---
+// Error: 27-33 expected "text", "checkbox", or "signature"
+#form.field("name", kind: "file")
@@ -0,0 +1,46 @@
+// Test the back-of-book index.
+// Ref: false
+
+---
+#set page(numbering: "i")
+#index(key: term => term.rev())
+
+Alpha #index.entry("alpha")
+Beta #index.entry("beta", key: "0")
+#pagebreak()
+Alpha #index.entry("alpha") again #index.entry("alpha")
+
+#locate(loc => {
+  let entries = query(index.entry, loc)
+  test(entries.len(), 4)
+  test(entries.map(it => it.term), ("alpha", "beta", "alpha", "alpha"))
+  test(entries.at(1).key, "0")
+})
+
+---
+// Terms are sorted, Chinese ones by their pinyin, and each page is listed
+// once.
+#let log = state("log", "")
+#set page(numbering: "1")
+#[
+  #show text: it => log.update(s => s + it.text) + it
+  #show linebreak: it => log.update(s => s + "|") + it
+  #index(title: none)
+]
+
+排版 #index.entry("排版")
+Typst #index.entry("Typst") #index.entry("Typst")
+#pagebreak()
+字体 #index.entry("字体")
+Typst #index.entry("Typst")
+#[
+  #set index.entry(key: "0")
+  Zebra #index.entry("Zebra")
+]
+
+#locate(loc => test(log.final(loc), "Zebra, 2|排版, 1|Typst, 1, 2|字体, 2|"))
+
+---
+// Error: 2-23 expected string, found integer
+#index(key: term => 1)
+#index.entry("alpha")
@@ -0,0 +1,20 @@
+// Test Chinese numbering patterns.
+// Ref: false
+
+---
+#test(numbering("一、", 3), "三、")
+#test(numbering("（一）", 1), "（一）")
+#test(numbering("第一章", 2), "第二章")
+#test(numbering("壹", 3), "叁")
+
+---
+// Test heavenly stems.
+#test(numbering("甲", 1), "甲")
+#test(numbering("（甲）", 3), "（丙）")
+#test(numbering("甲", 10), "癸")
+#test(numbering("一.甲", 2, 4), "二.丁")
+
+---
+// Test that patterns with Chinese counting symbols keep their case.
+#test(heading(numbering: "壹.", [A]).numbering, "壹.")
+#test(heading(numbering: "一.甲", [A]).numbering, "一.甲")
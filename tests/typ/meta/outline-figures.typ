@@ -0,0 +1,25 @@
+// Test the automatic titles of lists of figures and tables.
+// Ref: false
+
+---
+#set text(lang: "zh")
+#outline(target: figure.where(kind: table))
+#outline(target: figure.where(kind: image))
+#outline(target: figure.where(kind: raw))
+#outline()
+
+#figure(table[甲], caption: [数据])
+#figure(image("/files/tiger.jpg", width: 1cm), caption: [老虎])
+
+#locate(loc => {
+  let titles = query(heading, loc).map(it => it.body.text)
+  test(titles, ("附表清单", "插图清单", "目录", "目录"))
+})
+
+---
+#set text(lang: "en")
+#outline(target: figure.where(kind: table))
+#locate(loc => {
+  let titles = query(heading, loc).map(it => it.body.text)
+  test(titles, ("List of Tables",))
+})
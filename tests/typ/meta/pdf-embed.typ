@@ -0,0 +1,23 @@
+// Test embedding files into the PDF.
+// Ref: false
+
+---
+#pdf.embed("/files/data.csv", description: "Raw data", mime-type: "text/csv")
+#pdf.embed("/files/hello.txt")
+
+#locate(loc => {
+  let files = query(pdf.embed, loc)
+  test(files.len(), 2)
+  test(files.first().path, "/files/data.csv")
+  test(files.first().description, "Raw data")
+  test(files.first().mime-type, "text/csv")
+  test(files.last().has("description"), false)
+})
+
+---
+// Error: 12-32 file not found (searched at files/missing.csv)
+#pdf.embed("/files/missing.csv")
+
+---
+// Error: 11-13 missing argument: path to the file to be embedded
+#pdf.embed()
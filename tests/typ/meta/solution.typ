@@ -0,0 +1,17 @@
+// Test toggleable solutions.
+// Ref: false
+
+---
+#style(styles => {
+  let height(body) = measure(body, styles).height
+  let shown = height(solution[Answer])
+  test(shown, height[Answer])
+  test(height(solution(shown: false)[Answer]), 0pt)
+  test(height(solution(shown: false, space: auto)[Answer]), shown)
+  test(height(solution(space: 1cm)[Answer]), 1cm)
+  test(height(solution(shown: false, space: 1cm)[Answer]), 1cm)
+})
+
+---
+// Error: 32-37 expected length, auto, or none, found string
+#solution(shown: false, space: "1cm")[]
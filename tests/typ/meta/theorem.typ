@@ -0,0 +1,44 @@
+// Test theorem blocks.
+// Ref: false
+
+---
+#set heading(numbering: "1.")
+#set theorem(reset: 1, numbering: "1.1")
+
+#theorem[Before any chapter.]
+
+= First
+#theorem(title: [Pythagoras])[$a^2 + b^2 = c^2$] <pythagoras>
+#theorem(kind: "definition")[A definition.]
+#theorem(kind: "lemma", group: "theorem")[Shares the theorem numbers.]
+
+= Second
+#theorem[After @pythagoras.]
+
+#locate(loc => {
+  let numbers = query(theorem, loc).map(it => it.counter.at(it.location()))
+  test(numbers, ((0, 1), (1, 1), (1, 1), (1, 2), (2, 1)))
+})
+
+---
+#set text(lang: "zh")
+#theorem(kind: "exercise")[练习。]
+#locate(loc => {
+  let it = query(theorem, loc).first()
+  test(it.supplement, [习题])
+  test(it.counter.at(it.location()), (1,))
+})
+
+---
+#set text(lang: "zh", region: "TW")
+#theorem(kind: "proof")[證畢。]
+#set text(lang: "de")
+#theorem(kind: "lemma")[Hilfssatz.]
+#locate(loc => {
+  let supplements = query(theorem, loc).map(it => it.supplement)
+  test(supplements, ([證明], [Lemma]))
+})
+
+---
+// Error: 2-29 please specify the theorem's supplement
+#theorem(kind: "axiom")[...]
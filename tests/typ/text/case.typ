@@ -1,4 +1,4 @@
-// Test the `upper` and `lower` functions.
+// Test the `upper`, `lower`, and `title-case` functions.
 // Ref: false
 
 ---
@@ -6,6 +6,13 @@
 #test(lower(memes), "are memes great?")
 #test(upper(memes), "ARE MEMES GREAT?")
 #test(upper("Ελλάδα"), "ΕΛΛΆΔΑ")
+#test(upper("straße"), "STRASSE")
+
+---
+#test(title-case("the lord of the rings"), "The Lord Of The Rings")
+#test(title-case("don't panic, it's fine"), "Don't Panic, It's Fine")
+#test(title-case("mixed CASE words"), "Mixed CASE Words")
+#test(title-case("中文 text"), "中文 Text")
 
 ---
 // Error: 8-9 expected string or content, found integer
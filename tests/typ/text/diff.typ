@@ -0,0 +1,16 @@
+// Test marking the changes between two texts.
+// Ref: false
+
+---
+#let changes = state("changes", ())
+#show diff.insertion: it => changes.update(c => c + ("+" + it.body.text,)) + it
+#show diff.deletion: it => changes.update(c => c + ("-" + it.body.text,)) + it
+
+#diff("Due within 30 days.", "Due within 14 days of delivery.")
+#diff("甲方应于三十日内付款。", "甲方应于十四日内付款。")
+#diff("Unchanged text.", "Unchanged text.")
+
+#locate(loc => test(
+  changes.final(loc),
+  ("-30", "+14", "+ of delivery", "-三", "+四"),
+))
@@ -0,0 +1,38 @@
+// Test overriding the vertical metrics of font families.
+// Ref: false
+
+---
+#set text(size: 10pt, font: "Noto Serif CJK SC")
+#set text(top-edge: "ascender", bottom-edge: "descender")
+#set text(metrics: ("Noto Serif CJK SC": (ascender: 1em, descender: -0.5em)))
+#style(styles => {
+  test(measure([排版], styles).height, 15pt)
+
+  // Later overrides only replace the metrics they specify.
+  let body = text(metrics: ("noto serif cjk sc": (descender: -1em)))[排版]
+  test(measure(body, styles).height, 20pt)
+
+  // The ideographic em box is centered between ascender and descender.
+  let body = text(top-edge: "ideographic", bottom-edge: "ideographic")[排版]
+  test(measure(body, styles).height, 10pt)
+})
+
+---
+// The line gap is added below the descender.
+#set text(size: 10pt, font: "Noto Serif CJK SC", top-edge: 1em, bottom-edge: "descender")
+#set text(metrics: ("Noto Serif CJK SC": (descender: 0em, line-gap: 0.5em)))
+#style(styles => {
+  test(measure([排版], styles).height, 15pt)
+
+  // Other edges ignore it.
+  test(measure(text(bottom-edge: "baseline")[排版], styles).height, 10pt)
+  test(measure(text(bottom-edge: -1pt)[排版], styles).height, 11pt)
+})
+
+---
+// Error: 20-49 unexpected key "ascent", valid keys are "ascender", "cap-height", "x-height", "descender", and "line-gap"
+#set text(metrics: ("Noto Serif": (ascent: 1em)))
+
+---
+// Error: 20-53 expected length, found boolean
+#set text(metrics: ("Noto Serif": (descender: true)))
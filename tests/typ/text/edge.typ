@@ -1,6 +1,8 @@
 // Test top and bottom text edge.
 
 ---
+// Warning: 5:19-5:34 unknown font family: ibm plex mono
+// Warning: 10:19-10:34 unknown font family: ibm plex mono
 #set page(width: 160pt)
 #set text(size: 8pt)
 
@@ -27,13 +29,13 @@
 #try(1pt + 0.3em, -0.15em)
 
 ---
-// Error: 21-23 expected "ascender", "cap-height", "x-height", "baseline", "bounds", or length, found array
+// Error: 21-23 expected "ascender", "cap-height", "x-height", "baseline", "ideographic", "bounds", or length, found array
 #set text(top-edge: ())
 
 ---
-// Error: 24-26 expected "baseline", "descender", "bounds", or length
+// Error: 24-26 expected "baseline", "descender", "ideographic", "bounds", or length
 #set text(bottom-edge: "")
 
 ---
-// Error: 24-36 expected "baseline", "descender", "bounds", or length
+// Error: 24-36 expected "baseline", "descender", "ideographic", "bounds", or length
 #set text(bottom-edge: "cap-height")
@@ -17,4 +17,8 @@ Aب😀🏞سمB
 01️⃣2
 
 // Tofus are rendered with the first font.
+// Warning: 1-6 no font has a glyph for `ዲ` (U+12F2)
+// Warning: 1-6 no font has a glyph for `ሞ` (U+121E)
+// Hint: 1-6 the character is shown as a tofu
+// Hint: 1-6 try adding a font that covers it to `text(font: ..)`
 A🐈ዲሞB
@@ -0,0 +1,25 @@
+// Test stroked and rounded highlights.
+// Ref: false
+
+---
+// Uniform strokes and radii collapse into a single value.
+#let stroked = highlight(stroke: fuchsia, radius: 2pt)[Stroked]
+#test(stroked.stroke.paint, fuchsia)
+#test(stroked.radius, 2pt)
+#stroked
+
+---
+// Strokes and radii can be set per side and per corner.
+#let underlined = highlight(stroke: (bottom: 1pt + red), extent: 1pt)[Underlined]
+#test(underlined.stroke.keys(), ("bottom",))
+#test(underlined.stroke.bottom.thickness, 1pt)
+#test(underlined.stroke.bottom.paint, red)
+#underlined
+
+#let rounded = highlight(radius: (left: 50%))[Rounded on the left]
+#test(rounded.radius, (top-left: 50%, bottom-left: 50%))
+#rounded
+
+---
+// Error: 20-43 unexpected key "cake", valid keys are "top-left", "top-right", "bottom-right", "bottom-left", "left", "top", "right", "bottom", and "rest"
+#highlight(radius: (left: 10pt, cake: 5pt))[Nope]
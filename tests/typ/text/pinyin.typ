@@ -0,0 +1,66 @@
+// Test the `pinyin` function.
+// Ref: false
+
+---
+// Characters have built-in readings.
+#test(pinyin("中国"), (("中", "zhōng"), ("国", "guó")))
+#test(pinyin("中国", style: "numbers").map(array.last), ("zhong1", "guo2"))
+
+---
+// The dictionary only needs to cover polyphonic characters.
+#test(
+  pinyin("重庆很重要", dict: ("重庆": "chong2 qing4")),
+  (("重", "chóng"), ("庆", "qìng"), ("很", "hěn"), ("重", "zhòng"), ("要", "yào")),
+)
+#test(pinyin("行", dict: ("行": "hang2")), (("行", "háng"),))
+
+---
+// Pieces without a reading are grouped.
+#test(
+  pinyin("你好，世界!"),
+  (("你", "nǐ"), ("好", "hǎo"), ("，", none), ("世", "shì"), ("界", "jiè"), ("!", none)),
+)
+#test(pinyin("abc"), (("abc", none),))
+#test(pinyin(""), ())
+
+---
+// Words whose syllables don't match their characters stay together.
+#test(pinyin("一会儿", dict: ("一会儿": "yi2 huir4")), (("一会儿", "yí huìr"),))
+
+---
+// Test the different ways of writing readings.
+#let dict = ("绿": "lv4", "中": "zhōng", "北": "Bei3", "的": "de")
+#test(pinyin("绿中北的", dict: dict).map(array.last), ("lǜ", "zhōng", "Běi", "de"))
+#test(
+  pinyin("绿中北的", dict: dict, style: "numbers").map(array.last),
+  ("lü4", "zhong1", "Bei3", "de5"),
+)
+
+---
+// Test zhuyin.
+#let dict = (
+  "中": "zhong1",
+  "国": "guo2",
+  "是": "shi4",
+  "学": "xue2",
+  "有": "you3",
+  "的": "de5",
+  "贵": "gui4",
+  "女": "nv3",
+)
+#test(
+  pinyin("中国是学有的贵女", dict: dict, style: "zhuyin").map(array.last),
+  ("ㄓㄨㄥ", "ㄍㄨㄛˊ", "ㄕˋ", "ㄒㄩㄝˊ", "ㄧㄡˇ", "˙ㄉㄜ", "ㄍㄨㄟˋ", "ㄋㄩˇ"),
+)
+
+---
+// Error: 2-34 invalid pinyin syllable `ha$3`
+#pinyin("好", dict: ("好": "ha$3"))
+
+---
+// Error: 2-54 cannot write `huir4` in zhuyin
+#pinyin("会儿", dict: ("会儿": "huir4"), style: "zhuyin")
+
+---
+// Error: 2-29 expected string, found integer
+#pinyin("好", dict: ("好": 3))
@@ -0,0 +1,15 @@
+// Test that nested quotes alternate between double and single quotes.
+// Ref: false
+
+---
+#let kinds = state("kinds", ())
+#show smartquote: it => kinds.update(k => k + (it.double,))
+#quote[Outer #quote[inner #quote[innermost]]]
+#kinds.display(k => test(k, (true, false, true, true, false, true)))
+
+---
+// Test that a block quote without quotation marks doesn't count.
+#let kinds = state("kinds", ())
+#show smartquote: it => kinds.update(k => k + (it.double,))
+#quote(block: true)[#quote[Inner]]
+#kinds.display(k => test(k, (true, true)))
@@ -0,0 +1,30 @@
+// Test line numbers in raw blocks.
+// Ref: false
+
+---
+#set raw(numbering: "1", number-start: 9, number-step: 2)
+```rs
+fn main() {
+    println!("Hello, world!");
+}
+```
+
+---
+// Test that a numbering function receives the line number.
+#let numbers = state("numbers", ())
+#set raw(numbering: n => numbers.update(it => it + (n,)))
+```
+a
+b
+c
+```
+#numbers.display(it => test(it, (1, 2, 3)))
+
+---
+// Test that inline raw text is not numbered.
+#set raw(numbering: n => panic("numbered inline raw"))
+Some `inline` code.
+
+---
+// Error: 23-24 number must be positive
+#set raw(number-step: 0)
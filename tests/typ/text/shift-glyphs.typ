@@ -0,0 +1,19 @@
+// Test OpenType sub- and superscript glyphs.
+// Ref: false
+
+---
+// Letters without script codepoints use the font's OpenType script glyphs,
+// which keep the full text size.
+#style(styles => {
+  let full = measure([a], styles).height
+  test(measure(super[a], styles).height, full)
+  test(measure(super(typographic: false)[a], styles).height < full, true)
+})
+
+---
+// Fonts without script glyphs fall back to synthesized scripts.
+#set text(font: "DejaVu Sans Mono")
+#style(styles => {
+  let full = measure([a], styles).height
+  test(measure(super[a], styles).height < full, true)
+})
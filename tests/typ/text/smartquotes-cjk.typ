@@ -0,0 +1,13 @@
+// Test corner bracket quotes for Japanese and Traditional Chinese.
+// Ref: false
+
+---
+#set text(font: "Noto Serif CJK TC")
+#let same(a, b) = style(styles => {
+  test(measure(a, styles).width, measure(b, styles).width)
+})
+
+#same(text(lang: "ja")["引用" 'a'], [「引用」 『a』])
+#same(text(lang: "zh", region: "TW")["引用"], [「引用」])
+#same(text(lang: "zh", region: "HK")['引用'], [『引用』])
+#same(text(lang: "zh")["引用"], [“引用”])
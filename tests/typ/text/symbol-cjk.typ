@@ -0,0 +1,14 @@
+// Test symbols for CJK punctuation.
+// Ref: false
+
+---
+#test("" + sym.corner.l + sym.corner.r, "「」")
+#test("" + sym.corner.l.double + sym.corner.r.double, "『』")
+#test("" + sym.lenticular.l + sym.lenticular.r, "【】")
+#test("" + sym.lenticular.l.stroked + sym.lenticular.r.stroked, "〖〗")
+#test("" + sym.comma + sym.comma.ideo, ",、")
+#test("" + sym.dot.ideo + sym.space.ideo, "。\u{3000}")
+
+---
+// Error: 13-17 unknown symbol modifier
+#sym.corner.ideo
@@ -0,0 +1,10 @@
+// Test stroked text.
+// Ref: false
+
+---
+// Error: 15-21 expected length, color, gradient, pattern, dictionary, stroke, or none, found array
+#text(stroke: (1, 2))[Stroked]
+
+---
+// Error: 15-22 expected length, color, gradient, pattern, dictionary, stroke, or none, found string
+#text(stroke: "red")[Stroked]
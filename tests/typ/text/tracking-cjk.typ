@@ -0,0 +1,21 @@
+// Test tracking and spacing of CJK text.
+// Ref: false
+
+---
+// Test that tracking is added between, but not after characters.
+#set text(font: "Noto Serif CJK SC")
+#style(styles => {
+  let plain = measure([标题], styles)
+  let tracked = measure(text(tracking: 1em)[标题], styles)
+  test(tracked.width > plain.width + 10.9pt, true)
+  test(tracked.width < plain.width + 11.1pt, true)
+})
+
+---
+// Test that word spacing doesn't affect text without spaces.
+#set text(font: "Noto Serif CJK SC")
+#style(styles => {
+  let plain = measure([中文标题], styles)
+  let spaced = measure(text(spacing: 300%)[中文标题], styles)
+  test(plain.width, spaced.width)
+})
@@ -0,0 +1,28 @@
+// Test QR codes and barcodes.
+// Ref: false
+
+---
+#barcode("https://typst-doc-cn.github.io/docs/")
+#barcode("排版", correction: "high", fill: blue)
+#barcode("400638133393", format: "ean13")
+#barcode("4006381333931", format: "ean13", height: 1cm)
+#barcode("Typst 0.10", format: "code128", width: 100%)
+
+---
+#style(styles => {
+  test(measure(barcode("A"), styles), (width: 3cm, height: 3cm))
+  let size = measure(barcode("A", format: "code128", width: 60pt), styles)
+  test(size, (width: 60pt, height: 20pt))
+})
+
+---
+// Error: 2-35 EAN-13 data must consist of 12 or 13 digits
+#barcode("12345", format: "ean13")
+
+---
+// Error: 2-43 EAN-13 check digit should be 1
+#barcode("4006381333932", format: "ean13")
+
+---
+// Error: 2-34 Code 128 cannot encode '价'
+#barcode("价格", format: "code128")
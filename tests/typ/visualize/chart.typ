@@ -0,0 +1,25 @@
+// Test charts.
+// Ref: false
+
+---
+#set page(width: 240pt, height: auto)
+#let data = ("一月": (120, 90), "二月": (150, -20), "三月": (90, 130))
+#chart(data, width: 100%, legend: ([华东], [华南]))
+#chart(data, kind: "line", height: 3cm, colors: (black, gray))
+#chart((("Rent", 1200), ("Food", 600), ("Other", 0)), kind: "pie", height: 2cm)
+
+---
+#test(chart(("A": 1, "B": (2, 3))).data, ("A": (1.0,), "B": (2.0, 3.0)))
+#test(chart((("A", 1),)).data, ("A": (1.0,)))
+
+---
+// Error: 2-39 pie chart values must not be negative
+#chart(("A": -1, "B": 2), kind: "pie")
+
+---
+// Error: 2-11 chart data must not be empty
+#chart(())
+
+---
+// Error: 8-22 array must contain exactly two entries
+#chart((("A", 1, 2),))